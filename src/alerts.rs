@@ -0,0 +1,138 @@
+use crate::i18n::{tr, trf};
+use crate::metrics::{parse_number, MetricValue};
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+// --- Оповещения по порогам метрик ---
+
+// Операция сравнения значения метрики с порогом
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Comparison {
+    #[default]
+    Greater, // >
+    GreaterOrEqual, // >=
+    Less,           // <
+    LessOrEqual,    // <=
+    Equal,          // ==
+    NotEqual,       // !=
+}
+
+impl Comparison {
+    // Все варианты (для выпадающего списка в настройках)
+    pub const ALL: [Comparison; 6] = [
+        Comparison::Greater,
+        Comparison::GreaterOrEqual,
+        Comparison::Less,
+        Comparison::LessOrEqual,
+        Comparison::Equal,
+        Comparison::NotEqual,
+    ];
+
+    // Выполняется ли условие для значения
+    pub fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Greater => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+            Comparison::Less => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::Equal => (value - threshold).abs() < 1e-9,
+            Comparison::NotEqual => (value - threshold).abs() >= 1e-9,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+        })
+    }
+}
+
+// Правило оповещения: "если <метрика> <операция> <порог> в течение N минут - уведомить (и остановить)"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct AlertRule {
+    pub metric: String,         // Имя метрики из правил извлечения
+    pub comparison: Comparison, // Операция сравнения
+    pub threshold: String,      // Порог (в том виде, как введен пользователем)
+    pub hold_minutes: u32,      // Сколько минут условие должно выполняться (0 - сразу)
+    pub stop_process: bool,     // Останавливать ли процесс при срабатывании
+}
+
+impl AlertRule {
+    // Проверка правила; None - правило корректно
+    pub fn validate(&self, metric_names: &[String]) -> Option<String> {
+        if self.metric.trim().is_empty() {
+            return Some(tr("Не выбрана метрика").to_string());
+        }
+        if !metric_names.iter().any(|name| name == self.metric.trim()) {
+            return Some(trf!("Метрика \"{}\" не определена", self.metric.trim()));
+        }
+        if parse_number(&self.threshold).is_none() {
+            return Some(tr("Порог должен быть числом").to_string());
+        }
+        None
+    }
+
+    // Человекочитаемое описание условия
+    pub fn describe(&self) -> String {
+        let hold = if self.hold_minutes > 0 {
+            trf!(" в течение {} мин", self.hold_minutes)
+        } else {
+            String::new()
+        };
+        format!(
+            "{} {} {}{}",
+            self.metric.trim(),
+            self.comparison,
+            self.threshold.trim(),
+            hold
+        )
+    }
+}
+
+// Состояние вычисления правила между проверками
+#[derive(Debug, Clone, Default)]
+pub struct AlertState {
+    since: Option<DateTime<Local>>, // С какого момента условие выполняется непрерывно
+    fired: bool,                    // Оповещение уже отправлено (до сброса условия)
+}
+
+// Проверка правил по последним значениям метрик.
+// Возвращает индексы правил, сработавших на этой проверке. Повторно правило
+// срабатывает только после того, как условие перестанет выполняться.
+pub fn evaluate_alerts(
+    rules: &[AlertRule],
+    states: &mut Vec<AlertState>,
+    metrics: &HashMap<String, MetricValue>,
+    now: DateTime<Local>,
+) -> Vec<usize> {
+    states.resize_with(rules.len(), AlertState::default);
+    let mut fired = Vec::new();
+    for (index, (rule, state)) in rules.iter().zip(states.iter_mut()).enumerate() {
+        let holds = match (
+            metrics.get(rule.metric.trim()),
+            parse_number(&rule.threshold),
+        ) {
+            (Some(metric), Some(threshold)) => rule.comparison.holds(metric.value, threshold),
+            _ => false,
+        };
+        if !holds {
+            *state = AlertState::default();
+            continue;
+        }
+        let since = *state.since.get_or_insert(now);
+        if !state.fired && now - since >= ChronoDuration::minutes(rule.hold_minutes as i64) {
+            state.fired = true;
+            fired.push(index);
+        }
+    }
+    fired
+}
@@ -0,0 +1,320 @@
+use crate::events::{EventKind, LifecycleEvent};
+use crate::history::{RunOutcome, RunRecord};
+use crate::Message;
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use iced::mouse;
+use iced::widget::canvas::{self, Frame, Geometry, Path, Stroke, Text};
+use iced::{Color, Pixels, Point, Rectangle, Renderer, Size, Theme};
+
+// --- График истории запусков (временная шкала по дням) ---
+
+// Ширина колонки с подписями дней
+const LABEL_WIDTH: f32 = 60.0;
+// Высота строки одного дня и зазор между строками
+const ROW_HEIGHT: f32 = 22.0;
+const ROW_GAP: f32 = 6.0;
+// Высота подписи часов под графиком
+const AXIS_HEIGHT: f32 = 18.0;
+
+const UPTIME_COLOR: Color = Color::from_rgb(0.16, 0.65, 0.27); // Зеленый - процесс работал
+const CRASH_COLOR: Color = Color::from_rgb(0.86, 0.21, 0.27); // Красный - сбой
+const SCHEDULED_COLOR: Color = Color::from_rgb(1.0, 0.76, 0.03); // Желтый - плановая остановка
+const GRID_COLOR: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.15);
+const LABEL_COLOR: Color = Color::from_rgb(0.7, 0.7, 0.7);
+
+// Временная шкала за последние `days` дней: строка на каждый день (сверху - сегодня),
+// по горизонтали - 24 часа. Зеленые блоки - время работы, красные отметки - сбои,
+// желтые - плановые остановки.
+pub struct UptimeChart {
+    pub runs: Vec<RunRecord>,                   // Завершенные запуски
+    pub current_start: Option<DateTime<Local>>, // Начало текущего запуска (если процесс работает)
+    pub days: usize,                            // Сколько дней показывать
+    pub now: DateTime<Local>,                   // Момент построения графика
+}
+
+impl UptimeChart {
+    // Высота графика для заданного числа дней
+    pub fn height(days: usize) -> f32 {
+        days as f32 * (ROW_HEIGHT + ROW_GAP) + AXIS_HEIGHT
+    }
+
+    // Начало суток (локальная полночь) для дня со смещением `days_ago` от сегодня
+    fn day_start(&self, days_ago: usize) -> Option<DateTime<Local>> {
+        let date = self.now.date_naive() - ChronoDuration::days(days_ago as i64);
+        Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+    }
+}
+
+impl canvas::Program<Message> for UptimeChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let plot_width = (bounds.width - LABEL_WIDTH).max(1.0);
+        let day_seconds = 24.0 * 60.0 * 60.0;
+
+        // Вертикальные линии сетки и подписи часов
+        let grid_bottom = self.days as f32 * (ROW_HEIGHT + ROW_GAP);
+        for hour in (0..=24).step_by(3) {
+            let x = LABEL_WIDTH + plot_width * hour as f32 / 24.0;
+            frame.stroke(
+                &Path::line(Point::new(x, 0.0), Point::new(x, grid_bottom)),
+                Stroke::default().with_color(GRID_COLOR).with_width(1.0),
+            );
+            frame.fill_text(Text {
+                content: format!("{:02}", hour % 24),
+                position: Point::new(x - 6.0, grid_bottom + 2.0),
+                color: LABEL_COLOR,
+                size: Pixels(11.0),
+                ..Default::default()
+            });
+        }
+
+        // Запуски, включая текущий (до текущего момента)
+        let current = self
+            .current_start
+            .map(|start| (start, self.now, None::<RunOutcome>));
+        let runs = self
+            .runs
+            .iter()
+            .map(|run| (run.start, run.end, Some(run.outcome)))
+            .chain(current);
+
+        for days_ago in 0..self.days {
+            let Some(day_start) = self.day_start(days_ago) else {
+                continue;
+            };
+            let day_end = day_start + ChronoDuration::days(1);
+            let y = days_ago as f32 * (ROW_HEIGHT + ROW_GAP);
+
+            frame.fill_text(Text {
+                content: day_start.format("%d.%m").to_string(),
+                position: Point::new(0.0, y + 4.0),
+                color: LABEL_COLOR,
+                size: Pixels(12.0),
+                ..Default::default()
+            });
+            frame.fill_rectangle(
+                Point::new(LABEL_WIDTH, y),
+                Size::new(plot_width, ROW_HEIGHT),
+                GRID_COLOR,
+            );
+
+            let to_x = |time: DateTime<Local>| {
+                let seconds = (time - day_start).num_seconds() as f32;
+                LABEL_WIDTH + plot_width * (seconds / day_seconds).clamp(0.0, 1.0)
+            };
+
+            for (start, end, outcome) in runs.clone() {
+                if end <= day_start || start >= day_end {
+                    continue;
+                }
+                // Блок времени работы
+                let x_start = to_x(start.max(day_start));
+                let x_end = to_x(end.min(day_end));
+                frame.fill_rectangle(
+                    Point::new(x_start, y),
+                    Size::new((x_end - x_start).max(1.0), ROW_HEIGHT),
+                    UPTIME_COLOR,
+                );
+                // Отметка о причине завершения, если оно пришлось на этот день
+                let marker_color = match outcome {
+                    Some(RunOutcome::Crashed) => Some(CRASH_COLOR),
+                    Some(RunOutcome::Scheduled) => Some(SCHEDULED_COLOR),
+                    _ => None,
+                };
+                if let Some(color) = marker_color.filter(|_| end < day_end) {
+                    frame.fill_rectangle(
+                        Point::new(x_end - 1.5, y - 2.0),
+                        Size::new(3.0, ROW_HEIGHT + 4.0),
+                        color,
+                    );
+                }
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+// --- Хронология событий жизненного цикла ---
+
+// Высота полосы хронологии
+pub const TIMELINE_HEIGHT: f32 = 56.0;
+// Радиус "захвата" отметки курсором (пиксели)
+const MARKER_HIT_RADIUS: f32 = 5.0;
+const MAINTENANCE_COLOR: Color = Color::from_rgba(1.0, 0.76, 0.03, 0.25);
+
+// Цвет отметки события
+fn event_color(kind: EventKind) -> Color {
+    match kind {
+        EventKind::Start => UPTIME_COLOR,
+        EventKind::Stop => Color::from_rgb(0.7, 0.7, 0.7),
+        EventKind::Crash => CRASH_COLOR,
+        EventKind::Restart => Color::from_rgb(0.0, 0.48, 1.0),
+        EventKind::RuleMatch => Color::from_rgb(1.0, 0.55, 0.0),
+    }
+}
+
+// Полоса событий за последние `hours` часов: отметки событий и окна обслуживания.
+// Клик по отметке прокручивает лог к соответствующей строке.
+pub struct TimelineChart {
+    pub events: Vec<LifecycleEvent>, // События (в хронологическом порядке)
+    pub maintenance: Vec<(DateTime<Local>, DateTime<Local>)>, // Окна обслуживания
+    pub hours: i64,                  // Ширина окна хронологии (часы)
+    pub now: DateTime<Local>,        // Правая граница шкалы
+}
+
+impl TimelineChart {
+    // Координата X момента времени
+    fn x_of(&self, time: DateTime<Local>, width: f32) -> f32 {
+        let span = (self.hours * 3600) as f32;
+        let offset = span - (self.now - time).num_seconds() as f32;
+        width * (offset / span).clamp(0.0, 1.0)
+    }
+
+    // Ближайшее к курсору событие в пределах радиуса захвата
+    fn event_at(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<&LifecycleEvent> {
+        let position = cursor.position_in(bounds)?;
+        let start = self.now - ChronoDuration::hours(self.hours);
+        self.events
+            .iter()
+            .filter(|event| event.time >= start)
+            .map(|event| {
+                (
+                    (self.x_of(event.time, bounds.width) - position.x).abs(),
+                    event,
+                )
+            })
+            .filter(|(distance, _)| *distance <= MARKER_HIT_RADIUS)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, event)| event)
+    }
+}
+
+impl canvas::Program<Message> for TimelineChart {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        if let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+            if let Some(line) = self
+                .event_at(bounds, cursor)
+                .and_then(|event| event.log_line)
+            {
+                return (
+                    canvas::event::Status::Captured,
+                    Some(Message::JumpToLogLine(line)),
+                );
+            }
+        }
+        (canvas::event::Status::Ignored, None)
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let width = bounds.width;
+        let bar_top = 18.0;
+        let bar_height = TIMELINE_HEIGHT - bar_top - AXIS_HEIGHT + 4.0;
+
+        // Фон полосы и окна обслуживания
+        frame.fill_rectangle(
+            Point::new(0.0, bar_top),
+            Size::new(width, bar_height),
+            GRID_COLOR,
+        );
+        for (start, end) in &self.maintenance {
+            let x_start = self.x_of(*start, width);
+            let x_end = self.x_of(*end, width);
+            if x_end > x_start {
+                frame.fill_rectangle(
+                    Point::new(x_start, bar_top),
+                    Size::new(x_end - x_start, bar_height),
+                    MAINTENANCE_COLOR,
+                );
+            }
+        }
+
+        // Подписи часов
+        for step in 0..=self.hours / 3 {
+            let time = self.now - ChronoDuration::hours(self.hours - step * 3);
+            let x = self.x_of(time, width);
+            frame.fill_text(Text {
+                content: time.format("%H:%M").to_string(),
+                position: Point::new(
+                    (x - 14.0).clamp(0.0, width - 30.0),
+                    bar_top + bar_height + 2.0,
+                ),
+                color: LABEL_COLOR,
+                size: Pixels(10.0),
+                ..Default::default()
+            });
+        }
+
+        // Отметки событий
+        let start = self.now - ChronoDuration::hours(self.hours);
+        for event in self.events.iter().filter(|event| event.time >= start) {
+            let x = self.x_of(event.time, width);
+            frame.fill_rectangle(
+                Point::new(x - 1.5, bar_top),
+                Size::new(3.0, bar_height),
+                event_color(event.kind),
+            );
+        }
+
+        // Подсказка для отметки под курсором
+        if let Some(event) = self.event_at(bounds, cursor) {
+            let mut description: String = event.description.chars().take(120).collect();
+            if description.len() < event.description.len() {
+                description.push('…');
+            }
+            frame.fill_text(Text {
+                content: format!(
+                    "{} {}: {}",
+                    event.time.format("%H:%M:%S"),
+                    event.kind.label(),
+                    description
+                ),
+                position: Point::new(0.0, 0.0),
+                color: Color::WHITE,
+                size: Pixels(12.0),
+                ..Default::default()
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match self.event_at(bounds, cursor) {
+            Some(event) if event.log_line.is_some() => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
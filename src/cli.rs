@@ -0,0 +1,89 @@
+use crate::i18n::tr;
+use crate::settings::{sanitize_api_key, AppSettings};
+use clap::Parser;
+use std::path::PathBuf;
+
+// --- Параметры командной строки ---
+// Позволяют запускать лаунчер из скриптов без правки файла настроек: значения
+// накладываются на загруженные настройки только на время сеанса и в файл не попадают.
+
+// Параметры запуска лаунчера
+#[derive(Parser, Debug, Clone, Default)]
+#[command(version, about = "Лаунчер TradingStar 3")]
+pub struct CliArgs {
+    /// Файл настроек (.json или .toml) вместо стандартного
+    #[arg(long, value_name = "ПУТЬ")]
+    pub config: Option<PathBuf>,
+    /// Исполняемый файл TradingStar
+    #[arg(long, value_name = "ПУТЬ")]
+    pub exe: Option<PathBuf>,
+    /// Ключ API основного экземпляра
+    #[arg(long, value_name = "КЛЮЧ")]
+    pub api_key: Option<String>,
+    /// Дополнительные аргументы основного экземпляра (одной строкой, аргумент с пробелами - в кавычках)
+    #[arg(long, value_name = "АРГУМЕНТЫ", allow_hyphen_values = true)]
+    pub args: Option<String>,
+    /// Запустить процесс при открытии лаунчера
+    #[arg(long)]
+    pub autostart: bool,
+    /// Остановить процесс с этим PID и выйти (вспомогательный процесс лаунчера, запущенный
+    /// с повышенными правами, см. stop_elevated)
+    #[arg(long, value_name = "PID", hide = true)]
+    pub stop_pid: Option<u32>,
+    /// Ожидание корректного завершения для --stop-pid (с)
+    #[arg(long, value_name = "СЕКУНДЫ", hide = true, default_value_t = 0)]
+    pub stop_timeout: u64,
+}
+
+impl CliArgs {
+    // Наложение параметров на настройки
+    pub fn apply(&self, settings: &mut AppSettings) {
+        if let Some(exe) = &self.exe {
+            settings.executable_path = Some(exe.clone());
+        }
+        // Ключ из командной строки важнее ключа из переменной окружения
+        if let Some(key) = &self.api_key {
+            settings.api_key = sanitize_api_key(key).0;
+            settings.api_key_env.clear();
+        }
+        if let Some(args) = &self.args {
+            settings.extra_args = args.clone();
+        }
+        if self.autostart {
+            settings.autostart = true;
+        }
+    }
+
+    // Перед сохранением переопределенные поля получают значения из файла (`file`).
+    // Поле, измененное в настройках после запуска, сохраняется как есть.
+    pub fn restore(&self, settings: &mut AppSettings, file: &AppSettings) {
+        if self.exe.is_some() && settings.executable_path == self.exe {
+            settings.executable_path = file.executable_path.clone();
+        }
+        if let Some(key) = &self.api_key {
+            if settings.api_key == sanitize_api_key(key).0 && settings.api_key_env.is_empty() {
+                settings.api_key = file.api_key.clone();
+                settings.api_key_env = file.api_key_env.clone();
+            }
+        }
+        if self.args.is_some() && self.args.as_ref() == Some(&settings.extra_args) {
+            settings.extra_args = file.extra_args.clone();
+        }
+        if self.autostart && settings.autostart {
+            settings.autostart = file.autostart;
+        }
+    }
+
+    // Названия переопределенных настроек (для лога и экрана настроек)
+    pub fn overridden(&self) -> Vec<&'static str> {
+        [
+            (self.exe.is_some(), tr("исполняемый файл")),
+            (self.api_key.is_some(), tr("ключ API")),
+            (self.args.is_some(), tr("дополнительные аргументы")),
+            (self.autostart, tr("автозапуск")),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::i18n::trf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+
+// --- Контроль расхождения системных часов ---
+
+// Разница между эпохой NTP (1900 год) и эпохой Unix (1970 год), в секундах
+const NTP_UNIX_EPOCH_DELTA: f64 = 2_208_988_800.0;
+// Таймаут ожидания ответа NTP-сервера
+const NTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Текущее время в секундах от эпохи Unix
+fn now_unix_seconds() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+// Чтение 64-битной метки времени NTP (секунды + дробная часть) в секунды Unix
+fn read_ntp_timestamp(bytes: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+    seconds + fraction / 4_294_967_296.0 - NTP_UNIX_EPOCH_DELTA
+}
+
+// Запись секунд Unix в формат метки времени NTP
+fn write_ntp_timestamp(seconds: f64, bytes: &mut [u8]) {
+    let ntp = seconds + NTP_UNIX_EPOCH_DELTA;
+    let whole = ntp.trunc() as u32;
+    let fraction = (ntp.fract() * 4_294_967_296.0) as u32;
+    bytes[0..4].copy_from_slice(&whole.to_be_bytes());
+    bytes[4..8].copy_from_slice(&fraction.to_be_bytes());
+}
+
+// Измеряет смещение локальных часов относительно NTP-сервера (SNTP, RFC 4330).
+// Возвращает смещение в миллисекундах: положительное - локальные часы отстают.
+pub async fn measure_clock_offset(server: String) -> Result<f64, String> {
+    let address = if server.contains(':') {
+        server.clone()
+    } else {
+        format!("{}:123", server)
+    };
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| trf!("Не удалось открыть UDP-сокет: {}", e))?;
+    socket
+        .connect(&address)
+        .await
+        .map_err(|e| trf!("Не удалось подключиться к NTP-серверу {}: {}", server, e))?;
+
+    // Запрос: LI = 0, версия 4, режим 3 (клиент); время отправки в поле Transmit Timestamp
+    let mut request = [0u8; 48];
+    request[0] = 0x23;
+    let t1 = now_unix_seconds();
+    write_ntp_timestamp(t1, &mut request[40..48]);
+    socket
+        .send(&request)
+        .await
+        .map_err(|e| trf!("Не удалось отправить запрос NTP-серверу {}: {}", server, e))?;
+
+    let mut response = [0u8; 48];
+    let received = tokio::time::timeout(NTP_TIMEOUT, socket.recv(&mut response))
+        .await
+        .map_err(|_| {
+            trf!(
+                "NTP-сервер {} не ответил за {}",
+                server,
+                format!("{:?}", NTP_TIMEOUT)
+            )
+        })?
+        .map_err(|e| trf!("Ошибка получения ответа NTP-сервера {}: {}", server, e))?;
+    let t4 = now_unix_seconds();
+    if received < 48 {
+        return Err(trf!("Некорректный ответ NTP-сервера {}", server));
+    }
+
+    // t2 - время получения запроса сервером, t3 - время отправки ответа
+    let t2 = read_ntp_timestamp(&response[32..40]);
+    let t3 = read_ntp_timestamp(&response[40..48]);
+    let offset = ((t2 - t1) + (t3 - t4)) / 2.0;
+    Ok(offset * 1000.0)
+}
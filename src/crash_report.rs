@@ -0,0 +1,92 @@
+use crate::diagnostics::redacted_settings;
+use crate::i18n::{tr, trf};
+use crate::settings::AppSettings;
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+// --- Отчет о сбое: сохраняется автоматически при аварийном завершении процесса ---
+
+// Сколько последних строк лога попадает в отчет
+pub const CRASH_REPORT_LINES: usize = 200;
+// Имя поддиректории с отчетами о сбоях (рядом с файлом конфигурации)
+const CRASH_REPORTS_DIR_NAME: &str = "crash-reports";
+
+// Данные отчета о сбое
+pub struct CrashReport {
+    pub instance: String,                 // Название экземпляра
+    pub exit_code: i32,                   // Код завершения процесса
+    pub started: Option<DateTime<Local>>, // Время запуска
+    pub ended: DateTime<Local>,           // Время завершения
+    pub settings: AppSettings,            // Настройки (ключи API будут скрыты)
+    pub log_lines: Vec<String>,           // Последние строки лога (от старых к новым)
+}
+
+// Директория отчетов о сбоях рядом с файлом конфигурации
+pub fn crash_reports_dir(config_path: &Path) -> Option<PathBuf> {
+    config_path
+        .parent()
+        .map(|dir| dir.join(CRASH_REPORTS_DIR_NAME))
+}
+
+// Запись отчета в файл crash-YYYYMMDD-HHMMSS.txt. Возвращает путь к файлу
+pub async fn save_crash_report(dir: PathBuf, report: CrashReport) -> Result<PathBuf, String> {
+    fs::create_dir_all(&dir).await.map_err(|e| {
+        trf!(
+            "Не удалось создать директорию {}: {}",
+            format!("{:?}", dir),
+            e
+        )
+    })?;
+    let path = dir.join(format!(
+        "crash-{}.txt",
+        report.ended.format("%Y%m%d-%H%M%S")
+    ));
+    let started = match report.started {
+        Some(started) => started.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => tr("неизвестно").to_string(),
+    };
+    let content = trf!(
+        "Отчет о сбое TradingStar\n\
+         Экземпляр: {}\n\
+         Код завершения: {}\n\
+         Запуск: {}\n\
+         Завершение: {}\n\
+         Версия лаунчера: {}\n\
+         \n--- Последние строки лога ({}) ---\n{}\n\
+         \n--- Настройки ---\n{}\n",
+        report.instance,
+        report.exit_code,
+        started,
+        report.ended.format("%Y-%m-%d %H:%M:%S"),
+        env!("CARGO_PKG_VERSION"),
+        report.log_lines.len(),
+        report.log_lines.join("\n"),
+        redacted_settings(report.settings)?
+    );
+    fs::write(&path, content).await.map_err(|e| {
+        trf!(
+            "Не удалось записать отчет о сбое {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })?;
+    Ok(path)
+}
+
+// Открытие директории в файловом менеджере системы
+pub async fn open_folder(dir: PathBuf) -> Result<(), String> {
+    let opener = if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    // explorer возвращает ненулевой код даже при успехе, поэтому код не проверяется
+    tokio::process::Command::new(opener)
+        .arg(&dir)
+        .spawn()
+        .map(drop)
+        .map_err(|e| trf!("Не удалось открыть {}: {}", format!("{:?}", dir), e))
+}
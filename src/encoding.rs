@@ -0,0 +1,73 @@
+use crate::i18n::tr;
+use encoding_rs::{IBM866, WINDOWS_1251};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+
+// --- Кодировка вывода процесса ---
+// На Windows TradingStar иногда выводит текст в CP866 (кодовая страница консоли) или
+// Windows-1251 вместо UTF-8. Строки вывода декодируются по настройке до разбора.
+
+// Кодировка вывода
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputEncoding {
+    #[default]
+    Auto, // UTF-8, а строки, которые им не являются, - CP1251 или CP866 (что больше похоже на текст)
+    Utf8,
+    Windows1251,
+    Cp866,
+}
+
+impl OutputEncoding {
+    pub const ALL: [OutputEncoding; 4] = [
+        OutputEncoding::Auto,
+        OutputEncoding::Utf8,
+        OutputEncoding::Windows1251,
+        OutputEncoding::Cp866,
+    ];
+
+    // Декодирование строки вывода (без \n и \r)
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            OutputEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            OutputEncoding::Windows1251 => decode_with(WINDOWS_1251, bytes).into_owned(),
+            OutputEncoding::Cp866 => decode_with(IBM866, bytes).into_owned(),
+            OutputEncoding::Auto => match std::str::from_utf8(bytes) {
+                Ok(text) => text.to_string(),
+                Err(_) => {
+                    let windows = decode_with(WINDOWS_1251, bytes);
+                    let dos = decode_with(IBM866, bytes);
+                    // Русский текст в чужой кодировке превращается в знаки и псевдографику,
+                    // поэтому выбираем вариант, в котором больше кириллических букв
+                    if cyrillic_letters(&dos) > cyrillic_letters(&windows) {
+                        dos.into_owned()
+                    } else {
+                        windows.into_owned()
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for OutputEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutputEncoding::Auto => tr("Автоопределение"),
+            OutputEncoding::Utf8 => "UTF-8",
+            OutputEncoding::Windows1251 => "Windows-1251",
+            OutputEncoding::Cp866 => "CP866 (DOS)",
+        })
+    }
+}
+
+fn decode_with<'a>(encoding: &'static encoding_rs::Encoding, bytes: &'a [u8]) -> Cow<'a, str> {
+    encoding.decode_without_bom_handling(bytes).0
+}
+
+// Количество букв русского алфавита в тексте
+fn cyrillic_letters(text: &str) -> usize {
+    text.chars()
+        .filter(|c| matches!(c, 'а'..='я' | 'А'..='Я' | 'ё' | 'Ё'))
+        .count()
+}
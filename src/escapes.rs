@@ -0,0 +1,129 @@
+use ansi_parser::{AnsiParser, AnsiSequence, Output};
+
+// --- Управляющие последовательности терминала в выводе процесса ---
+// ansi_parser распознает только часть последовательностей CSI (и SGR не длиннее 5 кодов),
+// а нераспознанные возвращает текстом вместе с байтом ESC. Здесь вывод приводится к тексту,
+// стилям (SGR) и стиранию строки; перемещения курсора, режимы терминала и прочие
+// последовательности отбрасываются, управляющие символы в текст не попадают.
+
+// Часть стирания строки (ESC[0K / ESC[1K / ESC[2K)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EraseLine {
+    ToEnd,   // От курсора до конца строки
+    ToStart, // От начала строки до курсора
+    All,     // Вся строка
+}
+
+// Элемент строки после разбора управляющих последовательностей
+#[derive(Debug, Clone, PartialEq)]
+pub enum Piece<'a> {
+    Text(&'a str),        // Текст без управляющих символов
+    Sgr(Vec<u8>),         // Коды SGR (стиль и цвета; пусто - сброс)
+    EraseLine(EraseLine), // Стирание строки
+}
+
+// Разбор части строки (без OSC - их удаляет links::split_hyperlinks)
+pub fn parse_pieces(chunk: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    for block in chunk.ansi_parse() {
+        match block {
+            Output::Escape(AnsiSequence::SetGraphicsMode(codes)) => {
+                pieces.push(Piece::Sgr(codes.to_vec()));
+            }
+            Output::Escape(AnsiSequence::EraseLine) => {
+                pieces.push(Piece::EraseLine(EraseLine::ToEnd));
+            }
+            // Очистка экрана для строки лога равна стиранию всей строки
+            Output::Escape(AnsiSequence::EraseDisplay) => {
+                pieces.push(Piece::EraseLine(EraseLine::All));
+            }
+            // Курсор, режимы терминала и т.д. на строку лога не влияют
+            Output::Escape(_) => {}
+            // Нераспознанная последовательность: ansi_parser отдает ее текстом до следующего ESC
+            Output::TextBlock(text) => match text.strip_prefix('\u{1b}') {
+                Some(sequence) => {
+                    let (piece, rest) = parse_unknown_escape(sequence);
+                    pieces.extend(piece);
+                    push_text(&mut pieces, rest);
+                }
+                None => push_text(&mut pieces, text),
+            },
+        }
+    }
+    pieces
+}
+
+// Последовательность после ESC, которую не распознал ansi_parser.
+// Возвращает ее значение (None - отбрасывается) и текст после нее
+fn parse_unknown_escape(sequence: &str) -> (Option<Piece<'_>>, &str) {
+    let Some(params) = sequence.strip_prefix('[') else {
+        // ESC с одним символом (ESC 7, ESC =) или выбор набора символов (ESC ( B)
+        let mut chars = sequence.chars();
+        let skip = match chars.next() {
+            Some(c @ ('(' | ')' | '*' | '+' | '#' | '%')) => {
+                c.len_utf8() + chars.next().map_or(0, char::len_utf8)
+            }
+            Some(c) => c.len_utf8(),
+            None => 0,
+        };
+        return (None, &sequence[skip..]);
+    };
+    // CSI: параметры и промежуточные байты (0x20-0x3F), затем завершающий байт (0x40-0x7E)
+    let Some(end) = params.find(|c: char| ('\u{40}'..='\u{7e}').contains(&c)) else {
+        return (None, ""); // Незавершенная последовательность - отбрасываем до конца части
+    };
+    let (body, final_byte) = (&params[..end], params.as_bytes()[end]);
+    let rest = &params[end + 1..];
+    // Приватные последовательности (ESC[?25l и т.п.) и нечисловые параметры не разбираем
+    if !body.chars().all(|c| c.is_ascii_digit() || c == ';') {
+        return (None, rest);
+    }
+    let numbers = || {
+        body.split(';')
+            .filter(|value| !value.is_empty())
+            .map(|value| value.parse::<u32>().unwrap_or(u32::MAX))
+    };
+    let piece = match final_byte {
+        // SGR длиннее, чем понимает ansi_parser (например, 1;38;2;R;G;B)
+        b'm' => Some(Piece::Sgr(
+            numbers()
+                .map(|code| u8::try_from(code).unwrap_or(u8::MAX))
+                .collect(),
+        )),
+        b'K' => match numbers().next().unwrap_or(0) {
+            0 => Some(Piece::EraseLine(EraseLine::ToEnd)),
+            1 => Some(Piece::EraseLine(EraseLine::ToStart)),
+            2 => Some(Piece::EraseLine(EraseLine::All)),
+            _ => None,
+        },
+        b'J' if numbers().next().unwrap_or(0) >= 2 => Some(Piece::EraseLine(EraseLine::All)),
+        _ => None,
+    };
+    (piece, rest)
+}
+
+// Строка с управляющими символами в видимом виде (\e[31m, \x07 и т.д.):
+// показывает, что именно вывел процесс, рядом с разобранной строкой
+pub fn escape_raw(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{1b}' => result.push_str("\\e"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\\' => result.push_str("\\\\"),
+            c if c.is_control() => result.push_str(&format!("\\x{:02x}", u32::from(c))),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+// Добавление текста без управляющих символов (табуляция остается)
+fn push_text<'a>(pieces: &mut Vec<Piece<'a>>, text: &'a str) {
+    for part in text.split(|c: char| c.is_control() && c != '\t') {
+        if !part.is_empty() {
+            pieces.push(Piece::Text(part));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::i18n::tr;
+use chrono::{DateTime, Local};
+
+// --- События жизненного цикла процесса (для хронологии) ---
+
+// Сколько последних событий хранить
+pub const MAX_EVENTS: usize = 1000;
+
+// Тип события
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Start,     // Процесс запущен
+    Stop,      // Процесс остановлен (вручную или лаунчером)
+    Crash,     // Процесс аварийно завершился
+    Restart,   // Автоматический перезапуск (планировщик)
+    RuleMatch, // Сработало правило оповещения
+}
+
+impl EventKind {
+    // Название для подсказки
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Start => tr("Запуск"),
+            EventKind::Stop => tr("Остановка"),
+            EventKind::Crash => tr("Сбой"),
+            EventKind::Restart => tr("Перезапуск"),
+            EventKind::RuleMatch => tr("Правило"),
+        }
+    }
+}
+
+// Событие с привязкой к строке лога
+#[derive(Debug, Clone)]
+pub struct LifecycleEvent {
+    pub time: DateTime<Local>, // Когда произошло
+    pub kind: EventKind,       // Тип
+    pub description: String,   // Описание (текст строки лога)
+    pub log_line: Option<u64>, // Идентификатор соответствующей строки лога
+}
@@ -0,0 +1,195 @@
+use crate::i18n::trf;
+use chrono::{DateTime, Local};
+use iced::futures::channel::mpsc as iced_mpsc;
+use iced::futures::SinkExt;
+use iced::{subscription, Subscription};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+
+// --- Пересылка вывода процесса во внешнюю систему логирования ---
+
+// Размер очереди строк, ожидающих отправки (при переполнении новые строки отбрасываются)
+pub const FORWARD_QUEUE_SIZE: usize = 10_000;
+// Пауза перед повторным подключением
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+// Имя приложения в записях
+const APP_NAME: &str = "tradingstar";
+
+// Формат пересылки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ForwardFormat {
+    #[default]
+    Syslog, // RFC 5424 с octet counting (RFC 6587) - rsyslog, syslog-ng
+    JsonLines, // JSON по строке на запись - Vector (socket), Fluent Bit/Fluentd (tcp)
+}
+
+impl ForwardFormat {
+    pub const ALL: [ForwardFormat; 2] = [ForwardFormat::Syslog, ForwardFormat::JsonLines];
+}
+
+impl fmt::Display for ForwardFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ForwardFormat::Syslog => "Syslog (RFC 5424)",
+            ForwardFormat::JsonLines => "JSON Lines (Vector/Fluent)",
+        })
+    }
+}
+
+// Параметры подключения (идентифицируют подписку: при изменении она перезапускается)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForwardConfig {
+    pub address: String, // host:port
+    pub format: ForwardFormat,
+    pub tls: bool, // Шифровать соединение (TLS)
+}
+
+// Запись для пересылки
+#[derive(Debug, Clone)]
+pub struct ForwardRecord {
+    pub time: DateTime<Local>, // Время получения строки
+    pub level: &'static str,   // Уровень (error, warning, info, debug)
+    pub profile: String,       // Профиль, от которого пришла строка
+    pub message: String,       // Текст строки (без ANSI)
+}
+
+// События подписки пересылки
+#[derive(Debug, Clone)]
+pub enum ForwarderEvent {
+    Ready(mpsc::Sender<ForwardRecord>), // Очередь для отправки строк
+    Connected(String),                  // Соединение установлено (адрес)
+    Disconnected(String),               // Соединение потеряно или не установлено (ошибка)
+}
+
+// Определение уровня строки по ключевым словам
+pub fn detect_level(line: &str) -> &'static str {
+    let lower = line.to_lowercase();
+    if lower.contains("error") || lower.contains("fatal") || lower.contains("panic") {
+        "error"
+    } else if lower.contains("warn") {
+        "warning"
+    } else if lower.contains("debug") || lower.contains("trace") {
+        "debug"
+    } else {
+        "info"
+    }
+}
+
+// Подписка, поддерживающая соединение и отправляющая строки из очереди
+pub fn forwarder_subscription(config: ForwardConfig) -> Subscription<ForwarderEvent> {
+    subscription::channel(config.clone(), 100, move |mut output| async move {
+        let (sender, mut receiver) = mpsc::channel(FORWARD_QUEUE_SIZE);
+        let _ = output.send(ForwarderEvent::Ready(sender)).await;
+        let hostname = sysinfo::System::host_name().unwrap_or_else(|| "-".to_string());
+
+        loop {
+            let mut stream = match connect(&config).await {
+                Ok(stream) => {
+                    let _ = output
+                        .send(ForwarderEvent::Connected(config.address.clone()))
+                        .await;
+                    stream
+                }
+                Err(e) => {
+                    report_and_wait(&mut output, e).await;
+                    continue;
+                }
+            };
+            // Отправляем строки, пока соединение живо
+            loop {
+                let Some(record) = receiver.recv().await else {
+                    // Отправителей больше нет - подписка будет остановлена
+                    return iced::futures::future::pending().await;
+                };
+                let payload = format_record(&record, config.format, &hostname);
+                if let Err(e) = stream.write_all(payload.as_bytes()).await {
+                    report_and_wait(
+                        &mut output,
+                        trf!("Ошибка отправки на {}: {}", config.address, e),
+                    )
+                    .await;
+                    break;
+                }
+            }
+        }
+    })
+}
+
+// Сообщение об ошибке и пауза перед переподключением
+async fn report_and_wait(output: &mut iced_mpsc::Sender<ForwarderEvent>, error: String) {
+    let _ = output.send(ForwarderEvent::Disconnected(error)).await;
+    tokio::time::sleep(RECONNECT_DELAY).await;
+}
+
+// Подключение по TCP (при необходимости - с TLS)
+async fn connect(config: &ForwardConfig) -> Result<Box<dyn AsyncWrite + Unpin + Send>, String> {
+    let address = config.address.trim();
+    let tcp = TcpStream::connect(address)
+        .await
+        .map_err(|e| trf!("Не удалось подключиться к {}: {}", address, e))?;
+    if !config.tls {
+        return Ok(Box::new(tcp));
+    }
+    let domain = address
+        .rsplit_once(':')
+        .map(|(host, _)| host)
+        .unwrap_or(address)
+        .trim_matches(|c| c == '[' || c == ']');
+    let connector = tokio_native_tls::native_tls::TlsConnector::new()
+        .map_err(|e| trf!("Ошибка инициализации TLS: {}", e))?;
+    let tls = tokio_native_tls::TlsConnector::from(connector)
+        .connect(domain, tcp)
+        .await
+        .map_err(|e| trf!("Ошибка TLS-соединения с {}: {}", address, e))?;
+    Ok(Box::new(tls))
+}
+
+// Форматирование записи для отправки
+fn format_record(record: &ForwardRecord, format: ForwardFormat, hostname: &str) -> String {
+    match format {
+        ForwardFormat::Syslog => {
+            // PRI = facility (1 - user) * 8 + severity
+            let severity = match record.level {
+                "error" => 3,
+                "warning" => 4,
+                "debug" => 7,
+                _ => 6,
+            };
+            let message = format!(
+                "<{}>1 {} {} {} - - [meta profile=\"{}\" level=\"{}\"] {}",
+                8 + severity,
+                record.time.to_rfc3339(),
+                hostname,
+                APP_NAME,
+                escape_sd_value(&record.profile),
+                record.level,
+                record.message
+            );
+            // Octet counting: длина сообщения в байтах, пробел, сообщение
+            format!("{} {}", message.len(), message)
+        }
+        ForwardFormat::JsonLines => {
+            let json = serde_json::json!({
+                "timestamp": record.time.to_rfc3339(),
+                "host": hostname,
+                "app": APP_NAME,
+                "profile": record.profile,
+                "level": record.level,
+                "message": record.message,
+            });
+            format!("{}\n", json)
+        }
+    }
+}
+
+// Экранирование значения structured data (RFC 5424, раздел 6.3.3)
+fn escape_sd_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
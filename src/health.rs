@@ -0,0 +1,85 @@
+use crate::i18n::{tr, trf};
+use iced::futures::SinkExt;
+use iced::{subscription, Subscription};
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+// --- Проверка готовности: опрос порта веб-интерфейса/API TradingStar ---
+
+// Период опроса
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+// Таймаут одной проверки
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+// Сколько неудачных проверок подряд считать работающий процесс не отвечающим
+pub const FAILURE_LIMIT: u32 = 3;
+
+// Состояние процесса по результатам проверок
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthState {
+    Starting,     // Процесс запущен, но еще ни разу не ответил
+    Healthy,      // Процесс отвечает
+    Unresponsive, // Процесс перестал отвечать (или не ответил за время запуска)
+}
+
+impl std::fmt::Display for HealthState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HealthState::Starting => tr("Запускается"),
+            HealthState::Healthy => tr("Работает"),
+            HealthState::Unresponsive => tr("Не отвечает"),
+        })
+    }
+}
+
+// Проверка адреса: "хост:порт" (TCP) или URL http(s)://. Возвращает описание ошибки или None
+pub fn validate_target(target: &str) -> Option<String> {
+    let target = target.trim();
+    if target.is_empty() {
+        return None;
+    }
+    if target.starts_with("http://") || target.starts_with("https://") {
+        return reqwest::Url::parse(target)
+            .err()
+            .map(|e| trf!("Неверный URL проверки: {}", e));
+    }
+    match target.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => None,
+        _ => Some(trf!(
+            "Неверный адрес проверки {} (нужно хост:порт или http://...)",
+            format!("{:?}", target)
+        )),
+    }
+}
+
+// Одна проверка: для URL - любой успешный HTTP-ответ, иначе - установка TCP-соединения
+async fn probe(target: &str) -> bool {
+    if target.starts_with("http://") || target.starts_with("https://") {
+        let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+            return false;
+        };
+        return match client.get(target).send().await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        };
+    }
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(target)).await,
+        Ok(Ok(_))
+    )
+}
+
+// Периодический опрос адреса. Выдает результат каждой проверки (true - процесс ответил).
+// `run` отличает запуски друг от друга: для нового процесса опрос начинается заново.
+pub fn health_subscription(target: String, run: u32) -> Subscription<bool> {
+    subscription::channel(
+        (std::any::TypeId::of::<HealthState>(), target.clone(), run),
+        10,
+        move |mut output| async move {
+            let target = target.trim().to_string();
+            loop {
+                tokio::time::sleep(PROBE_INTERVAL).await;
+                let _ = output.send(probe(&target).await).await;
+            }
+        },
+    )
+}
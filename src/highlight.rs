@@ -0,0 +1,214 @@
+use crate::i18n::{tr, trf};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// --- Подсветка строк лога по ключевым словам и регулярным выражениям ---
+
+// Цвет подсветки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HighlightColor {
+    Red,
+    #[default]
+    Orange,
+    Yellow,
+    Green,
+    Cyan,
+    Blue,
+    Magenta,
+    Gray,
+}
+
+impl HighlightColor {
+    pub const ALL: [HighlightColor; 8] = [
+        HighlightColor::Red,
+        HighlightColor::Orange,
+        HighlightColor::Yellow,
+        HighlightColor::Green,
+        HighlightColor::Cyan,
+        HighlightColor::Blue,
+        HighlightColor::Magenta,
+        HighlightColor::Gray,
+    ];
+
+    // Цвет в RGB
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            HighlightColor::Red => (0xFF, 0x55, 0x55),
+            HighlightColor::Orange => (0xFF, 0xA5, 0x00),
+            HighlightColor::Yellow => (0xFF, 0xFF, 0x55),
+            HighlightColor::Green => (0x55, 0xFF, 0x55),
+            HighlightColor::Cyan => (0x55, 0xFF, 0xFF),
+            HighlightColor::Blue => (0x6C, 0x9E, 0xFF),
+            HighlightColor::Magenta => (0xFF, 0x55, 0xFF),
+            HighlightColor::Gray => (0x88, 0x88, 0x88),
+        }
+    }
+}
+
+impl fmt::Display for HighlightColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HighlightColor::Red => tr("Красный"),
+            HighlightColor::Orange => tr("Оранжевый"),
+            HighlightColor::Yellow => tr("Желтый"),
+            HighlightColor::Green => tr("Зеленый"),
+            HighlightColor::Cyan => tr("Голубой"),
+            HighlightColor::Blue => tr("Синий"),
+            HighlightColor::Magenta => tr("Пурпурный"),
+            HighlightColor::Gray => tr("Серый"),
+        })
+    }
+}
+
+// Правило подсветки: строка, содержащая ключевое слово или совпадающая с регулярным
+// выражением (без учета регистра), целиком окрашивается в заданный цвет поверх ANSI-кодов
+// (или получает фон этого цвета) и, по выбору, выделяется жирным
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)] // Поля стиля появились позже: в старых настройках их нет
+pub struct HighlightRule {
+    pub keyword: String,       // Ключевое слово или регулярное выражение
+    pub color: HighlightColor, // Цвет строки
+    pub regex: bool,           // keyword - регулярное выражение
+    pub bold: bool,            // Жирный шрифт
+    pub background: bool,      // Цвет применяется к фону, а не к тексту
+}
+
+// Оформление строки по сработавшему правилу
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HighlightStyle {
+    pub color: HighlightColor, // Цвет текста или фона
+    pub bold: bool,            // Жирный шрифт
+    pub background: bool,      // Цвет относится к фону
+}
+
+// Условие правила после компиляции
+#[derive(Debug)]
+enum Matcher {
+    Keyword(String), // Ключевое слово в нижнем регистре
+    Regex(Regex),    // Регулярное выражение без учета регистра
+}
+
+// Скомпилированные правила подсветки
+#[derive(Debug, Default)]
+pub struct Highlighter {
+    rules: Vec<(Matcher, HighlightStyle)>,
+}
+
+impl Highlighter {
+    // Компиляция правил. Возвращает подсветку из корректных правил и ошибку
+    // для каждого правила (None - правило корректно или пустое) в том же порядке.
+    pub fn compile(rules: &[HighlightRule]) -> (Self, Vec<Option<String>>) {
+        let mut compiled = Vec::new();
+        let mut errors = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let keyword = rule.keyword.trim();
+            let style = HighlightStyle {
+                color: rule.color,
+                bold: rule.bold,
+                background: rule.background,
+            };
+            if keyword.is_empty() {
+                errors.push(None);
+            } else if rule.regex {
+                match RegexBuilder::new(keyword).case_insensitive(true).build() {
+                    Ok(regex) => {
+                        compiled.push((Matcher::Regex(regex), style));
+                        errors.push(None);
+                    }
+                    Err(e) => errors.push(Some(trf!("Ошибка в регулярном выражении: {}", e))),
+                }
+            } else {
+                compiled.push((Matcher::Keyword(keyword.to_lowercase()), style));
+                errors.push(None);
+            }
+        }
+        (Self { rules: compiled }, errors)
+    }
+
+    // Оформление строки по первому подходящему правилу (правила проверяются по порядку)
+    pub fn style(&self, line: &str) -> Option<HighlightStyle> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let lowercase = line.to_lowercase();
+        self.rules
+            .iter()
+            .find(|(matcher, _)| match matcher {
+                Matcher::Keyword(keyword) => lowercase.contains(keyword.as_str()),
+                Matcher::Regex(regex) => regex.is_match(line),
+            })
+            .map(|(_, style)| *style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(keyword: &str, regex: bool, color: HighlightColor) -> HighlightRule {
+        HighlightRule {
+            keyword: keyword.to_string(),
+            color,
+            regex,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keywords_ignore_case_and_first_rule_wins() {
+        let (highlighter, errors) = Highlighter::compile(&[
+            rule(" Error ", false, HighlightColor::Red),
+            rule("   ", false, HighlightColor::Blue),
+            rule("err", false, HighlightColor::Orange),
+        ]);
+        // Пустое правило пропускается без ошибки
+        assert_eq!(errors, vec![None, None, None]);
+        assert_eq!(highlighter.rules.len(), 2);
+        let color = |line| highlighter.style(line).map(|style| style.color);
+        assert_eq!(color("[ERROR] connection lost"), Some(HighlightColor::Red));
+        assert_eq!(color("stderr output"), Some(HighlightColor::Orange));
+        assert_eq!(color("ОШИБКА"), None);
+        assert_eq!(Highlighter::default().style("error"), None);
+    }
+
+    #[test]
+    fn compile_reports_regex_errors_per_rule() {
+        let (highlighter, errors) = Highlighter::compile(&[
+            rule("(unclosed", true, HighlightColor::Green),
+            rule(r"order #\d+", true, HighlightColor::Cyan),
+        ]);
+        assert!(errors[0].is_some());
+        assert!(errors[1].is_none());
+        assert_eq!(highlighter.rules.len(), 1);
+    }
+
+    #[test]
+    fn regexes_ignore_case() {
+        let (highlighter, _) = Highlighter::compile(&[rule(
+            r"ORDER #\d+ (filled|canceled)",
+            true,
+            HighlightColor::Cyan,
+        )]);
+        let color = |line| highlighter.style(line).map(|style| style.color);
+        assert_eq!(color("Order #42 Filled"), Some(HighlightColor::Cyan));
+        assert_eq!(color("order #x filled"), None);
+    }
+
+    #[test]
+    fn rule_style_is_applied() {
+        let (highlighter, _) = Highlighter::compile(&[HighlightRule {
+            bold: true,
+            background: true,
+            ..rule("fill", false, HighlightColor::Yellow)
+        }]);
+        assert_eq!(
+            highlighter.style("order filled"),
+            Some(HighlightStyle {
+                color: HighlightColor::Yellow,
+                bold: true,
+                background: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::i18n::{tr, trf};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::fs;
+
+// --- История запусков процесса ---
+
+pub const HISTORY_FILE_NAME: &str = "run_history.json";
+// Сколько последних запусков хранить
+pub const MAX_HISTORY_RECORDS: usize = 1000;
+
+// Чем закончился запуск
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutcome {
+    Stopped,   // Остановлен пользователем или правилом лаунчера
+    Scheduled, // Остановлен планировщиком (обслуживание биржи, питание)
+    Exited,    // Процесс сам завершился с кодом 0
+    Crashed,   // Процесс аварийно завершился (ненулевой код или ошибка)
+}
+
+impl std::fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RunOutcome::Stopped => tr("Остановлен"),
+            RunOutcome::Scheduled => tr("Плановая остановка"),
+            RunOutcome::Exited => tr("Завершился"),
+            RunOutcome::Crashed => tr("Сбой"),
+        })
+    }
+}
+
+// Запись об одном завершенном запуске
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub start: DateTime<Local>, // Время запуска
+    pub end: DateTime<Local>,   // Время завершения
+    pub exit_code: Option<i32>, // Код завершения (если процесс завершился сам)
+    pub outcome: RunOutcome,    // Причина завершения
+}
+
+// Формат экспорта истории
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    // Расширение файла
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+// Строка экспорта: запись о запуске с вычисляемыми полями для отчетов
+#[derive(Serialize)]
+struct ExportRow {
+    start: String,
+    end: String,
+    duration_seconds: i64,
+    exit_code: Option<i32>,
+    crashed: bool,
+    outcome: &'static str,
+}
+
+impl ExportRow {
+    fn new(run: &RunRecord) -> Self {
+        ExportRow {
+            start: run.start.to_rfc3339(),
+            end: run.end.to_rfc3339(),
+            duration_seconds: (run.end - run.start).num_seconds(),
+            exit_code: run.exit_code,
+            crashed: run.outcome == RunOutcome::Crashed,
+            outcome: match run.outcome {
+                RunOutcome::Stopped => "stopped",
+                RunOutcome::Scheduled => "scheduled",
+                RunOutcome::Exited => "exited",
+                RunOutcome::Crashed => "crashed",
+            },
+        }
+    }
+}
+
+// Преобразование истории в текст выбранного формата
+pub fn export_history(history: &[RunRecord], format: ExportFormat) -> Result<String, String> {
+    let rows: Vec<ExportRow> = history.iter().map(ExportRow::new).collect();
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)
+            .map_err(|e| trf!("Ошибка сериализации истории запусков: {}", e)),
+        ExportFormat::Csv => {
+            let mut csv = String::from("start,end,duration_seconds,exit_code,crashed,outcome\n");
+            for row in rows {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    row.start,
+                    row.end,
+                    row.duration_seconds,
+                    row.exit_code
+                        .map(|code| code.to_string())
+                        .unwrap_or_default(),
+                    row.crashed,
+                    row.outcome
+                ));
+            }
+            Ok(csv)
+        }
+    }
+}
+
+pub async fn load_history(path: Option<PathBuf>) -> Result<Vec<RunRecord>, String> {
+    let path =
+        path.ok_or_else(|| tr("Не удалось определить путь к истории запусков").to_string())?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).await.map_err(|e| {
+        trf!(
+            "Ошибка чтения истории запусков {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        trf!(
+            "Ошибка парсинга истории запусков {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })
+}
+
+pub async fn save_history(path: Option<PathBuf>, history: Vec<RunRecord>) -> Result<(), String> {
+    let path =
+        path.ok_or_else(|| tr("Не удалось определить путь к истории запусков").to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await.map_err(|e| {
+            trf!(
+                "Не удалось создать директорию {}: {}",
+                format!("{:?}", parent),
+                e
+            )
+        })?;
+    }
+    let content = serde_json::to_string_pretty(&history)
+        .map_err(|e| trf!("Ошибка сериализации истории запусков: {}", e))?;
+    fs::write(&path, content).await.map_err(|e| {
+        trf!(
+            "Не удалось записать историю запусков {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })
+}
@@ -0,0 +1,808 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+// --- Язык интерфейса ---
+// Строки интерфейса пишутся в коде по-русски и служат ключами таблицы переводов:
+// tr("...") возвращает перевод на выбранный язык, а если перевода нет - исходную строку.
+// Строки с подстановками переводятся целиком (trf!), аргументы подставляются после
+// перевода, поэтому порядок слов в переводе может отличаться ({0}, {1} ...).
+
+// Язык интерфейса
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    Russian,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Russian, Language::English];
+}
+
+// Название языка показывается на нем самом, чтобы его можно было найти в списке
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::Russian => "Русский",
+            Language::English => "English",
+        })
+    }
+}
+
+// Текущий язык (строки переводятся и вне состояния приложения - в ошибках фоновых задач)
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::English,
+        _ => Language::Russian,
+    }
+}
+
+// Перевод строки интерфейса
+pub fn tr(text: &'static str) -> &'static str {
+    match language() {
+        Language::Russian => text,
+        Language::English => {
+            static TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+            TABLE
+                .get_or_init(|| ENGLISH.iter().copied().collect())
+                .get(text)
+                .copied()
+                .unwrap_or(text)
+        }
+    }
+}
+
+// Подстановка аргументов в переведенный шаблон: {} - по порядку, {N} - по номеру,
+// {{ и }} - фигурные скобки
+pub fn format_template(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                result.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    index.push(c);
+                }
+                let index = if index.is_empty() {
+                    next += 1;
+                    next - 1
+                } else {
+                    index.parse().unwrap_or(usize::MAX)
+                };
+                if let Some(arg) = args.get(index) {
+                    result.push_str(&arg.to_string());
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+// Перевод строки с подстановками: trf!("Ошибка: {}", e)
+macro_rules! trf {
+    ($template:literal $(, $arg:expr)* $(,)?) => {{
+        // Ссылки на аргументы не должны пережить вызов (future после await остается Send)
+        let text = $crate::i18n::format_template(
+            $crate::i18n::tr($template),
+            &[$(&$arg as &dyn std::fmt::Display),*],
+        );
+        text
+    }};
+}
+pub(crate) use trf;
+
+// Английские переводы (ключ - русская строка из кода)
+#[rustfmt::skip]
+const ENGLISH: &[(&str, &str)] = &[
+    // alerts.rs
+    ("Не выбрана метрика", "No metric selected"),
+    ("Метрика \"{}\" не определена", "Metric \"{}\" is not defined"),
+    ("Порог должен быть числом", "The threshold must be a number"),
+    (" в течение {} мин", " for {} min"),
+    // cli.rs
+    ("исполняемый файл", "executable"),
+    ("ключ API", "API key"),
+    ("дополнительные аргументы", "extra arguments"),
+    ("автозапуск", "autostart"),
+    // clock.rs
+    ("Не удалось открыть UDP-сокет: {}", "Failed to open a UDP socket: {}"),
+    ("Не удалось подключиться к NTP-серверу {}: {}", "Failed to connect to NTP server {}: {}"),
+    ("Не удалось отправить запрос NTP-серверу {}: {}", "Failed to send a request to NTP server {}: {}"),
+    ("NTP-сервер {} не ответил за {}", "NTP server {} did not respond within {}"),
+    ("Ошибка получения ответа NTP-сервера {}: {}", "Error receiving the response from NTP server {}: {}"),
+    ("Некорректный ответ NTP-сервера {}", "Invalid response from NTP server {}"),
+    // crash_report.rs
+    ("Не удалось создать директорию {}: {}", "Failed to create directory {}: {}"),
+    ("неизвестно", "unknown"),
+    ("Отчет о сбое TradingStar\nЭкземпляр: {}\nКод завершения: {}\nЗапуск: {}\nЗавершение: {}\nВерсия лаунчера: {}\n\n--- Последние строки лога ({}) ---\n{}\n\n--- Настройки ---\n{}\n", "TradingStar crash report\nInstance: {}\nExit code: {}\nStarted: {}\nFinished: {}\nLauncher version: {}\n\n--- Last log lines ({}) ---\n{}\n\n--- Settings ---\n{}\n"),
+    ("Не удалось записать отчет о сбое {}: {}", "Failed to write crash report {}: {}"),
+    ("Не удалось открыть {}: {}", "Failed to open {}: {}"),
+    // diagnostics.rs
+    ("Сохранить пакет диагностики", "Save diagnostics bundle"),
+    ("Ошибка фоновой задачи сборки диагностики: {}", "Diagnostics background task failed: {}"),
+    ("Не удалось создать файл {}: {}", "Failed to create file {}: {}"),
+    ("Ошибка записи архива {}: {}", "Error writing archive {}: {}"),
+    ("Ошибка сериализации сбоев: {}", "Error serializing crashes: {}"),
+    ("Ошибка сериализации истории запусков: {}", "Error serializing run history: {}"),
+    ("Не удалось прочитать лог сессии {}: {}", "Failed to read session log {}: {}"),
+    ("{} {}\nСобран для: {} ({})\nДата пакета: {}\n", "{} {}\nBuilt for: {} ({})\nBundle date: {}\n"),
+    ("ОС: {}\nВерсия ядра: {}\nИмя компьютера: {}\nЧисло CPU: {}\n", "OS: {}\nKernel version: {}\nHost name: {}\nCPU count: {}\n"),
+    ("<скрыт, {} симв.>", "<hidden, {} chars>"),
+    ("<скрыто>", "<hidden>"),
+    ("Ошибка сериализации настроек: {}", "Error serializing settings: {}"),
+    // encoding.rs
+    ("Автоопределение", "Auto-detect"),
+    // events.rs
+    ("Запуск", "Start"),
+    ("Остановка", "Stop"),
+    ("Сбой", "Crash"),
+    ("Перезапуск", "Restart"),
+    ("Правило", "Rule"),
+    // forwarder.rs
+    ("Ошибка отправки на {}: {}", "Error sending to {}: {}"),
+    ("Не удалось подключиться к {}: {}", "Failed to connect to {}: {}"),
+    ("Ошибка инициализации TLS: {}", "TLS initialization error: {}"),
+    ("Ошибка TLS-соединения с {}: {}", "TLS connection error with {}: {}"),
+    // health.rs
+    ("Запускается", "Starting"),
+    ("Работает", "Running"),
+    ("Не отвечает", "Not responding"),
+    ("Неверный URL проверки: {}", "Invalid check URL: {}"),
+    ("Неверный адрес проверки {} (нужно хост:порт или http://...)", "Invalid check address {} (expected host:port or http://...)"),
+    // highlight.rs
+    ("Красный", "Red"),
+    ("Оранжевый", "Orange"),
+    ("Желтый", "Yellow"),
+    ("Зеленый", "Green"),
+    ("Голубой", "Cyan"),
+    ("Синий", "Blue"),
+    ("Пурпурный", "Purple"),
+    ("Серый", "Gray"),
+    ("Ошибка в регулярном выражении: {}", "Regular expression error: {}"),
+    // history.rs
+    ("Остановлен", "Stopped"),
+    ("Плановая остановка", "Scheduled stop"),
+    ("Завершился", "Exited"),
+    ("Не удалось определить путь к истории запусков", "Could not determine the run history path"),
+    ("Ошибка чтения истории запусков {}: {}", "Error reading run history {}: {}"),
+    ("Ошибка парсинга истории запусков {}: {}", "Error parsing run history {}: {}"),
+    ("Не удалось записать историю запусков {}: {}", "Failed to write run history {}: {}"),
+    // i18n.rs
+    ("...", "..."),
+    ("Ошибка: {}", "Error: {}"),
+    // keyword_alerts.rs
+    ("Ошибка звукового сигнала: {}", "Sound alert error: {}"),
+    ("Не удалось подать звуковой сигнал", "Failed to play the sound alert"),
+    ("Не удалось запустить {}: {}", "Failed to start {}: {}"),
+    ("{} завершился с ошибкой ({})", "{} failed ({})"),
+    // links.rs
+    ("Ссылка {} не открыта: поддерживаются только http(s)", "Link {} not opened: only http(s) is supported"),
+    ("Не удалось открыть ссылку {}: {}", "Failed to open link {}: {}"),
+    // main.rs
+    ("Выберите исполняемый файл...", "Select the executable..."),
+    ("Выберите директорию для логов и истории запусков...", "Select the directory for logs and run history..."),
+    ("Выберите рабочую директорию процесса...", "Select the process working directory..."),
+    ("Экспорт истории запусков", "Export run history"),
+    ("Не удалось записать файл {}: {}", "Failed to write file {}: {}"),
+    ("Экспорт лога", "Export log"),
+    ("Текст", "Text"),
+    ("Экземпляр \"{}\" запущен - остановите его перед удалением.", "Instance \"{}\" is running - stop it before removing."),
+    ("Экземпляр \"{}\" удален.", "Instance \"{}\" removed."),
+    ("Профиль запуска можно сменить только при остановленном процессе.", "The launch profile can only be changed while the process is stopped."),
+    ("Выбран профиль запуска \"{}\".", "Launch profile \"{}\" selected."),
+    ("Исполняемый файл профиля: {}", "Profile executable: {}"),
+    ("Профиль {}", "Profile {}"),
+    ("Вставленный ключ API исправлен: {}", "Pasted API key corrected: {}"),
+    ("Проверка ключа API: {}", "API key check: {}"),
+    ("Применение новых настроек ({}): остановка процесса и запуск заново...", "Applying new settings ({}): stopping the process and starting it again..."),
+    ("PID процесса неизвестен - остановите процесс вручную и запустите снова.", "Process PID is unknown - stop the process manually and start it again."),
+    ("Интерфейс заблокирован: команда из меню значка отклонена.", "Interface is locked: tray menu command rejected."),
+    ("Выход из меню значка в области уведомлений...", "Exiting from the tray menu..."),
+    ("Интерфейс разблокирован.", "Interface unlocked."),
+    ("Неверный PIN", "Wrong PIN"),
+    ("Неудачная попытка разблокировать интерфейс.", "Failed attempt to unlock the interface."),
+    ("Отчет о сбое сохранен в {}.", "Crash report saved to {}."),
+    ("{}Процесс завершился аварийно, отчет о сбое сохранен.", "{}The process crashed, a crash report was saved."),
+    ("Не удалось сохранить отчет о сбое: {}", "Failed to save the crash report: {}"),
+    ("Директория данных недоступна: логи не сохраняются.", "Data directory is unavailable: logs are not saved."),
+    ("Не удалось создать {}: {}", "Failed to create {}: {}"),
+    ("История запусков экспортирована в {}.", "Run history exported to {}."),
+    ("Ошибка экспорта истории запусков: {}", "Run history export error: {}"),
+    ("Пакет диагностики сохранен в {}.", "Diagnostics bundle saved to {}."),
+    ("Ошибка сборки пакета диагностики: {}", "Diagnostics bundle error: {}"),
+    ("Набор правил экспортирован в {}.", "Rule pack exported to {}."),
+    ("Ошибка экспорта набора правил: {}", "Rule pack export error: {}"),
+    ("Импорт набора правил: добавлено {}, пропущено {} (уже есть или метрика с тем же именем).", "Rule pack import: {} added, {} skipped (already present or a metric with the same name)."),
+    ("Ошибка импорта набора правил: {}", "Rule pack import error: {}"),
+    ("Оповещение по ключевой фразе: {}", "Keyword alert: {}"),
+    ("Строка события уже вытеснена из окна лога - см. файл лога сессии.", "The event line has already left the log window - see the session log file."),
+    ("Директория данных изменена на {} (перенесено файлов: {}).", "Data directory changed to {} (files moved: {})."),
+    ("Лог текущей сессии остается в прежней директории до следующего запуска.", "The current session log stays in the previous directory until the next start."),
+    ("Не удалось сменить директорию данных: {}", "Failed to change the data directory: {}"),
+    ("Директория данных недоступна: {}", "Data directory is unavailable: {}"),
+    ("Логи скопированы в буфер обмена.", "Logs copied to the clipboard."),
+    ("Нет логов для копирования.", "No logs to copy."),
+    ("Выделенные строки скопированы в буфер обмена.", "Selected lines copied to the clipboard."),
+    ("Лог очищен.", "Log cleared."),
+    ("Новый файл лога сессии: {}", "New session log file: {}"),
+    ("Нет логов для экспорта.", "No logs to export."),
+    ("Лог сохранен в {}.", "Log saved to {}."),
+    ("Ошибка экспорта лога: {}", "Log export error: {}"),
+    ("Выбран пакет {}, исполняемый файл: {}", "Package {} selected, executable: {}"),
+    ("Выбран путь: {}", "Selected path: {}"),
+    ("Выбранный файл не подходит: {}", "The selected file is not suitable: {}"),
+    ("Ошибка выбора файла: {}", "File selection error: {}"),
+    ("Выбор файла отменен.", "File selection cancelled."),
+    ("Файл настроек зашифрован. Введите пароль.", "The settings file is encrypted. Enter the password."),
+    ("Настройки успешно загружены.", "Settings loaded successfully."),
+    ("Исполняемый файл из настроек: {}", "Executable from settings: {}"),
+    ("Обнаружен PID ({}) от предыдущего сеанса. Проверка процесса...", "Found a PID ({}) from the previous session. Checking the process..."),
+    ("--- Лог предыдущего сеанса ({}) ---", "--- Previous session log ({}) ---"),
+    ("--- Конец лога предыдущего сеанса ---", "--- End of previous session log ---"),
+    ("Не удалось восстановить лог предыдущего сеанса: {}", "Failed to restore the previous session log: {}"),
+    ("Ошибка загрузки истории запусков: {}", "Error loading run history: {}"),
+    ("Процесс работал при закрытии лаунчера - возобновляем.", "The process was running when the launcher closed - resuming."),
+    ("Ошибка загрузки рабочего пространства: {}", "Error loading the workspace: {}"),
+    ("Ошибка загрузки настроек: {}", "Error loading settings: {}"),
+    ("Файл настроек изменен, но не прочитан. Действуют прежние настройки.", "The settings file changed but could not be read. The previous settings remain in effect."),
+    ("Файл настроек зашифрован другой программой. Действуют прежние настройки.", "The settings file was encrypted by another program. The previous settings remain in effect."),
+    ("Файл настроек изменен другой программой - настройки перезагружены.", "The settings file was changed by another program - settings reloaded."),
+    ("Файл настроек изменен, но не прочитан: {}. Действуют прежние настройки.", "The settings file changed but could not be read: {}. The previous settings remain in effect."),
+    ("Не удалось получить список копий настроек: {}", "Failed to list settings backups: {}"),
+    ("Восстановлены настройки из копии от {}.", "Settings restored from the backup of {}."),
+    ("Настройки восстановлены из копии.", "Settings restored from the backup."),
+    ("Не удалось восстановить настройки: {}", "Failed to restore settings: {}"),
+    ("Настройки не сброшены: работает экземпляр \"{}\". Остановите его и повторите сброс.", "Settings not reset: instance \"{}\" is running. Stop it and reset again."),
+    ("Все настройки сброшены к значениям по умолчанию.", "All settings reset to defaults."),
+    ("Неудачная попытка открыть зашифрованные настройки.", "Failed attempt to open the encrypted settings."),
+    ("Пароль настроек изменен.", "Settings password changed."),
+    ("Файл настроек зашифрован.", "Settings file encrypted."),
+    ("Не удалось зашифровать настройки: {}", "Failed to encrypt settings: {}"),
+    ("Шифрование настроек отключено.", "Settings encryption disabled."),
+    ("Удалены незашифрованные копии настроек: {}.", "Unencrypted settings copies removed: {}."),
+    ("Не удалось удалить незашифрованные копии настроек: {}", "Failed to remove unencrypted settings copies: {}"),
+    ("Ошибка сохранения настроек: {}", "Error saving settings: {}"),
+    ("Процесс успешно запущен (PID: {}).", "Process started successfully (PID: {})."),
+    ("процесс не принимает ввод", "the process does not accept input"),
+    ("Не удалось отправить команду: {}", "Failed to send the command: {}"),
+    ("Процесс снова выводит данные.", "The process is producing output again."),
+    ("… пропущено строк{}: {} (вывод чаще {} строк/с) …", "… lines skipped{}: {} (output faster than {} lines/s) …"),
+    ("Процесс завершился (код: {}).", "Process exited (code: {})."),
+    ("Процесс не выводит данных больше {} мин - возможно, он завис.", "The process has produced no output for more than {} min - it may be hung."),
+    ("{} Процесс будет перезапущен.", "{} The process will be restarted."),
+    ("Процесс работает {} мин - достигнуто ограничение времени работы, остановка.", "The process has been running for {} min - the runtime limit is reached, stopping."),
+    ("Процесс будет остановлен через {} мин: ограничение времени работы {} мин.", "The process will be stopped in {} min: runtime limit of {} min."),
+    ("Процесс готов: {} отвечает.", "Process is ready: {} responds."),
+    ("Процесс снова отвечает на {}.", "The process responds on {} again."),
+    ("Процесс перестал отвечать на {}.", "The process stopped responding on {}."),
+    ("Процесс не ответил на {} за {} с после запуска.", "The process did not respond on {} within {} s after start."),
+    ("Процесс занял {} МБ памяти (лимит {} МБ) - перезапуск.", "The process uses {} MB of memory (limit {} MB) - restarting."),
+    ("Запрос на завершение отправлен, ожидание завершения процесса (до {} с)...", "Termination request sent, waiting for the process to exit (up to {} s)..."),
+    ("Не удалось отправить запрос на завершение ({}). Принудительное завершение (PID: {})...", "Failed to send the termination request ({}). Forcing termination (PID: {})..."),
+    ("Процесс завершился корректно.", "The process exited gracefully."),
+    ("Процесс не завершился за {} с. Принудительное завершение (PID: {})...", "The process did not exit within {} s. Forcing termination (PID: {})..."),
+    ("Команда остановки процесса отправлена.", "Stop command sent to the process."),
+    ("Ошибка отправки команды остановки: {}", "Error sending the stop command: {}"),
+    ("Команда завершения предыдущего процесса отправлена (или он уже не существовал).", "Termination command for the previous process sent (or it no longer existed)."),
+    ("Ошибка при попытке завершить предыдущий процесс: {}", "Error trying to terminate the previous process: {}"),
+    ("Запуск нового процесса после попытки очистки...", "Starting a new process after cleanup..."),
+    ("Ошибка: Не удалось получить путь/ключ для запуска после очистки.", "Error: Could not get the path/key to start after cleanup."),
+    ("Команда завершения процесса от предыдущего сеанса отправлена (или он не существовал).", "Termination command for the process from the previous session sent (or it did not exist)."),
+    ("Ошибка при попытке завершить процесс от предыдущего сеанса: {}", "Error trying to terminate the process from the previous session: {}"),
+    ("Процесс от предыдущего сеанса еще работает (PID: {}). Подключитесь к нему или завершите его.", "The process from the previous session is still running (PID: {}). Attach to it or terminate it."),
+    ("Процесс от предыдущего сеанса (PID: {}) уже завершен или PID занят другой программой.", "The process from the previous session (PID: {}) has already exited or the PID belongs to another program."),
+    ("Подключение к уже работающему процессу (PID: {}). Вывод процесса недоступен, отслеживается только его завершение.", "Attached to an already running process (PID: {}). Its output is unavailable, only its exit is tracked."),
+    ("Завершение уже работающего процесса (PID: {})...", "Terminating the already running process (PID: {})..."),
+    ("Подключенный процесс (PID: {}) завершился.", "The attached process (PID: {}) exited."),
+    ("Запуск процесса через подписку...", "Starting the process via subscription..."),
+    (" (и еще процессов: {})", " (and {} more processes)"),
+    ("Запуск отменен: TradingStar уже работает (PID: {}){}. Подключитесь к нему или завершите его - второй процесс будет конкурировать за тот же ключ API.", "Start cancelled: TradingStar is already running (PID: {}){}. Attach to it or terminate it - a second process would compete for the same API key."),
+    ("Процессы с этим исполняемым файлом не найдены.", "No processes with this executable were found."),
+    ("Найдено процессов: {}. Подтвердите завершение.", "Processes found: {}. Confirm termination."),
+    ("Завершение процессов: {}...", "Terminating processes: {}..."),
+    ("Завершение найденных процессов отменено.", "Termination of the found processes cancelled."),
+    ("Процесс (PID: {}) завершен.", "Process (PID: {}) terminated."),
+    ("Ошибка завершения процесса (PID: {}): {}", "Error terminating process (PID: {}): {}"),
+    (" (заряд {}%)", " (charge {}%)"),
+    ("Внимание: компьютер перешел на питание от батареи{}.", "Warning: the computer switched to battery power{}."),
+    ("Компьютер перешел на питание от батареи{}.", "The computer switched to battery power{}."),
+    ("Остановка процесса согласно настройкам питания.", "Stopping the process according to the power settings."),
+    ("Питание от сети восстановлено.", "Mains power restored."),
+    ("Календарь обслуживания загружен: предстоящих окон - {}.", "Maintenance calendar loaded: upcoming windows - {}."),
+    ("Ошибка загрузки календаря обслуживания: {}", "Error loading the maintenance calendar: {}"),
+    ("Планировщик: {}. Остановка процесса.", "Scheduler: {}. Stopping the process."),
+    ("Расписание: {}, процесс уже запущен.", "Schedule: {}, the process is already running."),
+    ("расписание, {}", "schedule, {}"),
+    ("Расписание: {}. Остановка процесса.", "Schedule: {}. Stopping the process."),
+    ("Расписание: {}, процесс уже остановлен.", "Schedule: {}, the process is already stopped."),
+    ("Внимание: системные часы расходятся с NTP на {} мс (допустимо {} мс). Подписи запросов к API биржи могут отклоняться.", "Warning: the system clock differs from NTP by {} ms (allowed {} ms). Exchange API request signatures may be rejected."),
+    ("Расхождение системных часов в норме ({} мс).", "System clock drift is within limits ({} ms)."),
+    ("Оповещение: {} (текущее значение {}).", "Alert: {} (current value {})."),
+    ("{} Процесс будет остановлен.", "{} The process will be stopped."),
+    ("Обслуживание логов: сжато файлов - {}, удалено по квоте - {}, размер директории - {} МБ.", "Log maintenance: files compressed - {}, removed by quota - {}, directory size - {} MB."),
+    ("Ошибка обслуживания директории логов: {}", "Log directory maintenance error: {}"),
+    ("Подключено к {}", "Connected to {}"),
+    ("Подключено", "Connected"),
+    ("Пересылка логов прервана: {}", "Log forwarding interrupted: {}"),
+    ("Окно скрыто в область уведомлений (выход - через меню значка).", "Window hidden to the notification area (exit via the tray menu)."),
+    ("Интерфейс заблокирован: закрытие окна отклонено, сначала введите PIN.", "Interface is locked: closing the window rejected, enter the PIN first."),
+    ("Получен запрос на закрытие окна...", "Window close requested..."),
+    ("Процесс (PID: {}) продолжит работу после закрытия лаунчера.", "Process (PID: {}) will keep running after the launcher closes."),
+    ("Инициирована остановка процесса (PID: {}) перед закрытием.", "Stopping the process (PID: {}) before closing."),
+    ("Процесс был запущен, но PID не найден.", "The process was started, but its PID was not found."),
+    ("Процесс не запущен. Закрытие окна.", "The process is not running. Closing the window."),
+    ("остановлен", "stopped"),
+    ("работает", "running"),
+    ("работает {} из {}", "{} of {} running"),
+    ("Не удалось определить путь к конфигурации", "Could not determine the configuration path"),
+    ("Файл настроек изменен, но в нем нет запущенного экземпляра \"{}\". Остановите его, чтобы применить новые настройки.", "The settings file changed, but it has no running instance \"{}\". Stop it to apply the new settings."),
+    ("Новая директория данных будет использована после перезапуска лаунчера.", "The new data directory will be used after the launcher restarts."),
+    ("Процесс работает со старыми параметрами запуска ({}). Перезапустите его, чтобы применить изменения.", "The process runs with the old launch parameters ({}). Restart it to apply the changes."),
+    ("Найдено \"{}\": {}", "Found \"{}\": {}"),
+    ("Интерфейс заблокирован.", "Interface locked."),
+    ("Экземпляр \"{}\": запущен: {}, PID: {}", "Instance \"{}\": running: {}, PID: {}"),
+    ("Питание: {}", "Power: {}"),
+    ("Смещение часов (мс): {}", "Clock offset (ms): {}"),
+    ("Окон обслуживания: {}", "Maintenance windows: {}"),
+    ("Директория данных: {}", "Data directory: {}"),
+    ("Пересылка логов: {}", "Log forwarding: {}"),
+    ("События жизненного цикла:", "Lifecycle events:"),
+    ("Запуск отложен: предыдущий процесс (PID: {}) еще завершается. Повторите позже.", "Start postponed: the previous process (PID: {}) is still exiting. Try again later."),
+    ("Запуск запрещен: системные часы расходятся с NTP на {} мс (допустимо {} мс). Синхронизируйте время или отключите запрет в настройках.", "Start blocked: the system clock differs from NTP by {} ms (allowed {} ms). Synchronize the time or disable the block in the settings."),
+    ("Запуск невозможен: дополнительные аргументы: {}", "Cannot start: extra arguments: {}"),
+    ("Запуск невозможен: исполняемый файл: {}", "Cannot start: executable: {}"),
+    ("Обнаружен PID предыдущего запуска: {}. Попытка завершения...", "Found the PID of the previous run: {}. Trying to terminate..."),
+    ("Ошибка: Проверьте путь и ключ API.", "Error: Check the path and the API key."),
+    ("Автозапуск пропущен: не выбран исполняемый файл.", "Autostart skipped: no executable selected."),
+    ("Автозапуск пропущен: не задан ключ API.", "Autostart skipped: no API key set."),
+    ("Автозапуск при открытии лаунчера.", "Autostart on launcher open."),
+    ("Планировщик: {}, но автоматический запуск отменен: питание от батареи с зарядом ниже {}%.", "Scheduler: {}, but the automatic start is cancelled: on battery with charge below {}%."),
+    ("Планировщик: {}. Запуск процесса.", "Scheduler: {}. Starting the process."),
+    ("Не удалось определить директорию данных", "Could not determine the data directory"),
+    ("Лог сессии не будет сохранен: {}", "The session log will not be saved: {}"),
+    ("Процесс продолжит работу после закрытия лаунчера. Вывод пишется в {}, консоль недоступна.", "The process will keep running after the launcher closes. Output is written to {}, the console is unavailable."),
+    ("Работа после закрытия лаунчера недоступна: не определена директория данных.", "Running after the launcher closes is unavailable: the data directory is not determined."),
+    ("Не удалось определить стандартную директорию данных.", "Could not determine the default data directory."),
+    ("Перенос данных в {}...", "Moving data to {}..."),
+    ("Остановка процесса с повышенными правами (PID: {}), потребуется подтверждение...", "Stopping the elevated process (PID: {}), confirmation will be required..."),
+    ("Принудительное завершение процесса (PID: {})...", "Forcing process termination (PID: {})..."),
+    ("Отправка процессу (PID: {}) команды остановки \"{}\"...", "Sending the stop command \"{1}\" to the process (PID: {0})..."),
+    ("Отправка процессу (PID: {}) запроса на корректное завершение...", "Sending a graceful termination request to the process (PID: {})..."),
+    ("Остановка процесса (PID: {})...", "Stopping the process (PID: {})..."),
+    ("Процесс не запущен или PID неизвестен.", "The process is not running or its PID is unknown."),
+    ("Поиск процессов, запущенных из {}...", "Searching for processes started from {}..."),
+    ("Процесс завершился аварийно (код: {}).", "The process crashed (code: {})."),
+    ("Процесс неожиданно завершился.", "The process exited unexpectedly."),
+    ("Из командной строки (в файл не сохраняются, пока не изменены в настройках): {}.", "From the command line (not saved to the file unless changed in settings): {}."),
+    ("Слишком много неудачных попыток. Повторите через {} с.", "Too many failed attempts. Try again in {} s."),
+    ("Неудачных попыток разблокировки подряд: {}. Следующая попытка - через {} с.", "Failed unlock attempts in a row: {}. Next attempt in {} s."),
+    // metrics.rs
+    ("Не задано имя метрики", "No metric name set"),
+    ("Не задано регулярное выражение", "No regular expression set"),
+    ("В выражении нет группы захвата для значения", "The expression has no capture group for the value"),
+    // notifications.rs
+    ("Не удалось показать системное уведомление: {}", "Failed to show the desktop notification: {}"),
+    // placeholders.rs
+    ("{api_key} - ключ API, {profile} - имя профиля запуска, {date} - дата запуска (ГГГГ-ММ-ДД), {time} - время запуска (ЧЧ-ММ-СС), {data_dir} - директория данных лаунчера", "{api_key} - API key, {profile} - launch profile name, {date} - start date (YYYY-MM-DD), {time} - start time (HH-MM-SS), {data_dir} - launcher data directory"),
+    // power.rs
+    ("Не удалось получить данные о питании: {}", "Failed to get power information: {}"),
+    ("Не удалось получить список батарей: {}", "Failed to list batteries: {}"),
+    ("Ошибка опроса состояния питания: {}", "Error polling the power state: {}"),
+    // process.rs
+    ("Файл не найден", "File not found"),
+    ("Файл недоступен: {}", "File is not accessible: {}"),
+    ("Указан не файл, а директория", "A directory was given instead of a file"),
+    ("Ожидается исполняемый файл с расширением .exe", "An executable with the .exe extension is expected"),
+    ("Нет права на выполнение (chmod +x)", "No execute permission (chmod +x)"),
+    ("Не удалось прочитать {}: {}", "Failed to read {}: {}"),
+    ("Не удалось найти исполняемый файл внутри пакета {}", "Could not find the executable inside package {}"),
+    ("Рабочая директория {} не существует", "Working directory {} does not exist"),
+    ("Для запуска с повышенными правами нужен pkexec или sudo", "Starting with elevated privileges requires pkexec or sudo"),
+    ("Не удалось создать объект задания: {}", "Failed to create a job object: {}"),
+    ("Не удалось настроить объект задания: {}", "Failed to configure the job object: {}"),
+    ("Не удалось добавить процесс в объект задания: {}", "Failed to add the process to the job object: {}"),
+    ("Не удалось прочитать атрибуты файла {}: {}", "Failed to read attributes of file {}: {}"),
+    ("AppImage {} не является исполняемым, и не удалось установить права (chmod +x): {}", "AppImage {} is not executable, and setting permissions failed (chmod +x): {}"),
+    ("Не удалось запустить {} из Flatpak: команда flatpak-spawn недоступна. Разрешите доступ к org.freedesktop.Flatpak (--talk-name=org.freedesktop.Flatpak). ({})", "Failed to start {} from Flatpak: the flatpak-spawn command is unavailable. Allow access to org.freedesktop.Flatpak (--talk-name=org.freedesktop.Flatpak). ({})"),
+    ("Исполняемый файл {} не найден ({})", "Executable {} not found ({})"),
+    ("Нет прав на запуск {}: проверьте права на выполнение (chmod +x) ({})", "No permission to run {}: check the execute permission (chmod +x) ({})"),
+    ("Файл {} не является исполняемым для этой системы (неверный формат или архитектура) ({})", "File {} is not an executable for this system (wrong format or architecture) ({})"),
+    ("Ошибка запуска процесса {}: {}", "Error starting process {}: {}"),
+    ("процесс не найден", "process not found"),
+    ("недостаточно прав", "insufficient permissions"),
+    ("у процесса нет окон, возможно только принудительное завершение", "the process has no windows, only forced termination is possible"),
+    ("остановка процесса не поддерживается на этой ОС", "stopping a process is not supported on this OS"),
+    ("ошибка ОС: {}", "OS error: {}"),
+    ("Запуск с правами администратора отменен пользователем", "Starting as administrator was cancelled by the user"),
+    ("Ошибка запуска {} с правами администратора: {}", "Error starting {} as administrator: {}"),
+    ("Не удалось получить процесс {}", "Failed to get process {}"),
+    ("Ошибка запуска процесса: {}", "Error starting the process: {}"),
+    ("[Лаунчер] Процесс запущен с правами администратора: его вывод и консоль недоступны.", "[Launcher] The process runs as administrator: its output and console are unavailable."),
+    ("[Лаунчер] Переменные окружения из настроек не передаются процессу с правами администратора.", "[Launcher] Environment variables from the settings are not passed to an elevated process."),
+    ("Не удалось создать файл вывода {}: {}", "Failed to create output file {}: {}"),
+    ("Не удалось получить PID запущенного процесса.", "Failed to get the PID of the started process."),
+    ("Ошибка ожидания процесса PID {}: {}", "Error waiting for process PID {}: {}"),
+    ("Процесс {} не запущен: лаунчер завершился", "Process {} was not started: the launcher exited"),
+    // rule_pack.rs
+    ("Ошибка сериализации набора правил: {}", "Error serializing the rule pack: {}"),
+    ("Экспорт набора правил", "Export rule pack"),
+    ("Импорт набора правил", "Import rule pack"),
+    ("Ошибка чтения файла {}: {}", "Error reading file {}: {}"),
+    ("Файл {} не является набором правил: {}", "File {} is not a rule pack: {}"),
+    ("Набор правил создан более новой версией лаунчера (формат {}, поддерживается до {})", "The rule pack was created by a newer launcher version (format {}, supported up to {})"),
+    // scheduler.rs
+    ("Не удалось загрузить календарь {}: {}", "Failed to load calendar {}: {}"),
+    ("Сервер календаря {} вернул ошибку: {}", "Calendar server {} returned an error: {}"),
+    ("Не удалось прочитать календарь {}: {}", "Failed to read calendar {}: {}"),
+    ("Ответ {} не похож на календарь iCal", "Response from {} does not look like an iCal calendar"),
+    ("Плановое обслуживание биржи{} с {} до {}", "Scheduled exchange maintenance{} from {} to {}"),
+    ("Окно обслуживания биржи завершено", "Exchange maintenance window ended"),
+    // session_log.rs
+    ("Не удалось создать директорию логов {}: {}", "Failed to create log directory {}: {}"),
+    ("Ошибка записи в файл лога {}: {}", "Error writing to log file {}: {}"),
+    ("Не удалось создать файл лога {}: {}", "Failed to create log file {}: {}"),
+    ("Ошибка фоновой задачи чтения лога: {}", "Log reading background task failed: {}"),
+    ("Не удалось открыть файл {}: {}", "Failed to open file {}: {}"),
+    ("Ошибка фоновой задачи обслуживания логов: {}", "Log maintenance background task failed: {}"),
+    ("Не удалось удалить файл лога {}: {}", "Failed to remove log file {}: {}"),
+    ("Не удалось прочитать директорию логов {}: {}", "Failed to read log directory {}: {}"),
+    ("Ошибка сжатия файла {}: {}", "Error compressing file {}: {}"),
+    ("Не удалось удалить файл {}: {}", "Failed to remove file {}: {}"),
+    // settings.rs
+    ("<ключ API скрыт>", "<API key hidden>"),
+    ("Ничего не делать", "Do nothing"),
+    ("Предупреждать", "Warn"),
+    ("Останавливать процесс", "Stop the process"),
+    ("аргументы", "arguments"),
+    ("переменные окружения", "environment variables"),
+    ("рабочая директория", "working directory"),
+    ("повышенные права", "elevated privileges"),
+    ("работа после закрытия", "running after close"),
+    ("Не задано имя переменной", "No variable name set"),
+    ("Недопустимое имя переменной {}: пробелы и знак = запрещены", "Invalid variable name {}: spaces and the = sign are not allowed"),
+    ("Значение переменной {} содержит нулевой символ", "The value of variable {} contains a null character"),
+    ("Основной", "Main"),
+    ("Экземпляр {}", "Instance {}"),
+    ("удалены пробелы и переводы строк", "spaces and line breaks removed"),
+    ("ключ был вставлен дважды - оставлена одна копия", "the key was pasted twice - one copy kept"),
+    ("Ключ API не задан", "API key is not set"),
+    ("Ключ содержит пробелы или переводы строк", "The key contains spaces or line breaks"),
+    ("Ключ содержит недопустимый символ {}", "The key contains an invalid character {}"),
+    ("Ключ слишком короткий ({} симв.)", "The key is too short ({} chars)"),
+    ("Ключ слишком длинный ({} симв.)", "The key is too long ({} chars)"),
+    ("Неверный формат времени {} (пример: %H:%M:%S%.3f)", "Invalid time format {} (example: %H:%M:%S%.3f)"),
+    ("Незакрытая кавычка или лишняя \\ в конце строки аргументов", "Unclosed quote or a trailing \\ in the arguments string"),
+    ("Ошибка сериализации настроек в TOML: {}", "Error serializing settings to TOML: {}"),
+    ("Ошибка чтения файла конфигурации {}: {}", "Error reading configuration file {}: {}"),
+    ("{}. Загружена предыдущая версия настроек из {}", "{}. The previous settings version was loaded from {}"),
+    ("Формат настроек обновлен с версии {} до {} (копия прежнего файла: {})", "Settings format upgraded from version {} to {} (copy of the previous file: {})"),
+    ("Формат настроек обновлен с версии {} до {}, копию прежнего файла сохранить не удалось: {}", "Settings format upgraded from version {} to {}, a copy of the previous file could not be saved: {}"),
+    ("Файл настроек записан более новой версией лаунчера (формат {}, поддерживается {}): незнакомые параметры не будут сохранены", "The settings file was written by a newer launcher version (format {}, supported {}): unknown parameters will not be saved"),
+    ("Файл конфигурации {} не содержит объекта настроек", "Configuration file {} does not contain a settings object"),
+    ("Ошибка парсинга файла конфигурации {}: {}", "Error parsing configuration file {}: {}"),
+    ("Файл настроек не зашифрован", "The settings file is not encrypted"),
+    ("Не удалось прочитать директорию {}: {}", "Failed to read directory {}: {}"),
+    ("Не удалось удалить {}: {}", "Failed to remove {}: {}"),
+    ("Параметр \"{}\" не прочитан ({}), используется значение по умолчанию", "Parameter \"{}\" could not be read ({}), the default value is used"),
+    ("Не удалось записать резервную копию {}: {}", "Failed to write backup {}: {}"),
+    ("Резервная копия {} не найдена", "Backup {} not found"),
+    ("Копия зашифрована другим паролем", "The backup is encrypted with a different password"),
+    ("Не удалось создать временный файл конфигурации {}: {}", "Failed to create temporary configuration file {}: {}"),
+    ("Не удалось записать в файл конфигурации {}: {}", "Failed to write configuration file {}: {}"),
+    ("Не удалось сбросить на диск файл {}: {}", "Failed to flush file {} to disk: {}"),
+    ("Не удалось заменить файл конфигурации {}: {}", "Failed to replace configuration file {}: {}"),
+    ("Не удалось открыть директорию {}: {}", "Failed to open directory {}: {}"),
+    ("Не удалось сбросить на диск директорию {}: {}", "Failed to flush directory {} to disk: {}"),
+    ("Незакрытая кавычка в строке аргументов", "Unclosed quote in the argument string"),
+    // settings_crypto.rs
+    ("Поврежден заголовок зашифрованного файла: {}", "Corrupted header of the encrypted file: {}"),
+    ("Повреждена соль зашифрованного файла: {}", "Corrupted salt of the encrypted file: {}"),
+    ("Неверная длина соли зашифрованного файла", "Invalid salt length of the encrypted file"),
+    ("Ошибка вывода ключа из пароля: {}", "Error deriving the key from the password: {}"),
+    ("Ошибка шифрования настроек", "Settings encryption error"),
+    ("Неподдерживаемый формат шифрования ({}, {})", "Unsupported encryption format ({}, {})"),
+    ("Файл зашифрован другим паролем", "The file is encrypted with a different password"),
+    ("Поврежден nonce зашифрованного файла: {}", "Corrupted nonce of the encrypted file: {}"),
+    ("Неверная длина nonce зашифрованного файла", "Invalid nonce length of the encrypted file"),
+    ("Поврежден зашифрованный текст: {}", "Corrupted ciphertext: {}"),
+    ("Неверный пароль или файл поврежден", "Wrong password or corrupted file"),
+    ("Расшифрованный текст не UTF-8: {}", "Decrypted text is not UTF-8: {}"),
+    // settings_watch.rs
+    ("у пути нет родительской директории", "the path has no parent directory"),
+    // storage.rs
+    ("Нет доступа на запись в {}: {}", "No write access to {}: {}"),
+    ("Не удалось удалить пробный файл {}: {}", "Failed to remove test file {}: {}"),
+    ("Не удалось скопировать {} в {}: {}", "Failed to copy {} to {}: {}"),
+    // theme_colors.rs
+    ("Неверный цвет {} (нужно #RRGGBB)", "Invalid color {} (expected #RRGGBB)"),
+    // trading_hours.rs
+    ("Пн", "Mon"),
+    ("запуска", "start"),
+    ("остановки", "stop"),
+    ("Неверное время {}: {} (нужно ЧЧ:ММ)", "Invalid {} time: {} (expected HH:MM)"),
+    ("Не задано ни время запуска, ни время остановки", "Neither start time nor stop time is set"),
+    ("Не выбран ни один день недели", "No weekday selected"),
+    ("начало торговых часов ({})", "trading hours start ({})"),
+    ("окончание торговых часов ({})", "trading hours end ({})"),
+    ("Вт", "Tue"),
+    ("Ср", "Wed"),
+    ("Чт", "Thu"),
+    ("Пт", "Fri"),
+    ("Сб", "Sat"),
+    ("Вс", "Sun"),
+    // tray.rs
+    ("Запустить", "Start"),
+    ("Остановить", "Stop"),
+    ("Показать окно", "Show window"),
+    ("Выход", "Quit"),
+    ("поток значка завершился", "the tray thread exited"),
+    // ui.rs
+    ("stdout и stderr", "stdout and stderr"),
+    ("только stdout", "stdout only"),
+    ("только stderr", "stderr only"),
+    ("Весь лог", "Entire log"),
+    ("Последние 5 минут", "Last 5 minutes"),
+    ("Последний час", "Last hour"),
+    ("Интервал...", "Range..."),
+    ("Процесс", "Process"),
+    ("Логи", "Logs"),
+    ("Оповещения", "Notifications"),
+    ("Дополнительно", "Advanced"),
+    ("Не удалось разобрать время \"{}\"", "Could not parse time \"{}\""),
+    ("Статистика", "Statistics"),
+    ("Настройки", "Settings"),
+    ("Остановка программы", "Stop program"),
+    ("Запуск программы", "Start program"),
+    ("Копировать лог", "Copy log"),
+    ("Экспорт лога...", "Export log..."),
+    ("Папка логов", "Logs folder"),
+    ("Очистить лог", "Clear log"),
+    ("Слишком частый вывод: пропущено строк {}", "Output too frequent: {} lines skipped"),
+    ("CPU: {}%  RAM: {} (пик {})", "CPU: {}%  RAM: {} (peak {})"),
+    ("Время работы: {}", "Uptime: {}"),
+    ("Питание от батареи (заряд {}%).", "On battery power (charge {}%)."),
+    ("Питание от батареи.", "On battery power."),
+    (" Автоматические запуски отключены: заряд ниже {}%.", " Automatic starts are disabled: charge below {}%."),
+    ("Системные часы расходятся с NTP на {} мс (допустимо {} мс)! Биржа может отклонять подписанные запросы - синхронизируйте время.", "The system clock differs from NTP by {} ms (allowed {} ms)! The exchange may reject signed requests - synchronize the time."),
+    ("Процесс не выводит данных уже {} мин - возможно, он завис.", "The process has produced no output for {} min - it may be hung."),
+    ("Ближайшее обслуживание биржи: {} - {}{}", "Next exchange maintenance: {} - {}{}"),
+    ("Открыть папку", "Open folder"),
+    ("Очистить", "Clear"),
+    ("Очистить и начать новый файл лога", "Clear and start a new log file"),
+    ("Отмена", "Cancel"),
+    ("Очистить окно лога? Уже записанный лог сессии на диске сохранится.", "Clear the log window? The session log already written to disk is kept."),
+    ("PID процесса неизвестен. Найдены процессы с тем же исполняемым файлом:", "Process PID is unknown. Processes with the same executable were found:"),
+    ("Завершить найденные процессы", "Terminate found processes"),
+    ("TradingStar уже работает (запущен в прошлом сеансе или без лаунчера):", "TradingStar is already running (started in a previous session or without the launcher):"),
+    ("После подключения вывод процесса недоступен, но его можно остановить.", "After attaching, the process output is unavailable, but it can be stopped."),
+    ("Подключиться", "Attach"),
+    ("Завершить", "Terminate"),
+    ("Показать:", "Show:"),
+    ("Уровни:", "Levels:"),
+    ("с (ЧЧ:ММ или ДД.ММ ЧЧ:ММ)", "from (HH:MM or DD.MM HH:MM)"),
+    ("по (пусто - до сейчас)", "to (empty - until now)"),
+    ("Таблица JSON", "JSON table"),
+    ("Исходный текст", "Raw text"),
+    ("Продолжить (новых строк: {})", "Resume (new lines: {})"),
+    ("Пауза", "Pause"),
+    ("К новым", "To newest"),
+    ("К старым", "To oldest"),
+    ("Копировать выделенное ({})", "Copy selection ({})"),
+    ("{} из {}", "{} of {}"),
+    ("Не найдено", "Not found"),
+    ("Поиск в логе (Enter - следующее совпадение)", "Search the log (Enter - next match)"),
+    ("Закладки ({}):", "Bookmarks ({}):"),
+    ("Удалить все", "Remove all"),
+    ("Время", "Time"),
+    ("Уровень", "Level"),
+    ("Модуль", "Module"),
+    ("Сообщение", "Message"),
+    ("Команда для процесса (Enter - отправить)", "Command for the process (Enter - send)"),
+    ("Консоль доступна, пока процесс запущен лаунчером", "The console is available while the process is started by the launcher"),
+    ("Отправить", "Send"),
+    ("Закрыть настройки", "Close settings"),
+    ("Путь не выбран", "No path selected"),
+    ("Профили запуска (исполняемый файл, ключ, аргументы и параметры процесса основного экземпляра):", "Launch profiles (executable, key, arguments and process parameters of the main instance):"),
+    ("Путь к исполняемому файлу:", "Executable path:"),
+    ("Выбрать...", "Browse..."),
+    ("Ключ API основного экземпляра (параметр -k):", "Main instance API key (-k parameter):"),
+    ("Введите ваш API ключ...", "Enter your API key..."),
+    ("Скрыть", "Hide"),
+    ("Показать", "Show"),
+    ("Переменная окружения с ключом API (пусто - ключ из поля выше):", "Environment variable with the API key (empty - key from the field above):"),
+    ("Например, TRADINGSTAR_API_KEY", "For example, TRADINGSTAR_API_KEY"),
+    ("Рабочая директория процесса:", "Process working directory:"),
+    ("Директория, из которой запущен лаунчер", "The directory the launcher was started from"),
+    ("По умолчанию", "Default"),
+    ("Возобновлять процесс, если он работал при закрытии лаунчера", "Resume the process if it was running when the launcher closed"),
+    ("Запускать TradingStar автоматически при открытии лаунчера", "Start TradingStar automatically when the launcher opens"),
+    ("Ожидание корректного завершения при остановке, с (0 - завершать сразу принудительно):", "Wait for graceful exit on stop, s (0 - force termination immediately):"),
+    ("Считать процесс зависшим, если нет вывода дольше (мин, 0 - не следить):", "Consider the process hung if there is no output for longer than (min, 0 - do not watch):"),
+    ("Перезапускать зависший процесс", "Restart a hung process"),
+    ("Пропускать вывод сверх (строк в секунду на поток, 0 - без ограничения; при следующем запуске):", "Skip output above (lines per second per stream, 0 - unlimited; on next start):"),
+    ("Кодировка вывода процесса (при следующем запуске):", "Process output encoding (on next start):"),
+    ("Останавливать процесс через (мин работы, 0 - без ограничения):", "Stop the process after (min of runtime, 0 - unlimited):"),
+    ("Запускать с повышенными правами (UAC в Windows, pkexec/sudo в Linux)", "Run with elevated privileges (UAC on Windows, pkexec/sudo on Linux)"),
+    ("Не останавливать процесс при закрытии лаунчера (вывод пишется в файл, консоль недоступна)", "Do not stop the process when the launcher closes (output goes to a file, the console is unavailable)"),
+    ("Проверка готовности: порт веб-интерфейса/API (хост:порт или http://..., пусто - не проверять):", "Readiness check: web interface/API port (host:port or http://..., empty - do not check):"),
+    ("Команда консоли для корректной остановки (пишется в stdin, пусто - сигнал завершения):", "Console command for a graceful stop (written to stdin, empty - termination signal):"),
+    ("Перезапускать процесс, если он занял больше памяти (МБ, 0 - не следить):", "Restart the process if it uses more memory than (MB, 0 - do not watch):"),
+    ("Считать процесс не отвечающим, если он не ответил после запуска за (с):", "Consider the process unresponsive if it does not respond after start within (s):"),
+    ("Дополнительные экземпляры (свой ключ API и аргументы, работают параллельно с основным):", "Additional instances (own API key and arguments, run in parallel with the main one):"),
+    ("Переменные окружения процесса (применяются при следующем запуске):", "Process environment variables (applied on next start):"),
+    ("Подстановки в аргументах и значениях переменных: {}", "Placeholders in arguments and variable values: {}"),
+    ("При переходе на питание от батареи:", "On switching to battery power:"),
+    ("Минимальный заряд батареи для автоматических запусков (%):", "Minimum battery charge for automatic starts (%):"),
+    ("Календарь обслуживания биржи (URL iCal/ICS):", "Exchange maintenance calendar (iCal/ICS URL):"),
+    ("Обновить", "Refresh"),
+    ("Загружено окон обслуживания: {}", "Maintenance windows loaded: {}"),
+    ("Останавливать процесс до начала обслуживания за (мин):", "Stop the process before maintenance begins by (min):"),
+    ("Директория данных (логи сессий, история запусков):", "Data directory (session logs, run history):"),
+    ("Стандартная директория", "Default directory"),
+    ("Хранятся в {}", "Stored in {}"),
+    ("Директория логов недоступна", "The logs directory is unavailable"),
+    ("Сжимать логи старше (дней, 0 - не сжимать):", "Compress logs older than (days, 0 - do not compress):"),
+    ("Максимальный размер директории логов (МБ, 0 - без ограничения):", "Maximum logs directory size (MB, 0 - unlimited):"),
+    ("Строк лога в окне (от {} до {}, применяется к новым строкам):", "Log lines in the window (from {} to {}, applies to new lines):"),
+    ("Размер шрифта лога (от {} до {}, Ctrl+= / Ctrl+- / Ctrl+0 на главном экране):", "Log font size (from {} to {}, Ctrl+= / Ctrl+- / Ctrl+0 on the main screen):"),
+    ("Начинать новый файл лога сессии после (МБ, 0 - без ограничения):", "Start a new session log file after (MB, 0 - unlimited):"),
+    ("Сохранять цвета (ANSI-последовательности) в файле лога сессии", "Keep colors (ANSI sequences) in the session log file"),
+    ("Сохранять цвета (ANSI-последовательности) при экспорте лога", "Keep colors (ANSI sequences) when exporting the log"),
+    ("Показывать номера строк и метки потоков ([out]/[err]) слева от лога", "Show line numbers and stream labels ([out]/[err]) left of the log"),
+    ("Показывать при запуске лаунчера конец лога предыдущего сеанса", "Show the end of the previous session log when the launcher starts"),
+    ("Окрашивать строки потока ошибок (stderr) в красный", "Color error stream (stderr) lines red"),
+    ("Показывать время получения перед каждой строкой лога", "Show the receive time before each log line"),
+    ("Формат времени строк лога (strftime, например %H:%M:%S%.3f или %d.%m %H:%M:%S):", "Log line time format (strftime, e.g. %H:%M:%S%.3f or %d.%m %H:%M:%S):"),
+    ("Пересылать вывод процесса в syslog / Vector / Fluent (TCP)", "Forward process output to syslog / Vector / Fluent (TCP)"),
+    ("Пересылка не активна", "Forwarding is not active"),
+    ("Подсветка строк лога по ключевым словам (первое совпадение сверху):", "Highlight log lines by keywords (first match from the top):"),
+    ("Метрики из вывода процесса (регулярное выражение с группой захвата):", "Metrics from process output (regular expression with a capture group):"),
+    ("Оповещения по порогам метрик:", "Metric threshold alerts:"),
+    ("Оповещения по ключевым фразам в выводе процесса (например, margin call):", "Alerts on key phrases in process output (e.g. margin call):"),
+    ("Язык интерфейса:", "Interface language:"),
+    ("NTP-сервер для проверки системных часов:", "NTP server for checking the system clock:"),
+    ("Допустимое расхождение часов (мс):", "Allowed clock drift (ms):"),
+    ("Запрещать запуск при превышении расхождения", "Block start when the drift is exceeded"),
+    ("Блокировать интерфейс после бездействия (мин, 0 - не блокировать):", "Lock the interface after inactivity (min, 0 - do not lock):"),
+    ("PIN для разблокировки:", "Unlock PIN:"),
+    ("Без PIN блокировка недоступна", "Locking is unavailable without a PIN"),
+    ("Скрывать лог, пока интерфейс заблокирован", "Hide the log while the interface is locked"),
+    ("Собрать диагностику", "Collect diagnostics"),
+    ("Архив с версией, сведениями об ОС, настройками (без ключа API) и последним логом для отчета об ошибке", "Archive with the version, OS details, settings (without the API key) and the latest log for a bug report"),
+    ("Наборы правил (подсветка, метрики, оповещения) для обмена с другими пользователями:", "Rule packs (highlighting, metrics, alerts) to share with other users:"),
+    ("Экспорт набора...", "Export pack..."),
+    ("Импорт набора...", "Import pack..."),
+    ("Восстановить предыдущие настройки (текущие тоже сохранятся в копию):", "Restore previous settings (the current ones are also backed up):"),
+    ("Закрывать окно в область уведомлений (выход - через меню значка)", "Close the window to the notification area (exit via the tray menu)"),
+    ("Сбросить все настройки...", "Reset all settings..."),
+    ("Сбросить все настройки к значениям по умолчанию? Профили, экземпляры и правила будут удалены; текущие настройки сохранятся в резервную копию.", "Reset all settings to defaults? Profiles, instances and rules will be removed; the current settings will be saved to a backup."),
+    ("Сбросить", "Reset"),
+    ("Файл настроек зашифрован паролем. Пароль запрашивается при запуске лаунчера.", "The settings file is encrypted with a password. The password is requested when the launcher starts."),
+    ("Файл настроек хранится открытым текстом. Его можно зашифровать паролем (незашифрованные копии будут удалены).", "The settings file is stored as plain text. It can be encrypted with a password (unencrypted copies will be removed)."),
+    ("Пароли не совпадают", "Passwords do not match"),
+    ("Сменить пароль", "Change password"),
+    ("Зашифровать", "Encrypt"),
+    ("Отключить шифрование", "Disable encryption"),
+    ("Новый пароль", "New password"),
+    ("Повторите пароль", "Repeat password"),
+    ("Резервных копий пока нет: они появляются при изменении настроек.", "No backups yet: they appear when settings change."),
+    ("Восстановить", "Restore"),
+    ("Копия от...", "Backup from..."),
+    ("Переменная {} задана, но ключ в ней: {}", "Variable {} is set, but the key in it: {}"),
+    ("Ключ берется из переменной {} при запуске процесса; поле ключа выше не используется.", "The key is read from variable {} when the process starts; the key field above is not used."),
+    ("Переменная окружения {} не задана: процесс не запустится без ключа.", "Environment variable {} is not set: the process will not start without a key."),
+    ("Следующее действие: {} в {}", "Next action: {} at {}"),
+    ("запуск", "start"),
+    ("остановка", "stop"),
+    ("Нет запланированных действий", "No scheduled actions"),
+    ("Расписание выключено", "Schedule is off"),
+    ("Запускать и останавливать процесс по расписанию торговых часов", "Start and stop the process on a trading hours schedule"),
+    ("Запуск (ЧЧ:ММ):", "Start (HH:MM):"),
+    ("Остановка (ЧЧ:ММ):", "Stop (HH:MM):"),
+    ("{} ГБ", "{} GB"),
+    ("{} МБ", "{} MB"),
+    ("{} д {} ч {} мин", "{} d {} h {} min"),
+    ("{} ч {} мин", "{} h {} min"),
+    ("{} мин {} с", "{} min {} s"),
+    ("Заблокировать", "Lock"),
+    ("Проверка пароля...", "Checking the password..."),
+    ("Открыть", "Open"),
+    ("Пароль", "Password"),
+    ("Интерфейс заблокирован. Процесс продолжает работать.", "The interface is locked. The process keeps running."),
+    ("Разблокировать", "Unlock"),
+    ("Исполняемый файл TradingStar: {}", "TradingStar executable: {}"),
+    ("Указан исполняемый файл TradingStar", "TradingStar executable is set"),
+    ("Выбрать файл...", "Select file..."),
+    ("Ключ API: {}", "API key: {}"),
+    ("Задан ключ API", "API key is set"),
+    ("Открыть настройки", "Open settings"),
+    ("Директория данных доступна для записи", "The data directory is writable"),
+    ("Выбрать директорию...", "Select directory..."),
+    ("Перед запуском:", "Before starting:"),
+    ("Изменены настройки запуска ({}) - перезапустите процесс, чтобы применить их.", "Launch settings changed ({}) - restart the process to apply them."),
+    ("Перезапустить сейчас", "Restart now"),
+    ("За {} дней: время работы {}% ({} ч {} мин), запусков - {}, сбоев - {}, плановых остановок - {}", "Last {} days: uptime {}% ({} h {} min), starts - {}, crashes - {}, scheduled stops - {}"),
+    ("Процесс работал", "Process was running"),
+    ("Всего в истории: {} запусков - завершились сами: {}, сбоев: {}, остановлено: {}, плановых остановок: {}", "Total in history: {} runs - exited on their own: {}, crashed: {}, stopped: {}, scheduled stops: {}"),
+    ("{} - {}  {} ч {} мин  код {}  {}", "{} - {}  {} h {} min  code {}  {}"),
+    ("Запуски", "Runs"),
+    ("Закрыть статистику", "Close statistics"),
+    ("Экспорт CSV", "Export CSV"),
+    ("Экспорт JSON", "Export JSON"),
+    ("Удалить", "Remove"),
+    ("Ключ API экземпляра", "Instance API key"),
+    ("Дополнительные аргументы", "Extra arguments"),
+    ("Адрес проверки готовности (хост:порт или URL)", "Readiness check address (host:port or URL)"),
+    ("Команда остановки (например, quit)", "Stop command (e.g. quit)"),
+    ("Лимит памяти для перезапуска, МБ (0 - не следить)", "Memory limit for restart, MB (0 - do not watch)"),
+    ("Запускать с повышенными правами", "Run with elevated privileges"),
+    ("Не останавливать при закрытии лаунчера", "Do not stop when the launcher closes"),
+    ("Добавить экземпляр", "Add instance"),
+    ("Добавить переменную", "Add variable"),
+    ("Имя", "Name"),
+    ("Добавить метрику", "Add metric"),
+    ("Регулярное выражение (например, order \\d+ (filled|rejected))", "Regular expression (e.g. order \\d+ (filled|rejected))"),
+    ("Ключевое слово (например, timeout)", "Keyword (e.g. timeout)"),
+    ("Фон", "Background"),
+    ("Жирный", "Bold"),
+    ("Добавить правило подсветки", "Add highlight rule"),
+    ("Метка: {}", "Label: {}"),
+    ("Без метки", "No label"),
+    ("Без профиля", "No profile"),
+    ("Профиль: {}", "Profile: {}"),
+    ("Профиль запуска", "Launch profile"),
+    ("выбран", "selected"),
+    ("Название профиля", "Profile name"),
+    ("Дополнение к заголовку окна (например, LIVE BTC)", "Window title suffix (e.g. LIVE BTC)"),
+    ("Сохранить текущие параметры как новый профиль", "Save the current parameters as a new profile"),
+    ("Ключевая фраза (например, connection lost)", "Key phrase (e.g. connection lost)"),
+    ("Баннер", "Banner"),
+    ("Звук", "Sound"),
+    ("Уведомление ОС", "OS notification"),
+    ("Добавить оповещение", "Add alert"),
+    ("Если", "If"),
+    ("метрика", "metric"),
+    ("в течение (мин):", "for (min):"),
+    ("уведомить и остановить процесс", "notify and stop the process"),
+    ("Цвета интерфейса:", "Interface colors:"),
+    ("Акцент (панель и кнопки)", "Accent (top bar and buttons)"),
+    ("Кнопка запуска", "Start button"),
+    ("Кнопка остановки", "Stop button"),
+    ("Цвета по умолчанию", "Default colors"),
+    ("Лог: {}", "Log: {}"),
+    ("Лог сессии не сохраняется", "Session log is not saved"),
+    ("Системные уведомления (видны, даже когда окно свернуто):", "Desktop notifications (visible even when the window is minimized):"),
+    ("При сбое процесса", "When the process crashes"),
+    ("При завершении процесса без команды остановки", "When the process exits without a stop command"),
+    ("Заданы в командной строке: {}. Пока значение не изменено здесь, в файле настроек остается прежнее.", "Set on the command line: {}. Until the value is changed here, the settings file keeps its previous value."),
+    ("Дополнительные аргументы командной строки (после -k, аргумент с пробелами - в кавычках):", "Extra command-line arguments (after -k, quote arguments that contain spaces):"),
+    // workspace.rs
+    ("Не удалось определить путь к рабочему пространству", "Could not determine the workspace path"),
+    ("Ошибка чтения рабочего пространства {}: {}", "Error reading workspace {}: {}"),
+    ("Ошибка парсинга рабочего пространства {}: {}", "Error parsing workspace {}: {}"),
+    ("Ошибка сериализации рабочего пространства: {}", "Error serializing the workspace: {}"),
+    ("Не удалось записать рабочее пространство {}: {}", "Failed to write workspace {}: {}"),
+];
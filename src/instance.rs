@@ -0,0 +1,97 @@
+use crate::alerts::AlertState;
+use crate::health::HealthState;
+use crate::metrics::MetricValue;
+use crate::process::{ProcessCandidate, ProcessUsage};
+use crate::session_log::SessionLog;
+use crate::settings::LaunchSettings;
+use crate::ui::LogLine;
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::Instant;
+use tokio::sync::mpsc::Sender;
+
+// --- Экземпляр TradingStar: состояние одного управляемого процесса ---
+// Лаунчер запускает несколько экземпляров параллельно (например, с разными ключами API).
+// Экземпляр с индексом 0 - основной, остальные соответствуют settings.instances по порядку.
+
+pub struct Instance {
+    pub id: u64,          // Постоянный идентификатор (для результатов асинхронных команд)
+    pub is_running: bool, // Запущен ли процесс экземпляра?
+    pub logs: VecDeque<LogLine>, // Очередь логов экземпляра
+    pub session_log: Option<SessionLog>, // Файл полного лога текущей сессии
+    pub subscription_id: Option<u64>, // ID активной подписки ProcessListener
+    pub actual_pid: Option<u32>, // PID запущенного процесса
+    pub stopping_pid: Option<u32>, // Процесс, который сейчас корректно завершается
+    pub orphan_process: Option<ProcessCandidate>, // Процесс прошлого сеанса, ожидающий решения
+    pub attached: bool,   // Процесс не запущен лаунчером, а подключен по PID прошлого сеанса
+    pub elevated: bool,   // Процесс запущен с повышенными правами (остановка тоже требует их)
+    pub detached_output: Option<PathBuf>, // Процесс запущен отсоединенным, его вывод пишется в этот файл
+    pub resume_pending: bool,             // Возобновить процесс после проверки PID прошлого сеанса
+    pub run_started: Option<DateTime<Local>>, // Время начала текущего запуска
+    pub runtime_warned: bool, // Предупреждение о скорой остановке по лимиту времени уже показано
+    pub running_launch: Option<LaunchSettings>, // Параметры, с которыми запущен текущий процесс
+    pub restart_pending: bool, // Ждем остановки процесса, чтобы запустить его снова
+    pub maintenance_restart_pending: bool, // Остановлен на время обслуживания и ждет перезапуска
+    pub metrics: HashMap<String, MetricValue>, // Последние значения метрик по имени
+    pub alert_states: Vec<AlertState>, // Состояние правил оповещений (по индексу правила)
+    pub keyword_alerts_fired: HashMap<String, Instant>, // Последнее срабатывание оповещений по ключевым фразам
+    pub process_usage: Option<ProcessUsage>,            // Последние загрузка CPU и память процесса
+    pub peak_rss_bytes: u64, // Максимальная память процесса за текущий запуск
+    pub process_stdin: Option<Sender<String>>, // Очередь строк в stdin процесса
+    pub last_output_at: Option<Instant>, // Когда процесс последний раз что-то вывел
+    pub line_count: u64, // Сколько строк добавлено в лог с начала файла лога сессии (номер последней)
+    pub progress_line: Option<u64>, // Строка лога, которую заменяет следующий фрагмент после \r
+    pub process_hung: bool, // Процесс молчит дольше порога сторожевого таймера
+    pub dropped_lines: u64, // Строк вывода пропущено из-за ограничения частоты за текущий запуск
+    pub output_flood_at: Option<Instant>, // Когда последний раз пропускались строки вывода
+    pub health: HealthState, // Результат проверок готовности
+    pub health_failures: u32, // Неудачных проверок готовности подряд
+    pub console_input: String, // Вводимая команда консоли
+    pub last_crashed: bool, // Последний запуск закончился сбоем (для строки состояния)
+}
+
+impl Instance {
+    pub fn new(id: u64) -> Self {
+        Instance {
+            id,
+            is_running: false,
+            logs: VecDeque::new(),
+            session_log: None,
+            subscription_id: None,
+            actual_pid: None,
+            stopping_pid: None,
+            orphan_process: None,
+            attached: false,
+            elevated: false,
+            detached_output: None,
+            resume_pending: false,
+            run_started: None,
+            runtime_warned: false,
+            running_launch: None,
+            restart_pending: false,
+            maintenance_restart_pending: false,
+            metrics: HashMap::new(),
+            alert_states: Vec::new(),
+            keyword_alerts_fired: HashMap::new(),
+            process_usage: None,
+            peak_rss_bytes: 0,
+            process_stdin: None,
+            last_output_at: None,
+            line_count: 0,
+            progress_line: None,
+            process_hung: false,
+            dropped_lines: 0,
+            output_flood_at: None,
+            health: HealthState::Starting,
+            health_failures: 0,
+            console_input: String::new(),
+            last_crashed: false,
+        }
+    }
+
+    // Процесс работает или еще корректно завершается
+    pub fn is_busy(&self) -> bool {
+        self.is_running || self.stopping_pid.is_some()
+    }
+}
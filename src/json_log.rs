@@ -0,0 +1,67 @@
+use serde_json::{Map, Value};
+
+// --- Структурированный лог (JSON) ---
+// TradingStar может выводить строки лога как JSON-объекты. Такие строки распознаются,
+// и в режиме таблицы показываются колонками (время, уровень, модуль, сообщение),
+// а исходный объект раскрывается по запросу.
+
+// Имена полей, из которых берутся колонки (первое найденное)
+const TIME_KEYS: &[&str] = &["time", "timestamp", "ts", "@timestamp", "datetime"];
+const LEVEL_KEYS: &[&str] = &["level", "lvl", "severity", "loglevel"];
+const MODULE_KEYS: &[&str] = &["module", "target", "logger", "name", "component"];
+const MESSAGE_KEYS: &[&str] = &["message", "msg", "text"];
+
+// Колонки строки JSON-лога
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonRecord {
+    pub time: Option<String>,   // Время из записи
+    pub level: Option<String>,  // Уровень (как записан)
+    pub module: Option<String>, // Модуль или источник записи
+    pub message: String,        // Сообщение (пусто, если его нет)
+}
+
+// Разбор строки как JSON-объекта (None - строка не является объектом JSON)
+pub fn parse_json_line(line: &str) -> Option<JsonRecord> {
+    let object = parse_object(line)?;
+    // Сообщение может быть вложено в "fields" (формат tracing-subscriber)
+    let message = field(&object, MESSAGE_KEYS)
+        .or_else(|| {
+            object
+                .get("fields")
+                .and_then(Value::as_object)
+                .and_then(|fields| field(fields, MESSAGE_KEYS))
+        })
+        .unwrap_or_default();
+    Some(JsonRecord {
+        time: field(&object, TIME_KEYS),
+        level: field(&object, LEVEL_KEYS),
+        module: field(&object, MODULE_KEYS),
+        message,
+    })
+}
+
+// Исходный объект строки с отступами (для раскрытой строки таблицы)
+pub fn pretty_payload(line: &str) -> Option<String> {
+    parse_object(line).and_then(|object| serde_json::to_string_pretty(&object).ok())
+}
+
+fn parse_object(line: &str) -> Option<Map<String, Value>> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    match serde_json::from_str(line) {
+        Ok(Value::Object(object)) => Some(object),
+        _ => None,
+    }
+}
+
+// Значение первого найденного поля в виде текста
+fn field(object: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| object.get(*key))
+        .map(|value| match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        })
+}
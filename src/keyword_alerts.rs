@@ -0,0 +1,108 @@
+use crate::i18n::trf;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// --- Оповещения по ключевым фразам в выводе процесса ---
+// Строка вывода, содержащая фразу правила (без учета регистра, например "margin call"),
+// вызывает баннер на главном экране, звуковой сигнал и/или системное уведомление
+// (notifications::show_desktop_notification).
+
+// Повторное срабатывание одного правила для экземпляра - не чаще, чем раз в минуту,
+// чтобы поток одинаковых строк не превращался в поток уведомлений
+pub const KEYWORD_ALERT_COOLDOWN: Duration = Duration::from_secs(60);
+
+// Правило оповещения по ключевой фразе
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)] // Отсутствующие поля берутся из Default
+pub struct KeywordAlertRule {
+    pub keyword: String, // Ключевая фраза
+    pub banner: bool,    // Показывать баннер на главном экране
+    pub sound: bool,     // Подавать звуковой сигнал
+    pub desktop: bool,   // Показывать системное уведомление
+}
+
+impl Default for KeywordAlertRule {
+    fn default() -> Self {
+        KeywordAlertRule {
+            keyword: String::new(),
+            banner: true,
+            sound: false,
+            desktop: false,
+        }
+    }
+}
+
+impl KeywordAlertRule {
+    // Ключ правила для учета повторных срабатываний (фраза в нижнем регистре)
+    pub fn key(&self) -> String {
+        self.keyword.trim().to_lowercase()
+    }
+}
+
+// Правила, фраза которых встречается в строке (пустые фразы пропускаются)
+pub fn matching_keyword_alerts<'a>(
+    rules: &'a [KeywordAlertRule],
+    line: &str,
+) -> Vec<&'a KeywordAlertRule> {
+    let line = line.to_lowercase();
+    rules
+        .iter()
+        .filter(|rule| !rule.key().is_empty() && line.contains(&rule.key()))
+        .collect()
+}
+
+// Звуковой сигнал оповещения
+pub async fn play_alert_sound() -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        use crate::i18n::tr;
+        use windows_sys::Win32::System::Diagnostics::Debug::MessageBeep;
+        use windows_sys::Win32::UI::WindowsAndMessaging::MB_ICONEXCLAMATION;
+        // SAFETY: MessageBeep не принимает указателей и только ставит звук в очередь
+        let played = tokio::task::spawn_blocking(|| unsafe { MessageBeep(MB_ICONEXCLAMATION) })
+            .await
+            .map_err(|e| trf!("Ошибка звукового сигнала: {}", e))?;
+        if played == 0 {
+            return Err(tr("Не удалось подать звуковой сигнал").to_string());
+        }
+        Ok(())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        run_helper("afplay", &["/System/Library/Sounds/Glass.aiff"]).await
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        // Звук из темы рабочего стола, если нет libcanberra - файл темы freedesktop
+        match run_helper("canberra-gtk-play", &["--id=dialog-warning"]).await {
+            Ok(()) => Ok(()),
+            Err(_) => {
+                run_helper(
+                    "paplay",
+                    &["/usr/share/sounds/freedesktop/stereo/dialog-warning.oga"],
+                )
+                .await
+            }
+        }
+    }
+}
+
+// Запуск вспомогательной программы и ожидание ее завершения
+#[cfg(unix)]
+async fn run_helper(program: &str, args: &[&str]) -> Result<(), String> {
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    let status = command
+        .status()
+        .await
+        .map_err(|e| trf!("Не удалось запустить {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(trf!("{} завершился с ошибкой ({})", program, status))
+    }
+}
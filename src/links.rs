@@ -0,0 +1,89 @@
+use crate::i18n::trf;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+// --- Ссылки в выводе процесса ---
+// Адреса http(s) в тексте и гиперссылки OSC 8 (ESC ]8;;URL ESC \ текст ESC ]8;; ESC \)
+// отображаются в логе кликабельными и открываются в браузере по умолчанию.
+
+// Символы, которыми обычно заканчивается предложение, а не адрес
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '\'', '"'];
+
+// Диапазоны (в байтах) адресов http(s) в тексте
+pub fn find_urls(text: &str) -> Vec<Range<usize>> {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    let url = URL.get_or_init(|| Regex::new(r#"https?://[^\s<>"'`]+"#).expect("верный шаблон"));
+    url.find_iter(text)
+        .filter_map(|found| {
+            let trimmed = found.as_str().trim_end_matches(TRAILING_PUNCTUATION);
+            // Адрес без хоста ("http://") ссылкой не считаем
+            (trimmed.len() > trimmed.find("://")? + 3)
+                .then(|| found.start()..found.start() + trimmed.len())
+        })
+        .collect()
+}
+
+// Разбиение строки по гиперссылкам OSC 8: части текста и адрес ссылки (None - вне ссылки).
+// Прочие последовательности OSC (например, заголовок окна) удаляются вместе с содержимым,
+// незавершенная последовательность - до конца строки.
+pub fn split_hyperlinks(line: &str) -> Vec<(&str, Option<String>)> {
+    let mut parts = Vec::new();
+    let mut link: Option<String> = None;
+    let mut rest = line;
+    while let Some(start) = rest.find("\u{1b}]") {
+        let body = &rest[start + 2..];
+        // Последовательность заканчивается BEL или ST (ESC \)
+        let Some((end, terminator)) = [
+            body.find('\u{7}').map(|i| (i, 1)),
+            body.find("\u{1b}\\").map(|i| (i, 2)),
+        ]
+        .into_iter()
+        .flatten()
+        .min_by_key(|(i, _)| *i) else {
+            // Незавершенная последовательность - отбрасываем остаток строки
+            if start > 0 {
+                parts.push((&rest[..start], link.clone()));
+            }
+            return parts;
+        };
+        if start > 0 {
+            parts.push((&rest[..start], link.clone()));
+        }
+        // OSC 8: "8;параметры;адрес" (пустой адрес закрывает ссылку)
+        if let Some(target) = body[..end].strip_prefix("8;") {
+            link = target
+                .split_once(';')
+                .map(|(_, url)| url)
+                .filter(|url| !url.is_empty())
+                .map(str::to_string);
+        }
+        rest = &body[end + terminator..];
+    }
+    if !rest.is_empty() {
+        parts.push((rest, link));
+    }
+    parts
+}
+
+// Открытие адреса в браузере по умолчанию
+pub async fn open_url(url: String) -> Result<(), String> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(trf!(
+            "Ссылка {} не открыта: поддерживаются только http(s)",
+            format!("{:?}", url)
+        ));
+    }
+    let opener = if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    tokio::process::Command::new(opener)
+        .arg(&url)
+        .spawn()
+        .map(drop)
+        .map_err(|e| trf!("Не удалось открыть ссылку {}: {}", url, e))
+}
@@ -1,37 +1,215 @@
 #![windows_subsystem = "windows"]
+mod alerts;
+mod chart;
+mod cli;
+mod clock;
+mod crash_report;
+mod diagnostics;
+mod encoding;
+mod escapes;
+mod events;
+mod forwarder;
+mod health;
+mod highlight;
+mod history;
+mod i18n;
+mod instance;
+mod json_log;
+mod keyword_alerts;
+mod links;
+mod metrics;
+mod modal;
+mod notifications;
+mod placeholders;
+mod power;
 mod process;
+mod rule_pack;
+mod scheduler;
+mod session_log;
 mod settings;
+mod settings_crypto;
+mod settings_watch;
+mod storage;
+mod theme_colors;
+mod trading_hours;
+mod tray;
 mod ui;
+mod workspace;
 
 // Импортируем необходимые элементы из стандартной библиотеки и внешних крейтов
+use clap::Parser; // Разбор параметров командной строки
 use iced::executor;
-use iced::widget::container;
+use iced::widget::{column, container, scrollable};
 use iced::{
     clipboard, event,
     window::{self, icon},
     Application, Command, Element, Event, Length, Settings, Subscription, Theme,
 };
-use image;
 use rfd::AsyncFileDialog; // Для диалога выбора файла
-use std::{collections::VecDeque, path::PathBuf}; // Для очереди логов и путей // Добавляем image
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    time::{Duration, Instant},
+}; // Для очереди логов и путей // Добавляем image
 
 // Импортируем элементы из наших модулей
-use process::{kill_process, ProcessListener}; // Функции и типы для работы с процессом
-use settings::{get_config_path, load_settings, save_settings, AppSettings}; // Функции и типы для настроек
-use ui::{AnsiSegment, MAX_LOG_LINES}; // Функции, типы и константы UI
+use alerts::{evaluate_alerts, AlertRule, Comparison}; // Оповещения по порогам метрик
+use cli::CliArgs; // Параметры командной строки
+use clock::measure_clock_offset; // Контроль расхождения системных часов
+use crash_report::{
+    crash_reports_dir, open_folder, save_crash_report, CrashReport, CRASH_REPORT_LINES,
+}; // Отчеты о сбоях процесса
+use diagnostics::{save_diagnostics_bundle, DiagnosticsInput}; // Пакет диагностики
+use encoding::OutputEncoding; // Кодировка вывода процесса
+use events::{EventKind, LifecycleEvent, MAX_EVENTS}; // События жизненного цикла
+use forwarder::{
+    detect_level, forwarder_subscription, ForwardConfig, ForwardFormat, ForwardRecord,
+    ForwarderEvent,
+}; // Пересылка логов во внешнюю систему
+use health::{health_subscription, HealthState}; // Проверка готовности процесса
+use highlight::{HighlightColor, HighlightRule, Highlighter}; // Подсветка строк лога
+use history::{
+    export_history, load_history, save_history, ExportFormat, RunOutcome, RunRecord,
+    MAX_HISTORY_RECORDS,
+}; // История запусков
+use i18n::{tr, trf, Language}; // Перевод строк интерфейса
+use instance::Instance; // Состояние управляемого экземпляра TradingStar
+use keyword_alerts::{
+    matching_keyword_alerts, play_alert_sound, KeywordAlertRule, KEYWORD_ALERT_COOLDOWN,
+}; // Оповещения по ключевым фразам в выводе
+use metrics::{MetricExtractor, MetricRule, MetricValue}; // Пользовательские метрики
+use modal::Modal; // Окно поверх главного экрана
+use notifications::{
+    show_desktop_notification, NotificationAction, NotificationCenter, NotificationLevel,
+}; // Уведомления пользователя
+use placeholders::Placeholders; // Подстановки в аргументах и окружении процесса
+use power::{read_power_state, PowerState}; // Состояние электропитания
+use process::{
+    exit_watch_subscription, find_orphan_process, find_processes_by_path, kill_process,
+    kill_processes, resolve_executable, run_stop_helper, stop_elevated, stop_process_gracefully,
+    terminate_process, usage_subscription, validate_executable, wait_for_exit, KillError,
+    ProcessCandidate, ProcessListener, ProcessOptions, ProcessUsage,
+}; // Функции и типы для работы с процессом
+use rule_pack::{export_rule_pack, import_rule_pack, RulePack, RULE_PACK_VERSION}; // Обмен наборами правил
+use scheduler::{
+    fetch_maintenance_windows, maintenance_action, next_maintenance_window, MaintenanceWindow,
+    ScheduledAction,
+}; // Планировщик (окна обслуживания биржи)
+use session_log::{
+    maintain_logs_dir, read_previous_session, LogMaintenanceReport, PreviousSessionLog, SessionLog,
+}; // Логи сессий
+use settings::{
+    backup_settings, get_config_path, list_settings_backups, load_settings, mask_api_keys,
+    read_data_dir_setting, remove_plaintext_copies, restore_settings_backup, sanitize_api_key,
+    save_settings, serialize_settings, split_args, unlock_settings, validate_api_key, AppSettings,
+    BatteryAction, EnvVar, InstanceSettings, LoadedSettings, SettingsBackup, DEFAULT_LOG_FONT_SIZE,
+    LOG_FONT_SIZE_MAX, LOG_FONT_SIZE_MIN,
+}; // Функции и типы для настроек
+use settings_crypto::{derive_cipher, SettingsCipher}; // Шифрование файла настроек
+use settings_watch::settings_watch_subscription; // Отслеживание изменений файла настроек
+use storage::{history_path, logs_dir, migrate_data_dir, resolve_data_dir, validate_data_dir}; // Директория данных
+use theme_colors::ThemeColors; // Цвета интерфейса
+use trading_hours::trading_hours_subscription; // Расписание торговых часов
+use tray::{tray_subscription, TrayAction, TrayEvent, TrayHandle, TrayState}; // Значок в области уведомлений
+use ui::{LogLevel, LogSource, LogStreamFilter, LogTimeFilter, SettingsSection}; // Функции, типы и константы UI
+use workspace::{load_workspace, save_workspace, workspace_path, WindowGeometry, Workspace}; // Рабочее пространство
+
+// За сколько до остановки по ограничению времени работы предупреждать пользователя
+const RUNTIME_LIMIT_WARNING: Duration = Duration::from_secs(5 * 60);
+// Сколько показывать предупреждение о слишком частом выводе после последнего пропуска строк
+const OUTPUT_FLOOD_WARNING: Duration = Duration::from_secs(10);
+// Сколько последних сохранений настроек помнить, чтобы не принимать их за внешние изменения
+const RECENT_SETTINGS_SAVES: usize = 8;
+// Как часто при сохранении настроек делать резервную копию прежнего файла
+const SETTINGS_BACKUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+// Неудачных попыток разблокировки без задержки; дальше задержка удваивается до предела
+const UNLOCK_FREE_ATTEMPTS: u32 = 3;
+const UNLOCK_BASE_DELAY: Duration = Duration::from_secs(5);
+const UNLOCK_MAX_DELAY: Duration = Duration::from_secs(5 * 60);
 
 // --- Состояние приложения ---
 // Основная структура, хранящая все состояние лаунчера
 pub struct Launcher {
-    settings: AppSettings,            // Текущие настройки (путь, ключ API)
-    is_running: bool,                 // Запущен ли дочерний процесс?
-    logs: VecDeque<Vec<AnsiSegment>>, // Очередь логов (каждая строка - вектор сегментов)
-    show_settings: bool,              // Показывать ли экран настроек?
-    config_path: Option<PathBuf>,     // Путь к файлу конфигурации
-    subscription_id_counter: u64,     // Счетчик для генерации ID подписок на процесс
-    subscription_id: Option<u64>,     // Текущий ID активной подписки на процесс
-    actual_pid: Option<u32>,          // PID запущенного дочернего процесса
-    close_requested: bool,            // Был ли запрошен выход из приложения?
+    settings: AppSettings,                 // Текущие настройки (путь, ключ API)
+    instances: Vec<Instance>,              // Управляемые экземпляры (0 - основной)
+    selected_instance: usize,              // Экземпляр, показанный на главном экране
+    next_instance_id: u64,                 // Счетчик постоянных идентификаторов экземпляров
+    log_filter: LogTimeFilter,             // Фильтр отображаемого лога по времени
+    log_range_from: String,                // Начало произвольного интервала фильтра
+    log_range_to: String,                  // Конец произвольного интервала фильтра
+    screen: Screen,                        // Текущий экран (главный, настройки, статистика)
+    settings_section: SettingsSection,     // Раздел, открытый на экране настроек
+    config_path: Option<PathBuf>,          // Путь к файлу конфигурации
+    cli: CliArgs, // Параметры командной строки (поверх настроек, только в этом сеансе)
+    file_settings: AppSettings, // Настройки в том виде, в каком они в файле (без параметров командной строки)
+    recent_saves: VecDeque<String>, // Содержимое последних сохранений файла конфигурации
+    last_settings_backup: Option<Instant>, // Когда делалась резервная копия настроек (None - в этом сеансе не делалась)
+    settings_backups: Vec<SettingsBackup>, // Резервные копии настроек (от новых к старым)
+    selected_settings_backup: Option<SettingsBackup>, // Копия, выбранная для восстановления
+    subscription_id_counter: u64,          // Счетчик для генерации ID подписок на процесс
+    close_requested: bool,                 // Был ли запрошен выход из приложения?
+    tray: Option<TrayHandle>, // Значок в области уведомлений (None - еще не создан или недоступен)
+    tray_state: Option<TrayState>, // Состояние, которое показывает значок
+    kill_candidates: Option<Vec<ProcessCandidate>>, // Найденные по пути процессы, ожидающие подтверждения завершения
+    power_state: Option<PowerState>, // Последнее известное состояние питания (батарея/сеть)
+    maintenance_windows: Vec<MaintenanceWindow>, // Окна обслуживания биржи из календаря
+    clock_offset_ms: Option<f64>, // Последнее измеренное смещение системных часов относительно NTP
+    metric_extractor: MetricExtractor, // Скомпилированные правила извлечения метрик
+    metric_rule_errors: Vec<Option<String>>, // Ошибки компиляции правил метрик (по индексу)
+    highlighter: Highlighter,     // Скомпилированные правила подсветки строк лога
+    masked_keys: Vec<String>,     // Ключи API, скрываемые в выводе процессов
+    highlight_rule_errors: Vec<Option<String>>, // Ошибки компиляции правил подсветки (по индексу)
+    notifications: NotificationCenter, // Активные уведомления пользователя
+    data_dir: Option<PathBuf>,    // Действующая директория данных (логи, история)
+    run_history: Vec<RunRecord>,  // Завершенные запуски (от старых к новым)
+    log_forwarder: Option<tokio::sync::mpsc::Sender<ForwardRecord>>, // Очередь пересылки логов
+    log_forward_status: Option<String>, // Состояние соединения пересылки (для настроек)
+    next_log_id: u64,             // Идентификатор следующей строки лога
+    events: Vec<LifecycleEvent>,  // События жизненного цикла (для хронологии)
+    highlighted_log_line: Option<u64>, // Строка лога, к которой перешли из хронологии или поиска
+    log_search: String,           // Искомый в логе текст
+    log_search_position: usize,   // Номер текущего совпадения поиска (от новых строк к старым)
+    hidden_log_levels: Vec<LogLevel>, // Скрытые уровни строк лога
+    log_stream: LogStreamFilter,  // Показываемые потоки вывода (stdout/stderr)
+    log_json_view: bool,          // Строки JSON показываются таблицей
+    log_raw_view: bool,           // Рядом со строками лога показывается их исходный текст
+    api_key_revealed: bool,       // Ключ API в настройках показан открытым текстом
+    window_badge: Option<HighlightColor>, // Цвет метки на текущем значке окна
+    expanded_log_lines: Vec<u64>, // Строки JSON с раскрытым исходным объектом
+    log_frozen_at: Option<u64>,   // Лог на паузе: показываются строки не новее этой (None - следим)
+    log_follow: bool, // Снимать паузу при возврате к новым строкам (выключается кнопкой)
+    log_selection: Option<(u64, u64)>, // Выделенные строки лога: первая выбранная и последняя (по ID)
+    clear_log_pending: bool,           // Очистка лога выбранного экземпляра ожидает подтверждения
+    reset_settings_pending: bool,      // Сброс настроек по умолчанию ожидает подтверждения
+    log_bookmarks: Vec<u64>,           // Строки лога с закладками (ID, по возрастанию)
+    keyboard_modifiers: iced::keyboard::Modifiers, // Нажатые клавиши-модификаторы
+    window_geometry: Option<WindowGeometry>, // Текущие размер и положение окна
+    saved_workspace: Option<Workspace>, // Последнее сохраненное рабочее пространство (None - еще не загружено)
+    data_dir_error: Option<String>,     // Почему в директорию данных нельзя писать (None - можно)
+    executable_problem: Option<String>, // Почему исполняемый файл нельзя запустить (None - можно)
+    last_activity: Instant,             // Последнее действие пользователя (мышь, клавиатура)
+    ui_locked: bool,                    // Интерфейс заблокирован до ввода PIN
+    unlock_pin_input: String,           // Введенный PIN на экране блокировки
+    unlock_error: Option<String>,       // Ошибка разблокировки (неверный PIN)
+    unlock_failures: u32,               // Неудачных попыток разблокировки подряд
+    unlock_retry_at: Option<Instant>,   // До этого момента попытки разблокировки не принимаются
+    settings_cipher: Option<SettingsCipher>, // Ключ шифрования файла настроек (None - файл не зашифрован)
+    settings_locked: bool,                   // Файл настроек зашифрован и еще не открыт паролем
+    settings_password_input: String,         // Пароль, введенный для открытия настроек
+    settings_password_error: Option<String>, // Ошибка открытия настроек (неверный пароль)
+    new_settings_password: String,           // Новый пароль шифрования настроек
+    new_settings_password_confirm: String,   // Повтор нового пароля
+    settings_password_pending: bool,         // Идет вывод ключа из пароля
+}
+
+// Экраны приложения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Screen {
+    #[default]
+    Main, // Главный экран (управление и логи)
+    Settings, // Настройки
+    Stats,    // Статистика и история запусков
 }
 
 // --- Сообщения для обновления состояния ---
@@ -39,31 +217,278 @@ pub struct Launcher {
 #[derive(Debug, Clone)]
 pub enum Message {
     // UI События
-    SettingsButtonPressed, // Нажата кнопка "Настройки"
-    StartButtonPressed,    // Нажата кнопка "Запуск"
-    StopButtonPressed,     // Нажата кнопка "Остановка"
-    SelectExecutablePath,  // Нажата кнопка выбора пути
-    ApiKeyChanged(String), // Изменился текст в поле API ключа
-    CloseSettingsPressed,  // Нажата кнопка "Закрыть настройки"
-    CopyLogsPressed,       // Нажата кнопка копирования логов
+    SettingsButtonPressed,                     // Нажата кнопка "Настройки"
+    SettingsSectionSelected(SettingsSection), // Выбран раздел настроек (вне настроек - открывает их)
+    StartButtonPressed,                       // Нажата кнопка "Запуск" (выбранный экземпляр)
+    StopButtonPressed,                        // Нажата кнопка "Остановка" (выбранный экземпляр)
+    SelectInstance(usize),                    // Выбрана вкладка экземпляра
+    AddInstance,                              // Добавить экземпляр
+    RemoveInstance(usize), // Удалить дополнительный экземпляр (индекс в settings.instances)
+    InstanceNameChanged(usize, String), // Изменено название дополнительного экземпляра
+    InstanceApiKeyChanged(usize, String), // Изменен ключ API дополнительного экземпляра
+    InstanceApiKeyPasted(usize, String), // Вставлен ключ API дополнительного экземпляра (значение поля после вставки)
+    InstanceArgsChanged(usize, String),  // Изменены аргументы дополнительного экземпляра
+    InstanceRunElevatedToggled(usize, bool), // Запуск дополнительного экземпляра с повышенными правами
+    InstanceKeepRunningToggled(usize, bool), // Работа дополнительного экземпляра после закрытия лаунчера
+    InstanceHealthCheckChanged(usize, String), // Изменен адрес проверки готовности дополнительного экземпляра
+    InstanceStopCommandChanged(usize, String), // Изменена команда остановки дополнительного экземпляра
+    InstanceMemoryLimitChanged(usize, String), // Изменен лимит памяти дополнительного экземпляра
+    SelectExecutablePath,                      // Нажата кнопка выбора пути
+    ApiKeyChanged(String),                     // Изменился текст в поле API ключа
+    LaunchProfileSelected(usize),              // Выбран профиль запуска
+    AddLaunchProfile, // Сохранить текущие параметры запуска как новый профиль
+    LaunchProfileRenamed(usize, String), // Изменено название профиля
+    RemoveLaunchProfile(usize), // Удалить профиль
+    LaunchProfileTitleChanged(usize, String), // Изменено дополнение к заголовку окна профиля
+    LaunchProfileBadgeSelected(usize, Option<HighlightColor>), // Выбран цвет метки значка профиля
+    ApiKeyPasted(String), // В поле API ключа вставлен текст (значение поля после вставки)
+    ApiKeyEnvChanged(String), // Изменено имя переменной окружения с ключом API
+    ApiKeyRevealToggled, // Показать или скрыть ключ API в поле ввода
+    ApplyLaunchSettingsNow, // Перезапустить процесс с новыми параметрами запуска
+    BatteryActionChanged(BatteryAction), // Выбрано действие при переходе на батарею
+    BatteryMinChargeChanged(String), // Изменен порог заряда для автоматических запусков
+    MaintenanceUrlChanged(String), // Изменен URL календаря обслуживания
+    MaintenanceLeadChanged(String), // Изменено время остановки до обслуживания (мин)
+    RefreshMaintenanceCalendar, // Загрузить (обновить) календарь обслуживания
+    NtpServerChanged(String), // Изменен NTP-сервер
+    ClockDriftThresholdChanged(String), // Изменен допустимый порог расхождения часов (мс)
+    BlockStartOnClockDriftToggled(bool), // Переключен запрет запуска при расхождении часов
+    TradingHoursToggled(bool), // Включено/выключено расписание торговых часов
+    TradingHoursStartChanged(String), // Изменено время запуска по расписанию
+    TradingHoursStopChanged(String), // Изменено время остановки по расписанию
+    TradingHoursDayToggled(usize, bool), // Переключен день недели расписания
+    ResumeWorkspaceToggled(bool), // Переключено возобновление процесса при запуске лаунчера
+    AutostartToggled(bool), // Переключен автозапуск процесса при открытии лаунчера
+    WatchdogMinutesChanged(String), // Изменен порог молчания процесса (мин)
+    WatchdogRestartToggled(bool), // Перезапускать зависший процесс
+    MaxRuntimeChanged(String), // Изменено ограничение времени работы процесса (мин)
+    RuntimeLimitTick, // Периодическая проверка ограничения времени работы
+    UptimeTick,       // Обновление счетчика времени работы на экране
+    GracefulStopTimeoutChanged(String), // Изменено время ожидания корректной остановки (с)
+    OutputRateLimitChanged(String), // Изменено ограничение частоты строк вывода (строк/с)
+    OutputEncodingSelected(OutputEncoding), // Выбрана кодировка вывода процесса
+    UiLockMinutesChanged(String), // Изменено время бездействия до блокировки (мин)
+    UiLockPinChanged(String), // Изменен PIN разблокировки
+    UiLockHideLogsToggled(bool), // Переключено скрытие лога при блокировке
+    LanguageSelected(Language), // Выбран язык интерфейса
+    AccentColorChanged(String), // Изменен цвет акцента (#RRGGBB)
+    StartColorChanged(String), // Изменен цвет кнопки запуска
+    StopColorChanged(String), // Изменен цвет кнопки остановки
+    ResetThemeColorsPressed, // Нажата кнопка "Цвета по умолчанию"
+    CloseToTrayToggled(bool), // Переключено закрытие окна в область уведомлений
+    Tray(TrayEvent),  // Событие значка в области уведомлений
+    LockNowPressed,   // Нажата кнопка "Заблокировать"
+    UnlockPinChanged(String), // Изменен PIN на экране блокировки
+    UnlockPressed,    // Нажата кнопка "Разблокировать"
+    LockedInteraction, // Действие с заблокированным интерфейсом (игнорируется)
+    ExtraArgsChanged(String), // Изменены дополнительные аргументы командной строки
+    RunElevatedToggled(bool), // Запуск основного экземпляра с повышенными правами
+    KeepRunningToggled(bool), // Работа основного экземпляра после закрытия лаунчера
+    HealthCheckChanged(String), // Изменен адрес проверки готовности основного экземпляра
+    StopCommandChanged(String), // Изменена команда остановки основного экземпляра
+    MemoryLimitChanged(String), // Изменен лимит памяти основного экземпляра
+    HealthStartTimeoutChanged(String), // Изменено время ожидания первого ответа (с)
+    HealthProbed(u64, bool), // Результат проверки готовности (экземпляр, ответил ли процесс)
+    AddEnvVar,        // Добавить переменную окружения процесса
+    RemoveEnvVar(usize), // Удалить переменную окружения по индексу
+    EnvVarKeyChanged(usize, String), // Изменено имя переменной окружения
+    EnvVarValueChanged(usize, String), // Изменено значение переменной окружения
+    AddMetricRule,    // Добавить правило метрики
+    RemoveMetricRule(usize), // Удалить правило метрики по индексу
+    MetricRuleNameChanged(usize, String), // Изменено имя метрики
+    MetricRulePatternChanged(usize, String), // Изменено регулярное выражение метрики
+    AddAlertRule,     // Добавить правило оповещения
+    RemoveAlertRule(usize), // Удалить правило оповещения по индексу
+    AlertMetricChanged(usize, String), // Выбрана метрика для правила оповещения
+    AlertComparisonChanged(usize, Comparison), // Выбрана операция сравнения
+    AlertThresholdChanged(usize, String), // Изменен порог срабатывания
+    AlertHoldChanged(usize, String), // Изменена длительность выполнения условия (мин)
+    AlertStopToggled(usize, bool), // Переключена остановка процесса при срабатывании
+    DismissNotification(u64), // Закрыть уведомление
+    CrashReportSaved(u64, Result<PathBuf, String>), // Результат сохранения отчета о сбое (экземпляр)
+    OpenLogFolderPressed,                           // Открыть директорию логов сессий
+    OpenFolderPressed(PathBuf),                     // Открыть директорию из уведомления
+    FolderOpened(Result<(), String>),               // Результат открытия директории
+    OpenUrlPressed(String),                         // Клик по ссылке в логе
+    UrlOpened(Result<(), String>),                  // Результат открытия ссылки в браузере
+    AddHighlightRule,                               // Добавить правило подсветки
+    RemoveHighlightRule(usize),                     // Удалить правило подсветки
+    MoveHighlightRuleUp(usize),                     // Поднять правило подсветки (выше приоритет)
+    MoveHighlightRuleDown(usize),                   // Опустить правило подсветки
+    HighlightKeywordChanged(usize, String),         // Изменено ключевое слово подсветки
+    HighlightColorChanged(usize, HighlightColor),   // Выбран цвет подсветки
+    HighlightRegexToggled(usize, bool), // Условие правила подсветки - регулярное выражение
+    HighlightBoldToggled(usize, bool),  // Жирный шрифт для правила подсветки
+    HighlightBackgroundToggled(usize, bool), // Цвет правила подсветки - для фона
+    AddKeywordAlert,                    // Добавить оповещение по ключевой фразе
+    RemoveKeywordAlert(usize),          // Удалить оповещение по ключевой фразе
+    KeywordAlertChanged(usize, String), // Изменена ключевая фраза оповещения
+    KeywordAlertBannerToggled(usize, bool), // Показывать ли баннер
+    KeywordAlertSoundToggled(usize, bool), // Подавать ли звуковой сигнал
+    KeywordAlertDesktopToggled(usize, bool), // Показывать ли системное уведомление
+    DesktopNotifyCrashToggled(bool),    // Системное уведомление при сбое процесса
+    DesktopNotifyExitToggled(bool),     // Системное уведомление при неожиданном завершении процесса
+    DesktopNotificationShown(Result<(), String>), // Системное уведомление показано
+    KeywordAlertSignalled(Result<(), String>), // Звук или системное уведомление выполнены
+    ExportRulePackPressed,              // Нажата кнопка экспорта набора правил
+    RulePackExported(Result<Option<PathBuf>, String>), // Результат экспорта (None - отменен)
+    ImportRulePackPressed,              // Нажата кнопка импорта набора правил
+    RulePackImported(Result<Option<RulePack>, String>), // Загруженный набор (None - отменен)
+    CloseSettingsPressed,               // Нажата кнопка "Закрыть настройки"
+    StatsButtonPressed,                 // Нажата кнопка "Статистика"
+    CloseStatsPressed,                  // Нажата кнопка "Закрыть статистику"
+    ExportHistoryPressed(ExportFormat), // Нажата кнопка экспорта истории запусков
+    HistoryExported(Result<Option<PathBuf>, String>), // Результат экспорта (None - отменен)
+    CollectDiagnosticsPressed,          // Нажата кнопка "Собрать диагностику"
+    DiagnosticsSaved(Result<Option<PathBuf>, String>), // Результат сборки пакета (None - отменен)
+    CopyLogsPressed,                    // Нажата кнопка копирования логов
+    ExportLogPressed,                   // Нажата кнопка экспорта лога в файл
+    LogLineClicked(u64),                // Клик по строке лога: выделение (с Shift - диапазон)
+    CopySelectedPressed,                // Нажата кнопка копирования выделенных строк
+    ClearLogPressed,                    // Нажата кнопка очистки лога (запрос подтверждения)
+    ConfirmClearLog(bool), // Очистка подтверждена (true - начать новый файл лога сессии)
+    CancelClearLog,        // Очистка лога отменена
+    LogExported(Result<Option<PathBuf>, String>), // Результат экспорта лога (None - отменен)
+    LogBufferLinesChanged(String), // Изменен размер буфера строк лога
+    LogExportKeepAnsiToggled(bool), // Сохранять ли цвета (ANSI) при экспорте лога
+    TintStderrToggled(bool), // Окрашивать ли строки потока ошибок
+    LogGutterToggled(bool), // Показывать ли номера строк и метки потоков
+    LogFontSizeChanged(String), // Изменен размер шрифта лога (поле в настройках)
+    LogZoomIn,             // Ctrl+= - увеличить шрифт лога
+    LogZoomOut,            // Ctrl+- - уменьшить шрифт лога
+    LogZoomReset,          // Ctrl+0 - стандартный размер шрифта лога
+    RestorePreviousLogToggled(bool), // Восстанавливать ли лог прошлого сеанса при запуске
+    PreviousSessionLoaded(usize, Result<Option<PreviousSessionLog>, String>), // Прочитан конец лога прошлого сеанса экземпляра
+    LogTimeFilterSelected(LogTimeFilter), // Выбран фильтр лога по времени
+    LogRangeFromChanged(String),          // Изменено начало интервала фильтра
+    LogRangeToChanged(String),            // Изменен конец интервала фильтра
+    LogSearchChanged(String),             // Изменен искомый в логе текст
+    LogSearchNext,                        // Перейти к следующему совпадению поиска
+    LogSearchPrevious,                    // Перейти к предыдущему совпадению поиска
+    LogLevelToggled(LogLevel),            // Скрыть/показать строки уровня
+    LogStreamFilterSelected(LogStreamFilter), // Выбраны показываемые потоки вывода
+    LogJsonViewToggled(bool),             // Показывать ли строки JSON таблицей
+    LogRawViewToggled(bool),              // Показывать ли исходный текст строк рядом с разобранным
+    LogLineExpandToggled(u64),            // Раскрыть/свернуть исходный объект строки JSON
+    LogScrolled(f32),                     // Лог прокручен (смещение от новых строк в пикселях)
+    LogFollowToggled,                     // Нажата кнопка паузы/продолжения вывода лога
+    JumpToNewest,                         // Прокрутить лог к самой новой строке (кнопка или Home)
+    JumpToOldest,                         // Прокрутить лог к самой старой строке (кнопка или End)
+    LogBookmarkToggled(u64),              // Поставить/снять закладку на строке лога
+    NextBookmark,                         // Перейти к следующей (более старой) закладке (F2)
+    PreviousBookmark,                     // Перейти к предыдущей (более новой) закладке (Shift+F2)
+    ClearBookmarks,                       // Удалить все закладки
+    LogCompressDaysChanged(String),       // Изменен возраст логов для сжатия (дни)
+    LogQuotaChanged(String),              // Изменена квота директории логов (МБ)
+    SessionLogMaxChanged(String),         // Изменен размер части лога сессии (МБ)
+    SessionLogKeepAnsiToggled(bool),      // Сохранять ли цвета (ANSI) в логе сессии
+    LogTimestampsToggled(bool),           // Показывать ли время перед строками лога
+    LogTimeFormatChanged(String),         // Изменен формат времени строк лога
+    SelectWorkingDir,                     // Нажата кнопка выбора рабочей директории процесса
+    ResetWorkingDir,                      // Запускать процесс в директории запуска лаунчера
+    WorkingDirSelected(Option<PathBuf>),  // Результат выбора рабочей директории (None - отменен)
+    SelectDataDir,                        // Нажата кнопка выбора директории данных
+    ResetDataDir,                         // Вернуть стандартную директорию данных
+    DataDirSelected(Option<PathBuf>),     // Результат выбора директории (None - отменен)
+    LogForwardToggled(bool),              // Включена/выключена пересылка логов
+    LogForwardAddressChanged(String),     // Изменен адрес приемника логов
+    LogForwardFormatChanged(ForwardFormat), // Выбран формат пересылки
+    LogForwardTlsToggled(bool),           // Включен/выключен TLS для пересылки
+    JumpToLogLine(u64),                   // Клик по событию хронологии: перейти к строке лога
+    DataDirMigrated(Option<PathBuf>, Result<usize, String>), // Результат переноса файлов в новую директорию
+    DataDirChecked(Result<(), String>), // Результат проверки доступа на запись в директорию данных
 
     // События выбора файла
     ExecutablePathSelected(Result<Option<PathBuf>, String>), // Результат выбора файла
 
     // События загрузки/сохранения настроек
-    SettingsLoaded(Result<AppSettings, String>), // Результат загрузки настроек
-    SettingsSaved(Result<(), String>),           // Результат сохранения настроек
+    SettingsLoaded(Result<Box<LoadedSettings>, String>), // Результат загрузки настроек
+    SettingsSaved(Result<(), String>),                   // Результат сохранения настроек
+    SettingsFileChanged, // Файл настроек изменен на диске (возможно, другой программой)
+    SettingsReloaded(Result<Box<LoadedSettings>, String>), // Результат повторного чтения настроек
+    SettingsBackupsListed(Result<Vec<SettingsBackup>, String>), // Получен список резервных копий настроек
+    SettingsBackupSelected(SettingsBackup), // Выбрана копия настроек для восстановления
+    RestoreSettingsBackup,                  // Восстановить выбранную копию настроек
+    SettingsBackupRestored(Result<Box<LoadedSettings>, String>), // Результат чтения копии настроек
+    ResetSettingsPressed,                   // Нажата кнопка сброса настроек (запрос подтверждения)
+    ConfirmResetSettings,                   // Сброс настроек по умолчанию подтвержден
+    CancelResetSettings,                    // Сброс настроек отменен
+    SettingsPasswordChanged(String),        // Изменен пароль для открытия зашифрованных настроек
+    SettingsPasswordSubmitted,              // Открыть зашифрованные настройки введенным паролем
+    SettingsUnlocked(Result<SettingsCipher, String>), // Результат проверки пароля настроек
+    NewSettingsPasswordChanged(String),     // Изменен новый пароль шифрования настроек
+    NewSettingsPasswordConfirmChanged(String), // Изменен повтор нового пароля
+    EncryptSettingsPressed, // Зашифровать настройки новым паролем (или сменить пароль)
+    SettingsEncryptionReady(Result<SettingsCipher, String>), // Ключ из нового пароля получен
+    DecryptSettingsPressed, // Отключить шифрование настроек
+    PlaintextCopiesRemoved(Result<usize, String>), // Удалены незашифрованные копии настроек
+    HistoryLoaded(Result<Vec<RunRecord>, String>), // Результат загрузки истории запусков
+    HistorySaved(Result<(), String>), // Результат сохранения истории запусков
+    WorkspaceLoaded(Result<Workspace, String>), // Результат загрузки рабочего пространства
+    WorkspaceSaved(Result<(), String>), // Результат сохранения рабочего пространства
+
+    // События дочернего процесса (из ProcessListener, первое поле - ID подписки)
+    ProcessActualPid(u64, u32), // Получен PID запущенного процесса
+    ProcessOutput(u64, LogSource, String), // Получена строка вывода (stdout/stderr)
+    ProcessOutputBatch(u64, LogSource, Vec<String>), // Получен пакет строк вывода (при потоке строк)
+    ProcessOutputDropped(u64, LogSource, u64), // Пропущено строк вывода сверх ограничения частоты
+    ProcessProgress(u64, LogSource, String), // Получен фрагмент, завершенный \r (заменяет предыдущий в той же строке)
+    ProcessStdinReady(u64, tokio::sync::mpsc::Sender<String>), // Процесс готов принимать команды в stdin
+    ProcessTerminated(u64, i32),                               // Процесс завершился (с кодом)
+    ProcessError(u64, String), // Произошла ошибка, связанная с процессом
+    ProcessUsageUpdated(u64, ProcessUsage), // Новые данные о загрузке CPU и памяти процесса (ID экземпляра)
+    WatchdogTick,                           // Пора проверить, не замолчали ли процессы
+
+    // События завершения асинхронных команд (первое поле - ID экземпляра)
+    ConsoleInputChanged(String),          // Изменен текст команды консоли
+    ConsoleSubmitted,                     // Отправить команду консоли процессу
+    ConsoleSent(u64, Result<(), String>), // Результат отправки команды в stdin
+
+    ProcessKillResult(u64, Result<(), KillError>), // Результат попытки остановить процесс (по кнопке/закрытию)
+    SoftStopSent(u64, u32, Result<(), KillError>), // Результат мягкого запроса завершения (PID)
+    GracefulStopWaited(u64, u32, bool), // Ожидание завершения окончено (PID, завершился ли процесс)
+    PreLaunchKillResult(u64, Result<(), KillError>), // Результат попытки убить старый PID перед запуском
+    InitialPidKillResult(u64, Result<(), KillError>), // Результат попытки убить PID при запуске приложения
+
+    // Процесс, оставшийся от прошлого сеанса лаунчера (поле - ID экземпляра)
+    OrphanChecked(u64, Option<ProcessCandidate>), // Результат проверки PID прошлого сеанса
+    ReattachPressed(u64),                         // Подключиться к процессу прошлого сеанса
+    StopOrphanPressed(u64),                       // Завершить процесс прошлого сеанса
+    AttachedProcessExited(u64),                   // Подключенный процесс завершился
+
+    // Поиск процессов по пути, когда PID неизвестен
+    RunningCheckDone(u64, Vec<ProcessCandidate>), // Найденные перед запуском процессы TradingStar (экземпляр)
+    KillCandidatesFound(Vec<ProcessCandidate>),   // Найдены процессы с тем же исполняемым файлом
+    ConfirmKillCandidates, // Пользователь подтвердил завершение найденных процессов
+    CancelKillCandidates,  // Пользователь отказался от завершения
+    KillCandidatesResult(Vec<(u32, Result<(), KillError>)>), // Результаты завершения по каждому PID
 
-    // События дочернего процесса (из ProcessListener)
-    ProcessActualPid(u32),  // Получен PID запущенного процесса
-    ProcessOutput(String),  // Получена строка вывода (stdout/stderr)
-    ProcessTerminated(i32), // Процесс завершился (с кодом)
-    ProcessError(String),   // Произошла ошибка, связанная с процессом
+    // События электропитания
+    PowerCheckTick,                                // Пора опросить состояние питания
+    PowerStateUpdated(Result<PowerState, String>), // Получено состояние питания
 
-    // События завершения асинхронных команд
-    ProcessKillResult(Result<(), String>), // Результат попытки остановить процесс (по кнопке/закрытию)
-    PreLaunchKillResult(Result<(), String>, Option<PathBuf>, String), // Результат попытки убить старый PID перед запуском
-    InitialPidKillResult(Result<(), String>), // <--- НОВОЕ: Результат попытки убить PID при запуске приложения
+    // События планировщика
+    MaintenanceCalendarLoaded(Result<Vec<MaintenanceWindow>, String>), // Загружен календарь обслуживания
+    SchedulerTick,                 // Периодическая проверка расписания
+    TradingHours(ScheduledAction), // Наступило время запуска/остановки по расписанию торговых часов
+
+    // События контроля системных часов
+    ClockCheckTick,                           // Пора проверить расхождение часов
+    ClockOffsetMeasured(Result<f64, String>), // Получено смещение часов (мс)
+
+    // События оповещений
+    AlertTick, // Периодическая проверка правил оповещений
+
+    // События блокировки интерфейса
+    UserActivity, // Пользователь двигал мышью или нажимал клавиши
+    IdleLockTick, // Пора проверить время бездействия
+
+    // События обслуживания директории логов
+    LogMaintenanceTick, // Пора сжать старые логи и проверить квоту
+    LogMaintenanceDone(Result<LogMaintenanceReport, String>), // Результат обслуживания
+
+    // События пересылки логов
+    LogForwarder(ForwarderEvent), // Событие подписки пересылки
 
     // Общие события Iced (включая закрытие окна)
     EventOccurred(iced::Event), // Произошло событие Iced (движение мыши, нажатие клавиш, закрытие окна и т.д.)
@@ -72,9 +497,10 @@ pub enum Message {
 // --- Асинхронная функция выбора файла ---
 // (Оставлена здесь, т.к. тесно связана с UI событием SelectExecutablePath)
 async fn select_executable_file() -> Result<Option<PathBuf>, String> {
-    // Используем rfd для открытия системного диалога выбора файла
+    // Используем rfd для открытия системного диалога выбора файла.
+    // На macOS пакеты .app отображаются как обычные файлы и тоже могут быть выбраны.
     let file_handle = AsyncFileDialog::new()
-        .set_title("Выберите исполняемый файл...")
+        .set_title(tr("Выберите исполняемый файл..."))
         // .set_directory("/") // Можно указать начальную директорию
         .pick_file() // Выбираем один файл
         .await; // Ожидаем выбора пользователя
@@ -86,40 +512,190 @@ async fn select_executable_file() -> Result<Option<PathBuf>, String> {
     }
 }
 
+// --- Выбор директории данных ---
+async fn select_data_dir() -> Option<PathBuf> {
+    AsyncFileDialog::new()
+        .set_title(tr("Выберите директорию для логов и истории запусков..."))
+        .pick_folder()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
+
+// --- Выбор рабочей директории процесса ---
+async fn select_working_dir() -> Option<PathBuf> {
+    AsyncFileDialog::new()
+        .set_title(tr("Выберите рабочую директорию процесса..."))
+        .pick_folder()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
+
+// --- Экспорт истории запусков в файл, выбранный пользователем ---
+async fn save_history_export(
+    history: Vec<RunRecord>,
+    format: ExportFormat,
+) -> Result<Option<PathBuf>, String> {
+    let content = export_history(&history, format)?;
+    let file_handle = AsyncFileDialog::new()
+        .set_title(tr("Экспорт истории запусков"))
+        .set_file_name(format!("run_history.{}", format.extension()))
+        .add_filter(format.extension().to_uppercase(), &[format.extension()])
+        .save_file()
+        .await;
+    let Some(handle) = file_handle else {
+        return Ok(None);
+    };
+    let path = handle.path().to_path_buf();
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| trf!("Не удалось записать файл {}: {}", format!("{:?}", path), e))?;
+    Ok(Some(path))
+}
+
+// --- Экспорт лога экземпляра в файл, выбранный пользователем ---
+async fn save_log_export(content: String, instance: String) -> Result<Option<PathBuf>, String> {
+    let file_handle = AsyncFileDialog::new()
+        .set_title(tr("Экспорт лога"))
+        .set_file_name(format!(
+            "log-{}-{}.txt",
+            instance.replace(|c: char| !c.is_alphanumeric(), "_"),
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ))
+        .add_filter(tr("Текст"), &["txt", "log"])
+        .save_file()
+        .await;
+    let Some(handle) = file_handle else {
+        return Ok(None);
+    };
+    let path = handle.path().to_path_buf();
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| trf!("Не удалось записать файл {}: {}", format!("{:?}", path), e))?;
+    Ok(Some(path))
+}
+
+// Задержка перед следующей попыткой разблокировки после `failures` неудачных подряд
+// (None - можно пробовать сразу): 5 с, 10 с, 20 с... но не больше UNLOCK_MAX_DELAY
+fn unlock_retry_delay(failures: u32) -> Option<Duration> {
+    let excess = failures.checked_sub(UNLOCK_FREE_ATTEMPTS)?;
+    Some(
+        UNLOCK_BASE_DELAY
+            .saturating_mul(2u32.saturating_pow(excess))
+            .min(UNLOCK_MAX_DELAY),
+    )
+}
+
 // --- Реализация трейта Application для Iced ---
 impl Application for Launcher {
     type Executor = executor::Default; // Стандартный исполнитель Tokio
     type Message = Message; // Тип сообщений нашего приложения
     type Theme = Theme; // Используем стандартные темы Iced
-    type Flags = (); // Флаги инициализации (не используем)
+    type Flags = CliArgs; // Параметры командной строки
 
     // Инициализация приложения
-    fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        // Получаем путь к конфигурации
-        let config_path = get_config_path();
+    fn new(cli: Self::Flags) -> (Self, Command<Self::Message>) {
+        // Получаем путь к конфигурации (файл из командной строки важнее стандартного)
+        let config_path = cli.config.clone().or_else(get_config_path);
         // Создаем начальное состояние
         let initial_state = Launcher {
-            settings: AppSettings::default(), // Настройки по умолчанию
-            is_running: false,
-            logs: VecDeque::with_capacity(MAX_LOG_LINES), // Пустая очередь логов
-            show_settings: false,
+            settings: AppSettings::default(),  // Настройки по умолчанию
+            instances: vec![Instance::new(0)], // Основной экземпляр есть всегда
+            selected_instance: 0,
+            next_instance_id: 1,
+            log_filter: LogTimeFilter::default(),
+            log_range_from: String::new(),
+            log_range_to: String::new(),
+            screen: Screen::Main,
+            settings_section: SettingsSection::default(),
             config_path: config_path.clone(),
+            cli,
+            file_settings: AppSettings::default(),
+            recent_saves: VecDeque::new(),
+            last_settings_backup: None,
+            settings_backups: Vec::new(),
+            selected_settings_backup: None,
             subscription_id_counter: 0,
-            subscription_id: None,
-            actual_pid: None,
             close_requested: false,
+            tray: None,
+            tray_state: None,
+            kill_candidates: None,
+            power_state: None,
+            maintenance_windows: Vec::new(),
+            clock_offset_ms: None,
+            metric_extractor: MetricExtractor::default(),
+            metric_rule_errors: Vec::new(),
+            highlighter: Highlighter::default(),
+            masked_keys: Vec::new(),
+            highlight_rule_errors: Vec::new(),
+            notifications: NotificationCenter::default(),
+            data_dir: resolve_data_dir(None),
+            run_history: Vec::new(),
+            log_forwarder: None,
+            log_forward_status: None,
+            next_log_id: 0,
+            events: Vec::new(),
+            highlighted_log_line: None,
+            log_search: String::new(),
+            log_search_position: 0,
+            hidden_log_levels: Vec::new(),
+            log_stream: LogStreamFilter::default(),
+            log_json_view: false,
+            log_raw_view: false,
+            api_key_revealed: false,
+            window_badge: None,
+            expanded_log_lines: Vec::new(),
+            log_frozen_at: None,
+            log_follow: true,
+            log_selection: None,
+            clear_log_pending: false,
+            reset_settings_pending: false,
+            log_bookmarks: Vec::new(),
+            keyboard_modifiers: iced::keyboard::Modifiers::default(),
+            window_geometry: None,
+            saved_workspace: None,
+            data_dir_error: None,
+            executable_problem: None,
+            last_activity: Instant::now(),
+            ui_locked: false,
+            unlock_pin_input: String::new(),
+            unlock_error: None,
+            unlock_failures: 0,
+            unlock_retry_at: None,
+            settings_cipher: None,
+            settings_locked: false,
+            settings_password_input: String::new(),
+            settings_password_error: None,
+            new_settings_password: String::new(),
+            new_settings_password_confirm: String::new(),
+            settings_password_pending: false,
         };
         // Возвращаем состояние и команду на загрузку настроек
         (
             initial_state,
-            // Запускаем асинхронную загрузку настроек
-            Command::perform(load_settings(config_path), Message::SettingsLoaded),
+            Command::batch(vec![
+                // Запускаем асинхронную загрузку настроек
+                // (зашифрованный файл загрузится после ввода пароля)
+                Command::perform(load_settings(config_path, None), |result| {
+                    Message::SettingsLoaded(result.map(Box::new))
+                }),
+                // И первичный опрос состояния питания
+                Command::perform(read_power_state(), Message::PowerStateUpdated),
+            ]),
         )
     }
 
     // Заголовок окна приложения
     fn title(&self) -> String {
-        String::from("TradingStar 3 Launcher")
+        // Дополнение из выбранного профиля отличает окна лаунчеров на панели задач
+        match self
+            .settings
+            .active_launch_profile()
+            .map(|profile| profile.title.trim())
+            .filter(|title| !title.is_empty())
+        {
+            Some(title) => format!("TradingStar 3 Launcher — {}", title),
+            None => String::from("TradingStar 3 Launcher"),
+        }
     }
 
     // Обновление состояния приложения при получении сообщения
@@ -128,78 +704,134 @@ impl Application for Launcher {
 
         match message {
             // --- Обработка событий UI ---
-            Message::SettingsButtonPressed => self.show_settings = true, // Показать настройки
-            Message::CloseSettingsPressed => self.show_settings = false, // Скрыть настройки
+            Message::SettingsButtonPressed => commands_to_batch.push(self.open_settings()),
+            Message::SettingsSectionSelected(section) => {
+                self.settings_section = section;
+                if self.screen != Screen::Settings {
+                    commands_to_batch.push(self.open_settings());
+                }
+            }
+            Message::CloseSettingsPressed => {
+                // Скрыть настройки; открытый ключ снова маскируется
+                self.screen = Screen::Main;
+                self.api_key_revealed = false;
+                self.reset_settings_pending = false;
+            }
+            Message::ApiKeyRevealToggled => self.api_key_revealed = !self.api_key_revealed,
+            Message::StatsButtonPressed => self.screen = Screen::Stats, // Показать статистику
+            Message::CloseStatsPressed => self.screen = Screen::Main,   // Скрыть статистику
             Message::StartButtonPressed => {
-                // Проверяем, можно ли запустить
-                if !self.is_running
-                    && self.settings.executable_path.is_some()
-                    && !self.settings.api_key.is_empty()
-                {
-                    let path = self.settings.executable_path.clone().unwrap(); // Безопасно, т.к. проверили is_some()
-                    let api_key = self.settings.api_key.clone();
-
-                    // Проверяем, есть ли старый PID
-                    if let Some(last_pid) = self.settings.last_pid {
-                        self.add_log(format!(
-                            "Обнаружен PID предыдущего запуска: {}. Попытка завершения...",
-                            last_pid
-                        ));
-                        // Пытаемся убить старый процесс и передаем path/api_key для последующего запуска
-                        commands_to_batch.push(Command::perform(
-                            kill_process(last_pid),
-                            move |result| Message::PreLaunchKillResult(result, Some(path), api_key), // Передаем path и api_key
-                        ));
-                    } else {
-                        // Старого PID нет, запускаем сразу
-                        self.logs.clear();
-                        self.add_log("Запуск процесса через подписку...".to_string());
-                        self.is_running = true;
-                        let new_id = self.subscription_id_counter;
-                        self.subscription_id_counter += 1;
-                        self.subscription_id = Some(new_id);
-                        self.actual_pid = None; // Сбрасываем, ждем новый PID от подписки
-                                                // Сохраняем настройки (на всякий случай, хотя PID еще не установлен)
-                        commands_to_batch.push(Command::perform(
-                            save_settings(self.config_path.clone(), self.settings.clone()),
-                            Message::SettingsSaved,
+                commands_to_batch.push(self.start_process(self.selected_instance))
+            }
+            Message::StopButtonPressed => commands_to_batch
+                .push(self.stop_process(self.selected_instance, RunOutcome::Stopped)),
+            Message::SelectInstance(index) => {
+                if index < self.instances.len() {
+                    self.selected_instance = index;
+                    self.highlighted_log_line = None;
+                    self.log_search_position = 0;
+                    self.log_selection = None;
+                    self.clear_log_pending = false;
+                }
+            }
+            Message::AddInstance => {
+                self.settings.instances.push(InstanceSettings::default());
+                self.push_instance();
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::RemoveInstance(extra) => {
+                let index = extra + 1;
+                match self.instances.get(index) {
+                    Some(instance) if instance.is_busy() => self.add_log(trf!(
+                        "Экземпляр \"{}\" запущен - остановите его перед удалением.",
+                        self.settings.instance_name(index)
+                    )),
+                    Some(_) => {
+                        self.add_log(trf!(
+                            "Экземпляр \"{}\" удален.",
+                            self.settings.instance_name(index)
                         ));
+                        self.instances.remove(index);
+                        self.settings.instances.remove(extra);
+                        if self.selected_instance >= index {
+                            self.selected_instance -= 1;
+                        }
+                        commands_to_batch.push(self.save_settings_command());
                     }
-                } else if self.is_running {
-                    // Игнорируем, если уже запущен
-                } else {
-                    self.add_log("Ошибка: Проверьте путь и ключ API.".to_string());
+                    None => {}
                 }
             }
-            Message::StopButtonPressed => {
-                if let Some(pid) = self.actual_pid.take() {
-                    self.add_log(format!("Остановка процесса (PID: {})...", pid));
-                    self.is_running = false;
-                    self.subscription_id = None;
-                    // Очищаем сохраненный PID и сохраняем настройки
-                    if self.settings.last_pid.is_some() {
-                        self.settings.last_pid = None;
-                        commands_to_batch.push(Command::perform(
-                            save_settings(self.config_path.clone(), self.settings.clone()),
-                            Message::SettingsSaved,
-                        ));
+            Message::InstanceNameChanged(extra, name) => {
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.name = name;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceApiKeyChanged(extra, key) => {
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.api_key = key;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceApiKeyPasted(extra, pasted) => {
+                // Как и для основного ключа: очищается только вставка, а не ввод с клавиатуры
+                if extra < self.settings.instances.len() {
+                    let (key, fixes) = sanitize_api_key(&pasted);
+                    if !fixes.is_empty() {
+                        self.log_to(
+                            extra + 1,
+                            trf!("Вставленный ключ API исправлен: {}", fixes.join(", ")),
+                        );
                     }
-                    commands_to_batch.push(Command::perform(
-                        kill_process(pid),
-                        Message::ProcessKillResult,
-                    ));
-                } else {
-                    self.add_log("Процесс не запущен или PID неизвестен.".to_string());
-                    // На всякий случай очищаем и сохраняем, если PID был, а is_running - нет
-                    if self.settings.last_pid.is_some() {
-                        self.settings.last_pid = None;
-                        commands_to_batch.push(Command::perform(
-                            save_settings(self.config_path.clone(), self.settings.clone()),
-                            Message::SettingsSaved,
-                        ));
+                    if let Some(problem) = validate_api_key(&key) {
+                        self.log_to(extra + 1, trf!("Проверка ключа API: {}", problem));
                     }
-                    self.is_running = false;
-                    self.subscription_id = None;
+                    self.settings.instances[extra].api_key = key;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceArgsChanged(extra, args) => {
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.extra_args = args;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceRunElevatedToggled(extra, enabled) => {
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.run_elevated = enabled;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceHealthCheckChanged(extra, target) => {
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.health_check = target;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceKeepRunningToggled(extra, enabled) => {
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.keep_running = enabled;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceStopCommandChanged(extra, command) => {
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.stop_command = command;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::InstanceMemoryLimitChanged(extra, value) => {
+                let value = value.trim();
+                let limit = if value.is_empty() {
+                    0
+                } else if let Ok(megabytes) = value.parse::<u32>() {
+                    megabytes
+                } else {
+                    return Command::none();
+                };
+                if let Some(instance) = self.settings.instances.get_mut(extra) {
+                    instance.memory_limit_mb = limit;
+                    commands_to_batch.push(self.save_settings_command());
                 }
             }
             Message::SelectExecutablePath => {
@@ -207,405 +839,4297 @@ impl Application for Launcher {
                 // Используем return, т.к. это единственная команда
                 return Command::perform(select_executable_file(), Message::ExecutablePathSelected);
             }
+            Message::LaunchProfileSelected(index) => {
+                if self.settings.active_profile == Some(index) {
+                    return Command::none();
+                }
+                if self.instances[0].is_running {
+                    self.add_log(
+                        tr("Профиль запуска можно сменить только при остановленном процессе.")
+                            .to_string(),
+                    );
+                    return Command::none();
+                }
+                self.settings.activate_profile(index);
+                if let Some(profile) = self.settings.profiles.get(index) {
+                    self.add_log(trf!("Выбран профиль запуска \"{}\".", profile.name));
+                }
+                commands_to_batch.push(self.window_badge_command());
+                if let Some(problem) = self.check_executable() {
+                    self.add_log(trf!("Исполняемый файл профиля: {}", problem));
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::AddLaunchProfile => {
+                self.settings.sync_active_profile();
+                let name = trf!("Профиль {}", self.settings.profiles.len() + 1);
+                let profile = self.settings.capture_profile(name);
+                self.settings.profiles.push(profile);
+                self.settings.active_profile = Some(self.settings.profiles.len() - 1);
+                commands_to_batch.push(self.window_badge_command());
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LaunchProfileRenamed(index, name) => {
+                if let Some(profile) = self.settings.profiles.get_mut(index) {
+                    profile.name = name;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::RemoveLaunchProfile(index) => {
+                if index < self.settings.profiles.len() {
+                    self.settings.profiles.remove(index);
+                    // Текущие параметры запуска остаются, но больше не привязаны к профилю
+                    self.settings.active_profile = match self.settings.active_profile {
+                        Some(active) if active == index => None,
+                        Some(active) if active > index => Some(active - 1),
+                        active => active,
+                    };
+                    commands_to_batch.push(self.window_badge_command());
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::LaunchProfileTitleChanged(index, title) => {
+                if let Some(profile) = self.settings.profiles.get_mut(index) {
+                    profile.title = title;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::LaunchProfileBadgeSelected(index, badge) => {
+                if let Some(profile) = self.settings.profiles.get_mut(index) {
+                    profile.badge = badge;
+                    commands_to_batch.push(self.window_badge_command());
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
             Message::ApiKeyChanged(new_key) => {
                 // Обновляем ключ API и запускаем сохранение настроек
                 self.settings.api_key = new_key;
-                commands_to_batch.push(Command::perform(
-                    save_settings(self.config_path.clone(), self.settings.clone()),
-                    Message::SettingsSaved,
-                ));
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::CopyLogsPressed => {
-                // Собираем все сегменты всех строк лога в единый текст
-                let log_text = self
-                    .logs
-                    .iter()
-                    .rev() // Итерируем от новых к старым
-                    .map(|line_segments| {
-                        // Для каждой строки
-                        line_segments
-                            .iter()
-                            .map(|segment| segment.text.as_str()) // Берем текст сегмента
-                            .collect::<String>() // Собираем сегменты строки в одну String
-                    })
-                    .collect::<Vec<String>>() // Собираем все строки в Vec<String>
-                    .join("\n"); // Объединяем строки через перевод строки
-
-                if !log_text.is_empty() {
-                    // Записываем собранный текст в буфер обмена
-                    commands_to_batch.push(clipboard::write(log_text));
-                    self.add_log("Логи скопированы в буфер обмена.".to_string());
+            Message::ApiKeyEnvChanged(name) => {
+                self.settings.api_key_env = name;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::ApiKeyPasted(pasted) => {
+                // Очищаем вставленный ключ и сразу сообщаем об исправлениях и проблемах
+                let (key, fixes) = sanitize_api_key(&pasted);
+                if !fixes.is_empty() {
+                    self.add_log(trf!("Вставленный ключ API исправлен: {}", fixes.join(", ")));
+                }
+                if let Some(problem) = validate_api_key(&key) {
+                    self.add_log(trf!("Проверка ключа API: {}", problem));
+                }
+                self.settings.api_key = key;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::ApplyLaunchSettingsNow => {
+                let index = self.selected_instance;
+                let changes = self.launch_changes(index);
+                if !changes.is_empty() && self.instances[index].actual_pid.is_some() {
+                    self.add_log(trf!(
+                        "Применение новых настроек ({}): остановка процесса и запуск заново...",
+                        changes.join(", ")
+                    ));
+                    self.record_event(index, EventKind::Restart);
+                    self.instances[index].restart_pending = true;
+                    commands_to_batch.push(self.stop_process(index, RunOutcome::Stopped));
+                } else if !changes.is_empty() {
+                    self.add_log(
+                        tr("PID процесса неизвестен - остановите процесс вручную и запустите снова.")
+                            .to_string(),
+                    );
+                }
+            }
+            Message::BatteryActionChanged(action) => {
+                self.settings.battery_action = action;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::BatteryMinChargeChanged(value) => {
+                // Принимаем только числа 0-100, пустое поле трактуем как 0
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.battery_min_charge = 0;
+                } else if let Ok(charge) = value.parse::<u8>() {
+                    self.settings.battery_min_charge = charge.min(100);
                 } else {
-                    self.add_log("Нет логов для копирования.".to_string());
+                    return Command::none();
                 }
+                commands_to_batch.push(self.save_settings_command());
             }
-
-            // --- Обработка событий выбора файла ---
-            Message::ExecutablePathSelected(Ok(Some(path))) => {
-                // Путь выбран, обновляем настройки и сохраняем
-                self.settings.executable_path = Some(path.clone());
-                self.add_log(format!("Выбран путь: {:?}", path));
-                commands_to_batch.push(Command::perform(
-                    save_settings(self.config_path.clone(), self.settings.clone()),
-                    Message::SettingsSaved,
-                ));
+            Message::MaintenanceUrlChanged(url) => {
+                self.settings.maintenance_ics_url = url;
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::ExecutablePathSelected(Ok(None)) => {
-                // Выбор файла отменен
-                self.add_log("Выбор файла отменен.".to_string());
+            Message::MaintenanceLeadChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.maintenance_lead_minutes = 0;
+                } else if let Ok(minutes) = value.parse::<u32>() {
+                    self.settings.maintenance_lead_minutes = minutes;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::ExecutablePathSelected(Err(e)) => {
-                // Ошибка выбора файла
-                eprintln!("Ошибка выбора файла: {}", e);
-                self.add_log(format!("Ошибка выбора файла: {}", e));
+            Message::RefreshMaintenanceCalendar => {
+                let url = self.settings.maintenance_ics_url.trim().to_string();
+                if url.is_empty() {
+                    self.maintenance_windows.clear();
+                } else {
+                    return Command::perform(
+                        fetch_maintenance_windows(url),
+                        Message::MaintenanceCalendarLoaded,
+                    );
+                }
             }
-
-            // --- Обработка событий загрузки/сохранения настроек ---
-            Message::SettingsLoaded(Ok(loaded_settings)) => {
-                self.settings = loaded_settings;
-                self.add_log("Настройки успешно загружены.".to_string());
-                // Проверяем, остался ли PID с прошлого запуска
-                if let Some(last_pid) = self.settings.last_pid {
-                    self.add_log(format!(
-                        "Обнаружен PID ({}) от предыдущего сеанса. Попытка завершения...",
-                        last_pid
-                    ));
-                    // Запускаем команду завершения старого процесса
-                    commands_to_batch.push(Command::perform(
-                        kill_process(last_pid),
-                        Message::InitialPidKillResult, // Используем новое сообщение
-                    ));
+            Message::NtpServerChanged(server) => {
+                self.settings.ntp_server = server;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::ClockDriftThresholdChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.clock_drift_threshold_ms = 0;
+                } else if let Ok(threshold) = value.parse::<u32>() {
+                    self.settings.clock_drift_threshold_ms = threshold;
+                } else {
+                    return Command::none();
                 }
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::SettingsLoaded(Err(e)) => {
-                eprintln!("Ошибка загрузки настроек: {}", e);
-                self.add_log(format!("Ошибка загрузки настроек: {}", e));
-                self.settings = AppSettings::default();
-                // В случае ошибки загрузки, last_pid будет None по умолчанию
+            Message::WatchdogMinutesChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.watchdog_silence_minutes = 0;
+                } else if let Ok(minutes) = value.parse::<u32>() {
+                    self.settings.watchdog_silence_minutes = minutes;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::SettingsSaved(Ok(())) => {
-                println!("Настройки сохранены.");
+            Message::WatchdogRestartToggled(enabled) => {
+                self.settings.watchdog_restart = enabled;
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::SettingsSaved(Err(e)) => {
-                eprintln!("Ошибка сохранения настроек: {}", e);
-                self.add_log(format!("Ошибка сохранения настроек: {}", e));
+            Message::MaxRuntimeChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.max_runtime_minutes = 0;
+                } else if let Ok(minutes) = value.parse::<u32>() {
+                    self.settings.max_runtime_minutes = minutes;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
             }
-
-            // --- Обработка событий дочернего процесса ---
-            Message::ProcessActualPid(pid) => {
-                self.add_log(format!("Процесс успешно запущен (PID: {}).", pid));
-                self.actual_pid = Some(pid);
-                // Сохраняем новый PID в настройках
-                self.settings.last_pid = Some(pid);
-                commands_to_batch.push(Command::perform(
-                    save_settings(self.config_path.clone(), self.settings.clone()),
-                    Message::SettingsSaved,
-                ));
+            Message::OutputEncodingSelected(encoding) => {
+                self.settings.output_encoding = encoding;
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::ProcessOutput(line) => {
-                self.add_log(line);
-            }
-            Message::ProcessTerminated(exit_code) => {
-                self.add_log(format!("Процесс завершился (код: {}).", exit_code));
-                self.is_running = false;
-                self.subscription_id = None;
-                self.actual_pid = None;
-                // Очищаем сохраненный PID и сохраняем настройки
-                if self.settings.last_pid.is_some() {
-                    self.settings.last_pid = None;
-                    commands_to_batch.push(Command::perform(
-                        save_settings(self.config_path.clone(), self.settings.clone()),
-                        Message::SettingsSaved,
-                    ));
+            Message::OutputRateLimitChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.output_rate_limit = 0;
+                } else if let Ok(limit) = value.parse::<u32>() {
+                    self.settings.output_rate_limit = limit;
+                } else {
+                    return Command::none();
                 }
-                if self.close_requested {
-                    commands_to_batch.push(window::close(window::Id::MAIN));
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::GracefulStopTimeoutChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.graceful_stop_timeout_secs = 0;
+                } else if let Ok(timeout) = value.parse::<u32>() {
+                    self.settings.graceful_stop_timeout_secs = timeout;
+                } else {
+                    return Command::none();
                 }
+                commands_to_batch.push(self.save_settings_command());
             }
-            Message::ProcessError(error_msg) => {
-                self.add_log(error_msg);
-                self.is_running = false;
-                self.subscription_id = None;
-                self.actual_pid = None;
-                // Очищаем сохраненный PID и сохраняем настройки
-                if self.settings.last_pid.is_some() {
-                    self.settings.last_pid = None;
-                    commands_to_batch.push(Command::perform(
-                        save_settings(self.config_path.clone(), self.settings.clone()),
-                        Message::SettingsSaved,
+            Message::UiLockMinutesChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.ui_lock_minutes = 0;
+                } else if let Ok(minutes) = value.parse::<u32>() {
+                    self.settings.ui_lock_minutes = minutes;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::UiLockPinChanged(pin) => {
+                self.settings.ui_lock_pin = pin.trim().to_string();
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::UiLockHideLogsToggled(enabled) => {
+                self.settings.ui_lock_hide_logs = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LanguageSelected(language) => {
+                self.settings.language = language;
+                i18n::set_language(language);
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::AccentColorChanged(value) => {
+                self.settings.theme_colors.accent = value.trim().to_string();
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::StartColorChanged(value) => {
+                self.settings.theme_colors.start = value.trim().to_string();
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::StopColorChanged(value) => {
+                self.settings.theme_colors.stop = value.trim().to_string();
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::ResetThemeColorsPressed => {
+                self.settings.theme_colors = ThemeColors::default();
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::CloseToTrayToggled(enabled) => {
+                self.settings.close_to_tray = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::Tray(TrayEvent::Ready(handle)) => {
+                self.tray = Some(handle);
+                self.tray_state = None; // Состояние передается значку в конце обработки
+            }
+            Message::Tray(TrayEvent::Action(action)) => match action {
+                TrayAction::Show => {
+                    commands_to_batch.push(window::change_mode(
+                        window::Id::MAIN,
+                        window::Mode::Windowed,
                     ));
+                    commands_to_batch.push(window::gain_focus(window::Id::MAIN));
                 }
-                if self.close_requested {
-                    commands_to_batch.push(window::close(window::Id::MAIN));
+                // Блокировка защищает управление процессом и из меню значка
+                _ if self.ui_locked => self.add_log(
+                    tr("Интерфейс заблокирован: команда из меню значка отклонена.").to_string(),
+                ),
+                TrayAction::Start => {
+                    if !self.current().is_busy() {
+                        commands_to_batch.push(self.update(Message::StartButtonPressed));
+                    }
+                }
+                TrayAction::Stop => {
+                    if self.current().is_running {
+                        commands_to_batch.push(self.update(Message::StopButtonPressed));
+                    }
+                }
+                TrayAction::Quit => {
+                    self.add_log(tr("Выход из меню значка в области уведомлений...").to_string());
+                    commands_to_batch.push(self.quit());
+                }
+            },
+            Message::LockNowPressed => self.lock_ui(),
+            Message::UnlockPinChanged(pin) => self.unlock_pin_input = pin,
+            Message::UnlockPressed => {
+                let wait = self
+                    .unlock_retry_at
+                    .map(|at| at.saturating_duration_since(Instant::now()))
+                    .filter(|wait| !wait.is_zero());
+                if let Some(wait) = wait {
+                    // Подбор PIN: пока не истекла задержка, введенный PIN не проверяется
+                    self.unlock_error = Some(trf!(
+                        "Слишком много неудачных попыток. Повторите через {} с.",
+                        wait.as_secs() + 1
+                    ));
+                } else if self.unlock_pin_input == self.settings.ui_lock_pin {
+                    self.ui_locked = false;
+                    self.unlock_error = None;
+                    self.unlock_failures = 0;
+                    self.unlock_retry_at = None;
+                    self.last_activity = Instant::now();
+                    self.add_log(tr("Интерфейс разблокирован.").to_string());
+                } else {
+                    self.unlock_failures += 1;
+                    self.unlock_error = Some(tr("Неверный PIN").to_string());
+                    self.add_log(tr("Неудачная попытка разблокировать интерфейс.").to_string());
+                    if let Some(delay) = unlock_retry_delay(self.unlock_failures) {
+                        self.unlock_retry_at = Some(Instant::now() + delay);
+                        self.add_log(trf!(
+                            "Неудачных попыток разблокировки подряд: {}. Следующая попытка - через {} с.",
+                            self.unlock_failures,
+                            delay.as_secs()
+                        ));
+                    }
                 }
+                self.unlock_pin_input.clear();
             }
-
-            // --- Обработка событий завершения команд ---
-            Message::ProcessKillResult(result) => {
-                match result {
-                    Ok(_) => self.add_log("Команда остановки процесса отправлена.".to_string()),
-                    Err(e) => self.add_log(format!("Ошибка отправки команды остановки: {}", e)),
+            Message::LockedInteraction => {}
+            Message::UserActivity => self.last_activity = Instant::now(),
+            Message::IdleLockTick => {
+                let idle_limit = Duration::from_secs(self.settings.ui_lock_minutes as u64 * 60);
+                if !self.ui_locked
+                    && self.settings.ui_lock_available()
+                    && self.settings.ui_lock_minutes > 0
+                    && self.last_activity.elapsed() >= idle_limit
+                {
+                    self.lock_ui();
                 }
-                // PID уже должен быть очищен и сохранен в StopButtonPressed или EventOccurred
-                // Просто сбрасываем флаги состояния
-                self.is_running = false;
-                self.subscription_id = None;
-                self.actual_pid = None;
-                if self.close_requested {
-                    commands_to_batch.push(window::close(window::Id::MAIN));
+            }
+            Message::TradingHoursToggled(enabled) => {
+                self.settings.trading_hours.enabled = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::TradingHoursStartChanged(value) => {
+                self.settings.trading_hours.start_time = value;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::TradingHoursStopChanged(value) => {
+                self.settings.trading_hours.stop_time = value;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::TradingHoursDayToggled(day, enabled) => {
+                if let Some(active) = self.settings.trading_hours.days.get_mut(day) {
+                    *active = enabled;
+                    commands_to_batch.push(self.save_settings_command());
                 }
             }
-
-            // --- Обработка событий завершения команд ---
-            Message::PreLaunchKillResult(kill_result, path_opt, api_key) => {
-                match kill_result {
-                    Ok(_) => self.add_log(
-                        "Команда завершения предыдущего процесса отправлена (или он уже не существовал)."
-                            .to_string(),
-                    ),
-                    Err(e) => self.add_log(format!(
-                        "Ошибка при попытке завершить предыдущий процесс: {}",
-                        e
-                    )),
+            Message::ResumeWorkspaceToggled(enabled) => {
+                self.settings.resume_workspace = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::AutostartToggled(enabled) => {
+                self.settings.autostart = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::BlockStartOnClockDriftToggled(enabled) => {
+                self.settings.block_start_on_clock_drift = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::ExtraArgsChanged(args) => {
+                self.settings.extra_args = args;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::RunElevatedToggled(enabled) => {
+                self.settings.run_elevated = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::KeepRunningToggled(enabled) => {
+                self.settings.keep_running = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::HealthCheckChanged(target) => {
+                self.settings.health_check = target;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::StopCommandChanged(command) => {
+                self.settings.stop_command = command;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::MemoryLimitChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.memory_limit_mb = 0;
+                } else if let Ok(megabytes) = value.parse::<u32>() {
+                    self.settings.memory_limit_mb = megabytes;
+                } else {
+                    return Command::none();
                 }
-                // Независимо от результата, пытаемся запустить новый процесс
-                // Проверки на path/api_key уже были в StartButtonPressed
-                if path_opt.is_some() && !api_key.is_empty() {
-                    self.logs.clear();
-                    self.add_log("Запуск нового процесса после попытки очистки...".to_string());
-                    self.is_running = true;
-                    let new_id = self.subscription_id_counter;
-                    self.subscription_id_counter += 1;
-                    self.subscription_id = Some(new_id);
-                    self.actual_pid = None; // Сбрасываем, ждем новый PID от подписки
-                                            // Сохраняем настройки (на всякий случай, хотя PID еще не установлен)
-                    commands_to_batch.push(Command::perform(
-                        save_settings(self.config_path.clone(), self.settings.clone()),
-                        Message::SettingsSaved,
-                    ));
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::HealthStartTimeoutChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.health_start_timeout_secs = 0;
+                } else if let Ok(timeout) = value.parse::<u32>() {
+                    self.settings.health_start_timeout_secs = timeout;
                 } else {
-                    // Этого не должно произойти, если логика StartButtonPressed верна
-                    self.add_log(
-                        "Ошибка: Не удалось получить путь/ключ для запуска после очистки."
-                            .to_string(),
-                    );
+                    return Command::none();
                 }
+                commands_to_batch.push(self.save_settings_command());
             }
-
-            // --- Обработка событий завершения команд ---
-            Message::InitialPidKillResult(result) => {
-                match result {
-                    Ok(_) => self.add_log(
-                        "Команда завершения процесса от предыдущего сеанса отправлена (или он не существовал)."
-                            .to_string(),
-                    ),
-                    Err(e) => self.add_log(format!(
-                        "Ошибка при попытке завершить процесс от предыдущего сеанса: {}",
-                        e
-                    )),
+            Message::AddEnvVar => {
+                self.settings.env_vars.push(EnvVar::default());
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::RemoveEnvVar(index) => {
+                if index < self.settings.env_vars.len() {
+                    self.settings.env_vars.remove(index);
+                    commands_to_batch.push(self.save_settings_command());
                 }
-                // В любом случае очищаем last_pid в настройках и сохраняем их
-                if self.settings.last_pid.is_some() {
-                    self.settings.last_pid = None;
-                    commands_to_batch.push(Command::perform(
-                        save_settings(self.config_path.clone(), self.settings.clone()),
-                        Message::SettingsSaved,
-                    ));
+            }
+            Message::EnvVarKeyChanged(index, key) => {
+                if let Some(var) = self.settings.env_vars.get_mut(index) {
+                    var.key = key;
+                    commands_to_batch.push(self.save_settings_command());
                 }
             }
-
-            // --- Обработка общих событий Iced ---
-            Message::EventOccurred(event) => {
-                match event {
-                    // Обработка запроса на закрытие окна
-                    Event::Window(id, window::Event::CloseRequested) => {
-                        if id == window::Id::MAIN {
-                            println!(
-                                "[EventOccurred] Окно - главное (MAIN). Запускаем логику закрытия."
-                            );
-                            self.add_log("Получен запрос на закрытие окна...".to_string());
-                            self.close_requested = true;
-                            if self.is_running {
-                                if let Some(pid) = self.actual_pid {
-                                    // Не используем .take() здесь
-                                    self.add_log(format!(
-                                        "Инициирована остановка процесса (PID: {}) перед закрытием.",
-                                        pid
-                                    ));
-                                    // Очищаем сохраненный PID и сохраняем настройки
-                                    if self.settings.last_pid.is_some() {
-                                        self.settings.last_pid = None;
-                                        commands_to_batch.push(Command::perform(
-                                            save_settings(
-                                                self.config_path.clone(),
-                                                self.settings.clone(),
-                                            ),
-                                            Message::SettingsSaved,
-                                        ));
-                                    }
-                                    commands_to_batch.push(Command::perform(
-                                        kill_process(pid),
-                                        Message::ProcessKillResult,
-                                    ));
-                                } else {
-                                    self.add_log(
-                                        "Процесс был запущен, но PID не найден. Закрытие окна."
-                                            .to_string(),
-                                    );
-                                    // На всякий случай очищаем и сохраняем, если PID был
-                                    if self.settings.last_pid.is_some() {
-                                        self.settings.last_pid = None;
-                                        commands_to_batch.push(Command::perform(
-                                            save_settings(
-                                                self.config_path.clone(),
-                                                self.settings.clone(),
-                                            ),
-                                            Message::SettingsSaved,
-                                        ));
-                                    }
-                                    self.is_running = false;
-                                    self.subscription_id = None;
-                                    commands_to_batch.push(window::close(window::Id::MAIN));
-                                }
-                            } else {
-                                println!("[EventOccurred] Процесс не запущен. Запрос на немедленное закрытие.");
-                                // На всякий случай очищаем и сохраняем, если PID был
-                                if self.settings.last_pid.is_some() {
-                                    self.settings.last_pid = None;
-                                    commands_to_batch.push(Command::perform(
-                                        save_settings(
-                                            self.config_path.clone(),
-                                            self.settings.clone(),
-                                        ),
-                                        Message::SettingsSaved,
-                                    ));
-                                }
-                                self.add_log("Процесс не запущен. Закрытие окна.".to_string());
-                                commands_to_batch.push(window::close(window::Id::MAIN));
-                            }
-                        } else {
-                            println!("[EventOccurred] Окно ID {:?} не является главным (MAIN). Игнорируем запрос.", id);
-                        }
-                    }
-                    // Обработка вставки из буфера обмена
-                    // Event::Keyboard(content) => {
-                    //     if self.show_settings {
-                    //         self.settings.api_key = content;
-                    //         commands_to_batch.push(Command::perform(
-                    //             save_settings(self.config_path.clone(), self.settings.clone()),
-                    //             Message::SettingsSaved,
-                    //         ));
-                    //         self.add_log("API ключ вставлен из буфера обмена.".to_string());
-                    //     }
-                    // }
-                    // Игнорируем остальные события окна и клавиатуры/мыши в этом глобальном обработчике
-                    _ => {}
+            Message::EnvVarValueChanged(index, value) => {
+                if let Some(var) = self.settings.env_vars.get_mut(index) {
+                    var.value = value;
+                    commands_to_batch.push(self.save_settings_command());
                 }
             }
-        }
-        // Возвращаем пакет команд для выполнения Iced
-        Command::batch(commands_to_batch)
-    }
-
-    // Настройка подписок на события
-    fn subscription(&self) -> Subscription<Self::Message> {
-        // Подписка на общие события Iced (для перехвата закрытия окна)
-        let window_events = event::listen().map(Message::EventOccurred);
-
-        // Подписка на события дочернего процесса (только если он запущен)
-        let process_subscription = if self.is_running {
-            // Проверяем наличие ID подписки, пути и ключа API
-            if let Some(id) = self.subscription_id {
-                if let Some(path) = self.settings.executable_path.clone() {
-                    if !self.settings.api_key.is_empty() {
-                        // Создаем подписку с помощью нашего ProcessListener
-                        Subscription::from_recipe(ProcessListener::new(
-                            id,
-                            path,
-                            self.settings.api_key.clone(),
-                        ))
-                    } else {
-                        Subscription::none() // Нет ключа API
+            Message::AddMetricRule => {
+                self.settings.metric_rules.push(MetricRule::default());
+                commands_to_batch.push(self.metric_rules_changed());
+            }
+            Message::RemoveMetricRule(index) => {
+                if index < self.settings.metric_rules.len() {
+                    let removed = self.settings.metric_rules.remove(index);
+                    for instance in &mut self.instances {
+                        instance.metrics.remove(removed.name.trim());
                     }
-                } else {
-                    Subscription::none() // Нет пути
+                    commands_to_batch.push(self.metric_rules_changed());
                 }
-            } else {
-                Subscription::none() // Нет ID подписки (не должно происходить, если is_running)
             }
-        } else {
-            Subscription::none() // Процесс не запущен
-        };
-
-        // Объединяем обе подписки в одну
-        Subscription::batch(vec![window_events, process_subscription])
-    }
-
-    // Отрисовка интерфейса приложения
-    fn view(&self) -> Element<Self::Message> {
-        // Выбираем, какую функцию отрисовки вызвать из модуля ui
-        let main_content = if self.show_settings {
-            // Передаем ссылку на настройки для отрисовки экрана настроек
-            ui::view_settings(&self.settings)
-        } else {
-            // Передаем флаг запуска, ссылку на логи и настройки для отрисовки главного экрана
-            ui::view_main(self.is_running, &self.logs, &self.settings)
-        };
+            Message::MetricRuleNameChanged(index, name) => {
+                if let Some(rule) = self.settings.metric_rules.get_mut(index) {
+                    rule.name = name;
+                    commands_to_batch.push(self.metric_rules_changed());
+                }
+            }
+            Message::MetricRulePatternChanged(index, pattern) => {
+                if let Some(rule) = self.settings.metric_rules.get_mut(index) {
+                    rule.pattern = pattern;
+                    commands_to_batch.push(self.metric_rules_changed());
+                }
+            }
+            Message::AddAlertRule => {
+                self.settings.alert_rules.push(AlertRule::default());
+                commands_to_batch.push(self.alert_rules_changed());
+            }
+            Message::RemoveAlertRule(index) => {
+                if index < self.settings.alert_rules.len() {
+                    self.settings.alert_rules.remove(index);
+                    commands_to_batch.push(self.alert_rules_changed());
+                }
+            }
+            Message::AlertMetricChanged(index, metric) => {
+                if let Some(rule) = self.settings.alert_rules.get_mut(index) {
+                    rule.metric = metric;
+                    commands_to_batch.push(self.alert_rules_changed());
+                }
+            }
+            Message::AlertComparisonChanged(index, comparison) => {
+                if let Some(rule) = self.settings.alert_rules.get_mut(index) {
+                    rule.comparison = comparison;
+                    commands_to_batch.push(self.alert_rules_changed());
+                }
+            }
+            Message::AlertThresholdChanged(index, threshold) => {
+                if let Some(rule) = self.settings.alert_rules.get_mut(index) {
+                    rule.threshold = threshold;
+                    commands_to_batch.push(self.alert_rules_changed());
+                }
+            }
+            Message::AlertHoldChanged(index, value) => {
+                let value = value.trim();
+                let minutes = if value.is_empty() {
+                    0
+                } else if let Ok(minutes) = value.parse::<u32>() {
+                    minutes
+                } else {
+                    return Command::none();
+                };
+                if let Some(rule) = self.settings.alert_rules.get_mut(index) {
+                    rule.hold_minutes = minutes;
+                    commands_to_batch.push(self.alert_rules_changed());
+                }
+            }
+            Message::AlertStopToggled(index, enabled) => {
+                if let Some(rule) = self.settings.alert_rules.get_mut(index) {
+                    rule.stop_process = enabled;
+                    commands_to_batch.push(self.alert_rules_changed());
+                }
+            }
+            Message::DismissNotification(id) => self.notifications.dismiss(id),
+            Message::CrashReportSaved(id, Ok(path)) => {
+                let index = self.instance_index(id).unwrap_or(self.selected_instance);
+                self.log_to(
+                    index,
+                    trf!("Отчет о сбое сохранен в {}.", format!("{:?}", path)),
+                );
+                let label = self.instance_label(index);
+                self.notifications.push_with_action(
+                    NotificationLevel::Critical,
+                    trf!(
+                        "{}Процесс завершился аварийно, отчет о сбое сохранен.",
+                        label
+                    ),
+                    path.parent()
+                        .map(|dir| NotificationAction::OpenFolder(dir.to_path_buf())),
+                );
+            }
+            Message::CrashReportSaved(id, Err(e)) => {
+                let index = self.instance_index(id).unwrap_or(self.selected_instance);
+                self.log_to(index, trf!("Не удалось сохранить отчет о сбое: {}", e));
+            }
+            Message::OpenLogFolderPressed => {
+                let Some(data_dir) = self.data_dir.as_deref() else {
+                    self.add_log(
+                        tr("Директория данных недоступна: логи не сохраняются.").to_string(),
+                    );
+                    return Command::none();
+                };
+                // До первого запуска директории логов может еще не быть
+                let dir = logs_dir(data_dir);
+                return Command::perform(
+                    async move {
+                        tokio::fs::create_dir_all(&dir).await.map_err(|e| {
+                            trf!("Не удалось создать {}: {}", format!("{:?}", dir), e)
+                        })?;
+                        open_folder(dir).await
+                    },
+                    Message::FolderOpened,
+                );
+            }
+            Message::OpenFolderPressed(dir) => {
+                return Command::perform(open_folder(dir), Message::FolderOpened);
+            }
+            Message::FolderOpened(Ok(())) => {}
+            Message::FolderOpened(Err(e)) => self.add_log(e),
+            Message::OpenUrlPressed(url) => {
+                return Command::perform(links::open_url(url), Message::UrlOpened);
+            }
+            Message::UrlOpened(Ok(())) => {}
+            Message::UrlOpened(Err(e)) => self.add_log(e),
+            Message::ExportHistoryPressed(format) => {
+                return Command::perform(
+                    save_history_export(self.run_history.clone(), format),
+                    Message::HistoryExported,
+                );
+            }
+            Message::HistoryExported(Ok(Some(path))) => {
+                self.add_log(trf!(
+                    "История запусков экспортирована в {}.",
+                    format!("{:?}", path)
+                ));
+            }
+            Message::HistoryExported(Ok(None)) => {}
+            Message::HistoryExported(Err(e)) => {
+                self.add_log(trf!("Ошибка экспорта истории запусков: {}", e));
+            }
+            Message::CollectDiagnosticsPressed => {
+                return Command::perform(
+                    save_diagnostics_bundle(self.diagnostics_input()),
+                    Message::DiagnosticsSaved,
+                );
+            }
+            Message::DiagnosticsSaved(Ok(Some(path))) => {
+                self.add_log(trf!(
+                    "Пакет диагностики сохранен в {}.",
+                    format!("{:?}", path)
+                ));
+            }
+            Message::DiagnosticsSaved(Ok(None)) => {}
+            Message::DiagnosticsSaved(Err(e)) => {
+                self.add_log(trf!("Ошибка сборки пакета диагностики: {}", e));
+            }
+            Message::ExportRulePackPressed => {
+                let pack = RulePack {
+                    version: RULE_PACK_VERSION,
+                    highlight_rules: self.settings.highlight_rules.clone(),
+                    metric_rules: self.settings.metric_rules.clone(),
+                    alert_rules: self.settings.alert_rules.clone(),
+                    keyword_alerts: self.settings.keyword_alerts.clone(),
+                };
+                return Command::perform(export_rule_pack(pack), Message::RulePackExported);
+            }
+            Message::RulePackExported(Ok(Some(path))) => {
+                self.add_log(trf!(
+                    "Набор правил экспортирован в {}.",
+                    format!("{:?}", path)
+                ));
+            }
+            Message::RulePackExported(Ok(None)) => {}
+            Message::RulePackExported(Err(e)) => {
+                self.add_log(trf!("Ошибка экспорта набора правил: {}", e));
+            }
+            Message::ImportRulePackPressed => {
+                return Command::perform(import_rule_pack(), Message::RulePackImported);
+            }
+            Message::RulePackImported(Ok(Some(pack))) => {
+                let summary = pack.merge_into(
+                    &mut self.settings.highlight_rules,
+                    &mut self.settings.metric_rules,
+                    &mut self.settings.alert_rules,
+                    &mut self.settings.keyword_alerts,
+                );
+                self.add_log(trf!("Импорт набора правил: добавлено {}, пропущено {} (уже есть или метрика с тем же именем).", summary.added, summary.skipped));
+                if summary.added > 0 {
+                    for instance in &mut self.instances {
+                        instance.alert_states.clear();
+                    }
+                    self.compile_highlight_rules();
+                    commands_to_batch.push(self.metric_rules_changed());
+                }
+            }
+            Message::RulePackImported(Ok(None)) => {}
+            Message::RulePackImported(Err(e)) => {
+                self.add_log(trf!("Ошибка импорта набора правил: {}", e));
+            }
+            Message::AddHighlightRule => {
+                self.settings.highlight_rules.push(HighlightRule::default());
+                commands_to_batch.push(self.highlight_rules_changed());
+            }
+            Message::RemoveHighlightRule(index) => {
+                if index < self.settings.highlight_rules.len() {
+                    self.settings.highlight_rules.remove(index);
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::MoveHighlightRuleUp(index) => {
+                if index > 0 && index < self.settings.highlight_rules.len() {
+                    self.settings.highlight_rules.swap(index - 1, index);
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::MoveHighlightRuleDown(index) => {
+                if index + 1 < self.settings.highlight_rules.len() {
+                    self.settings.highlight_rules.swap(index, index + 1);
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::HighlightKeywordChanged(index, keyword) => {
+                if let Some(rule) = self.settings.highlight_rules.get_mut(index) {
+                    rule.keyword = keyword;
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::HighlightColorChanged(index, color) => {
+                if let Some(rule) = self.settings.highlight_rules.get_mut(index) {
+                    rule.color = color;
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::HighlightRegexToggled(index, enabled) => {
+                if let Some(rule) = self.settings.highlight_rules.get_mut(index) {
+                    rule.regex = enabled;
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::HighlightBoldToggled(index, enabled) => {
+                if let Some(rule) = self.settings.highlight_rules.get_mut(index) {
+                    rule.bold = enabled;
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::HighlightBackgroundToggled(index, enabled) => {
+                if let Some(rule) = self.settings.highlight_rules.get_mut(index) {
+                    rule.background = enabled;
+                    commands_to_batch.push(self.highlight_rules_changed());
+                }
+            }
+            Message::AddKeywordAlert => {
+                self.settings
+                    .keyword_alerts
+                    .push(KeywordAlertRule::default());
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::RemoveKeywordAlert(index) => {
+                if index < self.settings.keyword_alerts.len() {
+                    self.settings.keyword_alerts.remove(index);
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::KeywordAlertChanged(index, keyword) => {
+                if let Some(rule) = self.settings.keyword_alerts.get_mut(index) {
+                    rule.keyword = keyword;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::KeywordAlertBannerToggled(index, enabled) => {
+                if let Some(rule) = self.settings.keyword_alerts.get_mut(index) {
+                    rule.banner = enabled;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::KeywordAlertSoundToggled(index, enabled) => {
+                if let Some(rule) = self.settings.keyword_alerts.get_mut(index) {
+                    rule.sound = enabled;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::KeywordAlertDesktopToggled(index, enabled) => {
+                if let Some(rule) = self.settings.keyword_alerts.get_mut(index) {
+                    rule.desktop = enabled;
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::DesktopNotifyCrashToggled(enabled) => {
+                self.settings.desktop_notify_crash = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::DesktopNotifyExitToggled(enabled) => {
+                self.settings.desktop_notify_exit = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::DesktopNotificationShown(Ok(())) => {}
+            Message::DesktopNotificationShown(Err(e)) => self.add_log(e),
+            Message::KeywordAlertSignalled(Ok(())) => {}
+            Message::KeywordAlertSignalled(Err(e)) => {
+                self.add_log(trf!("Оповещение по ключевой фразе: {}", e));
+            }
+            Message::LogTimeFilterSelected(filter) => self.log_filter = filter,
+            Message::LogRangeFromChanged(value) => self.log_range_from = value,
+            Message::LogRangeToChanged(value) => self.log_range_to = value,
+            Message::LogSearchChanged(query) => {
+                self.log_search = query;
+                self.log_search_position = 0;
+                self.highlighted_log_line = None;
+                // Сразу показываем самое свежее совпадение
+                if let Some(&id) = self.log_search_matches().first() {
+                    return self.update(Message::JumpToLogLine(id));
+                }
+            }
+            Message::LogScrolled(offset) => {
+                // Прокрутка от новых строк ставит лог на паузу, возврат к ним - снимает
+                // (если пауза не включена кнопкой)
+                let at_newest = offset < 1.0;
+                if !at_newest && self.log_frozen_at.is_none() {
+                    self.log_frozen_at = Some(self.next_log_id.saturating_sub(1));
+                } else if at_newest && self.log_follow && self.highlighted_log_line.is_none() {
+                    self.log_frozen_at = None;
+                }
+            }
+            Message::LogFollowToggled => {
+                if self.log_frozen_at.is_none() {
+                    self.log_frozen_at = Some(self.next_log_id.saturating_sub(1));
+                    self.log_follow = false;
+                } else {
+                    self.log_frozen_at = None;
+                    self.log_follow = true;
+                    self.highlighted_log_line = None;
+                    return scrollable::snap_to(
+                        ui::log_scroll_id(),
+                        scrollable::RelativeOffset::START,
+                    );
+                }
+            }
+            Message::JumpToNewest => {
+                // Возврат к новым строкам снимает паузу, как кнопка "Продолжить"
+                self.log_frozen_at = None;
+                self.log_follow = true;
+                self.highlighted_log_line = None;
+                return scrollable::snap_to(ui::log_scroll_id(), scrollable::RelativeOffset::START);
+            }
+            Message::JumpToOldest => {
+                // Лог ставится на паузу, чтобы новые строки не сдвигали старые
+                if self.log_frozen_at.is_none() {
+                    self.log_frozen_at = Some(self.next_log_id.saturating_sub(1));
+                }
+                return scrollable::snap_to(ui::log_scroll_id(), scrollable::RelativeOffset::END);
+            }
+            Message::LogBookmarkToggled(id) => match self.log_bookmarks.binary_search(&id) {
+                Ok(position) => {
+                    self.log_bookmarks.remove(position);
+                }
+                Err(position) => self.log_bookmarks.insert(position, id),
+            },
+            Message::NextBookmark | Message::PreviousBookmark => {
+                // Закладки в порядке отображения: от новых строк к старым
+                let bookmarks = self.current_bookmarks();
+                let current = self.highlighted_log_line;
+                let target = if matches!(message, Message::NextBookmark) {
+                    bookmarks
+                        .iter()
+                        .find(|id| current.is_none_or(|current| **id < current))
+                        .or(bookmarks.first())
+                } else {
+                    bookmarks
+                        .iter()
+                        .rev()
+                        .find(|id| current.is_none_or(|current| **id > current))
+                        .or(bookmarks.last())
+                };
+                if let Some(id) = target.copied() {
+                    return self.update(Message::JumpToLogLine(id));
+                }
+            }
+            Message::ClearBookmarks => {
+                let bookmarks = self.current_bookmarks();
+                self.log_bookmarks.retain(|id| !bookmarks.contains(id));
+            }
+            Message::LogLevelToggled(level) => {
+                if let Some(position) = self.hidden_log_levels.iter().position(|l| *l == level) {
+                    self.hidden_log_levels.remove(position);
+                } else {
+                    self.hidden_log_levels.push(level);
+                }
+                self.log_search_position = 0;
+            }
+            Message::LogStreamFilterSelected(stream) => {
+                self.log_stream = stream;
+                self.log_search_position = 0;
+            }
+            Message::LogJsonViewToggled(enabled) => {
+                self.log_json_view = enabled;
+                self.expanded_log_lines.clear();
+            }
+            Message::LogRawViewToggled(enabled) => {
+                self.log_raw_view = enabled;
+            }
+            Message::LogLineExpandToggled(id) => {
+                if let Some(position) = self.expanded_log_lines.iter().position(|l| *l == id) {
+                    self.expanded_log_lines.remove(position);
+                } else {
+                    self.expanded_log_lines.push(id);
+                }
+            }
+            Message::LogSearchNext | Message::LogSearchPrevious => {
+                let matches = self.log_search_matches();
+                if matches.is_empty() {
+                    return Command::none();
+                }
+                // Если к совпадению еще не переходили, начинаем с текущего
+                let step = match message {
+                    _ if self.highlighted_log_line.is_none() => 0,
+                    Message::LogSearchNext => 1,
+                    _ => matches.len() - 1,
+                };
+                self.log_search_position = (self.log_search_position + step) % matches.len();
+                return self.update(Message::JumpToLogLine(matches[self.log_search_position]));
+            }
+            Message::LogCompressDaysChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.log_compress_after_days = 0;
+                } else if let Ok(days) = value.parse::<u32>() {
+                    self.settings.log_compress_after_days = days;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::JumpToLogLine(id) => {
+                // Лог отображается от новых к старым, поэтому позиция считается с конца.
+                // Строка может быть в логе другого экземпляра - тогда переключаемся на него.
+                // Если уровень или поток строки скрыт, снова показываем все уровни и потоки.
+                if let Some(line) = self
+                    .instances
+                    .iter()
+                    .flat_map(|instance| instance.logs.iter())
+                    .find(|line| line.id == id)
+                {
+                    if !line.is_visible(&self.hidden_log_levels, self.log_stream) {
+                        self.hidden_log_levels.clear();
+                        self.log_stream = LogStreamFilter::Both;
+                    }
+                }
+                // Во время перехода лог ставится на паузу, чтобы новые строки не сдвигали его
+                let frozen = match self.log_frozen_at {
+                    Some(last) if last >= id => last,
+                    _ => self.next_log_id.saturating_sub(1),
+                };
+                let hidden = &self.hidden_log_levels;
+                let stream = self.log_stream;
+                let found = self
+                    .instances
+                    .iter()
+                    .enumerate()
+                    .find_map(|(index, instance)| {
+                        let visible: Vec<&ui::LogLine> = instance
+                            .logs
+                            .iter()
+                            .rev()
+                            .filter(|line| line.id <= frozen && line.is_visible(hidden, stream))
+                            .collect();
+                        visible
+                            .iter()
+                            .position(|line| line.id == id)
+                            .map(|position| (index, position, visible.len()))
+                    });
+                match found {
+                    Some((index, position, len)) => {
+                        self.selected_instance = index;
+                        self.log_filter = LogTimeFilter::All;
+                        self.highlighted_log_line = Some(id);
+                        self.log_frozen_at = Some(frozen);
+                        let last = len.saturating_sub(1).max(1);
+                        return scrollable::snap_to(
+                            ui::log_scroll_id(),
+                            scrollable::RelativeOffset {
+                                x: 0.0,
+                                y: position as f32 / last as f32,
+                            },
+                        );
+                    }
+                    None => self.add_log(
+                        tr("Строка события уже вытеснена из окна лога - см. файл лога сессии.")
+                            .to_string(),
+                    ),
+                }
+            }
+            Message::LogForwardToggled(enabled) => {
+                self.settings.log_forward_enabled = enabled;
+                if !enabled {
+                    self.log_forwarder = None;
+                    self.log_forward_status = None;
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogForwardAddressChanged(address) => {
+                self.settings.log_forward_address = address;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogForwardFormatChanged(format) => {
+                self.settings.log_forward_format = format;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogForwardTlsToggled(enabled) => {
+                self.settings.log_forward_tls = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::SelectWorkingDir => {
+                return Command::perform(select_working_dir(), Message::WorkingDirSelected);
+            }
+            Message::WorkingDirSelected(Some(dir)) => {
+                self.settings.working_dir = Some(dir);
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::WorkingDirSelected(None) => {}
+            Message::ResetWorkingDir => {
+                if self.settings.working_dir.take().is_some() {
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::SelectDataDir => {
+                return Command::perform(select_data_dir(), Message::DataDirSelected);
+            }
+            Message::DataDirSelected(Some(dir)) => {
+                return self.change_data_dir(Some(dir));
+            }
+            Message::DataDirSelected(None) => {}
+            Message::ResetDataDir => {
+                if self.settings.data_dir.is_some() {
+                    return self.change_data_dir(None);
+                }
+            }
+            Message::DataDirMigrated(custom_dir, Ok(moved)) => {
+                self.settings.data_dir = custom_dir;
+                self.data_dir = resolve_data_dir(self.settings.data_dir.as_deref());
+                // Перенос уже проверил доступ на запись в новую директорию
+                self.data_dir_error = None;
+                self.add_log(trf!(
+                    "Директория данных изменена на {} (перенесено файлов: {}).",
+                    format!("{:?}", self.data_dir),
+                    moved
+                ));
+                if self
+                    .instances
+                    .iter()
+                    .any(|instance| instance.session_log.is_some())
+                {
+                    self.add_log(
+                        tr("Лог текущей сессии остается в прежней директории до следующего запуска.")
+                            .to_string(),
+                    );
+                }
+                commands_to_batch.push(self.save_settings_command());
+                // История в памяти - актуальная, записываем ее в новую директорию
+                commands_to_batch.push(self.save_history_command());
+            }
+            Message::DataDirMigrated(_, Err(e)) => {
+                self.add_log(trf!("Не удалось сменить директорию данных: {}", e));
+            }
+            Message::DataDirChecked(result) => {
+                if let Err(e) = &result {
+                    self.add_log(trf!("Директория данных недоступна: {}", e));
+                }
+                self.data_dir_error = result.err();
+            }
+            Message::LogQuotaChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.log_dir_quota_mb = 0;
+                } else if let Ok(quota) = value.parse::<u32>() {
+                    self.settings.log_dir_quota_mb = quota;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::SessionLogMaxChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.session_log_max_mb = 0;
+                } else if let Ok(megabytes) = value.parse::<u32>() {
+                    self.settings.session_log_max_mb = megabytes;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::SessionLogKeepAnsiToggled(enabled) => {
+                self.settings.session_log_keep_ansi = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogTimestampsToggled(enabled) => {
+                self.settings.log_timestamps = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogTimeFormatChanged(format) => {
+                self.settings.log_time_format = format;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::CopyLogsPressed => {
+                // Собираем все сегменты всех строк лога в единый текст
+                let log_text = self
+                    .current()
+                    .logs
+                    .iter()
+                    .rev() // Итерируем от новых к старым
+                    .map(|line| match self.settings.log_time_prefix(line.time) {
+                        Some(time) => format!("{} {}", time, line.labeled_text()),
+                        None => line.labeled_text(),
+                    })
+                    .collect::<Vec<String>>() // Собираем все строки в Vec<String>
+                    .join("\n"); // Объединяем строки через перевод строки
+
+                if !log_text.is_empty() {
+                    // Записываем собранный текст в буфер обмена
+                    commands_to_batch.push(clipboard::write(log_text));
+                    self.add_log(tr("Логи скопированы в буфер обмена.").to_string());
+                } else {
+                    self.add_log(tr("Нет логов для копирования.").to_string());
+                }
+            }
+
+            Message::LogLineClicked(id) => {
+                self.log_selection = match self.log_selection {
+                    Some((anchor, _)) if self.keyboard_modifiers.shift() => Some((anchor, id)),
+                    // Повторный клик по единственной выделенной строке снимает выделение
+                    Some((anchor, end)) if anchor == id && end == id => None,
+                    _ => Some((id, id)),
+                };
+            }
+            Message::CopySelectedPressed => {
+                let Some((anchor, end)) = self.log_selection else {
+                    return Command::none();
+                };
+                let range = anchor.min(end)..=anchor.max(end);
+                let selected_text = self
+                    .current()
+                    .logs
+                    .iter()
+                    .filter(|line| range.contains(&line.id))
+                    .map(|line| match self.settings.log_time_prefix(line.time) {
+                        Some(time) => format!("{} {}", time, line.labeled_text()),
+                        None => line.labeled_text(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                if !selected_text.is_empty() {
+                    self.log_selection = None;
+                    commands_to_batch.push(clipboard::write(selected_text));
+                    self.add_log(tr("Выделенные строки скопированы в буфер обмена.").to_string());
+                }
+            }
+            Message::ClearLogPressed => {
+                self.clear_log_pending = true;
+            }
+            Message::CancelClearLog => {
+                self.clear_log_pending = false;
+            }
+            Message::ConfirmClearLog(new_file) => {
+                self.clear_log_pending = false;
+                let index = self.selected_instance;
+                let instance = &mut self.instances[index];
+                instance.logs.clear();
+                instance.progress_line = None;
+                self.highlighted_log_line = None;
+                self.log_search_position = 0;
+                self.log_selection = None;
+                self.log_frozen_at = None;
+                self.add_log(tr("Лог очищен.").to_string());
+                if new_file {
+                    self.start_new_session_log(index);
+                    if let Some(path) = self.instances[index]
+                        .session_log
+                        .as_ref()
+                        .map(|log| log.path().clone())
+                    {
+                        self.add_log(trf!("Новый файл лога сессии: {}", path.display()));
+                    }
+                }
+            }
+            Message::ExportLogPressed => {
+                // В файле строки идут от старых к новым, как в логе сессии
+                let keep_ansi = self.settings.log_export_keep_ansi;
+                let content: String = self
+                    .current()
+                    .logs
+                    .iter()
+                    .map(|line| {
+                        let text = if keep_ansi {
+                            line.ansi_text()
+                        } else {
+                            line.labeled_text()
+                        };
+                        match self.settings.log_time_prefix(line.time) {
+                            Some(time) => format!("{} {}\n", time, text),
+                            None => format!("{}\n", text),
+                        }
+                    })
+                    .collect();
+                if content.is_empty() {
+                    self.add_log(tr("Нет логов для экспорта.").to_string());
+                } else {
+                    return Command::perform(
+                        save_log_export(
+                            content,
+                            self.settings.instance_name(self.selected_instance),
+                        ),
+                        Message::LogExported,
+                    );
+                }
+            }
+            Message::LogExported(Ok(Some(path))) => {
+                self.add_log(trf!("Лог сохранен в {}.", format!("{:?}", path)));
+            }
+            Message::LogExported(Ok(None)) => {}
+            Message::LogExported(Err(e)) => {
+                self.add_log(trf!("Ошибка экспорта лога: {}", e));
+            }
+            Message::LogBufferLinesChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.log_buffer_lines = 0;
+                } else if let Ok(lines) = value.parse::<u32>() {
+                    self.settings.log_buffer_lines = lines;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogExportKeepAnsiToggled(enabled) => {
+                self.settings.log_export_keep_ansi = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::TintStderrToggled(enabled) => {
+                self.settings.tint_stderr = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogGutterToggled(enabled) => {
+                self.settings.log_gutter = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogFontSizeChanged(value) => {
+                let value = value.trim();
+                if value.is_empty() {
+                    self.settings.log_font_size = 0;
+                } else if let Ok(size) = value.parse::<u16>() {
+                    self.settings.log_font_size = size;
+                } else {
+                    return Command::none();
+                }
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::LogZoomIn | Message::LogZoomOut | Message::LogZoomReset => {
+                let size = self.settings.log_font_size();
+                self.settings.log_font_size = match message {
+                    Message::LogZoomIn => (size + 1).min(LOG_FONT_SIZE_MAX),
+                    Message::LogZoomOut => (size - 1).max(LOG_FONT_SIZE_MIN),
+                    _ => DEFAULT_LOG_FONT_SIZE,
+                };
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::RestorePreviousLogToggled(enabled) => {
+                self.settings.restore_previous_log = enabled;
+                commands_to_batch.push(self.save_settings_command());
+            }
+
+            // --- Обработка событий выбора файла ---
+            Message::ExecutablePathSelected(Ok(Some(path))) => {
+                // Путь выбран: определяем реальный исполняемый файл (пакет .app на macOS),
+                // обновляем настройки и сохраняем
+                match resolve_executable(&path) {
+                    Ok(resolved) => {
+                        if resolved != path {
+                            self.add_log(trf!(
+                                "Выбран пакет {}, исполняемый файл: {}",
+                                format!("{:?}", path),
+                                format!("{:?}", resolved)
+                            ));
+                        } else {
+                            self.add_log(trf!("Выбран путь: {}", format!("{:?}", resolved)));
+                        }
+                        self.settings.executable_path = Some(resolved);
+                        if let Some(problem) = self.check_executable() {
+                            self.add_log(trf!("Выбранный файл не подходит: {}", problem));
+                        }
+                        commands_to_batch.push(self.save_settings_command());
+                    }
+                    Err(e) => {
+                        self.add_log(trf!("Ошибка выбора файла: {}", e));
+                    }
+                }
+            }
+            Message::ExecutablePathSelected(Ok(None)) => {
+                // Выбор файла отменен
+                self.add_log(tr("Выбор файла отменен.").to_string());
+            }
+            Message::ExecutablePathSelected(Err(e)) => {
+                // Ошибка выбора файла
+                eprintln!("Ошибка выбора файла: {}", e);
+                self.add_log(trf!("Ошибка выбора файла: {}", e));
+            }
+
+            // --- Обработка событий загрузки/сохранения настроек ---
+            Message::SettingsLoaded(Ok(loaded)) if loaded.password_required => {
+                // Настройки загрузятся после ввода пароля; до этого файл не перезаписывается
+                self.settings_locked = true;
+                self.add_log(tr("Файл настроек зашифрован. Введите пароль.").to_string());
+            }
+            Message::SettingsLoaded(Ok(loaded)) => {
+                self.settings_locked = false;
+                let LoadedSettings {
+                    settings,
+                    migrated,
+                    recovered,
+                    notes,
+                    ..
+                } = *loaded;
+                self.set_file_settings(settings);
+                commands_to_batch.push(self.window_badge_command());
+                // Экземпляры из настроек (основной уже есть)
+                while self.instances.len() < self.settings.instance_count() {
+                    self.push_instance();
+                }
+                self.add_log(tr("Настройки успешно загружены.").to_string());
+                for note in notes {
+                    self.add_log(note);
+                }
+                // Файл старого формата (или поврежденный) сразу перезаписываем
+                if migrated || recovered {
+                    commands_to_batch.push(self.save_settings_command());
+                }
+                // Компилируем правила метрик и подсветки из настроек
+                self.compile_metric_rules();
+                self.compile_highlight_rules();
+                if let Some(problem) = self.check_executable() {
+                    self.add_log(trf!("Исполняемый файл из настроек: {}", problem));
+                }
+                // Загружаем историю запусков из директории данных из настроек
+                self.data_dir = resolve_data_dir(self.settings.data_dir.as_deref());
+                commands_to_batch.push(Command::perform(
+                    load_history(self.data_dir.as_deref().map(history_path)),
+                    Message::HistoryLoaded,
+                ));
+                // Проверяем, что в директорию данных можно писать
+                commands_to_batch.push(self.check_data_dir_command());
+                // Восстанавливаем рабочее пространство прошлого сеанса
+                commands_to_batch.push(Command::perform(
+                    load_workspace(self.data_dir.as_deref().map(workspace_path)),
+                    Message::WorkspaceLoaded,
+                ));
+                // Восстанавливаем окна логов из файлов прошлого сеанса
+                if self.settings.restore_previous_log {
+                    if let Some(dir) = self.data_dir.as_deref().map(logs_dir) {
+                        for index in 0..self.instances.len() {
+                            commands_to_batch.push(Command::perform(
+                                read_previous_session(
+                                    dir.clone(),
+                                    index,
+                                    self.settings.log_buffer_limit(),
+                                ),
+                                move |result| Message::PreviousSessionLoaded(index, result),
+                            ));
+                        }
+                    }
+                }
+                // Сжимаем старые логи сессий и проверяем квоту
+                commands_to_batch.push(self.log_maintenance_command());
+                // Проверяем системные часы по NTP-серверу из настроек
+                commands_to_batch.push(Command::perform(
+                    measure_clock_offset(self.settings.ntp_server.clone()),
+                    Message::ClockOffsetMeasured,
+                ));
+                // Загружаем календарь обслуживания биржи, если он задан
+                if !self.settings.maintenance_ics_url.trim().is_empty() {
+                    commands_to_batch.push(Command::perform(
+                        fetch_maintenance_windows(self.settings.maintenance_ics_url.clone()),
+                        Message::MaintenanceCalendarLoaded,
+                    ));
+                }
+                // Проверяем, работают ли еще процессы прошлого сеанса
+                for index in 0..self.instances.len() {
+                    if let Some(last_pid) = self.settings.instance_last_pid(index) {
+                        self.log_to(
+                            index,
+                            trf!(
+                                "Обнаружен PID ({}) от предыдущего сеанса. Проверка процесса...",
+                                last_pid
+                            ),
+                        );
+                        let id = self.instances[index].id;
+                        commands_to_batch.push(Command::perform(
+                            find_orphan_process(last_pid, self.settings.executable_path.clone()),
+                            move |orphan| Message::OrphanChecked(id, orphan),
+                        ));
+                    }
+                }
+            }
+            Message::PreviousSessionLoaded(index, Ok(Some(previous))) => {
+                // Процесс мог успеть запуститься и начать новый лог - тогда старый не нужен
+                if index < self.instances.len()
+                    && self.instances[index].session_log.is_none()
+                    && !previous.lines.is_empty()
+                {
+                    self.log_to(
+                        index,
+                        trf!(
+                            "--- Лог предыдущего сеанса ({}) ---",
+                            previous.path.display()
+                        ),
+                    );
+                    // Строки уже есть в файле - добавляем только в окно лога
+                    for (time, line) in previous.lines {
+                        match line.strip_prefix(LogSource::Stderr.text_prefix()) {
+                            Some(text) => {
+                                self.push_line(index, time, LogSource::Stderr, text.to_string())
+                            }
+                            None => self.push_line(index, time, LogSource::Stdout, line),
+                        };
+                    }
+                    self.log_to(
+                        index,
+                        tr("--- Конец лога предыдущего сеанса ---").to_string(),
+                    );
+                }
+            }
+            Message::PreviousSessionLoaded(_, Ok(None)) => {}
+            Message::PreviousSessionLoaded(index, Err(e)) => {
+                if index < self.instances.len() {
+                    self.log_to(
+                        index,
+                        trf!("Не удалось восстановить лог предыдущего сеанса: {}", e),
+                    );
+                }
+            }
+            Message::HistoryLoaded(Ok(history)) => {
+                // Запуски, завершившиеся до загрузки (маловероятно), идут после загруженных
+                let finished_early = std::mem::replace(&mut self.run_history, history);
+                self.run_history.extend(finished_early);
+            }
+            Message::HistoryLoaded(Err(e)) => {
+                self.add_log(trf!("Ошибка загрузки истории запусков: {}", e));
+            }
+            Message::WorkspaceLoaded(Ok(workspace)) => {
+                self.screen = workspace.screen;
+                self.log_filter = workspace.log_filter;
+                self.log_range_from = workspace.log_range_from.clone();
+                self.log_range_to = workspace.log_range_to.clone();
+                self.hidden_log_levels = workspace.hidden_log_levels.clone();
+                self.log_stream = workspace.log_stream;
+                self.log_json_view = workspace.log_json_view;
+                self.log_raw_view = workspace.log_raw_view;
+                // Обычно окно уже открыто с этими размерами (см. main), но директорию данных
+                // из зашифрованных настроек до ввода пароля узнать нельзя
+                if let Some(geometry) = workspace.window {
+                    commands_to_batch.push(window::resize(
+                        window::Id::MAIN,
+                        iced::Size::new(geometry.width as f32, geometry.height as f32),
+                    ));
+                    if let (Some(x), Some(y)) = (geometry.x, geometry.y) {
+                        commands_to_batch.push(window::move_to(
+                            window::Id::MAIN,
+                            iced::Point::new(x as f32, y as f32),
+                        ));
+                    }
+                    self.window_geometry = Some(geometry);
+                }
+                self.selected_instance = workspace.selected_instance.min(self.instances.len() - 1);
+                let count = self.instances.len();
+                let was_running: Vec<usize> = workspace
+                    .running_instances
+                    .iter()
+                    .copied()
+                    .chain(workspace.was_running.then_some(0))
+                    .filter(|index| *index < count)
+                    .collect();
+                let mut resumed = Vec::new();
+                for index in was_running {
+                    if !self.settings.resume_workspace || self.instances[index].is_running {
+                        continue;
+                    }
+                    self.log_to(
+                        index,
+                        tr("Процесс работал при закрытии лаунчера - возобновляем.").to_string(),
+                    );
+                    commands_to_batch.push(self.resume_instance(index));
+                    resumed.push(index);
+                }
+                commands_to_batch.push(self.autostart_command(&resumed));
+                self.saved_workspace = Some(workspace);
+            }
+            Message::WorkspaceLoaded(Err(e)) => {
+                self.add_log(trf!("Ошибка загрузки рабочего пространства: {}", e));
+                self.saved_workspace = Some(Workspace::default());
+                commands_to_batch.push(self.autostart_command(&[]));
+            }
+            Message::WorkspaceSaved(Ok(())) => {}
+            Message::WorkspaceSaved(Err(e)) => {
+                eprintln!("Ошибка сохранения рабочего пространства: {}", e);
+            }
+            Message::HistorySaved(Ok(())) => {}
+            Message::HistorySaved(Err(e)) => {
+                eprintln!("Ошибка сохранения истории запусков: {}", e);
+            }
+            Message::SettingsLoaded(Err(e)) => {
+                eprintln!("Ошибка загрузки настроек: {}", e);
+                self.add_log(trf!("Ошибка загрузки настроек: {}", e));
+                self.set_file_settings(AppSettings::default());
+                // В случае ошибки загрузки, last_pid будет None по умолчанию
+            }
+            Message::SettingsFileChanged => {
+                // Пока настройки не открыты паролем, перечитывать нечего
+                if self.settings_locked {
+                    return Command::none();
+                }
+                return Command::perform(
+                    load_settings(self.config_path.clone(), self.settings_cipher.clone()),
+                    |result| Message::SettingsReloaded(result.map(Box::new)),
+                );
+            }
+            Message::SettingsReloaded(Ok(loaded)) => {
+                // Собственные сохранения и удаление файла не перезагружают настройки
+                if loaded.content.is_empty() || self.recent_saves.contains(&loaded.content) {
+                    return Command::none();
+                }
+                // Файл мог быть прочитан посреди записи - предыдущая копия тут не нужна
+                if loaded.recovered {
+                    self.add_log(
+                        tr("Файл настроек изменен, но не прочитан. Действуют прежние настройки.")
+                            .to_string(),
+                    );
+                    return Command::none();
+                }
+                // Файл зашифровали другим паролем (или в другом экземпляре лаунчера)
+                if loaded.password_required {
+                    self.add_log(
+                        tr("Файл настроек зашифрован другой программой. Действуют прежние настройки.")
+                            .to_string(),
+                    );
+                    return Command::none();
+                }
+                commands_to_batch.push(self.apply_reloaded_settings(
+                    *loaded,
+                    tr("Файл настроек изменен другой программой - настройки перезагружены."),
+                ));
+            }
+            Message::SettingsReloaded(Err(e)) => {
+                // Файл мог быть прочитан посреди записи - действующие настройки не меняем
+                self.add_log(trf!(
+                    "Файл настроек изменен, но не прочитан: {}. Действуют прежние настройки.",
+                    e
+                ));
+            }
+            Message::SettingsBackupsListed(Ok(backups)) => {
+                // Выбранная копия могла быть удалена при очистке старых
+                if let Some(selected) = &self.selected_settings_backup {
+                    if !backups.contains(selected) {
+                        self.selected_settings_backup = None;
+                    }
+                }
+                self.settings_backups = backups;
+            }
+            Message::SettingsBackupsListed(Err(e)) => {
+                self.add_log(trf!("Не удалось получить список копий настроек: {}", e));
+            }
+            Message::SettingsBackupSelected(backup) => self.selected_settings_backup = Some(backup),
+            Message::RestoreSettingsBackup => {
+                if let Some(backup) = self.selected_settings_backup.clone() {
+                    return Command::perform(
+                        restore_settings_backup(
+                            self.config_path.clone(),
+                            backup.path,
+                            self.settings_cipher.clone(),
+                        ),
+                        |result| Message::SettingsBackupRestored(result.map(Box::new)),
+                    );
+                }
+            }
+            Message::SettingsBackupRestored(Ok(loaded)) => {
+                let description = match &self.selected_settings_backup {
+                    Some(backup) => trf!("Восстановлены настройки из копии от {}.", backup),
+                    None => tr("Настройки восстановлены из копии.").to_string(),
+                };
+                commands_to_batch.push(self.apply_reloaded_settings(*loaded, &description));
+                // Восстановленные настройки становятся действующими и в файле
+                commands_to_batch.push(self.save_settings_command());
+                commands_to_batch.push(self.list_settings_backups_command());
+            }
+            Message::SettingsBackupRestored(Err(e)) => {
+                self.add_log(trf!("Не удалось восстановить настройки: {}", e));
+            }
+            Message::ResetSettingsPressed => self.reset_settings_pending = true,
+            Message::CancelResetSettings => self.reset_settings_pending = false,
+            Message::ConfirmResetSettings => {
+                self.reset_settings_pending = false;
+                // По умолчанию экземпляр один: дополнительные сначала нужно остановить
+                if let Some(index) =
+                    (1..self.instances.len()).find(|&i| self.instances[i].is_busy())
+                {
+                    self.add_log(trf!("Настройки не сброшены: работает экземпляр \"{}\". Остановите его и повторите сброс.", self.settings.instance_name(index)));
+                    return Command::none();
+                }
+                let defaults = LoadedSettings {
+                    settings: AppSettings::default(),
+                    migrated: false,
+                    recovered: false,
+                    password_required: false,
+                    notes: Vec::new(),
+                    content: String::new(),
+                };
+                commands_to_batch.push(self.apply_reloaded_settings(
+                    defaults,
+                    tr("Все настройки сброшены к значениям по умолчанию."),
+                ));
+                // Прежние настройки остаются в резервной копии
+                self.last_settings_backup = None;
+                commands_to_batch.push(self.save_settings_command());
+            }
+            Message::SettingsPasswordChanged(password) => self.settings_password_input = password,
+            Message::SettingsPasswordSubmitted => {
+                if self.settings_password_pending || self.settings_password_input.is_empty() {
+                    return Command::none();
+                }
+                self.settings_password_pending = true;
+                let password = std::mem::take(&mut self.settings_password_input);
+                return Command::perform(
+                    unlock_settings(self.config_path.clone(), password),
+                    Message::SettingsUnlocked,
+                );
+            }
+            Message::SettingsUnlocked(Ok(cipher)) => {
+                self.settings_password_pending = false;
+                self.settings_password_error = None;
+                self.settings_cipher = Some(cipher.clone());
+                return Command::perform(
+                    load_settings(self.config_path.clone(), Some(cipher)),
+                    |result| Message::SettingsLoaded(result.map(Box::new)),
+                );
+            }
+            Message::SettingsUnlocked(Err(e)) => {
+                self.settings_password_pending = false;
+                self.settings_password_error = Some(e);
+                self.add_log(tr("Неудачная попытка открыть зашифрованные настройки.").to_string());
+            }
+            Message::NewSettingsPasswordChanged(password) => self.new_settings_password = password,
+            Message::NewSettingsPasswordConfirmChanged(password) => {
+                self.new_settings_password_confirm = password;
+            }
+            Message::EncryptSettingsPressed => {
+                if self.settings_password_pending
+                    || self.new_settings_password.is_empty()
+                    || self.new_settings_password != self.new_settings_password_confirm
+                {
+                    return Command::none();
+                }
+                self.settings_password_pending = true;
+                let password = std::mem::take(&mut self.new_settings_password);
+                self.new_settings_password_confirm.clear();
+                return Command::perform(
+                    derive_cipher(password, None),
+                    Message::SettingsEncryptionReady,
+                );
+            }
+            Message::SettingsEncryptionReady(Ok(cipher)) => {
+                self.settings_password_pending = false;
+                let changed = self.settings_cipher.is_some();
+                self.settings_cipher = Some(cipher);
+                self.add_log(if changed {
+                    tr("Пароль настроек изменен.").to_string()
+                } else {
+                    tr("Файл настроек зашифрован.").to_string()
+                });
+                commands_to_batch.push(self.save_settings_command());
+                // Прежние копии файла хранят настройки открытым текстом
+                commands_to_batch.push(Command::perform(
+                    remove_plaintext_copies(self.config_path.clone()),
+                    Message::PlaintextCopiesRemoved,
+                ));
+            }
+            Message::SettingsEncryptionReady(Err(e)) => {
+                self.settings_password_pending = false;
+                self.add_log(trf!("Не удалось зашифровать настройки: {}", e));
+            }
+            Message::DecryptSettingsPressed => {
+                if self.settings_cipher.take().is_some() {
+                    self.add_log(tr("Шифрование настроек отключено.").to_string());
+                    commands_to_batch.push(self.save_settings_command());
+                }
+            }
+            Message::PlaintextCopiesRemoved(Ok(removed)) => {
+                if removed > 0 {
+                    self.add_log(trf!("Удалены незашифрованные копии настроек: {}.", removed));
+                }
+                commands_to_batch.push(self.list_settings_backups_command());
+            }
+            Message::PlaintextCopiesRemoved(Err(e)) => {
+                self.add_log(trf!(
+                    "Не удалось удалить незашифрованные копии настроек: {}",
+                    e
+                ));
+            }
+            Message::SettingsSaved(Ok(())) => {
+                println!("Настройки сохранены.");
+            }
+            Message::SettingsSaved(Err(e)) => {
+                eprintln!("Ошибка сохранения настроек: {}", e);
+                self.add_log(trf!("Ошибка сохранения настроек: {}", e));
+            }
+
+            // --- Обработка событий дочернего процесса ---
+            // (события устаревших подписок не находят экземпляр и игнорируются)
+            Message::ProcessActualPid(subscription_id, pid) => {
+                if let Some(index) = self.listener_index(subscription_id) {
+                    self.log_to(index, trf!("Процесс успешно запущен (PID: {}).", pid));
+                    let instance = &mut self.instances[index];
+                    instance.actual_pid = Some(pid);
+                    // Начинаем отсчет времени работы для истории запусков
+                    instance.run_started = Some(chrono::Local::now());
+                    instance.runtime_warned = false;
+                    // Сохраняем новый PID в настройках
+                    if self.settings.set_instance_last_pid(index, Some(pid)) {
+                        commands_to_batch.push(self.save_settings_command());
+                    }
+                    self.record_event(index, EventKind::Start);
+                }
+            }
+            Message::ProcessStdinReady(subscription_id, stdin) => {
+                if let Some(index) = self.listener_index(subscription_id) {
+                    self.instances[index].process_stdin = Some(stdin);
+                }
+            }
+            Message::ConsoleInputChanged(value) => {
+                self.instances[self.selected_instance].console_input = value;
+            }
+            Message::ConsoleSubmitted => {
+                let instance = &mut self.instances[self.selected_instance];
+                let line = instance.console_input.trim_end().to_string();
+                if let Some(stdin) = instance.process_stdin.clone().filter(|_| !line.is_empty()) {
+                    instance.console_input.clear();
+                    let id = instance.id;
+                    // Команда попадает в лог, чтобы ответ процесса был понятен
+                    self.add_log(format!("> {}", line));
+                    commands_to_batch.push(Command::perform(
+                        async move {
+                            stdin
+                                .send(line)
+                                .await
+                                .map_err(|_| tr("процесс не принимает ввод").to_string())
+                        },
+                        move |result| Message::ConsoleSent(id, result),
+                    ));
+                }
+            }
+            Message::ConsoleSent(_, Ok(())) => {}
+            Message::ConsoleSent(id, Err(e)) => {
+                if let Some(index) = self.instance_index(id) {
+                    self.log_to(index, trf!("Не удалось отправить команду: {}", e));
+                    self.instances[index].process_stdin = None;
+                }
+            }
+            Message::ProcessOutput(subscription_id, source, line) => {
+                return self.update(Message::ProcessOutputBatch(
+                    subscription_id,
+                    source,
+                    vec![line],
+                ));
+            }
+            Message::ProcessOutputBatch(subscription_id, source, lines) => {
+                if let Some(index) = self.listener_index(subscription_id) {
+                    let instance = &mut self.instances[index];
+                    instance.last_output_at = Some(Instant::now());
+                    if std::mem::take(&mut instance.process_hung) {
+                        self.log_to(index, tr("Процесс снова выводит данные.").to_string());
+                    }
+                    for line in lines {
+                        let line = mask_api_keys(&self.masked_keys, line);
+                        self.extract_metrics(index, &line);
+                        self.forward_line(index, &line);
+                        self.output_to(index, source, line.clone(), false);
+                        commands_to_batch.push(self.check_keyword_alerts(index, &line));
+                    }
+                }
+            }
+            Message::ProcessOutputDropped(subscription_id, source, count) => {
+                if let Some(index) = self.listener_index(subscription_id) {
+                    let instance = &mut self.instances[index];
+                    instance.dropped_lines += count;
+                    instance.output_flood_at = Some(Instant::now());
+                    let stream = match source {
+                        LogSource::Stderr => " (stderr)",
+                        _ => "",
+                    };
+                    self.log_to(
+                        index,
+                        trf!(
+                            "… пропущено строк{}: {} (вывод чаще {} строк/с) …",
+                            stream,
+                            ui::format_line_number(count),
+                            self.settings.output_rate_limit
+                        ),
+                    );
+                }
+            }
+            Message::ProcessProgress(subscription_id, source, line) => {
+                if let Some(index) = self.listener_index(subscription_id) {
+                    let instance = &mut self.instances[index];
+                    instance.last_output_at = Some(Instant::now());
+                    if std::mem::take(&mut instance.process_hung) {
+                        self.log_to(index, tr("Процесс снова выводит данные.").to_string());
+                    }
+                    let line = mask_api_keys(&self.masked_keys, line);
+                    self.output_to(index, source, line, true);
+                }
+            }
+            Message::ProcessTerminated(subscription_id, exit_code) => {
+                if let Some(index) = self.listener_index(subscription_id) {
+                    self.log_to(index, trf!("Процесс завершился (код: {}).", exit_code));
+                    // Завершение, которого пользователь не запрашивал, - повод для уведомления
+                    let requested = self.instances[index].stopping_pid.is_some();
+                    if exit_code != 0 && !requested && self.settings.desktop_notify_crash {
+                        commands_to_batch.push(self.desktop_notification_command(
+                            index,
+                            trf!("Процесс завершился аварийно (код: {}).", exit_code),
+                        ));
+                    } else if exit_code == 0 && !requested && self.settings.desktop_notify_exit {
+                        commands_to_batch.push(self.desktop_notification_command(
+                            index,
+                            tr("Процесс неожиданно завершился.").to_string(),
+                        ));
+                    }
+                    // Ненулевой код после запрошенной остановки (например, -1 при завершении
+                    // сигналом) - не сбой: отчет не пишем
+                    let crashed = exit_code != 0 && !requested;
+                    self.record_event(
+                        index,
+                        if crashed {
+                            EventKind::Crash
+                        } else {
+                            EventKind::Stop
+                        },
+                    );
+                    let outcome = if crashed {
+                        commands_to_batch.push(self.crash_report_command(index, exit_code));
+                        RunOutcome::Crashed
+                    } else if exit_code == 0 {
+                        RunOutcome::Exited
+                    } else {
+                        RunOutcome::Stopped
+                    };
+                    commands_to_batch.push(self.finish_run(index, outcome, Some(exit_code)));
+                    commands_to_batch.push(self.process_gone(index));
+                }
+            }
+            Message::ProcessError(subscription_id, error_msg) => {
+                if let Some(index) = self.listener_index(subscription_id) {
+                    if self.settings.desktop_notify_crash {
+                        commands_to_batch
+                            .push(self.desktop_notification_command(index, error_msg.clone()));
+                    }
+                    self.log_to(index, error_msg);
+                    self.record_event(index, EventKind::Crash);
+                    commands_to_batch.push(self.finish_run(index, RunOutcome::Crashed, None));
+                    commands_to_batch.push(self.process_gone(index));
+                }
+            }
+
+            // --- Обработка событий завершения команд ---
+            Message::WatchdogTick => {
+                let limit = Duration::from_secs(self.settings.watchdog_silence_minutes as u64 * 60);
+                for index in 0..self.instances.len() {
+                    let instance = &self.instances[index];
+                    let silent = instance
+                        .last_output_at
+                        .is_some_and(|last| last.elapsed() >= limit);
+                    if !instance.is_running
+                        || instance.attached
+                        || limit.is_zero()
+                        || !silent
+                        || instance.process_hung
+                    {
+                        continue;
+                    }
+                    self.instances[index].process_hung = true;
+                    self.record_event(index, EventKind::RuleMatch);
+                    let message = trf!(
+                        "Процесс не выводит данных больше {} мин - возможно, он завис.",
+                        self.settings.watchdog_silence_minutes
+                    );
+                    if self.settings.watchdog_restart && self.instances[index].actual_pid.is_some()
+                    {
+                        self.notify(
+                            index,
+                            NotificationLevel::Critical,
+                            trf!("{} Процесс будет перезапущен.", message),
+                        );
+                        self.record_event(index, EventKind::Restart);
+                        self.instances[index].restart_pending = true;
+                        commands_to_batch.push(self.stop_process(index, RunOutcome::Crashed));
+                    } else {
+                        self.notify(index, NotificationLevel::Critical, message);
+                    }
+                }
+            }
+            Message::RuntimeLimitTick => {
+                let limit = self.settings.max_runtime_minutes as i64 * 60;
+                if limit == 0 {
+                    return Command::none();
+                }
+                for index in 0..self.instances.len() {
+                    let instance = &self.instances[index];
+                    let Some(started) = instance.run_started.filter(|_| instance.is_running) else {
+                        continue;
+                    };
+                    let remaining = limit - (chrono::Local::now() - started).num_seconds();
+                    if remaining <= 0 {
+                        self.notify(
+                            index,
+                            NotificationLevel::Warning,
+                            trf!("Процесс работает {} мин - достигнуто ограничение времени работы, остановка.", self.settings.max_runtime_minutes),
+                        );
+                        commands_to_batch.push(self.stop_process(index, RunOutcome::Scheduled));
+                    } else if remaining <= RUNTIME_LIMIT_WARNING.as_secs() as i64
+                        && !instance.runtime_warned
+                    {
+                        self.instances[index].runtime_warned = true;
+                        self.notify(
+                            index,
+                            NotificationLevel::Warning,
+                            trf!("Процесс будет остановлен через {} мин: ограничение времени работы {} мин.", (remaining + 59) / 60, self.settings.max_runtime_minutes),
+                        );
+                    }
+                }
+            }
+            Message::HealthProbed(id, responded) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                if !self.instances[index].is_running {
+                    return Command::none();
+                }
+                let target = self.settings.instance_health_check(index).to_string();
+                let instance = &mut self.instances[index];
+                let previous = instance.health;
+                if responded {
+                    instance.health_failures = 0;
+                    instance.health = HealthState::Healthy;
+                    match previous {
+                        HealthState::Starting => {
+                            self.log_to(index, trf!("Процесс готов: {} отвечает.", target))
+                        }
+                        HealthState::Unresponsive => {
+                            self.log_to(index, trf!("Процесс снова отвечает на {}.", target))
+                        }
+                        HealthState::Healthy => {}
+                    }
+                    return Command::none();
+                }
+                instance.health_failures += 1;
+                let starting_too_long = instance.run_started.is_some_and(|started| {
+                    (chrono::Local::now() - started).num_seconds()
+                        >= self.settings.health_start_timeout_secs as i64
+                });
+                let message = match previous {
+                    HealthState::Healthy if instance.health_failures >= health::FAILURE_LIMIT => {
+                        trf!("Процесс перестал отвечать на {}.", target)
+                    }
+                    HealthState::Starting if starting_too_long => trf!(
+                        "Процесс не ответил на {} за {} с после запуска.",
+                        target,
+                        self.settings.health_start_timeout_secs
+                    ),
+                    _ => return Command::none(),
+                };
+                instance.health = HealthState::Unresponsive;
+                self.notify(index, NotificationLevel::Warning, message);
+            }
+            Message::ProcessUsageUpdated(id, usage) => {
+                if let Some(index) = self.instance_index(id) {
+                    let instance = &mut self.instances[index];
+                    instance.peak_rss_bytes = instance.peak_rss_bytes.max(usage.rss_bytes);
+                    instance.process_usage = Some(usage);
+                    // Контролируемый перезапуск при превышении лимита памяти (вместо OOM)
+                    let limit_mb = self.settings.instance_memory_limit_mb(index);
+                    let instance = &self.instances[index];
+                    if limit_mb > 0
+                        && usage.rss_bytes > limit_mb as u64 * 1024 * 1024
+                        && instance.is_running
+                        && instance.actual_pid.is_some()
+                        && instance.stopping_pid.is_none()
+                        && !instance.restart_pending
+                    {
+                        self.notify(
+                            index,
+                            NotificationLevel::Warning,
+                            trf!(
+                                "Процесс занял {} МБ памяти (лимит {} МБ) - перезапуск.",
+                                usage.rss_bytes / (1024 * 1024),
+                                limit_mb
+                            ),
+                        );
+                        self.record_event(index, EventKind::Restart);
+                        self.instances[index].restart_pending = true;
+                        commands_to_batch.push(self.stop_process(index, RunOutcome::Stopped));
+                    }
+                }
+            }
+            Message::SoftStopSent(id, pid, Ok(())) => {
+                let timeout = self.graceful_stop_timeout();
+                if let Some(index) = self.instance_index(id) {
+                    self.log_to(
+                        index,
+                        trf!("Запрос на завершение отправлен, ожидание завершения процесса (до {} с)...", timeout.as_secs()),
+                    );
+                }
+                commands_to_batch.push(Command::perform(
+                    wait_for_exit(pid, timeout),
+                    move |exited| Message::GracefulStopWaited(id, pid, exited),
+                ));
+            }
+            Message::SoftStopSent(id, pid, Err(e)) => {
+                if let Some(index) = self.instance_index(id) {
+                    self.log_to(
+                        index,
+                        trf!("Не удалось отправить запрос на завершение ({}). Принудительное завершение (PID: {})...", e, pid),
+                    );
+                }
+                commands_to_batch.push(Command::perform(kill_process(pid), move |result| {
+                    Message::ProcessKillResult(id, result)
+                }));
+            }
+            Message::GracefulStopWaited(id, _, true) => {
+                if let Some(index) = self.instance_index(id) {
+                    self.log_to(index, tr("Процесс завершился корректно.").to_string());
+                }
+                commands_to_batch.push(self.update(Message::ProcessKillResult(id, Ok(()))));
+            }
+            Message::GracefulStopWaited(id, pid, false) => {
+                if let Some(index) = self.instance_index(id) {
+                    self.log_to(
+                        index,
+                        trf!(
+                            "Процесс не завершился за {} с. Принудительное завершение (PID: {})...",
+                            self.settings.graceful_stop_timeout_secs,
+                            pid
+                        ),
+                    );
+                }
+                commands_to_batch.push(Command::perform(kill_process(pid), move |result| {
+                    Message::ProcessKillResult(id, result)
+                }));
+            }
+            Message::ProcessKillResult(id, result) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                let stopped = result.is_ok();
+                match result {
+                    Ok(_) => self.log_to(
+                        index,
+                        tr("Команда остановки процесса отправлена.").to_string(),
+                    ),
+                    Err(e) => self.log_to(index, trf!("Ошибка отправки команды остановки: {}", e)),
+                }
+                // PID уже должен быть очищен и сохранен в stop_process или EventOccurred
+                // Просто сбрасываем флаги состояния
+                let instance = &mut self.instances[index];
+                instance.stopping_pid = None;
+                instance.is_running = false;
+                instance.subscription_id = None;
+                instance.actual_pid = None;
+                let restart = std::mem::take(&mut instance.restart_pending) && stopped;
+                if self.close_requested {
+                    commands_to_batch.push(self.close_if_idle());
+                } else if restart {
+                    // Вторая половина перезапуска ("перезапустить сейчас", зависание)
+                    commands_to_batch.push(self.start_process(index));
+                }
+            }
+
+            // --- Обработка событий завершения команд ---
+            Message::PreLaunchKillResult(id, kill_result) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                match kill_result {
+                    Ok(_) => self.log_to(
+                        index,
+                        tr("Команда завершения предыдущего процесса отправлена (или он уже не существовал).")
+                            .to_string(),
+                    ),
+                    Err(e) => self.log_to(
+                        index,
+                        trf!("Ошибка при попытке завершить предыдущий процесс: {}", e),
+                    ),
+                }
+                // Независимо от результата, пытаемся запустить новый процесс
+                // (путь и ключ могли измениться, пока завершался старый процесс)
+                if self.settings.executable_path.is_some()
+                    && !self.settings.instance_api_key(index).is_empty()
+                {
+                    commands_to_batch.push(self.launch_subscription(
+                        index,
+                        tr("Запуск нового процесса после попытки очистки..."),
+                    ));
+                } else {
+                    self.log_to(
+                        index,
+                        tr("Ошибка: Не удалось получить путь/ключ для запуска после очистки.")
+                            .to_string(),
+                    );
+                }
+            }
+
+            // --- Обработка событий завершения команд ---
+            Message::InitialPidKillResult(id, result) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                match result {
+                    Ok(_) => self.log_to(
+                        index,
+                        tr("Команда завершения процесса от предыдущего сеанса отправлена (или он не существовал).")
+                            .to_string(),
+                    ),
+                    Err(e) => self.log_to(
+                        index,
+                        trf!("Ошибка при попытке завершить процесс от предыдущего сеанса: {}", e),
+                    ),
+                }
+                // В любом случае очищаем last_pid в настройках и сохраняем их
+                if self.settings.set_instance_last_pid(index, None) {
+                    commands_to_batch.push(self.save_settings_command());
+                }
+                if std::mem::take(&mut self.instances[index].resume_pending) {
+                    commands_to_batch.push(self.start_process(index));
+                }
+            }
+
+            // --- Процесс прошлого сеанса: подключение или завершение ---
+            Message::OrphanChecked(id, Some(orphan)) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                // Пока шла проверка, процесс могли уже запустить заново
+                if !self.instances[index].is_running
+                    && self.settings.instance_last_pid(index) == Some(orphan.pid)
+                {
+                    if std::mem::take(&mut self.instances[index].resume_pending) {
+                        // Процесс работал при закрытии лаунчера и работает до сих пор
+                        self.instances[index].orphan_process = Some(orphan);
+                        commands_to_batch.push(self.update(Message::ReattachPressed(id)));
+                    } else {
+                        self.log_to(
+                            index,
+                            trf!("Процесс от предыдущего сеанса еще работает (PID: {}). Подключитесь к нему или завершите его.", orphan.pid),
+                        );
+                        self.instances[index].orphan_process = Some(orphan);
+                    }
+                }
+            }
+            Message::OrphanChecked(id, None) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                let last_pid = self
+                    .settings
+                    .instance_last_pid(index)
+                    .filter(|_| !self.instances[index].is_running);
+                if let Some(last_pid) = last_pid {
+                    self.log_to(
+                        index,
+                        trf!("Процесс от предыдущего сеанса (PID: {}) уже завершен или PID занят другой программой.", last_pid),
+                    );
+                    commands_to_batch.push(self.update(Message::InitialPidKillResult(id, Ok(()))));
+                }
+            }
+            Message::ReattachPressed(id) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                let instance = &mut self.instances[index];
+                instance.resume_pending = false;
+                if let Some(orphan) = instance
+                    .orphan_process
+                    .take()
+                    .filter(|_| !instance.is_running)
+                {
+                    instance.is_running = true;
+                    instance.attached = true;
+                    instance.elevated = self.settings.instance_run_elevated(index);
+                    instance.runtime_warned = false;
+                    instance.health = HealthState::Starting;
+                    instance.health_failures = 0;
+                    instance.actual_pid = Some(orphan.pid);
+                    instance.running_launch = None;
+                    instance.process_usage = None;
+                    instance.peak_rss_bytes = 0;
+                    instance.run_started = Some(chrono::Local::now());
+                    self.log_to(
+                        index,
+                        trf!("Подключение к уже работающему процессу (PID: {}). Вывод процесса недоступен, отслеживается только его завершение.", orphan.pid),
+                    );
+                    self.record_event(index, EventKind::Start);
+                }
+            }
+            Message::StopOrphanPressed(id) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                if let Some(orphan) = self.instances[index].orphan_process.take() {
+                    self.log_to(
+                        index,
+                        trf!(
+                            "Завершение уже работающего процесса (PID: {})...",
+                            orphan.pid
+                        ),
+                    );
+                    commands_to_batch.push(Command::perform(
+                        stop_process_gracefully(orphan.pid, self.graceful_stop_timeout()),
+                        move |result| Message::InitialPidKillResult(id, result),
+                    ));
+                }
+            }
+            Message::AttachedProcessExited(id) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                if self.instances[index].attached {
+                    self.log_to(
+                        index,
+                        trf!(
+                            "Подключенный процесс (PID: {}) завершился.",
+                            self.instances[index].actual_pid.unwrap_or_default()
+                        ),
+                    );
+                    if self.settings.desktop_notify_exit {
+                        commands_to_batch.push(self.desktop_notification_command(
+                            index,
+                            tr("Процесс неожиданно завершился.").to_string(),
+                        ));
+                    }
+                    self.record_event(index, EventKind::Stop);
+                    commands_to_batch.push(self.finish_run(index, RunOutcome::Exited, None));
+                    commands_to_batch.push(self.process_gone(index));
+                }
+            }
+
+            Message::RunningCheckDone(id, found) => {
+                let Some(index) = self.instance_index(id) else {
+                    return Command::none();
+                };
+                if self.instances[index].is_busy() {
+                    return Command::none(); // Пока шла проверка, процесс уже запустили
+                }
+                // Процессы других экземпляров лаунчера запущены из того же файла
+                let known: Vec<u32> = self
+                    .instances
+                    .iter()
+                    .flat_map(|instance| [instance.actual_pid, instance.stopping_pid])
+                    .flatten()
+                    .collect();
+                let mut found: Vec<ProcessCandidate> = found
+                    .into_iter()
+                    .filter(|candidate| !known.contains(&candidate.pid))
+                    .collect();
+                if found.is_empty() {
+                    // Путь и ключ могли измениться, пока шла проверка
+                    if self.settings.executable_path.is_some()
+                        && !self.settings.instance_api_key(index).is_empty()
+                    {
+                        commands_to_batch.push(
+                            self.launch_subscription(
+                                index,
+                                tr("Запуск процесса через подписку..."),
+                            ),
+                        );
+                    }
+                } else {
+                    let others = if found.len() > 1 {
+                        trf!(" (и еще процессов: {})", found.len() - 1)
+                    } else {
+                        String::new()
+                    };
+                    let running = found.remove(0);
+                    self.notify(
+                    index,
+                    NotificationLevel::Warning,
+                    trf!("Запуск отменен: TradingStar уже работает (PID: {}){}. Подключитесь к нему или завершите его - второй процесс будет конкурировать за тот же ключ API.", running.pid, others),
+                );
+                    self.instances[index].orphan_process = Some(running);
+                }
+            }
+
+            // --- Обработка поиска процессов по пути ---
+            Message::KillCandidatesFound(candidates) => {
+                if candidates.is_empty() {
+                    self.add_log(tr("Процессы с этим исполняемым файлом не найдены.").to_string());
+                    self.kill_candidates = None;
+                } else {
+                    self.add_log(trf!(
+                        "Найдено процессов: {}. Подтвердите завершение.",
+                        candidates.len()
+                    ));
+                    self.kill_candidates = Some(candidates);
+                }
+            }
+            Message::ConfirmKillCandidates => {
+                if let Some(candidates) = self.kill_candidates.take() {
+                    let pids: Vec<u32> = candidates.iter().map(|c| c.pid).collect();
+                    self.add_log(trf!("Завершение процессов: {}...", format!("{:?}", pids)));
+                    commands_to_batch.push(Command::perform(
+                        kill_processes(pids, self.graceful_stop_timeout()),
+                        Message::KillCandidatesResult,
+                    ));
+                }
+            }
+            Message::CancelKillCandidates => {
+                self.kill_candidates = None;
+                self.add_log(tr("Завершение найденных процессов отменено.").to_string());
+            }
+            Message::KillCandidatesResult(results) => {
+                for (pid, result) in results {
+                    match result {
+                        Ok(_) => self.add_log(trf!("Процесс (PID: {}) завершен.", pid)),
+                        Err(e) => {
+                            self.add_log(trf!("Ошибка завершения процесса (PID: {}): {}", pid, e))
+                        }
+                    }
+                }
+            }
+
+            // --- Обработка событий электропитания ---
+            Message::PowerCheckTick => {
+                return Command::perform(read_power_state(), Message::PowerStateUpdated);
+            }
+            Message::PowerStateUpdated(Ok(state)) => {
+                let was_on_battery = self.power_state.is_some_and(|p| p.on_battery);
+                self.power_state = Some(state);
+                if state.on_battery && !was_on_battery {
+                    let charge = state
+                        .charge_percent
+                        .map(|c| trf!(" (заряд {}%)", format!("{:.0}", c)))
+                        .unwrap_or_default();
+                    match self.settings.battery_action {
+                        BatteryAction::Ignore => {}
+                        BatteryAction::Warn => self.add_log(trf!(
+                            "Внимание: компьютер перешел на питание от батареи{}.",
+                            charge
+                        )),
+                        BatteryAction::Stop => {
+                            self.add_log(trf!(
+                                "Компьютер перешел на питание от батареи{}.",
+                                charge
+                            ));
+                            for index in self.running_instances() {
+                                self.log_to(
+                                    index,
+                                    tr("Остановка процесса согласно настройкам питания.")
+                                        .to_string(),
+                                );
+                                commands_to_batch
+                                    .push(self.stop_process(index, RunOutcome::Scheduled));
+                            }
+                        }
+                    }
+                } else if !state.on_battery
+                    && was_on_battery
+                    && self.settings.battery_action != BatteryAction::Ignore
+                {
+                    self.add_log(tr("Питание от сети восстановлено.").to_string());
+                }
+            }
+            Message::PowerStateUpdated(Err(e)) => {
+                eprintln!("Ошибка опроса состояния питания: {}", e);
+            }
+
+            // --- Обработка событий планировщика ---
+            Message::MaintenanceCalendarLoaded(Ok(windows)) => {
+                let upcoming = windows
+                    .iter()
+                    .filter(|window| window.end > chrono::Local::now())
+                    .count();
+                if windows != self.maintenance_windows {
+                    self.add_log(trf!(
+                        "Календарь обслуживания загружен: предстоящих окон - {}.",
+                        upcoming
+                    ));
+                }
+                self.maintenance_windows = windows;
+            }
+            Message::MaintenanceCalendarLoaded(Err(e)) => {
+                self.add_log(trf!("Ошибка загрузки календаря обслуживания: {}", e));
+            }
+            Message::SchedulerTick => {
+                // Окна обслуживания касаются всех экземпляров, но перезапускаются
+                // только те, что были остановлены планировщиком
+                for index in 0..self.instances.len() {
+                    let action = maintenance_action(
+                        &self.maintenance_windows,
+                        chrono::Local::now(),
+                        self.settings.maintenance_lead_minutes,
+                        self.instances[index].is_running,
+                        self.instances[index].maintenance_restart_pending,
+                    );
+                    match action {
+                        Some(ScheduledAction::Stop(reason)) => {
+                            self.log_to(
+                                index,
+                                trf!("Планировщик: {}. Остановка процесса.", reason),
+                            );
+                            self.instances[index].maintenance_restart_pending = true;
+                            commands_to_batch.push(self.stop_process(index, RunOutcome::Scheduled));
+                        }
+                        Some(ScheduledAction::Start(reason)) => {
+                            self.instances[index].maintenance_restart_pending = false;
+                            commands_to_batch.push(self.scheduled_start(index, &reason));
+                        }
+                        None => {}
+                    }
+                }
+            }
+
+            Message::TradingHours(ScheduledAction::Start(reason)) => {
+                // По расписанию запускаются все экземпляры с заданным ключом API
+                for index in 0..self.instances.len() {
+                    if self.instances[index].is_running {
+                        self.log_to(index, trf!("Расписание: {}, процесс уже запущен.", reason));
+                    } else if !self.settings.instance_api_key(index).is_empty() {
+                        commands_to_batch
+                            .push(self.scheduled_start(index, &trf!("расписание, {}", reason)));
+                    }
+                }
+            }
+            Message::TradingHours(ScheduledAction::Stop(reason)) => {
+                for index in 0..self.instances.len() {
+                    // Остановка по расписанию отменяет перезапуск после окна обслуживания
+                    self.instances[index].maintenance_restart_pending = false;
+                    if self.instances[index].is_running {
+                        self.log_to(index, trf!("Расписание: {}. Остановка процесса.", reason));
+                        commands_to_batch.push(self.stop_process(index, RunOutcome::Scheduled));
+                    } else {
+                        self.log_to(
+                            index,
+                            trf!("Расписание: {}, процесс уже остановлен.", reason),
+                        );
+                    }
+                }
+            }
+
+            // --- Обработка событий контроля системных часов ---
+            Message::ClockCheckTick => {
+                if !self.settings.ntp_server.trim().is_empty() {
+                    return Command::perform(
+                        measure_clock_offset(self.settings.ntp_server.trim().to_string()),
+                        Message::ClockOffsetMeasured,
+                    );
+                }
+            }
+            Message::ClockOffsetMeasured(Ok(offset_ms)) => {
+                let was_drifted = self.clock_drift_exceeded();
+                self.clock_offset_ms = Some(offset_ms);
+                if self.clock_drift_exceeded() && !was_drifted {
+                    self.add_log(trf!(
+                        "Внимание: системные часы расходятся с NTP на {} мс (допустимо {} мс). \
+                         Подписи запросов к API биржи могут отклоняться.",
+                        format!("{:+.0}", offset_ms),
+                        self.settings.clock_drift_threshold_ms
+                    ));
+                } else if !self.clock_drift_exceeded() && was_drifted {
+                    self.add_log(trf!(
+                        "Расхождение системных часов в норме ({} мс).",
+                        format!("{:+.0}", offset_ms)
+                    ));
+                }
+            }
+            Message::ClockOffsetMeasured(Err(e)) => {
+                eprintln!("Ошибка проверки системных часов: {}", e);
+            }
+
+            // --- Обработка событий оповещений ---
+            // Состояние не меняется: сообщение нужно только для перерисовки счетчика
+            Message::UptimeTick => {}
+            Message::AlertTick => {
+                // Правила общие, но метрики и состояние правил у каждого экземпляра свои
+                for instance_index in self.running_instances() {
+                    let instance = &mut self.instances[instance_index];
+                    let fired = evaluate_alerts(
+                        &self.settings.alert_rules,
+                        &mut instance.alert_states,
+                        &instance.metrics,
+                        chrono::Local::now(),
+                    );
+                    let mut stop = false;
+                    for index in fired {
+                        let rule = &self.settings.alert_rules[index];
+                        let value = self.instances[instance_index]
+                            .metrics
+                            .get(rule.metric.trim())
+                            .map(|metric| metric.value)
+                            .unwrap_or_default();
+                        let message = trf!(
+                            "Оповещение: {} (текущее значение {}).",
+                            rule.describe(),
+                            value
+                        );
+                        if rule.stop_process {
+                            stop = true;
+                            self.notify(
+                                instance_index,
+                                NotificationLevel::Critical,
+                                trf!("{} Процесс будет остановлен.", message),
+                            );
+                        } else {
+                            self.notify(instance_index, NotificationLevel::Warning, message);
+                        }
+                        self.record_event(instance_index, EventKind::RuleMatch);
+                    }
+                    if stop {
+                        commands_to_batch
+                            .push(self.stop_process(instance_index, RunOutcome::Stopped));
+                    }
+                }
+            }
+
+            // --- Обработка событий обслуживания логов ---
+            Message::LogMaintenanceTick => {
+                return self.log_maintenance_command();
+            }
+            Message::LogMaintenanceDone(Ok(report)) => {
+                if report.compressed > 0 || report.removed > 0 {
+                    self.add_log(trf!("Обслуживание логов: сжато файлов - {}, удалено по квоте - {}, размер директории - {} МБ.", report.compressed, report.removed, format!("{:.1}", report.total_bytes as f64 / (1024.0 * 1024.0))));
+                }
+            }
+            Message::LogMaintenanceDone(Err(e)) => {
+                self.add_log(trf!("Ошибка обслуживания директории логов: {}", e));
+            }
+
+            // --- Обработка событий пересылки логов ---
+            Message::LogForwarder(ForwarderEvent::Ready(sender)) => {
+                self.log_forwarder = Some(sender);
+            }
+            Message::LogForwarder(ForwarderEvent::Connected(address)) => {
+                self.log_forward_status = Some(trf!("Подключено к {}", address));
+            }
+            Message::LogForwarder(ForwarderEvent::Disconnected(error)) => {
+                // В лог пишем только потерю ранее установленного соединения
+                if self
+                    .log_forward_status
+                    .as_deref()
+                    .is_some_and(|status| status.starts_with(tr("Подключено")))
+                {
+                    self.add_log(trf!("Пересылка логов прервана: {}", error));
+                }
+                self.log_forward_status = Some(error);
+            }
+
+            // --- Обработка общих событий Iced ---
+            Message::EventOccurred(event) => {
+                // Shift при клике по строке лога расширяет выделение
+                if let Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) = &event
+                {
+                    self.keyboard_modifiers = *modifiers;
+                }
+                // Home/End на главном экране - к началу/концу окна лога (новые строки сверху).
+                // Нажатия в полях ввода сюда не попадают: их обрабатывает само поле.
+                if let Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                    key: iced::keyboard::Key::Named(named),
+                    ..
+                }) = &event
+                {
+                    if self.screen == Screen::Main && !self.ui_locked {
+                        match named {
+                            iced::keyboard::key::Named::Home => {
+                                return self.update(Message::JumpToNewest)
+                            }
+                            iced::keyboard::key::Named::End => {
+                                return self.update(Message::JumpToOldest)
+                            }
+                            // F2 / Shift+F2 - к следующей / предыдущей закладке
+                            iced::keyboard::key::Named::F2 if self.keyboard_modifiers.shift() => {
+                                return self.update(Message::PreviousBookmark)
+                            }
+                            iced::keyboard::key::Named::F2 => {
+                                return self.update(Message::NextBookmark)
+                            }
+                            _ => {}
+                        }
+                    }
+                    // Escape закрывает окно настроек
+                    if self.screen == Screen::Settings
+                        && *named == iced::keyboard::key::Named::Escape
+                    {
+                        return self.update(Message::CloseSettingsPressed);
+                    }
+                }
+                // Ctrl+B (Cmd+B) - закладка на выделенной строке или строке, к которой перешли.
+                // Ctrl+= / Ctrl+- / Ctrl+0 - размер шрифта лога
+                if let Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                    key: iced::keyboard::Key::Character(character),
+                    modifiers,
+                    ..
+                }) = &event
+                {
+                    if self.screen == Screen::Main && !self.ui_locked && modifiers.command() {
+                        match character.as_str() {
+                            "b" => {
+                                let line = self
+                                    .log_selection
+                                    .map(|(_, end)| end)
+                                    .or(self.highlighted_log_line);
+                                if let Some(id) = line {
+                                    return self.update(Message::LogBookmarkToggled(id));
+                                }
+                            }
+                            "=" | "+" => return self.update(Message::LogZoomIn),
+                            "-" => return self.update(Message::LogZoomOut),
+                            "0" => return self.update(Message::LogZoomReset),
+                            _ => {}
+                        }
+                    }
+                }
+                // Запоминаем размер и положение главного окна для рабочего пространства
+                if let Event::Window(window::Id::MAIN, window_event) = &event {
+                    match *window_event {
+                        window::Event::Resized { width, height } => {
+                            let geometry =
+                                self.window_geometry.get_or_insert_with(Default::default);
+                            geometry.width = width;
+                            geometry.height = height;
+                        }
+                        window::Event::Moved { x, y } => {
+                            let geometry =
+                                self.window_geometry.get_or_insert_with(Default::default);
+                            geometry.x = Some(x);
+                            geometry.y = Some(y);
+                        }
+                        _ => {}
+                    }
+                }
+                // Обработка запроса на закрытие окна
+                // (остальные события окна и клавиатуры/мыши в этом глобальном обработчике игнорируем)
+                if let Event::Window(id, window::Event::CloseRequested) = event {
+                    if id == window::Id::MAIN && self.settings.close_to_tray && self.tray.is_some()
+                    {
+                        // Процесс продолжает работать, окно возвращается из меню значка
+                        self.add_log(
+                            tr("Окно скрыто в область уведомлений (выход - через меню значка).")
+                                .to_string(),
+                        );
+                        commands_to_batch
+                            .push(window::change_mode(window::Id::MAIN, window::Mode::Hidden));
+                    } else if id == window::Id::MAIN && self.ui_locked {
+                        // Закрытие окна остановит процесс - только после разблокировки
+                        self.add_log(
+                            tr("Интерфейс заблокирован: закрытие окна отклонено, сначала введите PIN.")
+                                .to_string(),
+                        );
+                    } else if id == window::Id::MAIN {
+                        println!(
+                            "[EventOccurred] Окно - главное (MAIN). Запускаем логику закрытия."
+                        );
+                        self.add_log(tr("Получен запрос на закрытие окна...").to_string());
+                        commands_to_batch.push(self.quit());
+                    } else {
+                        println!("[EventOccurred] Окно ID {:?} не является главным (MAIN). Игнорируем запрос.", id);
+                    }
+                }
+            }
+        }
+        // Сохраняем рабочее пространство, если оно изменилось
+        // (размер и положение окна сами по себе сохранение не вызывают - они меняются
+        // непрерывно при перетаскивании и попадают в файл вместе с другими изменениями)
+        if let Some(saved) = &self.saved_workspace {
+            let workspace = self.workspace_snapshot();
+            let changed = Workspace {
+                window: saved.window,
+                ..workspace.clone()
+            } != *saved;
+            if changed && !self.close_requested {
+                self.saved_workspace = Some(workspace.clone());
+                commands_to_batch.push(Command::perform(
+                    save_workspace(self.data_dir.as_deref().map(workspace_path), workspace),
+                    Message::WorkspaceSaved,
+                ));
+            }
+        }
+        // Значок в области уведомлений показывает, запущен ли процесс
+        if let Some(tray) = &self.tray {
+            let state = self.tray_state();
+            if self.tray_state.as_ref() != Some(&state) {
+                tray.update(state.clone());
+                self.tray_state = Some(state);
+            }
+        }
+        // Возвращаем пакет команд для выполнения Iced
+        Command::batch(commands_to_batch)
+    }
+
+    // Настройка подписок на события
+    fn subscription(&self) -> Subscription<Self::Message> {
+        // Подписка на общие события Iced (для перехвата закрытия окна)
+        let window_events = event::listen().map(Message::EventOccurred);
+
+        // Подписки на события дочерних процессов (по одной на запущенный экземпляр)
+        let process_subscriptions = Subscription::batch(
+            (0..self.instances.len()).map(|index| self.process_subscription(index)),
+        );
+
+        // Периодический опрос состояния питания (батарея/сеть)
+        let power_subscription =
+            iced::time::every(Duration::from_secs(30)).map(|_| Message::PowerCheckTick);
+
+        // Проверка расписания и периодическое обновление календаря обслуживания
+        let scheduler_subscription = if self.maintenance_windows.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(Duration::from_secs(15)).map(|_| Message::SchedulerTick)
+        };
+        // Опрос загрузки CPU и памяти процессов (пока они запущены и известен PID)
+        let usage_subscriptions =
+            Subscription::batch(self.instances.iter().filter_map(|instance| {
+                let id = instance.id;
+                let pid = instance.actual_pid.filter(|_| instance.is_running)?;
+                Some(
+                    usage_subscription(pid)
+                        .map(move |usage| Message::ProcessUsageUpdated(id, usage)),
+                )
+            }));
+
+        // Проверка готовности процессов, для которых задан адрес проверки
+        let health_subscriptions =
+            Subscription::batch(self.instances.iter().enumerate().filter_map(
+                |(index, instance)| {
+                    let id = instance.id;
+                    let pid = instance.actual_pid.filter(|_| instance.is_running)?;
+                    let target = self.settings.instance_health_check(index);
+                    if target.is_empty() || health::validate_target(target).is_some() {
+                        return None;
+                    }
+                    Some(
+                        health_subscription(target.to_string(), pid)
+                            .map(move |responded| Message::HealthProbed(id, responded)),
+                    )
+                },
+            ));
+
+        // Отслеживание завершения процессов, подключенных по PID прошлого сеанса
+        let attached_subscriptions =
+            Subscription::batch(self.instances.iter().filter_map(|instance| {
+                let id = instance.id;
+                let pid = instance.actual_pid.filter(|_| instance.attached)?;
+                Some(exit_watch_subscription(pid).map(move |_| Message::AttachedProcessExited(id)))
+            }));
+
+        // Сторожевой таймер молчания (вывод есть только у запущенных лаунчером процессов)
+        let watchdog_subscription = if self.settings.watchdog_silence_minutes > 0
+            && self
+                .instances
+                .iter()
+                .any(|instance| instance.is_running && !instance.attached)
+        {
+            iced::time::every(Duration::from_secs(15)).map(|_| Message::WatchdogTick)
+        } else {
+            Subscription::none()
+        };
+
+        // Ограничение времени работы процессов
+        let runtime_limit_subscription = if self.settings.max_runtime_minutes > 0
+            && self.instances.iter().any(|instance| instance.is_running)
+        {
+            iced::time::every(Duration::from_secs(15)).map(|_| Message::RuntimeLimitTick)
+        } else {
+            Subscription::none()
+        };
+
+        // Счетчик времени работы выбранного экземпляра обновляется каждую секунду
+        let uptime_subscription =
+            if self.current().is_running && self.current().run_started.is_some() {
+                iced::time::every(Duration::from_secs(1)).map(|_| Message::UptimeTick)
+            } else {
+                Subscription::none()
+            };
+
+        // Запуск и остановка по расписанию торговых часов
+        let trading_hours_subscription = if self.settings.trading_hours.is_active() {
+            trading_hours_subscription(self.settings.trading_hours.clone())
+                .map(Message::TradingHours)
+        } else {
+            Subscription::none()
+        };
+        let calendar_subscription = if self.settings.maintenance_ics_url.trim().is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(Duration::from_secs(60 * 60))
+                .map(|_| Message::RefreshMaintenanceCalendar)
+        };
+
+        // Периодическая проверка системных часов по NTP
+        let clock_subscription =
+            iced::time::every(Duration::from_secs(10 * 60)).map(|_| Message::ClockCheckTick);
+
+        // Проверка правил оповещений (только пока запущен хотя бы один процесс)
+        let alert_subscription = if !self.settings.alert_rules.is_empty()
+            && self.instances.iter().any(|instance| instance.is_running)
+        {
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::AlertTick)
+        } else {
+            Subscription::none()
+        };
+
+        // Периодическое обслуживание директории логов (сжатие и квота)
+        let log_maintenance_subscription =
+            iced::time::every(Duration::from_secs(60 * 60)).map(|_| Message::LogMaintenanceTick);
+
+        // Отслеживание бездействия для автоматической блокировки интерфейса
+        let idle_lock_subscription =
+            if self.settings.ui_lock_available() && self.settings.ui_lock_minutes > 0 {
+                Subscription::batch(vec![
+                    event::listen_with(|event, _status| match event {
+                        Event::Mouse(_) | Event::Keyboard(_) | Event::Touch(_) => {
+                            Some(Message::UserActivity)
+                        }
+                        _ => None,
+                    }),
+                    iced::time::every(Duration::from_secs(5)).map(|_| Message::IdleLockTick),
+                ])
+            } else {
+                Subscription::none()
+            };
+
+        // Пересылка логов во внешнюю систему (если включена и задан адрес)
+        let forwarder_subscription = if self.settings.log_forward_enabled
+            && !self.settings.log_forward_address.trim().is_empty()
+        {
+            forwarder_subscription(ForwardConfig {
+                address: self.settings.log_forward_address.trim().to_string(),
+                format: self.settings.log_forward_format,
+                tls: self.settings.log_forward_tls,
+            })
+            .map(Message::LogForwarder)
+        } else {
+            Subscription::none()
+        };
+
+        // Изменения файла настроек другими программами
+        let settings_watch = match &self.config_path {
+            Some(path) => {
+                settings_watch_subscription(path.clone()).map(|_| Message::SettingsFileChanged)
+            }
+            None => Subscription::none(),
+        };
+
+        // Объединяем подписки в одну
+        Subscription::batch(vec![
+            window_events,
+            process_subscriptions,
+            usage_subscriptions,
+            health_subscriptions,
+            runtime_limit_subscription,
+            uptime_subscription,
+            attached_subscriptions,
+            watchdog_subscription,
+            power_subscription,
+            scheduler_subscription,
+            trading_hours_subscription,
+            calendar_subscription,
+            clock_subscription,
+            alert_subscription,
+            log_maintenance_subscription,
+            idle_lock_subscription,
+            forwarder_subscription,
+            settings_watch,
+            tray_subscription().map(Message::Tray),
+        ])
+    }
+
+    // Отрисовка интерфейса приложения
+    fn view(&self) -> Element<'_, Self::Message> {
+        // Выбираем, какую функцию отрисовки вызвать из модуля ui
+        let main_content = match self.screen {
+            // Настройки - окно поверх главного экрана, лог под ним продолжает обновляться.
+            // Клик по затемненному фону закрывает настройки.
+            Screen::Settings => Modal::new(
+                self.view_main_screen(false),
+                ui::view_settings(
+                    &self.settings,
+                    self.data_dir.as_deref().map(logs_dir),
+                    self.log_forward_status.as_deref(),
+                    self.launch_changes(self.selected_instance),
+                    self.maintenance_windows.len(),
+                    ui::SettingsExtras {
+                        section: self.settings_section,
+                        reset_pending: self.reset_settings_pending,
+                        metric_rule_errors: &self.metric_rule_errors,
+                        highlight_rule_errors: &self.highlight_rule_errors,
+                        api_key_revealed: self.api_key_revealed,
+                        executable_problem: self.executable_problem.as_deref(),
+                        settings_backups: &self.settings_backups,
+                        selected_settings_backup: self.selected_settings_backup.as_ref(),
+                        cli_overrides: self.cli.overridden(),
+                        encryption: ui::EncryptionState {
+                            enabled: self.settings_cipher.is_some(),
+                            new_password: &self.new_settings_password,
+                            new_password_confirm: &self.new_settings_password_confirm,
+                            pending: self.settings_password_pending,
+                        },
+                    },
+                    &self
+                        .instances
+                        .iter()
+                        .map(|instance| instance.is_running)
+                        .collect::<Vec<_>>(),
+                ),
+            )
+            .on_blur(Message::CloseSettingsPressed)
+            .into(),
+            // История запусков и график времени работы
+            Screen::Stats => ui::view_stats(&self.run_history, self.current().run_started),
+            // Передаем флаг запуска, ссылку на логи и настройки для отрисовки главного экрана
+            Screen::Main => self.view_main_screen(false),
+        };
+
+        // Зашифрованные настройки еще не открыты: кроме ввода пароля показывать нечего
+        let main_content = if self.settings_locked {
+            ui::view_settings_unlock(
+                &self.settings_password_input,
+                self.settings_password_error.as_deref(),
+                self.settings_password_pending,
+            )
+        // Заблокированный интерфейс: ввод PIN поверх неактивного главного экрана
+        // (экран настроек не показываем - на нем ключ API)
+        } else if self.ui_locked {
+            column![
+                ui::view_lock(&self.unlock_pin_input, self.unlock_error.as_deref()),
+                self.view_main_screen(self.settings.ui_lock_hide_logs)
+                    .map(|_| Message::LockedInteraction),
+            ]
+            .into()
+        } else {
+            main_content
+        };
+
+        // Оборачиваем основной контент в контейнер для центрирования
+        container(main_content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .into()
+    }
+
+    // Тема приложения
+    fn theme(&self) -> Self::Theme {
+        self.settings.theme_colors.theme() // Темная тема с цветами из настроек
+    }
+}
+
+// Реализация методов для структуры Launcher (не связанных с Application)
+impl Launcher {
+    // Строки лога выбранного экземпляра, содержащие искомый текст (от новых к старым)
+    fn log_search_matches(&self) -> Vec<u64> {
+        ui::log_search_matches(
+            &self.current().logs,
+            &self.log_search,
+            &self.hidden_log_levels,
+            self.log_stream,
+        )
+    }
+
+    // Закладки на строках лога выбранного экземпляра, еще не вытесненных из окна
+    // (от новых строк к старым, как в окне лога)
+    fn current_bookmarks(&self) -> Vec<u64> {
+        let logs = &self.current().logs;
+        self.log_bookmarks
+            .iter()
+            .rev()
+            .copied()
+            .filter(|id| logs.iter().any(|line| line.id == *id))
+            .collect()
+    }
+
+    // Метод для добавления строки лога выбранного экземпляра (общие сообщения лаунчера)
+    fn add_log(&mut self, message: String) {
+        self.log_to(self.selected_instance, message);
+    }
+
+    // Добавление строки в лог экземпляра (делегирует парсинг модулю ui)
+    fn log_to(&mut self, index: usize, message: String) {
+        self.add_line(index, LogSource::Launcher, message);
+    }
+
+    // Добавление строки из источника `source` в лог экземпляра и в файл сессии
+    fn add_line(&mut self, index: usize, source: LogSource, message: String) {
+        let now = chrono::Local::now();
+        self.write_session_log(index, now, source, &message);
+        self.push_line(index, now, source, message);
+    }
+
+    // Добавление строки только в окно лога экземпляра; возвращает ID строки
+    fn push_line(
+        &mut self,
+        index: usize,
+        time: chrono::DateTime<chrono::Local>,
+        source: LogSource,
+        message: String,
+    ) -> u64 {
+        let id = self.next_log_id;
+        let instance = &mut self.instances[index];
+        instance.line_count += 1;
+        // Вызываем функцию парсинга и добавления из модуля ui
+        ui::add_log_impl(
+            &mut instance.logs,
+            self.settings.log_buffer_limit(),
+            id,
+            instance.line_count,
+            time,
+            source,
+            message,
+        );
+        self.next_log_id += 1;
+        id
+    }
+
+    // Вывод процесса в лог экземпляра. Фрагмент прогресса (`progress`) заменяет текущую
+    // строку прогресса или начинает новую; обычная строка заменяет незавершенную строку
+    // прогресса (как в терминале после \r) и завершает ее. В файл сессии попадают только
+    // завершенные строки.
+    fn output_to(&mut self, index: usize, source: LogSource, line: String, progress: bool) {
+        let now = chrono::Local::now();
+        let instance = &mut self.instances[index];
+        let open_line = instance.progress_line.filter(|id| {
+            instance
+                .logs
+                .iter()
+                .rev()
+                .any(|log_line| log_line.id == *id)
+        });
+        match open_line {
+            Some(id) => {
+                ui::replace_log_line(&mut instance.logs, id, now, source, line.clone());
+                if !progress {
+                    instance.progress_line = None;
+                    self.write_session_log(index, now, source, &line);
+                }
+            }
+            None if progress => {
+                let id = self.push_line(index, now, source, line);
+                self.instances[index].progress_line = Some(id);
+            }
+            None => {
+                instance.progress_line = None;
+                self.add_line(index, source, line);
+            }
+        }
+    }
 
-        // Оборачиваем основной контент в контейнер для центрирования
-        container(main_content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x()
-            .into()
+    // Запись строки в файл лога сессии экземпляра (ANSI-последовательности - по настройке)
+    fn write_session_log(
+        &mut self,
+        index: usize,
+        time: chrono::DateTime<chrono::Local>,
+        source: LogSource,
+        message: &str,
+    ) {
+        let instance = &mut self.instances[index];
+        if let Some(session_log) = instance.session_log.as_mut() {
+            let text = if self.settings.session_log_keep_ansi {
+                message.to_string()
+            } else {
+                ui::strip_ansi(message)
+            };
+            let line = format!("{}{}", source.text_prefix(), text);
+            if let Err(e) = session_log.write_line(time, &line) {
+                eprintln!("{}", e);
+                instance.session_log = None;
+            }
+        }
     }
 
-    // Тема приложения
-    fn theme(&self) -> Self::Theme {
-        Theme::Dark // Используем темную тему
+    // Запись события жизненного цикла, привязанного к последней строке лога экземпляра
+    fn record_event(&mut self, index: usize, kind: EventKind) {
+        let (text, log_line) = match self.instances[index].logs.back() {
+            Some(line) => (
+                line.segments.iter().map(|s| s.text.as_str()).collect(),
+                Some(line.id),
+            ),
+            None => (String::new(), None),
+        };
+        let description = format!("{}{}", self.instance_label(index), text);
+        self.events.push(LifecycleEvent {
+            time: chrono::Local::now(),
+            kind,
+            description,
+            log_line,
+        });
+        if self.events.len() > MAX_EVENTS {
+            self.events.remove(0);
+        }
     }
-}
 
-// Реализация методов для структуры Launcher (не связанных с Application)
-impl Launcher {
-    // Метод для добавления строки лога (делегирует парсинг модулю ui)
-    fn add_log(&mut self, message: String) {
-        // Вызываем функцию парсинга и добавления из модуля ui
-        ui::add_log_impl(&mut self.logs, message);
+    // Выбранный экземпляр (показан на главном экране)
+    fn current(&self) -> &Instance {
+        &self.instances[self.selected_instance]
+    }
+
+    // Индекс экземпляра по его постоянному идентификатору (None - экземпляр удален)
+    fn instance_index(&self, id: u64) -> Option<usize> {
+        self.instances.iter().position(|instance| instance.id == id)
+    }
+
+    // Индекс экземпляра по ID подписки ProcessListener (None - подписка устарела)
+    fn listener_index(&self, subscription_id: u64) -> Option<usize> {
+        self.instances
+            .iter()
+            .position(|instance| instance.subscription_id == Some(subscription_id))
+    }
+
+    // Индексы экземпляров с запущенным процессом
+    fn running_instances(&self) -> Vec<usize> {
+        (0..self.instances.len())
+            .filter(|index| self.instances[*index].is_running)
+            .collect()
+    }
+
+    // Добавление экземпляра в конец списка (настройки добавляются отдельно)
+    fn push_instance(&mut self) {
+        self.instances.push(Instance::new(self.next_instance_id));
+        self.next_instance_id += 1;
+    }
+
+    // Префикс с названием экземпляра для уведомлений и хронологии
+    // (пока экземпляр один, название не нужно)
+    fn instance_label(&self, index: usize) -> String {
+        if self.instances.len() > 1 {
+            format!("[{}] ", self.settings.instance_name(index))
+        } else {
+            String::new()
+        }
+    }
+
+    // Системное уведомление рабочего стола о событии экземпляра
+    fn desktop_notification_command(&self, index: usize, message: String) -> Command<Message> {
+        Command::perform(
+            show_desktop_notification(format!("{}{}", self.instance_label(index), message)),
+            Message::DesktopNotificationShown,
+        )
+    }
+
+    // Выход из лаунчера: сохранение рабочего пространства и остановка процессов;
+    // окно закроется, когда завершится последний из них (close_if_idle)
+    fn quit(&mut self) -> Command<Message> {
+        let mut commands = Vec::new();
+        // Сохраняем рабочее пространство до остановки процесса: при следующем
+        // запуске он будет считаться работавшим. Пишем синхронно - окно
+        // может закрыться раньше, чем выполнится асинхронная команда.
+        if self.saved_workspace.is_some() {
+            let path = self.data_dir.as_deref().map(workspace_path);
+            if let Err(e) = workspace::write_workspace(path, &self.workspace_snapshot()) {
+                eprintln!("Ошибка сохранения рабочего пространства: {}", e);
+            }
+        }
+        self.close_requested = true;
+        // Останавливаем все запущенные экземпляры; окно закроется,
+        // когда завершится последний из них (close_if_idle)
+        let mut pid_cleared = false;
+        for index in 0..self.instances.len() {
+            if self.keeps_running(index) {
+                // PID остается в настройках: при следующем запуске лаунчер
+                // найдет процесс и предложит подключиться к нему
+                let instance = &mut self.instances[index];
+                let pid = instance.actual_pid.unwrap_or_default();
+                instance.is_running = false;
+                instance.attached = false;
+                instance.subscription_id = None;
+                instance.process_stdin = None;
+                self.log_to(
+                    index,
+                    trf!(
+                        "Процесс (PID: {}) продолжит работу после закрытия лаунчера.",
+                        pid
+                    ),
+                );
+                continue;
+            }
+            // Очищаем сохраненный PID (на всякий случай и у остановленных)
+            pid_cleared |= self.settings.set_instance_last_pid(index, None);
+            if !self.instances[index].is_running {
+                continue;
+            }
+            if let Some(pid) = self.instances[index].actual_pid {
+                // Не используем .take() здесь
+                self.log_to(
+                    index,
+                    trf!(
+                        "Инициирована остановка процесса (PID: {}) перед закрытием.",
+                        pid
+                    ),
+                );
+                commands.push(self.finish_run(index, RunOutcome::Stopped, None));
+                let stdin = self.instances[index].process_stdin.take();
+                commands.push(self.graceful_stop_command(index, pid, stdin));
+            } else {
+                self.log_to(
+                    index,
+                    tr("Процесс был запущен, но PID не найден.").to_string(),
+                );
+                let instance = &mut self.instances[index];
+                instance.is_running = false;
+                instance.subscription_id = None;
+            }
+        }
+        if pid_cleared {
+            commands.push(self.save_settings_command());
+        }
+        if !self.instances.iter().any(Instance::is_busy) {
+            println!("[EventOccurred] Процессы не запущены. Запрос на немедленное закрытие.");
+            self.add_log(tr("Процесс не запущен. Закрытие окна.").to_string());
+        }
+        commands.push(self.close_if_idle());
+        Command::batch(commands)
+    }
+
+    // Состояние для значка в области уведомлений
+    fn tray_state(&self) -> TrayState {
+        let running = self.instances.iter().filter(|i| i.is_running).count();
+        let status = match running {
+            0 => tr("остановлен").to_string(),
+            _ if self.instances.len() == 1 => tr("работает").to_string(),
+            _ => trf!("работает {} из {}", running, self.instances.len()),
+        };
+        TrayState {
+            running: self.current().is_running,
+            tooltip: format!("{}: {}", self.title(), status),
+        }
+    }
+
+    // Закрытие окна после запроса на выход, когда все процессы остановлены
+    fn close_if_idle(&self) -> Command<Message> {
+        if self.close_requested && !self.instances.iter().any(Instance::is_busy) {
+            window::close(window::Id::MAIN)
+        } else {
+            Command::none()
+        }
+    }
+
+    // Процесс экземпляра завершился сам: сброс состояния и сохраненного PID
+    fn process_gone(&mut self, index: usize) -> Command<Message> {
+        let instance = &mut self.instances[index];
+        instance.is_running = false;
+        instance.attached = false;
+        instance.subscription_id = None;
+        instance.actual_pid = None;
+        instance.process_stdin = None;
+        let mut commands = vec![self.close_if_idle()];
+        // Очищаем сохраненный PID и сохраняем настройки
+        if self.settings.set_instance_last_pid(index, None) {
+            commands.push(self.save_settings_command());
+        }
+        Command::batch(commands)
+    }
+
+    // Подписка ProcessListener экземпляра (только если он запущен лаунчером)
+    fn process_subscription(&self, index: usize) -> Subscription<Message> {
+        let instance = &self.instances[index];
+        let api_key = self.settings.instance_api_key(index);
+        // Проверяем наличие ID подписки, пути и ключа API
+        match (instance.subscription_id, &self.settings.executable_path) {
+            (Some(id), Some(path)) if instance.is_running && !api_key.is_empty() => {
+                // Подстановки раскрываются в момент запуска процесса
+                let placeholders = Placeholders {
+                    api_key: &api_key,
+                    // Профили запуска есть только у основного экземпляра
+                    profile: if index == 0 {
+                        self.settings
+                            .active_launch_profile()
+                            .map_or("", |profile| profile.name.as_str())
+                    } else {
+                        ""
+                    },
+                    data_dir: self.data_dir.as_deref(),
+                    now: chrono::Local::now(),
+                };
+                Subscription::from_recipe(ProcessListener::new(
+                    id,
+                    path.clone(),
+                    api_key.to_string(),
+                    self.settings
+                        .process_args(index, &placeholders)
+                        .unwrap_or_default(),
+                    ProcessOptions {
+                        env: self.settings.process_env(&placeholders),
+                        working_dir: self.settings.working_dir.clone(),
+                        elevated: instance.elevated,
+                        detached_output: instance.detached_output.clone(),
+                        max_lines_per_sec: self.settings.output_rate_limit,
+                        output_encoding: self.settings.output_encoding,
+                    },
+                ))
+            }
+            _ => Subscription::none(), // Процесс не запущен (или подключен по PID)
+        }
+    }
+
+    // Показать настройки; файл мог быть удален или перемещен с прошлой проверки
+    fn open_settings(&mut self) -> Command<Message> {
+        self.screen = Screen::Settings;
+        self.check_executable();
+        self.list_settings_backups_command()
+    }
+
+    // Команда асинхронного сохранения текущих настроек
+    fn save_settings_command(&mut self) -> Command<Message> {
+        // Сохранение следует за каждым изменением настроек, в том числе ключей API
+        self.masked_keys = self.settings.masked_api_keys();
+        // Зашифрованный файл до ввода пароля не трогаем: в памяти настройки по умолчанию
+        if self.settings_locked {
+            return Command::none();
+        }
+        // Параметры командной строки в файл не попадают, выбранный профиль
+        // сохраняется с текущими параметрами запуска
+        let mut settings = self.settings.clone();
+        self.cli.restore(&mut settings, &self.file_settings);
+        settings.sync_active_profile();
+        // Перед первым сохранением в сеансе и затем периодически копируем прежний файл
+        let backup = self
+            .last_settings_backup
+            .is_none_or(|at| at.elapsed() >= SETTINGS_BACKUP_INTERVAL);
+        if backup {
+            self.last_settings_backup = Some(Instant::now());
+        }
+        // Запоминаем записываемый текст: отслеживание файла увидит и это сохранение
+        let content = match self.config_path.as_deref() {
+            Some(path) => serialize_settings(path, &settings, self.settings_cipher.as_ref()),
+            None => Err(tr("Не удалось определить путь к конфигурации").to_string()),
+        };
+        if let Ok(content) = &content {
+            if self.recent_saves.len() == RECENT_SETTINGS_SAVES {
+                self.recent_saves.pop_front();
+            }
+            self.recent_saves.push_back(content.clone());
+        }
+        let path = self.config_path.clone();
+        Command::perform(
+            async move {
+                if backup {
+                    if let Err(e) = backup_settings(path.clone()).await {
+                        eprintln!("Не удалось сделать резервную копию настроек: {}", e);
+                    }
+                }
+                save_settings(path, content?).await
+            },
+            Message::SettingsSaved,
+        )
+    }
+
+    // Настройки из файла: действующие получаются наложением параметров командной строки
+    fn set_file_settings(&mut self, settings: AppSettings) {
+        self.settings = settings.clone();
+        self.file_settings = settings;
+        self.cli.apply(&mut self.settings);
+        self.masked_keys = self.settings.masked_api_keys();
+        i18n::set_language(self.settings.language);
+        let overridden = self.cli.overridden();
+        if !overridden.is_empty() {
+            self.add_log(trf!(
+                "Из командной строки (в файл не сохраняются, пока не изменены в настройках): {}.",
+                overridden.join(", ")
+            ));
+        }
+    }
+
+    // Значок окна с меткой выбранного профиля (меняется только при смене цвета)
+    fn window_badge_command(&mut self) -> Command<Message> {
+        let badge = self
+            .settings
+            .active_launch_profile()
+            .and_then(|profile| profile.badge);
+        if badge == self.window_badge {
+            return Command::none();
+        }
+        self.window_badge = badge;
+        match window_icon(badge) {
+            Some(icon) => window::change_icon(window::Id::MAIN, icon),
+            None => Command::none(),
+        }
+    }
+
+    // Обновление списка резервных копий настроек
+    fn list_settings_backups_command(&self) -> Command<Message> {
+        Command::perform(
+            list_settings_backups(self.config_path.clone()),
+            Message::SettingsBackupsListed,
+        )
+    }
+
+    // Применение настроек, измененных в файле другой программой
+    fn apply_reloaded_settings(
+        &mut self,
+        loaded: LoadedSettings,
+        description: &str, // Сообщение в лог о смене настроек
+    ) -> Command<Message> {
+        let LoadedSettings {
+            mut settings,
+            migrated,
+            notes,
+            ..
+        } = loaded;
+        // Экземпляр, которого нет в новом файле, нельзя убрать, пока он работает
+        let count = settings.instance_count();
+        if let Some(index) = (count..self.instances.len()).find(|&i| self.instances[i].is_busy()) {
+            self.add_log(trf!(
+                "Файл настроек изменен, но в нем нет запущенного экземпляра \"{}\". \
+                 Остановите его, чтобы применить новые настройки.",
+                self.settings.instance_name(index)
+            ));
+            return Command::none();
+        }
+        // PID процессов ведет лаунчер, а не файл
+        for index in 0..count.min(self.instances.len()) {
+            settings.set_instance_last_pid(index, self.settings.instance_last_pid(index));
+        }
+        let calendar_changed = settings.maintenance_ics_url != self.settings.maintenance_ics_url;
+        let data_dir_changed = settings.data_dir != self.settings.data_dir;
+        self.set_file_settings(settings);
+        self.instances.truncate(count);
+        while self.instances.len() < count {
+            self.push_instance();
+        }
+        self.selected_instance = self.selected_instance.min(count - 1);
+        self.add_log(description.to_string());
+        for note in notes {
+            self.add_log(note);
+        }
+        self.compile_metric_rules();
+        self.compile_highlight_rules();
+        if let Some(problem) = self.check_executable() {
+            self.add_log(trf!("Исполняемый файл из настроек: {}", problem));
+        }
+        if data_dir_changed {
+            self.add_log(
+                tr("Новая директория данных будет использована после перезапуска лаунчера.")
+                    .to_string(),
+            );
+        }
+        // Работающие процессы не перезапускаем сами - предлагаем (баннер в окне)
+        for index in 0..self.instances.len() {
+            let changes = self.launch_changes(index);
+            if !changes.is_empty() {
+                self.log_to(
+                    index,
+                    trf!("Процесс работает со старыми параметрами запуска ({}). Перезапустите его, чтобы применить изменения.", changes.join(", ")),
+                );
+            }
+        }
+        let mut commands = vec![self.window_badge_command()];
+        if migrated {
+            commands.push(self.save_settings_command());
+        }
+        if calendar_changed && !self.settings.maintenance_ics_url.trim().is_empty() {
+            commands.push(Command::perform(
+                fetch_maintenance_windows(self.settings.maintenance_ics_url.clone()),
+                Message::MaintenanceCalendarLoaded,
+            ));
+        }
+        Command::batch(commands)
+    }
+
+    // Перекомпиляция правил метрик из текущих настроек
+    fn compile_metric_rules(&mut self) {
+        let (extractor, errors) = MetricExtractor::compile(&self.settings.metric_rules);
+        self.metric_extractor = extractor;
+        self.metric_rule_errors = errors;
+    }
+
+    // Перекомпиляция правил подсветки из текущих настроек
+    fn compile_highlight_rules(&mut self) {
+        let (highlighter, errors) = Highlighter::compile(&self.settings.highlight_rules);
+        self.highlighter = highlighter;
+        self.highlight_rule_errors = errors;
+    }
+
+    // Правила подсветки изменены: перекомпилируем и сохраняем настройки
+    fn highlight_rules_changed(&mut self) -> Command<Message> {
+        self.compile_highlight_rules();
+        self.save_settings_command()
+    }
+
+    // Правила метрик изменены: перекомпилируем и сохраняем настройки
+    fn metric_rules_changed(&mut self) -> Command<Message> {
+        self.compile_metric_rules();
+        self.save_settings_command()
+    }
+
+    // Правила оповещений изменены: сбрасываем их состояние и сохраняем настройки
+    fn alert_rules_changed(&mut self) -> Command<Message> {
+        for instance in &mut self.instances {
+            instance.alert_states.clear();
+        }
+        self.save_settings_command()
+    }
+
+    // Текущие файлы логов сессий всех экземпляров
+    fn active_session_logs(&self) -> Vec<PathBuf> {
+        self.instances
+            .iter()
+            .flat_map(|instance| {
+                let session_log = instance.session_log.as_ref().map(|log| log.path().clone());
+                session_log
+                    .into_iter()
+                    .chain(instance.detached_output.clone())
+            })
+            .collect()
+    }
+
+    // Процесс экземпляра продолжит работу после закрытия лаунчера: запущен отсоединенным
+    // или подключен по PID прошлого сеанса при включенной работе после закрытия
+    fn keeps_running(&self, index: usize) -> bool {
+        let instance = &self.instances[index];
+        instance.is_running
+            && instance.actual_pid.is_some()
+            && (instance.detached_output.is_some()
+                || (instance.attached && self.settings.instance_keep_running(index)))
+    }
+
+    // Команда обслуживания директории логов по текущим настройкам
+    fn log_maintenance_command(&self) -> Command<Message> {
+        let Some(data_dir) = self.data_dir.as_deref() else {
+            return Command::none();
+        };
+        Command::perform(
+            maintain_logs_dir(
+                logs_dir(data_dir),
+                self.settings.log_compress_after_days,
+                self.settings.log_dir_quota_mb,
+                self.active_session_logs(),
+            ),
+            Message::LogMaintenanceDone,
+        )
+    }
+
+    // Уведомление пользователя (баннер на главном экране + запись в лог экземпляра)
+    fn notify(&mut self, index: usize, level: NotificationLevel, message: String) {
+        self.log_to(index, message.clone());
+        let label = self.instance_label(index);
+        self.notifications
+            .push(level, format!("{}{}", label, message));
+    }
+
+    // Пересылка строки вывода во внешнюю систему логирования (если включена)
+    fn forward_line(&mut self, index: usize, line: &str) {
+        let Some(sender) = self.log_forwarder.as_ref() else {
+            return;
+        };
+        let message = ui::strip_ansi(line);
+        let record = ForwardRecord {
+            time: chrono::Local::now(),
+            level: detect_level(&message),
+            profile: self.settings.instance_name(index),
+            message,
+        };
+        // При переполнении очереди (приемник недоступен) строка отбрасывается
+        if let Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) = sender.try_send(record) {
+            self.log_forwarder = None;
+        }
+    }
+
+    // Проверка строки вывода процесса экземпляра по правилам оповещений по ключевым фразам.
+    // Баннер и запись в лог - сразу, звук и системное уведомление - фоновыми командами.
+    fn check_keyword_alerts(&mut self, index: usize, line: &str) -> Command<Message> {
+        let plain = ui::strip_ansi(line);
+        let rules: Vec<KeywordAlertRule> =
+            matching_keyword_alerts(&self.settings.keyword_alerts, &plain)
+                .into_iter()
+                .cloned()
+                .collect();
+        let now = Instant::now();
+        let mut commands = Vec::new();
+        for rule in rules {
+            let fired = &mut self.instances[index].keyword_alerts_fired;
+            if fired
+                .get(&rule.key())
+                .is_some_and(|last| now.duration_since(*last) < KEYWORD_ALERT_COOLDOWN)
+            {
+                continue;
+            }
+            fired.insert(rule.key(), now);
+            let message = trf!("Найдено \"{}\": {}", rule.keyword.trim(), plain.trim());
+            if rule.banner {
+                self.notify(index, NotificationLevel::Warning, message.clone());
+            } else {
+                self.log_to(index, message.clone());
+            }
+            if rule.sound {
+                commands.push(Command::perform(
+                    play_alert_sound(),
+                    Message::KeywordAlertSignalled,
+                ));
+            }
+            if rule.desktop {
+                commands.push(self.desktop_notification_command(index, message));
+            }
+        }
+        Command::batch(commands)
+    }
+
+    // Извлечение метрик из строки вывода процесса экземпляра
+    fn extract_metrics(&mut self, index: usize, line: &str) {
+        let plain = ui::strip_ansi(line);
+        let now = chrono::Local::now();
+        for (name, value) in self.metric_extractor.extract(&plain) {
+            self.instances[index].metrics.insert(
+                name,
+                MetricValue {
+                    value,
+                    updated: now,
+                },
+            );
+        }
+    }
+
+    // Блокировка интерфейса (процессы продолжают работать)
+    fn lock_ui(&mut self) {
+        if !self.settings.ui_lock_available() || self.ui_locked {
+            return;
+        }
+        self.ui_locked = true;
+        self.unlock_pin_input.clear();
+        self.unlock_error = None;
+        self.add_log(tr("Интерфейс заблокирован.").to_string());
+    }
+
+    // Данные для пакета диагностики
+    fn diagnostics_input(&self) -> DiagnosticsInput {
+        let launcher_log = self
+            .current()
+            .logs
+            .iter()
+            .rev() // В окне лога новые строки идут первыми
+            .map(|line| {
+                let time = line.time.format("%Y-%m-%d %H:%M:%S%.3f");
+                format!("{} {}", time, line.labeled_text())
+            })
+            .collect();
+        let mut launcher_state: Vec<String> = self
+            .instances
+            .iter()
+            .enumerate()
+            .map(|(index, instance)| {
+                trf!(
+                    "Экземпляр \"{}\": запущен: {}, PID: {}",
+                    self.settings.instance_name(index),
+                    instance.is_running,
+                    format!("{:?}", instance.actual_pid)
+                )
+            })
+            .collect();
+        launcher_state.extend([
+            trf!("Питание: {}", format!("{:?}", self.power_state)),
+            trf!(
+                "Смещение часов (мс): {}",
+                format!("{:?}", self.clock_offset_ms)
+            ),
+            trf!("Окон обслуживания: {}", self.maintenance_windows.len()),
+            trf!("Директория данных: {}", format!("{:?}", self.data_dir)),
+            trf!(
+                "Пересылка логов: {}",
+                format!("{:?}", self.log_forward_status)
+            ),
+            String::new(),
+            tr("События жизненного цикла:").to_string(),
+        ]);
+        launcher_state.extend(self.events.iter().map(|event| {
+            format!(
+                "{} [{}] {}",
+                event.time.format("%Y-%m-%d %H:%M:%S"),
+                event.kind.label(),
+                event.description
+            )
+        }));
+        DiagnosticsInput {
+            settings: self.settings.clone(),
+            launcher_log,
+            launcher_state,
+            history: self.run_history.clone(),
+            logs_dir: self.data_dir.as_deref().map(logs_dir),
+            session_log: self
+                .current()
+                .session_log
+                .as_ref()
+                .map(|log| log.path().clone()),
+        }
+    }
+
+    // Сохранение отчета о сбое экземпляра: последние строки лога, код завершения,
+    // время запуска и настройки без секретов
+    fn crash_report_command(&self, index: usize, exit_code: i32) -> Command<Message> {
+        let Some(dir) = self.config_path.as_deref().and_then(crash_reports_dir) else {
+            return Command::none();
+        };
+        let instance = &self.instances[index];
+        let log_lines = instance
+            .logs
+            .iter()
+            .take(CRASH_REPORT_LINES)
+            .rev() // В окне лога новые строки идут первыми
+            .map(|line| {
+                let time = line.time.format("%Y-%m-%d %H:%M:%S%.3f");
+                format!("{} {}", time, line.labeled_text())
+            })
+            .collect();
+        let report = CrashReport {
+            instance: self.settings.instance_name(index),
+            exit_code,
+            started: instance.run_started,
+            ended: chrono::Local::now(),
+            settings: self.settings.clone(),
+            log_lines,
+        };
+        let id = instance.id;
+        Command::perform(save_crash_report(dir, report), move |result| {
+            Message::CrashReportSaved(id, result)
+        })
+    }
+
+    // Главный экран. `hide_logs` - скрыть лог и хронологию (заблокированный интерфейс)
+    fn view_main_screen(&self, hide_logs: bool) -> Element<'static, Message> {
+        let hidden_logs = VecDeque::new();
+        let instance = self.current();
+        ui::view_main(
+            ui::InstanceView {
+                id: instance.id,
+                tabs: self
+                    .instances
+                    .iter()
+                    .enumerate()
+                    .map(|(index, instance)| {
+                        (self.settings.instance_name(index), instance.is_running)
+                    })
+                    .collect(),
+                selected: self.selected_instance,
+                is_running: instance.is_running,
+                health: Some(instance.health).filter(|_| {
+                    instance.is_running
+                        && !self
+                            .settings
+                            .instance_health_check(self.selected_instance)
+                            .is_empty()
+                }),
+                uptime: instance
+                    .run_started
+                    .filter(|_| instance.is_running)
+                    .map(|started| chrono::Local::now() - started),
+                pid: instance.actual_pid.filter(|_| instance.is_running),
+                crashed: instance.last_crashed,
+                api_key: self.settings.instance_api_key(self.selected_instance),
+                orphan_process: instance.orphan_process.as_ref(),
+            },
+            if hide_logs {
+                &hidden_logs
+            } else {
+                &instance.logs
+            },
+            &self.settings,
+            self.kill_candidates.as_deref(),
+            ui::LogViewState {
+                filter: self.log_filter,
+                custom_from: &self.log_range_from,
+                custom_to: &self.log_range_to,
+                session_file: instance
+                    .session_log
+                    .as_ref()
+                    .map(|log| log.path().as_path()),
+                highlighted_line: self.highlighted_log_line,
+                search: &self.log_search,
+                hidden_levels: &self.hidden_log_levels,
+                stream: self.log_stream,
+                clear_pending: self.clear_log_pending,
+                json_view: self.log_json_view,
+                raw_view: self.log_raw_view,
+                bookmarks: &self.log_bookmarks,
+                highlighter: &self.highlighter,
+                expanded: &self.expanded_log_lines,
+                frozen_at: self.log_frozen_at,
+                selection: self
+                    .log_selection
+                    .map(|(anchor, end)| (anchor.min(end), anchor.max(end))),
+                search_position: {
+                    let total = self.log_search_matches().len();
+                    let current = self.log_search_position.min(total.saturating_sub(1));
+                    (total > 0).then_some((current + 1, total))
+                },
+                console_input: instance
+                    .process_stdin
+                    .as_ref()
+                    .filter(|_| instance.is_running)
+                    .map(|_| instance.console_input.as_str()),
+            },
+            ui::MonitorState {
+                power_state: self.power_state,
+                next_maintenance: next_maintenance_window(
+                    &self.maintenance_windows,
+                    chrono::Local::now(),
+                ),
+                clock_drift_ms: self.clock_offset_ms.filter(|_| self.clock_drift_exceeded()),
+                metrics: self
+                    .settings
+                    .metric_rules
+                    .iter()
+                    .map(|rule| rule.name.trim())
+                    .filter(|name| !name.is_empty())
+                    .map(|name| (name.to_string(), instance.metrics.get(name).copied()))
+                    .collect(),
+                notifications: self.notifications.active(),
+                events: if hide_logs { &[] } else { &self.events },
+                maintenance_windows: &self.maintenance_windows,
+                launch_changes: self.launch_changes(self.selected_instance),
+                data_dir_error: self.data_dir_error.as_deref(),
+                executable_problem: self.executable_problem.as_deref(),
+                process_usage: instance
+                    .process_usage
+                    .filter(|_| instance.is_running)
+                    .map(|usage| (usage, instance.peak_rss_bytes)),
+                silent_minutes: instance
+                    .last_output_at
+                    .filter(|_| instance.is_running && instance.process_hung)
+                    .map(|last| last.elapsed().as_secs() / 60),
+                dropped_lines: instance
+                    .output_flood_at
+                    .filter(|at| at.elapsed() < OUTPUT_FLOOD_WARNING)
+                    .map(|_| instance.dropped_lines),
+            },
+        )
+    }
+
+    // Текущее состояние для сохранения рабочего пространства
+    fn workspace_snapshot(&self) -> Workspace {
+        Workspace {
+            was_running: self.instances[0].is_running,
+            running_instances: self
+                .running_instances()
+                .into_iter()
+                .filter(|index| *index > 0)
+                .collect(),
+            selected_instance: self.selected_instance,
+            screen: self.screen,
+            log_filter: self.log_filter,
+            log_range_from: self.log_range_from.clone(),
+            log_range_to: self.log_range_to.clone(),
+            hidden_log_levels: self.hidden_log_levels.clone(),
+            log_stream: self.log_stream,
+            log_json_view: self.log_json_view,
+            log_raw_view: self.log_raw_view,
+            window: self.window_geometry,
+        }
+    }
+
+    // Параметры запуска экземпляра, измененные, пока процесс работает со старыми
+    fn launch_changes(&self, index: usize) -> Vec<&'static str> {
+        let current = self.settings.launch_settings(index);
+        match &self.instances[index].running_launch {
+            // Без ключа API перезапуск невозможен - не предлагаем его
+            Some(running) if self.instances[index].is_running && !current.api_key.is_empty() => {
+                running.changes(&current)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // Превышено ли допустимое расхождение системных часов
+    fn clock_drift_exceeded(&self) -> bool {
+        self.clock_offset_ms
+            .is_some_and(|offset| offset.abs() > self.settings.clock_drift_threshold_ms as f64)
+    }
+
+    // Запуск процесса экземпляра (кнопка "Запуск" и автоматические запуски)
+    fn start_process(&mut self, index: usize) -> Command<Message> {
+        // Предыдущий процесс еще корректно завершается
+        if let Some(pid) = self.instances[index].stopping_pid {
+            self.log_to(
+                index,
+                trf!("Запуск отложен: предыдущий процесс (PID: {}) еще завершается. Повторите позже.", pid),
+            );
+            return Command::none();
+        }
+        let is_running = self.instances[index].is_running;
+        // Запрет запуска при расхождении часов (если включен в настройках)
+        if !is_running && self.settings.block_start_on_clock_drift && self.clock_drift_exceeded() {
+            self.log_to(
+                index,
+                trf!(
+                    "Запуск запрещен: системные часы расходятся с NTP на {} мс (допустимо {} мс). \
+                     Синхронизируйте время или отключите запрет в настройках.",
+                    format!("{:+.0}", self.clock_offset_ms.unwrap_or_default()),
+                    self.settings.clock_drift_threshold_ms
+                ),
+            );
+            return Command::none();
+        }
+
+        // Дополнительные аргументы должны разбираться без ошибок
+        if let Err(e) = split_args(self.settings.instance_extra_args(index)) {
+            self.log_to(
+                index,
+                trf!("Запуск невозможен: дополнительные аргументы: {}", e),
+            );
+            return Command::none();
+        }
+
+        // Исполняемый файл должен существовать и запускаться
+        if !is_running {
+            if let Some(problem) = self.check_executable() {
+                self.log_to(
+                    index,
+                    trf!("Запуск невозможен: исполняемый файл: {}", problem),
+                );
+                return Command::none();
+            }
+        }
+
+        // Проверяем, можно ли запустить
+        if !is_running
+            && self.settings.executable_path.is_some()
+            && !self.settings.instance_api_key(index).is_empty()
+        {
+            // Проверяем, есть ли старый PID
+            if let Some(last_pid) = self.settings.instance_last_pid(index) {
+                // Процесс прошлого сеанса будет завершен перед запуском
+                self.instances[index].orphan_process = None;
+                self.log_to(
+                    index,
+                    trf!(
+                        "Обнаружен PID предыдущего запуска: {}. Попытка завершения...",
+                        last_pid
+                    ),
+                );
+                // Пытаемся убить старый процесс, после этого запускаем новый
+                let id = self.instances[index].id;
+                Command::perform(
+                    stop_process_gracefully(last_pid, self.graceful_stop_timeout()),
+                    move |result| Message::PreLaunchKillResult(id, result),
+                )
+            } else {
+                // Старого PID нет: проверяем, не запущен ли TradingStar без лаунчера
+                let id = self.instances[index].id;
+                let path = self.settings.executable_path.clone().unwrap_or_default();
+                Command::perform(find_processes_by_path(path), move |found| {
+                    Message::RunningCheckDone(id, found)
+                })
+            }
+        } else if is_running {
+            // Игнорируем, если уже запущен
+            Command::none()
+        } else {
+            self.log_to(index, tr("Ошибка: Проверьте путь и ключ API.").to_string());
+            Command::none()
+        }
+    }
+
+    // Запуск экземпляра при открытии лаунчера: если процесс прошлого сеанса еще работает,
+    // лаунчер подключается к нему вместо запуска второго процесса
+    fn resume_instance(&mut self, index: usize) -> Command<Message> {
+        if self.instances[index].orphan_process.is_some() {
+            // Процесс прошлого сеанса еще работает - подключаемся к нему
+            let id = self.instances[index].id;
+            self.update(Message::ReattachPressed(id))
+        } else if self.settings.instance_last_pid(index).is_some() {
+            // Сначала дожидаемся проверки процесса прошлого сеанса
+            self.instances[index].resume_pending = true;
+            Command::none()
+        } else {
+            self.start_process(index)
+        }
+    }
+
+    // Проверка исполняемого файла из настроек (результат показывается в настройках
+    // и в чек-листе готовности, с ошибкой кнопка запуска недоступна)
+    fn check_executable(&mut self) -> Option<String> {
+        self.executable_problem = self
+            .settings
+            .executable_path
+            .as_deref()
+            .and_then(validate_executable);
+        self.executable_problem.clone()
+    }
+
+    // Автозапуск при открытии лаунчера (если включен): все экземпляры с ключом API,
+    // кроме уже возобновленных (`resumed`)
+    fn autostart_command(&mut self, resumed: &[usize]) -> Command<Message> {
+        if !self.settings.autostart {
+            return Command::none();
+        }
+        if self.settings.executable_path.is_none() {
+            self.add_log(tr("Автозапуск пропущен: не выбран исполняемый файл.").to_string());
+            return Command::none();
+        }
+        let mut commands = Vec::new();
+        for index in 0..self.instances.len() {
+            if resumed.contains(&index) || self.instances[index].is_busy() {
+                continue;
+            }
+            if self.settings.instance_api_key(index).is_empty() {
+                self.log_to(
+                    index,
+                    tr("Автозапуск пропущен: не задан ключ API.").to_string(),
+                );
+                continue;
+            }
+            self.log_to(index, tr("Автозапуск при открытии лаунчера.").to_string());
+            commands.push(self.resume_instance(index));
+        }
+        Command::batch(commands)
+    }
+
+    // Автоматический запуск по расписанию (с учетом состояния питания)
+    fn scheduled_start(&mut self, index: usize, reason: &str) -> Command<Message> {
+        if let Some(power) = self.power_state {
+            if !power.allows_auto_start(self.settings.battery_min_charge) {
+                self.log_to(
+                    index,
+                    trf!("Планировщик: {}, но автоматический запуск отменен: питание от батареи с зарядом ниже {}%.", reason, self.settings.battery_min_charge),
+                );
+                return Command::none();
+            }
+        }
+        self.log_to(index, trf!("Планировщик: {}. Запуск процесса.", reason));
+        self.record_event(index, EventKind::Restart);
+        self.start_process(index)
+    }
+
+    // Создание нового файла лога сессии экземпляра (при запуске и по запросу пользователя)
+    fn start_new_session_log(&mut self, index: usize) {
+        // Номера строк в окне лога отсчитываются заново вместе с новым файлом
+        self.instances[index].line_count = 0;
+        let session_log = match self.data_dir.as_deref() {
+            Some(data_dir) => SessionLog::create(
+                &logs_dir(data_dir),
+                index,
+                chrono::Local::now(),
+                self.settings.session_log_max_mb,
+            ),
+            None => Err(tr("Не удалось определить директорию данных").to_string()),
+        };
+        self.instances[index].session_log = match session_log {
+            Ok(session_log) => Some(session_log),
+            Err(e) => {
+                self.log_to(index, trf!("Лог сессии не будет сохранен: {}", e));
+                None
+            }
+        };
+    }
+
+    // Создание новой подписки ProcessListener (собственно запуск процесса экземпляра)
+    fn launch_subscription(&mut self, index: usize, log_message: &str) -> Command<Message> {
+        self.instances[index].logs.clear();
+        // Новый запуск - новый файл лога сессии
+        self.start_new_session_log(index);
+        self.log_to(index, log_message.to_string());
+        let new_id = self.subscription_id_counter;
+        self.subscription_id_counter += 1;
+        let launch = self.settings.launch_settings(index);
+        let elevated = launch.run_elevated;
+        // Процесс, который должен пережить лаунчер, пишет вывод в файл рядом с логами сессий
+        let detached_output = match self.data_dir.as_deref() {
+            Some(data_dir) if launch.keep_running => {
+                Some(logs_dir(data_dir).join(format!("detached-{}.out", index + 1)))
+            }
+            _ => None,
+        };
+        if let Some(output) = &detached_output {
+            self.log_to(
+                index,
+                trf!("Процесс продолжит работу после закрытия лаунчера. Вывод пишется в {}, консоль недоступна.", format!("{:?}", output)),
+            );
+        } else if launch.keep_running {
+            self.log_to(
+                index,
+                tr("Работа после закрытия лаунчера недоступна: не определена директория данных.")
+                    .to_string(),
+            );
+        }
+        let instance = &mut self.instances[index];
+        instance.elevated = elevated;
+        instance.detached_output = detached_output;
+        // Метрики, оповещения и потребление ресурсов относятся к текущему запуску
+        instance.process_usage = None;
+        instance.peak_rss_bytes = 0;
+        instance.metrics.clear();
+        instance.alert_states.clear();
+        instance.is_running = true;
+        instance.running_launch = Some(launch);
+        instance.process_stdin = None;
+        // Сторожевой таймер отсчитывает молчание с момента запуска
+        instance.last_output_at = Some(Instant::now());
+        instance.process_hung = false;
+        instance.dropped_lines = 0;
+        instance.output_flood_at = None;
+        instance.health = HealthState::Starting;
+        instance.health_failures = 0;
+        instance.subscription_id = Some(new_id);
+        instance.actual_pid = None; // Сбрасываем, ждем новый PID от подписки
+                                    // Сохраняем настройки (на всякий случай, хотя PID еще не установлен)
+        self.save_settings_command()
+    }
+
+    // Завершение текущего запуска экземпляра: запись в историю и ее сохранение
+    fn finish_run(
+        &mut self,
+        index: usize,
+        outcome: RunOutcome,
+        exit_code: Option<i32>,
+    ) -> Command<Message> {
+        self.instances[index].last_crashed = outcome == RunOutcome::Crashed;
+        let Some(start) = self.instances[index].run_started.take() else {
+            return Command::none();
+        };
+        self.run_history.push(RunRecord {
+            start,
+            end: chrono::Local::now(),
+            exit_code,
+            outcome,
+        });
+        if self.run_history.len() > MAX_HISTORY_RECORDS {
+            let excess = self.run_history.len() - MAX_HISTORY_RECORDS;
+            self.run_history.drain(..excess);
+        }
+        self.save_history_command()
+    }
+
+    // Команда асинхронного сохранения истории запусков
+    fn save_history_command(&self) -> Command<Message> {
+        Command::perform(
+            save_history(
+                self.data_dir.as_deref().map(history_path),
+                self.run_history.clone(),
+            ),
+            Message::HistorySaved,
+        )
+    }
+
+    // Проверка доступа на запись в текущую директорию данных
+    fn check_data_dir_command(&self) -> Command<Message> {
+        let data_dir = self.data_dir.clone();
+        Command::perform(
+            async move {
+                match data_dir {
+                    Some(dir) => validate_data_dir(&dir).await,
+                    None => Err(tr("Не удалось определить директорию данных").to_string()),
+                }
+            },
+            Message::DataDirChecked,
+        )
+    }
+
+    // Смена директории данных (None - стандартная) с переносом существующих файлов
+    fn change_data_dir(&mut self, custom_dir: Option<PathBuf>) -> Command<Message> {
+        let Some(target) = resolve_data_dir(custom_dir.as_deref()) else {
+            self.add_log(tr("Не удалось определить стандартную директорию данных.").to_string());
+            return Command::none();
+        };
+        self.add_log(trf!("Перенос данных в {}...", format!("{:?}", target)));
+        Command::perform(
+            migrate_data_dir(self.data_dir.clone(), target, self.active_session_logs()),
+            move |result| Message::DataDirMigrated(custom_dir, result),
+        )
+    }
+
+    // Таймаут корректной остановки из настроек (0 - сразу принудительно)
+    fn graceful_stop_timeout(&self) -> Duration {
+        Duration::from_secs(self.settings.graceful_stop_timeout_secs as u64)
+    }
+
+    // Корректная остановка процесса по шагам: мягкий запрос завершения, ожидание,
+    // принудительное завершение. Каждый шаг пишется в лог (SoftStopSent, GracefulStopWaited).
+    // Если задана команда остановки и доступен stdin процесса, мягкий запрос - эта команда.
+    fn graceful_stop_command(
+        &mut self,
+        index: usize,
+        pid: u32,
+        stdin: Option<tokio::sync::mpsc::Sender<String>>,
+    ) -> Command<Message> {
+        self.instances[index].stopping_pid = Some(pid);
+        let id = self.instances[index].id;
+        if self.instances[index].elevated {
+            // У лаунчера нет прав на сигналы такому процессу: остановка тоже через UAC / pkexec
+            self.log_to(
+                index,
+                trf!("Остановка процесса с повышенными правами (PID: {}), потребуется подтверждение...", pid),
+            );
+            return Command::perform(
+                stop_elevated(pid, self.graceful_stop_timeout()),
+                move |result| Message::ProcessKillResult(id, result),
+            );
+        }
+        if self.settings.graceful_stop_timeout_secs == 0 {
+            self.log_to(
+                index,
+                trf!("Принудительное завершение процесса (PID: {})...", pid),
+            );
+            return Command::perform(kill_process(pid), move |result| {
+                Message::ProcessKillResult(id, result)
+            });
+        }
+        let stop_command = self.settings.instance_stop_command(index).to_string();
+        if let Some(stdin) = stdin.filter(|_| !stop_command.is_empty()) {
+            self.log_to(
+                index,
+                trf!(
+                    "Отправка процессу (PID: {}) команды остановки \"{}\"...",
+                    pid,
+                    stop_command
+                ),
+            );
+            return Command::perform(
+                async move {
+                    match stdin.send(stop_command).await {
+                        Ok(()) => Ok(()),
+                        // stdin уже закрыт - обычный запрос завершения
+                        Err(_) => terminate_process(pid).await,
+                    }
+                },
+                move |result| Message::SoftStopSent(id, pid, result),
+            );
+        }
+        self.log_to(
+            index,
+            trf!(
+                "Отправка процессу (PID: {}) запроса на корректное завершение...",
+                pid
+            ),
+        );
+        Command::perform(terminate_process(pid), move |result| {
+            Message::SoftStopSent(id, pid, result)
+        })
+    }
+
+    // Остановка процесса экземпляра (кнопка "Остановка" и автоматические остановки).
+    // `outcome` - как записать остановку в историю запусков.
+    fn stop_process(&mut self, index: usize, outcome: RunOutcome) -> Command<Message> {
+        let mut commands = vec![self.finish_run(index, outcome, None)];
+        let instance = &mut self.instances[index];
+        instance.attached = false;
+        let stdin = instance.process_stdin.take();
+        instance.is_running = false;
+        instance.subscription_id = None;
+        if let Some(pid) = instance.actual_pid.take() {
+            self.log_to(index, trf!("Остановка процесса (PID: {})...", pid));
+            self.record_event(index, EventKind::Stop);
+            commands.push(self.graceful_stop_command(index, pid, stdin));
+        } else {
+            self.log_to(
+                index,
+                tr("Процесс не запущен или PID неизвестен.").to_string(),
+            );
+            // Пытаемся найти процессы по пути к исполняемому файлу
+            if let Some(path) = self.settings.executable_path.clone() {
+                self.log_to(
+                    index,
+                    trf!(
+                        "Поиск процессов, запущенных из {}...",
+                        format!("{:?}", path)
+                    ),
+                );
+                commands.push(Command::perform(
+                    find_processes_by_path(path),
+                    Message::KillCandidatesFound,
+                ));
+            }
+        }
+        // Очищаем сохраненный PID и сохраняем настройки
+        if self.settings.set_instance_last_pid(index, None) {
+            commands.push(self.save_settings_command());
+        }
+        Command::batch(commands)
     }
 }
 
 // --- Точка входа в приложение ---
-fn main() -> iced::Result {
-    // Встраиваем байты иконки в исполняемый файл
-    // Используем путь относительно корня проекта
-    const ICON_BYTES: &[u8] = include_bytes!("assets/favicon-128x128.png");
+// Встраиваем байты иконки в исполняемый файл
+// Используем путь относительно корня проекта
+const ICON_BYTES: &[u8] = include_bytes!("assets/favicon-128x128.png");
 
+// Иконка приложения (RGBA); с цветом - с круглой меткой в правом нижнем углу
+fn app_icon_rgba(badge: Option<HighlightColor>) -> Option<image::RgbaImage> {
     // Загрузка иконки
-    let window_icon = match image::load_from_memory(ICON_BYTES) {
-        Ok(image) => {
-            let image = image.to_rgba8(); // Преобразуем в RGBA8
-            let (width, height) = image.dimensions();
-            let pixel_data = image.into_raw();
-            // Создаем иконку Iced
-            match icon::from_rgba(pixel_data, width, height) {
-                Ok(icon) => Some(icon),
-                Err(e) => {
-                    eprintln!("Ошибка создания иконки Iced: {}", e);
-                    None
-                }
+    let mut image = match image::load_from_memory(ICON_BYTES) {
+        Ok(image) => image.to_rgba8(), // Преобразуем в RGBA8
+        Err(e) => {
+            eprintln!("Ошибка загрузки файла иконки: {}", e);
+            return None;
+        }
+    };
+    let (width, height) = image.dimensions();
+    if let Some(color) = badge {
+        let (r, g, b) = color.rgb();
+        let radius = width.min(height) as f32 / 4.0;
+        let center = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let distance = (x as f32 + 0.5 - center.0).hypot(y as f32 + 0.5 - center.1);
+            if distance <= radius - 2.0 {
+                *pixel = image::Rgba([r, g, b, 0xFF]);
+            } else if distance <= radius {
+                // Темная обводка, чтобы метка была видна на любом фоне
+                *pixel = image::Rgba([0x20, 0x20, 0x20, 0xFF]);
             }
         }
+    }
+    Some(image)
+}
+
+// Иконка окна; с цветом профиля - с меткой, чтобы окна лаунчеров с разными
+// профилями различались на панели задач
+fn window_icon(badge: Option<HighlightColor>) -> Option<icon::Icon> {
+    let image = app_icon_rgba(badge)?;
+    let (width, height) = image.dimensions();
+    // Создаем иконку Iced
+    match icon::from_rgba(image.into_raw(), width, height) {
+        Ok(icon) => Some(icon),
         Err(e) => {
-            eprintln!("Ошибка загрузки файла иконки: {}", e);
+            eprintln!("Ошибка создания иконки Iced: {}", e);
             None
         }
+    }
+}
+
+fn main() -> iced::Result {
+    let window_icon = window_icon(None);
+
+    // Параметры командной строки (при ошибке clap выводит справку и завершает программу)
+    let cli = CliArgs::parse();
+    // Вспомогательный процесс остановки с повышенными правами (см. stop_elevated)
+    if let Some(pid) = cli.stop_pid {
+        std::process::exit(run_stop_helper(pid, Duration::from_secs(cli.stop_timeout)));
+    }
+
+    // Размер и положение окна из прошлого сеанса: окно сразу открывается на прежнем месте
+    let config_path = cli.config.clone().or_else(get_config_path);
+    let data_dir = resolve_data_dir(
+        config_path
+            .as_deref()
+            .and_then(read_data_dir_setting)
+            .as_deref(),
+    );
+    let geometry = data_dir
+        .as_deref()
+        .map(workspace_path)
+        .and_then(|path| workspace::read_window_geometry(&path));
+    let position = match geometry {
+        Some(WindowGeometry {
+            x: Some(x),
+            y: Some(y),
+            ..
+        }) => window::Position::Specific(iced::Point::new(x as f32, y as f32)),
+        _ => window::Position::default(),
     };
 
     // Настройки окна приложения
     let settings = Settings {
+        flags: cli,
         window: iced::window::Settings {
-            size: iced::Size::new(800.0, 600.0),
+            size: geometry.map_or(iced::Size::new(800.0, 600.0), |geometry| {
+                iced::Size::new(geometry.width as f32, geometry.height as f32)
+            }),
+            position,
             exit_on_close_request: false,
             icon: window_icon, // <-- Устанавливаем иконку окна
             ..iced::window::Settings::default()
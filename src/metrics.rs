@@ -0,0 +1,143 @@
+use crate::i18n::{tr, trf};
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// --- Извлечение пользовательских метрик из вывода процесса ---
+
+// Правило извлечения метрики: имя и регулярное выражение с группой захвата.
+// Значение берется из именованной группы `value`, а при ее отсутствии - из первой группы.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MetricRule {
+    pub name: String,    // Имя метрики (например, PnL)
+    pub pattern: String, // Регулярное выражение, например `PnL: (?P<value>-?\d+\.\d+)`
+}
+
+// Последнее значение метрики
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricValue {
+    pub value: f64,               // Значение
+    pub updated: DateTime<Local>, // Когда оно было получено
+}
+
+// Скомпилированный набор правил
+#[derive(Debug, Default)]
+pub struct MetricExtractor {
+    rules: Vec<(String, Regex)>,
+}
+
+impl MetricExtractor {
+    // Компиляция правил. Возвращает экстрактор из корректных правил и ошибку
+    // для каждого правила (None - правило корректно) в том же порядке.
+    pub fn compile(rules: &[MetricRule]) -> (Self, Vec<Option<String>>) {
+        let mut compiled = Vec::new();
+        let mut errors = Vec::with_capacity(rules.len());
+        for rule in rules {
+            match compile_rule(rule) {
+                Ok(regex) => {
+                    compiled.push((rule.name.trim().to_string(), regex));
+                    errors.push(None);
+                }
+                Err(e) => errors.push(Some(e)),
+            }
+        }
+        (Self { rules: compiled }, errors)
+    }
+
+    // Применяет правила к строке и возвращает найденные значения (имя, значение)
+    pub fn extract(&self, line: &str) -> Vec<(String, f64)> {
+        self.rules
+            .iter()
+            .filter_map(|(name, regex)| {
+                let captures = regex.captures(line)?;
+                let matched = captures.name("value").or_else(|| captures.get(1))?.as_str();
+                parse_number(matched).map(|value| (name.clone(), value))
+            })
+            .collect()
+    }
+}
+
+// Проверка и компиляция одного правила
+fn compile_rule(rule: &MetricRule) -> Result<Regex, String> {
+    if rule.name.trim().is_empty() {
+        return Err(tr("Не задано имя метрики").to_string());
+    }
+    if rule.pattern.is_empty() {
+        return Err(tr("Не задано регулярное выражение").to_string());
+    }
+    let regex =
+        Regex::new(&rule.pattern).map_err(|e| trf!("Ошибка в регулярном выражении: {}", e))?;
+    if regex.captures_len() < 2 {
+        return Err(tr("В выражении нет группы захвата для значения").to_string());
+    }
+    Ok(regex)
+}
+
+// Разбор числа с учетом запятой как десятичного разделителя и пробелов между разрядами
+pub fn parse_number(text: &str) -> Option<f64> {
+    let normalized: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect();
+    normalized.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, pattern: &str) -> MetricRule {
+        MetricRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[test]
+    fn compile_reports_errors_per_rule() {
+        let (extractor, errors) = MetricExtractor::compile(&[
+            rule(" PnL ", r"PnL: (?P<value>-?[\d.,]+)"),
+            rule("  ", r"(\d+)"),
+            rule("Пусто", ""),
+            rule("Ошибка", r"(\d+"),
+            rule("Без группы", r"\d+"),
+        ]);
+        assert!(errors[0].is_none());
+        assert!(errors[1..].iter().all(Option::is_some));
+        assert_eq!(extractor.rules.len(), 1);
+        assert_eq!(extractor.rules[0].0, "PnL"); // Имя без пробелов по краям
+    }
+
+    #[test]
+    fn extract_prefers_named_group() {
+        let (extractor, _) = MetricExtractor::compile(&[
+            rule("PnL", r"(PnL): (?P<value>-?[\d.,]+)"),
+            rule("Orders", r"orders=(\d+)"),
+        ]);
+        assert_eq!(
+            extractor.extract("PnL: -12.5 orders=3"),
+            vec![("PnL".to_string(), -12.5), ("Orders".to_string(), 3.0)]
+        );
+        assert!(extractor.extract("нет значений").is_empty());
+    }
+
+    #[test]
+    fn extract_skips_values_that_are_not_numbers() {
+        let (extractor, _) = MetricExtractor::compile(&[rule("Status", r"status=(\w+)")]);
+        assert!(extractor.extract("status=ok").is_empty());
+        assert_eq!(
+            extractor.extract("status=7"),
+            vec![("Status".to_string(), 7.0)]
+        );
+    }
+
+    #[test]
+    fn parse_number_accepts_separators() {
+        assert_eq!(parse_number("1 234,5"), Some(1234.5));
+        assert_eq!(parse_number("1_000"), Some(1000.0));
+        assert_eq!(parse_number("-0.25"), Some(-0.25));
+        assert_eq!(parse_number(""), None);
+        assert_eq!(parse_number("1,2,3"), None);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::i18n::trf;
+use chrono::{DateTime, Local};
+use std::path::PathBuf;
+
+// --- Уведомления пользователя ---
+// Баннеры на главном экране (NotificationCenter) и системные уведомления рабочего стола:
+// их видно, даже когда окно лаунчера свернуто или спрятано в область уведомлений.
+
+// Заголовок системного уведомления
+const NOTIFICATION_TITLE: &str = "TradingStar 3 Launcher";
+
+// Сколько последних уведомлений показывать на главном экране
+pub const MAX_NOTIFICATIONS: usize = 5;
+
+// Важность уведомления (определяет цвет баннера)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Warning,  // Предупреждение
+    Critical, // Критическое событие (например, процесс остановлен)
+}
+
+// Действие, доступное из уведомления
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotificationAction {
+    OpenFolder(PathBuf), // Открыть директорию в файловом менеджере
+}
+
+// Уведомление, ожидающее просмотра пользователем
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub id: u64,                            // Идентификатор (для закрытия)
+    pub level: NotificationLevel,           // Важность
+    pub message: String,                    // Текст уведомления
+    pub time: DateTime<Local>,              // Время возникновения
+    pub action: Option<NotificationAction>, // Кнопка действия в баннере
+}
+
+// Центр уведомлений: единая точка, через которую проходят все уведомления лаунчера
+#[derive(Debug, Default)]
+pub struct NotificationCenter {
+    items: Vec<Notification>, // Активные (не закрытые) уведомления, от старых к новым
+    next_id: u64,             // Счетчик идентификаторов
+}
+
+impl NotificationCenter {
+    // Добавление уведомления (самые старые вытесняются при превышении лимита)
+    pub fn push(&mut self, level: NotificationLevel, message: String) {
+        self.push_with_action(level, message, None);
+    }
+
+    // Добавление уведомления с кнопкой действия
+    pub fn push_with_action(
+        &mut self,
+        level: NotificationLevel,
+        message: String,
+        action: Option<NotificationAction>,
+    ) {
+        self.items.push(Notification {
+            id: self.next_id,
+            level,
+            message,
+            time: Local::now(),
+            action,
+        });
+        self.next_id += 1;
+        if self.items.len() > MAX_NOTIFICATIONS {
+            self.items.remove(0);
+        }
+    }
+
+    // Закрытие уведомления пользователем
+    pub fn dismiss(&mut self, id: u64) {
+        self.items.retain(|notification| notification.id != id);
+    }
+
+    // Активные уведомления
+    pub fn active(&self) -> &[Notification] {
+        &self.items
+    }
+}
+
+// Системное уведомление рабочего стола (D-Bus в Linux, центр уведомлений Windows и macOS)
+pub async fn show_desktop_notification(message: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let mut notification = notify_rust::Notification::new();
+        notification
+            .appname(NOTIFICATION_TITLE)
+            .summary(NOTIFICATION_TITLE)
+            .body(&message);
+        #[cfg(all(unix, not(target_os = "macos")))]
+        notification.urgency(notify_rust::Urgency::Critical);
+        notification
+            .show()
+            .map(|_| ())
+            .map_err(|e| trf!("Не удалось показать системное уведомление: {}", e))
+    })
+    .await
+    .map_err(|e| trf!("Не удалось показать системное уведомление: {}", e))?
+}
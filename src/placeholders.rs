@@ -0,0 +1,115 @@
+use chrono::{DateTime, Local};
+use std::path::Path;
+
+// --- Подстановки в аргументах и переменных окружения процесса ---
+// Раскрываются в момент запуска процесса. Неизвестные подстановки остаются как есть.
+
+// Список поддерживаемых подстановок (для подсказки в настройках)
+pub const PLACEHOLDERS_HELP: &str =
+    "{api_key} - ключ API, {profile} - имя профиля запуска, {date} - дата запуска (ГГГГ-ММ-ДД), {time} - время запуска (ЧЧ-ММ-СС), {data_dir} - директория данных лаунчера";
+
+// Значения подстановок для одного запуска
+pub struct Placeholders<'a> {
+    pub api_key: &'a str,           // Ключ API
+    pub profile: &'a str,           // Имя выбранного профиля запуска (пусто без профиля)
+    pub data_dir: Option<&'a Path>, // Директория данных лаунчера
+    pub now: DateTime<Local>,       // Момент запуска
+}
+
+impl Placeholders<'_> {
+    // Раскрытие подстановок вида {имя} в строке (за один проход: значение
+    // подстановки повторно не раскрывается)
+    pub fn expand(&self, value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let candidate = &rest[start..];
+            let replacement = candidate
+                .find('}')
+                .and_then(|end| Some((end, self.value_of(&candidate[1..end])?)));
+            match replacement {
+                Some((end, replacement)) => {
+                    result.push_str(&replacement);
+                    rest = &candidate[end + 1..];
+                }
+                None => {
+                    result.push('{');
+                    rest = &candidate[1..];
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn value_of(&self, name: &str) -> Option<String> {
+        match name {
+            "api_key" => Some(self.api_key.to_string()),
+            "profile" => Some(self.profile.to_string()),
+            "date" => Some(self.now.format("%Y-%m-%d").to_string()),
+            "time" => Some(self.now.format("%H-%M-%S").to_string()),
+            "data_dir" => Some(
+                self.data_dir
+                    .map(|dir| dir.display().to_string())
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn placeholders(data_dir: Option<&Path>) -> Placeholders<'_> {
+        Placeholders {
+            api_key: "KEY123",
+            profile: "Основной",
+            data_dir,
+            now: Local.with_ymd_and_hms(2024, 3, 5, 7, 8, 9).unwrap(),
+        }
+    }
+
+    #[test]
+    fn expands_known_placeholders() {
+        let dir = Path::new("/data");
+        let values = placeholders(Some(dir));
+        assert_eq!(
+            values.expand("--key={api_key} --profile {profile}"),
+            "--key=KEY123 --profile Основной"
+        );
+        assert_eq!(
+            values.expand("{data_dir}/logs/{date}_{time}.log"),
+            format!("{}/logs/2024-03-05_07-08-09.log", dir.display())
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_and_unclosed_placeholders() {
+        let values = placeholders(None);
+        assert_eq!(values.expand("{unknown} {api_key"), "{unknown} {api_key");
+        assert_eq!(values.expand("{{api_key}}"), "{KEY123}");
+        assert_eq!(values.expand("}{"), "}{");
+    }
+
+    #[test]
+    fn empty_values_without_data_dir_or_profile() {
+        let values = Placeholders {
+            profile: "",
+            ..placeholders(None)
+        };
+        assert_eq!(values.expand("[{data_dir}][{profile}]"), "[][]");
+    }
+
+    #[test]
+    fn replacement_is_not_expanded_again() {
+        let values = Placeholders {
+            api_key: "{date}",
+            ..placeholders(None)
+        };
+        assert_eq!(values.expand("{api_key}"), "{date}");
+    }
+}
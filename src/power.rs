@@ -0,0 +1,57 @@
+use crate::i18n::trf;
+use starship_battery::units::ratio::percent;
+use starship_battery::{Manager, State};
+
+// --- Состояние электропитания ---
+
+// Снимок состояния питания: от батареи или от сети, и уровень заряда
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerState {
+    pub on_battery: bool,            // Работаем от батареи (батарея разряжается)
+    pub charge_percent: Option<f32>, // Средний заряд батарей в процентах (None - батарей нет)
+}
+
+impl PowerState {
+    // Разрешены ли автоматические запуски при заданном минимальном заряде
+    // (от сети - всегда, от батареи - только при заряде не ниже порога)
+    pub fn allows_auto_start(&self, min_charge_percent: u8) -> bool {
+        if !self.on_battery {
+            return true;
+        }
+        match self.charge_percent {
+            Some(charge) => charge >= min_charge_percent as f32,
+            None => true,
+        }
+    }
+}
+
+// Чтение состояния питания (опрос батарей выполняется вне потока UI)
+pub async fn read_power_state() -> Result<PowerState, String> {
+    tokio::task::spawn_blocking(|| {
+        let manager =
+            Manager::new().map_err(|e| trf!("Не удалось получить данные о питании: {}", e))?;
+        let batteries = manager
+            .batteries()
+            .map_err(|e| trf!("Не удалось получить список батарей: {}", e))?;
+
+        let mut on_battery = false;
+        let mut charges = Vec::new();
+        for battery in batteries.flatten() {
+            if battery.state() == State::Discharging {
+                on_battery = true;
+            }
+            charges.push(battery.state_of_charge().get::<percent>());
+        }
+        let charge_percent = if charges.is_empty() {
+            None
+        } else {
+            Some(charges.iter().sum::<f32>() / charges.len() as f32)
+        };
+        Ok(PowerState {
+            on_battery,
+            charge_percent,
+        })
+    })
+    .await
+    .map_err(|e| trf!("Ошибка опроса состояния питания: {}", e))?
+}
@@ -1,156 +1,1521 @@
+use crate::encoding::OutputEncoding; // Кодировка вывода процесса
+use crate::i18n::{tr, trf};
+use crate::ui::LogSource; // Источник строки лога (stdout/stderr)
 use crate::Message; // Импортируем типы из корневого модуля
 use iced::{
     advanced::subscription::{EventStream, Recipe},
     futures::stream::{BoxStream, StreamExt},
+    futures::SinkExt,
+    subscription, Subscription,
 };
-// Добавляем нужные use для Hasher и Hash
-use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
+// Добавляем нужный use для Hash
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command as TokioCommand};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
+// --- Определение исполняемого файла ---
+
+// Возвращает путь к реальному исполняемому файлу.
+// На macOS пользователь может выбрать пакет `.app` - в этом случае
+// ищем исполняемый файл внутри пакета (Contents/MacOS/...).
+// На остальных ОС путь возвращается без изменений.
+pub fn resolve_executable(path: &Path) -> Result<PathBuf, String> {
+    #[cfg(target_os = "macos")]
+    {
+        if is_app_bundle(path) {
+            return resolve_app_bundle(path);
+        }
+    }
+    Ok(path.to_path_buf())
+}
+
+// Проверка исполняемого файла до запуска: None - файл можно запускать, иначе - причина.
+// Без нее устаревший путь обнаруживался бы только при запуске ошибкой ОС.
+pub fn validate_executable(path: &Path) -> Option<String> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Some(tr("Файл не найден").to_string());
+        }
+        Err(e) => return Some(trf!("Файл недоступен: {}", e)),
+    };
+    if !metadata.is_file() {
+        return Some(tr("Указан не файл, а директория").to_string());
+    }
+    #[cfg(windows)]
+    {
+        let is_exe = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("exe"))
+            .unwrap_or(false);
+        if !is_exe {
+            return Some(tr("Ожидается исполняемый файл с расширением .exe").to_string());
+        }
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Some(tr("Нет права на выполнение (chmod +x)").to_string());
+        }
+    }
+    None
+}
+
+// Является ли путь пакетом приложения macOS (директория с расширением .app)
+#[cfg(target_os = "macos")]
+fn is_app_bundle(path: &Path) -> bool {
+    path.is_dir()
+        && path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("app"))
+            .unwrap_or(false)
+}
+
+// Поиск исполняемого файла внутри пакета .app
+#[cfg(target_os = "macos")]
+fn resolve_app_bundle(bundle: &Path) -> Result<PathBuf, String> {
+    let macos_dir = bundle.join("Contents").join("MacOS");
+
+    // 1. Имя из ключа CFBundleExecutable в Info.plist (XML-формат)
+    let plist_path = bundle.join("Contents").join("Info.plist");
+    if let Ok(content) = std::fs::read_to_string(&plist_path) {
+        if let Some(name) = plist_string_value(&content, "CFBundleExecutable") {
+            let candidate = macos_dir.join(name);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    // 2. Файл с тем же именем, что и пакет (TradingStar.app -> TradingStar)
+    if let Some(stem) = bundle.file_stem() {
+        let candidate = macos_dir.join(stem);
+        if candidate.is_file() {
+            return Ok(candidate);
+        }
+    }
+
+    // 3. Единственный файл в Contents/MacOS
+    let files: Vec<PathBuf> = std::fs::read_dir(&macos_dir)
+        .map_err(|e| trf!("Не удалось прочитать {}: {}", format!("{:?}", macos_dir), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    match files.as_slice() {
+        [single] => Ok(single.clone()),
+        _ => Err(trf!(
+            "Не удалось найти исполняемый файл внутри пакета {}",
+            format!("{:?}", bundle)
+        )),
+    }
+}
+
+// Простейшее извлечение строкового значения ключа из XML plist
+// (<key>Имя</key><string>Значение</string>). Бинарные plist не поддерживаются -
+// для них сработают запасные варианты поиска.
+#[cfg(target_os = "macos")]
+fn plist_string_value(content: &str, key: &str) -> Option<String> {
+    let key_tag = format!("<key>{}</key>", key);
+    let after_key = &content[content.find(&key_tag)? + key_tag.len()..];
+    let start = after_key.find("<string>")? + "<string>".len();
+    let end = after_key[start..].find("</string>")? + start;
+    let value = after_key[start..end].trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+// --- Подготовка команды запуска ---
+
+// Создает команду запуска для исполняемого файла с учетом особенностей платформы.
+// На Linux:
+//  - AppImage делается исполняемым (после скачивания бит +x обычно отсутствует);
+//  - если в системе нет FUSE, AppImage запускается в режиме распаковки;
+//  - если сам лаунчер работает внутри Flatpak, процесс запускается на хосте
+//    через `flatpak-spawn --host`, иначе файл вне песочницы недоступен.
+// Окружение, рабочая директория и режим запуска берутся из `options`
+// (повышенные права в Windows обрабатываются отдельно, см. run_elevated).
+// Отсоединенный процесс не привязывается к времени жизни лаунчера.
+fn build_command(path: &Path, options: &ProcessOptions) -> Result<TokioCommand, String> {
+    let env = &options.env;
+    let working_dir = options.working_dir.as_deref();
+    let detached = options.detached_output.is_some();
+    if let Some(dir) = working_dir {
+        if !dir.is_dir() {
+            return Err(trf!(
+                "Рабочая директория {} не существует",
+                format!("{:?}", dir)
+            ));
+        }
+    }
+    #[cfg(unix)]
+    if options.elevated {
+        let mut command = build_elevated_command(path, env, working_dir, detached)?;
+        command.kill_on_drop(!detached); // Завершать процесс при остановке подписки
+        command.process_group(0); // Своя группа процессов: остановка завершит и дочерние
+        return Ok(command);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut command = if running_in_flatpak() {
+            let mut command = TokioCommand::new("flatpak-spawn");
+            command.arg("--host");
+            if !detached {
+                // --watch-bus: процесс на хосте завершится вместе с лаунчером
+                command.arg("--watch-bus");
+            }
+            if is_appimage(path) && !fuse_available() {
+                command.arg("--env=APPIMAGE_EXTRACT_AND_RUN=1");
+            }
+            // Окружение песочницы на хост не передается - только явно через --env
+            for (key, value) in env {
+                command.arg(format!("--env={}={}", key, value));
+            }
+            if let Some(dir) = working_dir {
+                command.arg(format!("--directory={}", dir.display()));
+            }
+            command.arg(path);
+            command
+        } else {
+            let mut command = TokioCommand::new(path);
+            if is_appimage(path) && !fuse_available() {
+                command.env("APPIMAGE_EXTRACT_AND_RUN", "1");
+            }
+            command.envs(env.iter().cloned());
+            if let Some(dir) = working_dir {
+                command.current_dir(dir);
+            }
+            if !detached {
+                exit_with_launcher(&mut command);
+            }
+            command
+        };
+        if is_appimage(path) {
+            ensure_executable(path)?;
+        }
+        command.kill_on_drop(!detached); // Завершать процесс при остановке подписки
+        command.process_group(0); // Своя группа процессов: остановка завершит и дочерние
+        Ok(command)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let mut command = TokioCommand::new(path);
+        command.envs(env.iter().cloned());
+        if let Some(dir) = working_dir {
+            command.current_dir(dir);
+        }
+        command.kill_on_drop(!detached); // Завершать процесс при остановке подписки
+        #[cfg(unix)]
+        command.process_group(0); // Своя группа процессов: остановка завершит и дочерние
+        Ok(command)
+    }
+}
+
+// Команда запуска с повышенными правами (Linux, macOS): через pkexec (системное окно
+// запроса пароля) или sudo. Оба сбрасывают окружение, поэтому переменные передаются через env,
+// а рабочая директория - через sh. pkexec, sh и env заменяют себя следующей программой (exec),
+// так что PID запущенного процесса остается PID процесса TradingStar.
+#[cfg(unix)]
+fn build_elevated_command(
+    path: &Path,
+    env: &[(String, String)],
+    working_dir: Option<&Path>,
+    detached: bool,
+) -> Result<TokioCommand, String> {
+    #[allow(unused_mut)]
+    let mut env = env.to_vec();
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        if is_appimage(path) {
+            ensure_executable(path)?;
+            if !fuse_available() {
+                env.push(("APPIMAGE_EXTRACT_AND_RUN".to_string(), "1".to_string()));
+            }
+        }
+        if running_in_flatpak() {
+            // pkexec запускается на хосте: внутри песочницы повысить права нельзя
+            let mut command = TokioCommand::new("flatpak-spawn");
+            command.arg("--host");
+            if !detached {
+                command.arg("--watch-bus");
+            }
+            command.arg("pkexec");
+            command
+        } else {
+            elevation_command()?
+        }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let mut command = elevation_command()?;
+    #[cfg(not(target_os = "linux"))]
+    let _ = detached;
+
+    if let Some(dir) = working_dir {
+        command
+            .arg("/bin/sh")
+            .arg("-c")
+            .arg("cd -- \"$0\" && exec \"$@\"")
+            .arg(dir);
+    }
+    command.arg("/usr/bin/env");
+    for (key, value) in &env {
+        command.arg(format!("{}={}", key, value));
+    }
+    command.arg(path);
+    Ok(command)
+}
+
+// Программа повышения прав: pkexec, если он есть, иначе sudo. sudo не может спросить
+// пароль без терминала, поэтому работает через SUDO_ASKPASS или правило NOPASSWD.
+#[cfg(unix)]
+fn elevation_command() -> Result<TokioCommand, String> {
+    if find_in_path("pkexec") {
+        return Ok(TokioCommand::new("pkexec"));
+    }
+    if find_in_path("sudo") {
+        let mut command = TokioCommand::new("sudo");
+        if std::env::var_os("SUDO_ASKPASS").is_some() {
+            command.arg("-A");
+        } else {
+            command.arg("-n");
+        }
+        command.arg("--");
+        return Ok(command);
+    }
+    Err(tr("Для запуска с повышенными правами нужен pkexec или sudo").to_string())
+}
+
+// Есть ли программа в одной из директорий PATH
+#[cfg(unix)]
+fn find_in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+// Процесс получит SIGTERM, если лаунчер завершится, даже аварийно (kill_on_drop
+// срабатывает только при штатном освобождении Child). PR_SET_PDEATHSIG привязан
+// к потоку, запустившему процесс, - это рабочий поток tokio, живущий до выхода лаунчера.
+#[cfg(target_os = "linux")]
+fn exit_with_launcher(command: &mut TokioCommand) {
+    use nix::errno::Errno;
+    use nix::sys::signal::Signal;
+    use nix::unistd::{getppid, Pid};
+
+    let launcher = Pid::from_raw(std::process::id() as i32);
+    // SAFETY: в замыкании только системные вызовы prctl и getppid, безопасные после fork.
+    // Ошибки - коды errno без выделения памяти (после fork выделять память нельзя).
+    unsafe {
+        command.pre_exec(move || {
+            nix::sys::prctl::set_pdeathsig(Signal::SIGTERM)?;
+            // Лаунчер завершился еще до prctl - сигнал уже не придет.
+            // Сообщение об ошибке формирует describe_spawn_error в родительском процессе.
+            if getppid() != launcher {
+                return Err(Errno::ESRCH.into());
+            }
+            Ok(())
+        });
+    }
+}
+
+// Объект задания Windows, в который помещаются все запущенные процессы (и их потомки).
+// Дескриптор задания не закрывается: система закроет его при выходе лаунчера, в том
+// числе аварийном, и благодаря JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE завершит процессы задания.
+#[cfg(windows)]
+fn launcher_job() -> Result<isize, String> {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    static JOB: OnceLock<Result<isize, String>> = OnceLock::new();
+    JOB.get_or_init(|| {
+        // SAFETY: дескриптор проверяется на null, структура ограничений живет до конца вызова
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err(trf!(
+                    "Не удалось создать объект задания: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+            let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let ok = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &limits as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const _,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            ) != 0;
+            if !ok {
+                let error = std::io::Error::last_os_error();
+                CloseHandle(job);
+                return Err(trf!("Не удалось настроить объект задания: {}", error));
+            }
+            Ok(job as isize)
+        }
+    })
+    .clone()
+}
+
+// Помещает запущенный процесс в объект задания лаунчера
+#[cfg(windows)]
+fn exit_with_launcher(child: &Child) -> Result<(), String> {
+    use windows_sys::Win32::System::JobObjects::AssignProcessToJobObject;
+
+    let job = launcher_job()?;
+    let Some(handle) = child.raw_handle() else {
+        return Ok(()); // Процесс уже завершился
+    };
+    // SAFETY: оба дескриптора действительны: задание не закрывается, процесс принадлежит child
+    if unsafe { AssignProcessToJobObject(job as _, handle as _) } == 0 {
+        return Err(trf!(
+            "Не удалось добавить процесс в объект задания: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+// Запущен ли лаунчер внутри песочницы Flatpak
+#[cfg(target_os = "linux")]
+fn running_in_flatpak() -> bool {
+    Path::new("/.flatpak-info").exists() || std::env::var_os("FLATPAK_ID").is_some()
+}
+
+// Является ли файл образом AppImage (по расширению или по сигнатуре "AI" в заголовке ELF)
+#[cfg(target_os = "linux")]
+fn is_appimage(path: &Path) -> bool {
+    use std::io::Read;
+
+    let by_extension = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("appimage"))
+        .unwrap_or(false);
+    if by_extension {
+        return true;
+    }
+    let mut header = [0u8; 11];
+    match std::fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => &header[0..4] == b"\x7fELF" && &header[8..10] == b"AI" && header[10] <= 2,
+        Err(_) => false,
+    }
+}
+
+// Доступен ли FUSE, необходимый AppImage для монтирования образа
+#[cfg(target_os = "linux")]
+fn fuse_available() -> bool {
+    Path::new("/dev/fuse").exists()
+}
+
+// Добавляет права на выполнение, если их нет
+#[cfg(target_os = "linux")]
+fn ensure_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = std::fs::metadata(path).map_err(|e| {
+        trf!(
+            "Не удалось прочитать атрибуты файла {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })?;
+    let mut permissions = metadata.permissions();
+    if permissions.mode() & 0o111 == 0 {
+        permissions.set_mode(permissions.mode() | 0o755);
+        std::fs::set_permissions(path, permissions).map_err(|e| {
+            trf!(
+                "AppImage {} не является исполняемым, и не удалось установить права (chmod +x): {}",
+                format!("{:?}", path),
+                e
+            )
+        })?;
+        println!(
+            "[build_command] Установлены права на выполнение для {:?}",
+            path
+        );
+    }
+    Ok(())
+}
+
+// Понятное описание ошибки запуска вместо системного сообщения
+fn describe_spawn_error(path: &Path, error: &std::io::Error) -> String {
+    #[cfg(target_os = "linux")]
+    {
+        if running_in_flatpak() && error.kind() == std::io::ErrorKind::NotFound {
+            return trf!("Не удалось запустить {} из Flatpak: команда flatpak-spawn недоступна. \
+                 Разрешите доступ к org.freedesktop.Flatpak (--talk-name=org.freedesktop.Flatpak). ({})", format!("{:?}", path), error);
+        }
+        // ESRCH из exit_with_launcher: лаунчер завершился раньше, чем запустился процесс
+        if error.raw_os_error() == Some(nix::errno::Errno::ESRCH as i32) {
+            return trf!(
+                "Процесс {} не запущен: лаунчер завершился",
+                format!("{:?}", path)
+            );
+        }
+    }
+    match error.kind() {
+        std::io::ErrorKind::NotFound => {
+            trf!("Исполняемый файл {} не найден ({})", format!("{:?}", path), error)
+        }
+        std::io::ErrorKind::PermissionDenied => trf!("Нет прав на запуск {}: проверьте права на выполнение (chmod +x) ({})", format!("{:?}", path), error),
+        // ENOEXEC: файл не является исполняемым для этой ОС/архитектуры
+        _ if error.raw_os_error() == Some(8) && cfg!(unix) => trf!("Файл {} не является исполняемым для этой системы (неверный формат или архитектура) ({})", format!("{:?}", path), error),
+        _ => trf!("Ошибка запуска процесса {}: {}", format!("{:?}", path), error),
+    }
+}
+
 // --- Управление процессом ---
 
-// Функция для принудительного завершения процесса по PID
-pub async fn kill_process(pid: u32) -> Result<(), String> {
-    println!("[kill_process] Попытка завершить процесс с PID: {}", pid);
+// Ошибка остановки процесса
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillError {
+    NotFound,     // Процесса с таким PID нет (уже завершился)
+    AccessDenied, // Недостаточно прав для остановки процесса
+    NoWindows,    // У процесса нет окон - мягкий запрос на закрытие невозможен (Windows)
+    Unsupported,  // Остановка процесса не поддерживается на этой ОС
+    Os(i32),      // Прочая ошибка ОС (код ошибки)
+}
 
+impl std::fmt::Display for KillError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KillError::NotFound => f.write_str(tr("процесс не найден")),
+            KillError::AccessDenied => f.write_str(tr("недостаточно прав")),
+            KillError::NoWindows => f.write_str(tr(
+                "у процесса нет окон, возможно только принудительное завершение",
+            )),
+            KillError::Unsupported => {
+                f.write_str(tr("остановка процесса не поддерживается на этой ОС"))
+            }
+            KillError::Os(code) => f.write_str(&trf!(
+                "ошибка ОС: {}",
+                std::io::Error::from_raw_os_error(*code)
+            )),
+        }
+    }
+}
+
+// Мягкий запрос завершения (SIGTERM / WM_CLOSE окнам процесса): процесс может
+// корректно закрыть позиции и соединения. Не ждет завершения процесса.
+pub async fn terminate_process(pid: u32) -> Result<(), KillError> {
+    send_kill(pid, false).await
+}
+
+// Принудительное завершение процесса по PID (SIGKILL / TerminateProcess)
+pub async fn kill_process(pid: u32) -> Result<(), KillError> {
+    send_kill(pid, true).await
+}
+
+// Ожидание завершения процесса не дольше `timeout`. Возвращает true, если процесс завершился
+pub async fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if !process_alive(pid).await {
+            return true;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+}
+
+// Корректная остановка без пошаговых сообщений: мягкий запрос, ожидание `timeout`,
+// затем принудительное завершение (используется для процессов прошлых сеансов)
+pub async fn stop_process_gracefully(pid: u32, timeout: Duration) -> Result<(), KillError> {
+    if !timeout.is_zero() {
+        match terminate_process(pid).await {
+            Err(KillError::NotFound) => return Ok(()), // Процесс уже завершен
+            Ok(()) if wait_for_exit(pid, timeout).await => return Ok(()),
+            _ => {}
+        }
+    }
+    match kill_process(pid).await {
+        Err(KillError::NotFound) => Ok(()), // Завершился, пока шло ожидание
+        result => result,
+    }
+}
+
+// Остановка процесса, запущенного с повышенными правами. У лаунчера нет прав на сигналы
+// такому процессу, поэтому он запускает сам себя через pkexec/sudo (Linux, macOS) или
+// с запросом UAC (Windows) с параметром --stop-pid. Вспомогательный процесс выполняет
+// stop_process_gracefully: мягкий запрос, ожидание `timeout`, принудительное завершение
+// (SIGKILL / TerminateProcess) - подтверждение запрашивается один раз на всю остановку.
+#[cfg(any(unix, windows))]
+pub async fn stop_elevated(pid: u32, timeout: Duration) -> Result<(), KillError> {
+    // В Flatpak процессом лаунчера является flatpak-spawn, он сам передает сигналы на хост
+    #[cfg(target_os = "linux")]
+    if running_in_flatpak() {
+        return stop_process_gracefully(pid, timeout).await;
+    }
+    let launcher =
+        std::env::current_exe().map_err(|e| KillError::Os(e.raw_os_error().unwrap_or(0)))?;
+    let args = stop_helper_args(pid, timeout);
     #[cfg(unix)]
+    let stopped = elevation_command()
+        .map_err(|_| KillError::AccessDenied)?
+        .arg(&launcher)
+        .args(&args)
+        .stdin(Stdio::null())
+        .status()
+        .await
+        .map_err(|e| KillError::Os(e.raw_os_error().unwrap_or(0)))?
+        .success();
+    #[cfg(windows)]
+    let stopped = {
+        let parameters = args.join(" ");
+        let exit_code = tokio::task::spawn_blocking(move || {
+            shell_execute_elevated(&launcher, &parameters, None).map(wait_elevated)
+        })
+        .await;
+        matches!(exit_code, Ok(Ok(0)))
+    };
+    if stopped {
+        Ok(())
+    } else if !process_alive(pid).await {
+        Err(KillError::NotFound)
+    } else {
+        // Пользователь отменил запрос пароля / UAC или процесс не удалось остановить
+        Err(KillError::AccessDenied)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub async fn stop_elevated(_pid: u32, _timeout: Duration) -> Result<(), KillError> {
+    Err(KillError::Unsupported)
+}
+
+// Параметры командной строки вспомогательного процесса остановки (см. CliArgs::stop_pid)
+fn stop_helper_args(pid: u32, timeout: Duration) -> Vec<String> {
+    vec![
+        "--stop-pid".to_string(),
+        pid.to_string(),
+        "--stop-timeout".to_string(),
+        timeout.as_secs().to_string(),
+    ]
+}
+
+// Работа лаунчера, запущенного с параметром --stop-pid (см. stop_elevated):
+// остановка процесса без окна приложения. Возвращает код завершения (0 - остановлен).
+pub fn run_stop_helper(pid: u32, timeout: Duration) -> i32 {
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
     {
-        println!("[kill_process] Выполнение команды: kill {}", pid);
-        // Используем TokioCommand для выполнения системной команды
-        let kill_cmd = TokioCommand::new("kill")
-            .arg(pid.to_string())
-            .output() // Получаем вывод команды
-            .await;
-        match kill_cmd {
-            Ok(output) => {
-                println!("[kill_process] Статус kill: {}", output.status);
-                // Логируем stdout и stderr команды kill
-                if !output.stdout.is_empty() {
-                    println!(
-                        "[kill_process] kill stdout: {}",
-                        String::from_utf8_lossy(&output.stdout)
-                    );
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("[stop_helper] Ошибка создания среды выполнения: {}", e);
+            return 1;
+        }
+    };
+    match runtime.block_on(stop_process_gracefully(pid, timeout)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("[stop_helper] Ошибка остановки PID {}: {}", pid, e);
+            1
+        }
+    }
+}
+
+// Все потомки процесса (дети, их дети и т.д.)
+async fn descendant_pids(pid: u32) -> Vec<u32> {
+    tokio::task::spawn_blocking(move || {
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        let mut descendants = Vec::new();
+        let mut queue = vec![sysinfo::Pid::from_u32(pid)];
+        while let Some(parent) = queue.pop() {
+            for process in system.processes().values() {
+                if process.parent() == Some(parent) {
+                    descendants.push(process.pid().as_u32());
+                    queue.push(process.pid());
                 }
-                if !output.stderr.is_empty() {
-                    println!(
-                        "[kill_process] kill stderr: {}",
-                        String::from_utf8_lossy(&output.stderr)
-                    );
+            }
+        }
+        descendants
+    })
+    .await
+    .unwrap_or_default()
+}
+
+// Проверка, существует ли еще процесс (зомби считаем завершенным)
+async fn process_alive(pid: u32) -> bool {
+    tokio::task::spawn_blocking(move || {
+        let pid = sysinfo::Pid::from_u32(pid);
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        system
+            .process(pid)
+            .is_some_and(|process| process.status() != sysinfo::ProcessStatus::Zombie)
+    })
+    .await
+    .unwrap_or(false)
+}
+
+// Отправка сигнала завершения процессу и всем его потомкам:
+// `force` - принудительно, иначе мягкий запрос
+async fn send_kill(pid: u32, force: bool) -> Result<(), KillError> {
+    println!(
+        "[kill_process] Попытка завершить процесс с PID: {} (принудительно: {})",
+        pid, force
+    );
+    // PID из настроек (last_pid) может быть любым числом: см. is_signalable_pid
+    if !is_signalable_pid(pid) {
+        return Err(KillError::NotFound);
+    }
+    // Дерево процессов собираем до отправки сигнала: после завершения корневого
+    // процесса его потомки теряют связь с ним
+    let descendants = descendant_pids(pid).await;
+    if !descendants.is_empty() {
+        println!("[kill_process] Дочерние процессы: {:?}", descendants);
+    }
+    let result = tokio::task::spawn_blocking(move || signal_tree(pid, &descendants, force))
+        .await
+        .unwrap_or(Err(KillError::Unsupported));
+    match result {
+        Ok(()) => println!("[kill_process] Сигнал отправлен процессу PID: {}", pid),
+        Err(e) => println!("[kill_process] Ошибка остановки PID {}: {}", pid, e),
+    }
+    result
+}
+
+// PID, которому можно отправить сигнал. На Unix kill(0) - собственная группа лаунчера,
+// kill(1) - init, а числа от 2^31 становятся отрицательными: kill(-N) - группа N,
+// kill(-1) - все процессы пользователя. Такие PID считаются ненайденными.
+fn is_signalable_pid(pid: u32) -> bool {
+    pid > 1 && i32::try_from(pid).is_ok()
+}
+
+// Сигнал корневому процессу, его группе и потомкам (Unix). Результат - по корневому
+// процессу: группы может не быть (процесс прошлого сеанса), а потомок мог уже
+// завершиться, поэтому их ошибки игнорируются.
+#[cfg(unix)]
+fn signal_tree(pid: u32, descendants: &[u32], force: bool) -> Result<(), KillError> {
+    use nix::errno::Errno;
+    use nix::sys::signal::{kill, killpg, Signal};
+    use nix::unistd::Pid;
+
+    let signal = if force {
+        Signal::SIGKILL
+    } else {
+        Signal::SIGTERM
+    };
+    let to_pid = |pid: u32| Pid::from_raw(pid as i32);
+    let result = kill(to_pid(pid), signal).map_err(|errno| match errno {
+        Errno::ESRCH => KillError::NotFound,
+        Errno::EPERM => KillError::AccessDenied,
+        errno => KillError::Os(errno as i32),
+    });
+    // Процесс запускается в собственной группе (см. build_command)
+    let _ = killpg(to_pid(pid), signal);
+    for &child in descendants
+        .iter()
+        .filter(|&&child| is_signalable_pid(child))
+    {
+        let _ = kill(to_pid(child), signal);
+    }
+    result
+}
+
+// Завершение корневого процесса и его потомков (Windows). Мягкий запрос - сообщение
+// WM_CLOSE окнам процессов, как это делает taskkill без /F; принудительно - TerminateProcess.
+#[cfg(windows)]
+fn signal_tree(pid: u32, descendants: &[u32], force: bool) -> Result<(), KillError> {
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER,
+    };
+    use windows_sys::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+
+    let last_error = || {
+        // SAFETY: GetLastError только читает код ошибки текущего потока
+        match unsafe { GetLastError() } {
+            ERROR_INVALID_PARAMETER => KillError::NotFound,
+            ERROR_ACCESS_DENIED => KillError::AccessDenied,
+            code => KillError::Os(code as i32),
+        }
+    };
+    let terminate = |pid: u32| -> Result<(), KillError> {
+        // SAFETY: дескриптор проверяется на null и закрывается после использования
+        unsafe {
+            let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if handle.is_null() {
+                return Err(last_error());
+            }
+            let terminated = TerminateProcess(handle, 1) != 0;
+            let result = if terminated {
+                Ok(())
+            } else {
+                Err(last_error())
+            };
+            CloseHandle(handle);
+            result
+        }
+    };
+
+    if force {
+        // Сначала потомки: корневой процесс мог бы перезапустить их
+        for &child in descendants {
+            let _ = terminate(child);
+        }
+        return terminate(pid);
+    }
+    let mut pids = vec![pid];
+    pids.extend_from_slice(descendants);
+    if close_windows(&pids) {
+        return Ok(());
+    }
+    // Окон нет: отличаем завершившийся процесс от процесса без окон
+    // SAFETY: дескриптор проверяется на null и закрывается после использования
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            return Err(last_error());
+        }
+        CloseHandle(handle);
+    }
+    Err(KillError::NoWindows)
+}
+
+// Отправка WM_CLOSE всем окнам верхнего уровня указанных процессов.
+// Возвращает true, если найдено хотя бы одно окно.
+#[cfg(windows)]
+fn close_windows(pids: &[u32]) -> bool {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowThreadProcessId, PostMessageW, WM_CLOSE,
+    };
+
+    struct Search<'a> {
+        pids: &'a [u32],
+        found: bool,
+    }
+
+    unsafe extern "system" fn visit(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        // SAFETY: lparam - указатель на Search, живой на время вызова EnumWindows
+        let search = &mut *(lparam as *mut Search);
+        let mut window_pid = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut window_pid);
+        if search.pids.contains(&window_pid) {
+            PostMessageW(hwnd, WM_CLOSE, 0, 0);
+            search.found = true;
+        }
+        1 // Продолжаем перебор окон
+    }
+
+    let mut search = Search { pids, found: false };
+    // SAFETY: обратный вызов используется только внутри EnumWindows
+    unsafe {
+        EnumWindows(Some(visit), &mut search as *mut Search as LPARAM);
+    }
+    search.found
+}
+
+#[cfg(not(any(unix, windows)))]
+fn signal_tree(_pid: u32, _descendants: &[u32], _force: bool) -> Result<(), KillError> {
+    Err(KillError::Unsupported)
+}
+
+// --- Потребление ресурсов дочерним процессом ---
+
+// Как часто опрашивать потребление ресурсов
+const USAGE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Загрузка CPU и память процесса
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProcessUsage {
+    pub cpu_percent: f32, // Загрузка CPU, % от всех ядер
+    pub rss_bytes: u64,   // Резидентная память (RSS)
+}
+
+// Подписка, опрашивающая потребление ресурсов процесса с указанным PID.
+// Загрузка CPU считается между двумя опросами, поэтому System живет внутри подписки.
+pub fn usage_subscription(pid: u32) -> Subscription<ProcessUsage> {
+    subscription::channel(
+        (std::any::TypeId::of::<ProcessUsage>(), pid),
+        10,
+        move |mut output| async move {
+            let pid = sysinfo::Pid::from_u32(pid);
+            let cpus = std::thread::available_parallelism()
+                .map(|n| n.get() as f32)
+                .unwrap_or(1.0);
+            let mut system = System::new();
+            loop {
+                system.refresh_processes_specifics(
+                    ProcessesToUpdate::Some(&[pid]),
+                    true,
+                    ProcessRefreshKind::nothing().with_cpu().with_memory(),
+                );
+                if let Some(process) = system.process(pid) {
+                    let usage = ProcessUsage {
+                        cpu_percent: process.cpu_usage() / cpus,
+                        rss_bytes: process.memory(),
+                    };
+                    let _ = output.send(usage).await;
                 }
-                // Проверяем успешность выполнения команды
-                if output.status.success() {
-                    println!(
-                        "[kill_process] Команда kill успешно завершена для PID: {}",
-                        pid
-                    );
-                    Ok(())
+                tokio::time::sleep(USAGE_POLL_INTERVAL).await;
+            }
+        },
+    )
+}
+
+// --- Поиск процессов по пути к исполняемому файлу ---
+
+// Процесс, найденный по пути к исполняемому файлу (кандидат на завершение)
+#[derive(Debug, Clone)]
+pub struct ProcessCandidate {
+    pub pid: u32,     // PID найденного процесса
+    pub exe: PathBuf, // Путь к его исполняемому файлу
+}
+
+// Ищет запущенные процессы, исполняемый файл которых совпадает с указанным.
+// Используется, когда PID процесса неизвестен (не был получен или лаунчер перезапускался).
+pub async fn find_processes_by_path(path: PathBuf) -> Vec<ProcessCandidate> {
+    // Перебор процессов - блокирующая операция, выполняем вне потока UI
+    tokio::task::spawn_blocking(move || {
+        let target = resolve_executable(&path).unwrap_or(path);
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .without_tasks(),
+        );
+        let own_pid = std::process::id();
+        let mut candidates: Vec<ProcessCandidate> = system
+            .processes()
+            .values()
+            .filter(|process| process.pid().as_u32() != own_pid)
+            .filter_map(|process| {
+                let exe = process.exe()?;
+                if same_executable(exe, &target) {
+                    Some(ProcessCandidate {
+                        pid: process.pid().as_u32(),
+                        exe: exe.to_path_buf(),
+                    })
                 } else {
-                    // Возвращаем ошибку, если команда завершилась неудачно
-                    Err(format!(
-                        "Команда kill для PID {} завершилась с кодом: {}. Stderr: {}",
-                        pid,
-                        output.status,
-                        String::from_utf8_lossy(&output.stderr)
-                    ))
+                    None
                 }
+            })
+            .collect();
+        candidates.sort_by_key(|candidate| candidate.pid);
+        candidates
+    })
+    .await
+    .unwrap_or_default()
+}
+
+// Сравнение путей к исполняемым файлам с учетом символических ссылок
+// (и без учета регистра на Windows)
+fn same_executable(a: &Path, b: &Path) -> bool {
+    let a = std::fs::canonicalize(a).unwrap_or_else(|_| a.to_path_buf());
+    let b = std::fs::canonicalize(b).unwrap_or_else(|_| b.to_path_buf());
+    if cfg!(windows) {
+        a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+// --- Процесс, оставшийся от прошлого сеанса лаунчера ---
+
+// Проверяет, что процесс с PID из прошлого сеанса еще работает и запущен из того же
+// исполняемого файла (после перезагрузки PID может достаться другой программе).
+// Возвращает найденный процесс или None
+pub async fn find_orphan_process(pid: u32, path: Option<PathBuf>) -> Option<ProcessCandidate> {
+    let path = path?;
+    tokio::task::spawn_blocking(move || {
+        let target = resolve_executable(&path).unwrap_or(path);
+        let sys_pid = sysinfo::Pid::from_u32(pid);
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[sys_pid]),
+            true,
+            ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+        );
+        let process = system.process(sys_pid)?;
+        if process.status() == sysinfo::ProcessStatus::Zombie {
+            return None;
+        }
+        let exe = process.exe()?;
+        same_executable(exe, &target).then(|| ProcessCandidate {
+            pid,
+            exe: exe.to_path_buf(),
+        })
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+// Подписка, сообщающая о завершении подключенного по PID процесса. Он не является
+// дочерним для лаунчера, поэтому код завершения недоступен - процесс просто опрашивается
+pub fn exit_watch_subscription(pid: u32) -> Subscription<()> {
+    subscription::channel(
+        (std::any::TypeId::of::<ProcessCandidate>(), pid),
+        1,
+        move |mut output| async move {
+            while process_alive(pid).await {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            let _ = output.send(()).await;
+            iced::futures::future::pending().await
+        },
+    )
+}
+
+// Завершает несколько процессов по очереди (корректно, с ожиданием до `timeout`),
+// возвращая результат для каждого PID
+pub async fn kill_processes(
+    pids: Vec<u32>,
+    timeout: Duration,
+) -> Vec<(u32, Result<(), KillError>)> {
+    let mut results = Vec::with_capacity(pids.len());
+    for pid in pids {
+        results.push((pid, stop_process_gracefully(pid, timeout).await));
+    }
+    results
+}
+
+// --- Запуск с правами администратора (Windows) ---
+
+// Запуск программы через ShellExecuteEx с глаголом runas: Windows покажет запрос UAC.
+// Возвращает дескриптор процесса (его нужно закрыть, см. wait_elevated).
+#[cfg(windows)]
+fn shell_execute_elevated(
+    file: &Path,
+    parameters: &str,
+    directory: Option<&Path>,
+) -> Result<isize, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let wide = |value: &std::ffi::OsStr| -> Vec<u16> {
+        value.encode_wide().chain(std::iter::once(0)).collect()
+    };
+    let verb = wide("runas".as_ref());
+    let file_w = wide(file.as_os_str());
+    let parameters_w = wide(parameters.as_ref());
+    let directory_w = directory.map(|dir| wide(dir.as_os_str()));
+
+    // SAFETY: структура заполнена нулями и указателями на строки, живущие до конца вызова
+    unsafe {
+        let mut info: SHELLEXECUTEINFOW = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+        info.fMask = SEE_MASK_NOCLOSEPROCESS;
+        info.lpVerb = verb.as_ptr();
+        info.lpFile = file_w.as_ptr();
+        info.lpParameters = parameters_w.as_ptr();
+        info.lpDirectory = directory_w
+            .as_ref()
+            .map(|dir| dir.as_ptr())
+            .unwrap_or(std::ptr::null());
+        info.nShow = SW_SHOWNORMAL;
+        if ShellExecuteExW(&mut info) == 0 {
+            let error = std::io::Error::last_os_error();
+            // ERROR_CANCELLED: пользователь отклонил запрос UAC
+            return Err(if error.raw_os_error() == Some(1223) {
+                tr("Запуск с правами администратора отменен пользователем").to_string()
+            } else {
+                trf!(
+                    "Ошибка запуска {} с правами администратора: {}",
+                    format!("{:?}", file),
+                    error
+                )
+            });
+        }
+        if info.hProcess.is_null() {
+            return Err(trf!(
+                "Не удалось получить процесс {}",
+                format!("{:?}", file)
+            ));
+        }
+        Ok(info.hProcess as isize)
+    }
+}
+
+// Ожидание завершения процесса по дескриптору, возвращает код завершения.
+// Дескриптор закрывается.
+#[cfg(windows)]
+fn wait_elevated(handle: isize) -> i32 {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, WaitForSingleObject, INFINITE,
+    };
+
+    // SAFETY: дескриптор получен из ShellExecuteEx и закрывается только здесь
+    unsafe {
+        WaitForSingleObject(handle as _, INFINITE);
+        let mut code = 0u32;
+        let ok = GetExitCodeProcess(handle as _, &mut code) != 0;
+        CloseHandle(handle as _);
+        if ok {
+            code as i32
+        } else {
+            -1
+        }
+    }
+}
+
+// Экранирование аргумента командной строки по правилам CommandLineToArgvW
+#[cfg(windows)]
+fn quote_windows_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
             }
-            Err(e) => {
-                // Обрабатываем ошибку выполнения самой команды kill
-                let error_msg = format!("Ошибка выполнения команды kill для PID {}: {}", pid, e);
-                println!("[kill_process] {}", error_msg);
-                Err(error_msg)
+            _ => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes));
+                quoted.push(c);
+                backslashes = 0;
             }
         }
     }
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
 
-    #[cfg(windows)]
+// Запуск процесса с правами администратора и ожидание его завершения (Windows).
+// Повышенный процесс создает система, а не лаунчер, поэтому его вывод перехватить
+// нельзя: отслеживаются только PID и код завершения.
+#[cfg(windows)]
+async fn run_elevated(
+    id: u64,
+    path: PathBuf,
+    arguments: Vec<String>,
+    env: Vec<(String, String)>,
+    working_dir: Option<PathBuf>,
+    sender: mpsc::Sender<Message>,
+) {
+    use windows_sys::Win32::System::Threading::GetProcessId;
+
+    if let Some(dir) = &working_dir {
+        if !dir.is_dir() {
+            let message = trf!("Рабочая директория {} не существует", format!("{:?}", dir));
+            let _ = sender.send(Message::ProcessError(id, message)).await;
+            return;
+        }
+    }
+    let parameters = arguments
+        .iter()
+        .map(|arg| quote_windows_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let launched = tokio::task::spawn_blocking(move || {
+        shell_execute_elevated(&path, &parameters, working_dir.as_deref())
+    })
+    .await
+    .unwrap_or_else(|e| Err(trf!("Ошибка запуска процесса: {}", e)));
+    let handle = match launched {
+        Ok(handle) => handle,
+        Err(e) => {
+            let _ = sender.send(Message::ProcessError(id, e)).await;
+            return;
+        }
+    };
+    // SAFETY: дескриптор действителен до вызова wait_elevated
+    let pid = unsafe { GetProcessId(handle as _) };
+    if sender
+        .send(Message::ProcessActualPid(id, pid))
+        .await
+        .is_err()
     {
-        println!(
-            "[kill_process] Выполнение команды: taskkill /F /PID {}",
-            pid
+        // Канал закрыт, но дескриптор все равно нужно закрыть
+        tokio::task::spawn_blocking(move || wait_elevated(handle));
+        return;
+    }
+    let mut notes = vec![tr(
+        "[Лаунчер] Процесс запущен с правами администратора: его вывод и консоль недоступны.",
+    )
+    .to_string()];
+    if !env.is_empty() {
+        notes.push(
+            tr("[Лаунчер] Переменные окружения из настроек не передаются процессу с правами администратора.")
+                .to_string(),
         );
-        // Используем taskkill для Windows
-        let kill_cmd = TokioCommand::new("taskkill")
-            .arg("/F") // Принудительное завершение
-            .arg("/PID") // Указываем PID
-            .arg(pid.to_string())
-            .output()
+    }
+    for note in notes {
+        let _ = sender
+            .send(Message::ProcessOutput(id, LogSource::Launcher, note))
             .await;
+    }
+    let code = tokio::task::spawn_blocking(move || wait_elevated(handle))
+        .await
+        .unwrap_or(-1);
+    let _ = sender.send(Message::ProcessTerminated(id, code)).await;
+}
 
-        match kill_cmd {
-            Ok(output) => {
-                println!("[kill_process] Статус taskkill: {}", output.status);
-                if !output.stdout.is_empty() {
-                    println!(
-                        "[kill_process] taskkill stdout: {}",
-                        String::from_utf8_lossy(&output.stdout)
-                    );
+// Чтение вывода отсоединенного процесса из файла по мере дозаписи.
+// Завершается, когда процесс завершился (`exited`) и файл дочитан до конца.
+async fn tail_output(
+    path: PathBuf,
+    id: u64,
+    sender: mpsc::Sender<Message>,
+    exited: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    encoding: OutputEncoding,
+) {
+    use std::sync::atomic::Ordering;
+
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("[Recipe] Не удалось открыть файл вывода {:?}: {}", path, e);
+            return;
+        }
+    };
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        // Флаг читается до чтения: все, что процесс успел записать, будет прочитано
+        let finished = exited.load(Ordering::Acquire);
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => {
+                if finished {
+                    break;
                 }
-                if !output.stderr.is_empty() {
-                    println!(
-                        "[kill_process] taskkill stderr: {}",
-                        String::from_utf8_lossy(&output.stderr)
-                    );
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+            // Строка дописана не до конца - дочитаем ее на следующей итерации
+            Ok(_) if !line.ends_with(b"\n") => {}
+            Ok(_) => {
+                let text = encoding.decode(&line).trim_end().to_string();
+                line.clear();
+                // stdout и stderr отсоединенного процесса пишутся в один файл
+                if sender
+                    .send(Message::ProcessOutput(id, LogSource::Stdout, text))
+                    .await
+                    .is_err()
+                {
+                    return; // Канал закрыт
                 }
-                if output.status.success() {
-                    // На Windows taskkill может завершиться успешно, даже если процесс уже мертв.
-                    // Проверяем stdout для большей уверенности (хотя это не идеально).
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
-                    if stdout.contains(&format!("pid {} ", pid)) || stdout.contains("success") {
-                        println!(
-                            "[kill_process] Команда taskkill успешно завершена для PID: {}",
-                            pid
-                        );
-                        Ok(())
-                    } else {
-                        println!("[kill_process] taskkill stdout не содержит подтверждения успеха для PID {}. Возможно, процесс уже был завершен.", pid);
-                        // Считаем успехом, т.к. цель - отсутствие процесса
-                        Ok(())
+            }
+        }
+    }
+    if !line.is_empty() {
+        let text = encoding.decode(&line).trim_end().to_string();
+        let _ = sender
+            .send(Message::ProcessOutput(id, LogSource::Stdout, text))
+            .await;
+    }
+    println!("[Recipe] Output file reader finished.");
+}
+
+// Строки вывода копятся в пакет и отправляются одним сообщением, когда набралось
+// OUTPUT_BATCH_LINES строк или с первой строки пакета прошло OUTPUT_BATCH_INTERVAL:
+// при потоке строк интерфейс обновляется раз в пакет, а не на каждую строку
+const OUTPUT_BATCH_LINES: usize = 100;
+const OUTPUT_BATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+// Спиннеры перерисовывают строку десятки раз в секунду: в интерфейс уходит не больше
+// одного кадра за этот интервал (промежуточные кадры пропускаются, последний доходит всегда)
+const PROGRESS_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+// Ограничение частоты строк вывода одного потока: сверх лимита за секунду строки
+// пропускаются, а по окончании секунды отправляется их количество (ProcessOutputDropped)
+struct RateLimit {
+    max_per_sec: u32,      // Лимит строк в секунду (0 - без ограничения)
+    window_start: Instant, // Начало текущей секунды
+    lines: u32,            // Строк пропущено в интерфейс за текущую секунду
+    dropped: u64,          // Строк пропущено сверх лимита за текущую секунду
+}
+
+impl RateLimit {
+    fn new(max_per_sec: u32) -> Self {
+        RateLimit {
+            max_per_sec,
+            window_start: Instant::now(),
+            lines: 0,
+            dropped: 0,
+        }
+    }
+
+    // Начало новой секунды, если текущая закончилась. Возвращает число строк,
+    // пропущенных за закончившуюся секунду (если они были)
+    fn roll(&mut self) -> Option<u64> {
+        if self.window_start.elapsed() < Duration::from_secs(1) {
+            return None;
+        }
+        self.window_start = Instant::now();
+        self.lines = 0;
+        Some(std::mem::take(&mut self.dropped)).filter(|dropped| *dropped > 0)
+    }
+
+    // Учет очередной строки: false - строка сверх лимита и пропускается
+    fn admit(&mut self) -> bool {
+        if self.max_per_sec > 0 && self.lines >= self.max_per_sec {
+            self.dropped += 1;
+            return false;
+        }
+        self.lines += 1;
+        true
+    }
+
+    // Когда нужно отправить количество пропущенных строк, даже если вывод прекратился
+    fn deadline(&self) -> Option<Instant> {
+        (self.dropped > 0).then(|| self.window_start + Duration::from_secs(1))
+    }
+}
+
+// Чтение потока вывода процесса. Строки, завершенные \n, отправляются пакетами ProcessOutputBatch.
+// Фрагменты, завершенные \r (индикаторы прогресса), и строки, перерисованные на месте через
+// \x08 (спиннеры), отправляются как ProcessProgress: каждый следующий кадр заменяет
+// предыдущий в той же строке лога, как в терминале. Строки сверх `max_lines_per_sec`
+// пропускаются (защита интерфейса от потока в десятки тысяч строк в секунду).
+// Строки декодируются из кодировки `encoding`.
+async fn read_output<R>(
+    stream: R,
+    id: u64,
+    sender: mpsc::Sender<Message>,
+    source: LogSource,
+    max_lines_per_sec: u32,
+    encoding: OutputEncoding,
+) where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+    let mut line = Vec::new();
+    // Последний фрагмент прогресса: "\r\n" оставляет его в логе как обычную строку
+    let mut progress: Option<String> = None;
+    let mut batch: Vec<String> = Vec::new(); // Строки, еще не отправленные в интерфейс
+    let mut batch_started: Option<Instant> = None; // Когда в пакет попала первая строка
+    let mut pending_frame: Option<String> = None; // Кадр прогресса, еще не отправленный
+    let mut frame_sent_at: Option<Instant> = None; // Когда отправлен последний кадр
+    let mut line_rewritten = false; // В текущей строке символы перезаписывались через \x08
+    let mut rate = RateLimit::new(max_lines_per_sec);
+    loop {
+        // Пока есть неотправленный пакет или кадр, новых данных ждем не дольше, чем до их отправки
+        let deadline = [
+            batch_started.map(|started| started + OUTPUT_BATCH_INTERVAL),
+            frame_sent_at
+                .filter(|_| pending_frame.is_some())
+                .map(|sent| sent + PROGRESS_FRAME_INTERVAL),
+            rate.deadline(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        let read = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), reader.fill_buf())
+                .await
+                .ok(),
+            None => Some(reader.fill_buf().await),
+        };
+        let mut messages = Vec::new();
+        match read {
+            // Конец потока или ошибка чтения
+            Some(Ok([])) | Some(Err(_)) => break,
+            Some(Ok(buffer)) => {
+                for &byte in buffer {
+                    match byte {
+                        b'\n' => {
+                            let mut text = encoding.decode(&line);
+                            line.clear();
+                            if let Some(last) = progress.take().filter(|_| text.is_empty()) {
+                                text = last;
+                            }
+                            // Завершенная строка заменяет строку прогресса: кадр уже не нужен
+                            pending_frame = None;
+                            line_rewritten = false;
+                            if let Some(dropped) = rate.roll() {
+                                flush_dropped(&mut messages, &mut batch, id, source, dropped);
+                                batch_started = None;
+                            }
+                            if !rate.admit() {
+                                continue; // Строка сверх лимита частоты
+                            }
+                            batch_started.get_or_insert_with(Instant::now);
+                            batch.push(text);
+                            if batch.len() >= OUTPUT_BATCH_LINES {
+                                batch_started = None;
+                                messages.push(Message::ProcessOutputBatch(
+                                    id,
+                                    source,
+                                    std::mem::take(&mut batch),
+                                ));
+                            }
+                        }
+                        b'\r' if !line.is_empty() => {
+                            let text = encoding.decode(&line);
+                            line.clear();
+                            line_rewritten = false;
+                            progress = Some(text.clone());
+                            pending_frame = Some(text);
+                        }
+                        b'\r' => {}
+                        // Шаг назад: следующий символ перерисует предыдущий на том же месте
+                        0x08 => {
+                            // Удаляем последний символ UTF-8 (продолжения и начальный байт)
+                            while let Some(removed) = line.pop() {
+                                if removed & 0xC0 != 0x80 {
+                                    break;
+                                }
+                            }
+                            line_rewritten = true;
+                        }
+                        _ => line.push(byte),
                     }
-                } else {
-                    Err(format!(
-                        "Команда taskkill для PID {} завершилась с кодом: {}. Stderr: {}",
-                        pid,
-                        output.status,
-                        String::from_utf8_lossy(&output.stderr)
-                    ))
+                }
+                let consumed = buffer.len();
+                reader.consume(consumed);
+                // Строка, перерисованная на месте, показывается кадром прогресса
+                if line_rewritten && !line.is_empty() {
+                    pending_frame = Some(encoding.decode(&line));
                 }
             }
-            Err(e) => {
-                let error_msg =
-                    format!("Ошибка выполнения команды taskkill для PID {}: {}", pid, e);
-                println!("[kill_process] {}", error_msg);
-                Err(error_msg)
+            None => {} // Время ожидания пакета или кадра истекло
+        }
+        if let Some(dropped) = rate.roll() {
+            flush_dropped(&mut messages, &mut batch, id, source, dropped);
+            batch_started = None;
+        }
+        let frame_due = frame_sent_at.is_none_or(|sent| sent.elapsed() >= PROGRESS_FRAME_INTERVAL);
+        if let Some(frame) = pending_frame.take_if(|_| frame_due) {
+            // Строки до кадра прогресса должны попасть в лог раньше него
+            if !batch.is_empty() {
+                batch_started = None;
+                messages.push(Message::ProcessOutputBatch(
+                    id,
+                    source,
+                    std::mem::take(&mut batch),
+                ));
+            }
+            messages.push(Message::ProcessProgress(id, source, frame));
+            frame_sent_at = Some(Instant::now());
+        }
+        if batch_started.is_some_and(|started| started.elapsed() >= OUTPUT_BATCH_INTERVAL) {
+            batch_started = None;
+            messages.push(Message::ProcessOutputBatch(
+                id,
+                source,
+                std::mem::take(&mut batch),
+            ));
+        }
+        for message in messages {
+            if sender.send(message).await.is_err() {
+                return; // Канал закрыт
             }
         }
     }
-
-    #[cfg(not(any(unix, windows)))]
+    if !line.is_empty() {
+        batch.push(encoding.decode(&line));
+        pending_frame = None; // Последняя строка заменит строку прогресса
+    }
+    if !batch.is_empty()
+        && sender
+            .send(Message::ProcessOutputBatch(id, source, batch))
+            .await
+            .is_err()
+    {
+        return;
+    }
+    if rate.dropped > 0
+        && sender
+            .send(Message::ProcessOutputDropped(id, source, rate.dropped))
+            .await
+            .is_err()
     {
-        // Заглушка для неподдерживаемых ОС
-        let error_msg = "Остановка процесса не поддерживается на этой ОС.".to_string();
-        println!("[kill_process] {}", error_msg);
-        Err(error_msg)
+        return;
+    }
+    // Последний кадр прогресса, пропущенный из-за ограничения частоты
+    if let Some(frame) = pending_frame {
+        let _ = sender
+            .send(Message::ProcessProgress(id, source, frame))
+            .await;
     }
 }
 
+// Сводка пропущенных строк: после уже принятых строк пакета, чтобы сохранить порядок
+fn flush_dropped(
+    messages: &mut Vec<Message>,
+    batch: &mut Vec<String>,
+    id: u64,
+    source: LogSource,
+    dropped: u64,
+) {
+    if !batch.is_empty() {
+        messages.push(Message::ProcessOutputBatch(
+            id,
+            source,
+            std::mem::take(batch),
+        ));
+    }
+    messages.push(Message::ProcessOutputDropped(id, source, dropped));
+}
+
+// Параметры запуска процесса помимо исполняемого файла, ключа и аргументов
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    pub env: Vec<(String, String)>, // Дополнительные переменные окружения
+    pub working_dir: Option<PathBuf>, // Рабочая директория процесса
+    pub elevated: bool,             // Запуск с повышенными правами
+    // Отсоединенный режим: процесс переживает закрытие лаунчера. Вывод пишется
+    // в этот файл (а не в канал, который закроется вместе с лаунчером), stdin недоступен
+    pub detached_output: Option<PathBuf>,
+    pub max_lines_per_sec: u32, // Ограничение частоты строк вывода на поток (0 - без ограничения)
+    pub output_encoding: OutputEncoding, // Кодировка вывода процесса
+}
+
 // --- ProcessListener Recipe для подписки Iced ---
 #[derive(Debug)]
 pub struct ProcessListener {
     // Структура для хранения данных подписки
-    id: u64,         // Уникальный идентификатор подписки
-    path: PathBuf,   // Путь к исполняемому файлу
-    api_key: String, // Ключ API
+    id: u64,                 // Уникальный идентификатор подписки
+    path: PathBuf,           // Путь к исполняемому файлу
+    api_key: String,         // Ключ API
+    args: Vec<String>,       // Дополнительные аргументы командной строки
+    options: ProcessOptions, // Окружение, рабочая директория и режим запуска
 }
 impl ProcessListener {
     // Публичный конструктор
-    pub fn new(id: u64, path: PathBuf, api_key: String) -> Self {
-        Self { id, path, api_key }
+    pub fn new(
+        id: u64,
+        path: PathBuf,
+        api_key: String,
+        args: Vec<String>,
+        options: ProcessOptions,
+    ) -> Self {
+        Self {
+            id,
+            path,
+            api_key,
+            args,
+            options,
+        }
     }
 }
 // Реализация Recipe для интеграции с Iced
@@ -169,30 +1534,97 @@ impl Recipe for ProcessListener {
         // Создаем MPSC канал для передачи сообщений из асинхронных задач в Iced
         let (sender, receiver) = mpsc::channel(100);
 
+        // Сообщения помечаются идентификатором подписки: по нему лаунчер находит экземпляр
+        let id = self.id;
         let path = self.path;
         let api_key = self.api_key;
+        let args = self.args;
+        let options = self.options;
 
         // Запускаем главную асинхронную задачу
         tokio::spawn(async move {
             let mut child: Child;
             let actual_pid: u32;
-            // Запускаем дочерний процесс
-            match TokioCommand::new(&path)
+            // Определяем реальный исполняемый файл (например, внутри пакета .app на macOS)
+            let path = match resolve_executable(&path) {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    let _ = sender.send(Message::ProcessError(id, e)).await;
+                    return;
+                }
+            };
+            #[cfg(windows)]
+            if options.elevated {
+                let mut arguments = vec!["-k".to_string(), api_key];
+                arguments.extend(args);
+                run_elevated(
+                    id,
+                    path,
+                    arguments,
+                    options.env,
+                    options.working_dir,
+                    sender,
+                )
+                .await;
+                return;
+            }
+            // Подготавливаем команду запуска с учетом платформы (AppImage, Flatpak)
+            let mut command = match build_command(&path, &options) {
+                Ok(command) => command,
+                Err(e) => {
+                    let _ = sender.send(Message::ProcessError(id, e)).await;
+                    return;
+                }
+            };
+            command
                 .arg("-k") // Передаем ключ API как аргумент
                 .arg(&api_key)
-                .stdout(Stdio::piped()) // Перехватываем stdout
-                .stderr(Stdio::piped()) // Перехватываем stderr
-                .kill_on_drop(true) // Завершать процесс, если лаунчер упадет
-                .spawn()
-            {
+                .args(&args); // Дополнительные аргументы из настроек
+            if let Some(output) = &options.detached_output {
+                // Вывод отсоединенного процесса - в файл, чтобы после закрытия
+                // лаунчера процесс не получил ошибку записи в закрытый канал
+                let file = match std::fs::File::create(output).and_then(|file| {
+                    let stderr = file.try_clone()?;
+                    Ok((file, stderr))
+                }) {
+                    Ok(files) => files,
+                    Err(e) => {
+                        let message = trf!(
+                            "Не удалось создать файл вывода {}: {}",
+                            format!("{:?}", output),
+                            e
+                        );
+                        let _ = sender.send(Message::ProcessError(id, message)).await;
+                        return;
+                    }
+                };
+                command
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::from(file.0))
+                    .stderr(Stdio::from(file.1));
+            } else {
+                command
+                    .stdin(Stdio::piped()) // Консоль: команды пользователя пишутся в stdin
+                    .stdout(Stdio::piped()) // Перехватываем stdout
+                    .stderr(Stdio::piped()); // Перехватываем stderr
+            }
+            // Запускаем дочерний процесс
+            match command.spawn() {
                 Ok(spawned_child) => {
                     child = spawned_child;
+                    // Процесс не должен пережить лаунчер, даже если тот упадет
+                    #[cfg(windows)]
+                    if options.detached_output.is_none() {
+                        if let Err(e) = exit_with_launcher(&child) {
+                            eprintln!("[Recipe] {}", e);
+                        }
+                    }
                     // Получаем PID запущенного процесса
                     if let Some(pid) = child.id() {
                         actual_pid = pid;
                         // Отправляем PID в основной поток Iced
                         if sender
-                            .send(Message::ProcessActualPid(actual_pid))
+                            .send(Message::ProcessActualPid(id, actual_pid))
                             .await
                             .is_err()
                         {
@@ -203,7 +1635,8 @@ impl Recipe for ProcessListener {
                         // Обрабатываем ошибку получения PID
                         let _ = sender
                             .send(Message::ProcessError(
-                                "Не удалось получить PID запущенного процесса.".to_string(),
+                                id,
+                                tr("Не удалось получить PID запущенного процесса.").to_string(),
                             ))
                             .await;
                         return;
@@ -212,50 +1645,97 @@ impl Recipe for ProcessListener {
                 Err(e) => {
                     // Обрабатываем ошибку запуска процесса
                     let _ = sender
-                        .send(Message::ProcessError(format!(
-                            "Ошибка запуска процесса {:?}: {}",
-                            path, e
-                        )))
+                        .send(Message::ProcessError(id, describe_spawn_error(&path, &e)))
                         .await;
                     return;
                 }
             }
 
-            // Получаем пайпы stdout и stderr
+            // Отсоединенный процесс: читаем его вывод из файла до завершения процесса
+            if let Some(output) = options.detached_output {
+                let exited = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+                let reader = tokio::spawn(tail_output(
+                    output,
+                    id,
+                    sender.clone(),
+                    exited.clone(),
+                    options.output_encoding,
+                ));
+                tokio::spawn(async move {
+                    let message = match child.wait().await {
+                        Ok(status) => Message::ProcessTerminated(id, status.code().unwrap_or(-1)),
+                        Err(e) => Message::ProcessError(
+                            id,
+                            trf!("Ошибка ожидания процесса PID {}: {}", actual_pid, e),
+                        ),
+                    };
+                    // Сообщение о завершении - после последних строк вывода
+                    exited.store(true, std::sync::atomic::Ordering::Release);
+                    let _ = reader.await;
+                    let _ = sender.send(message).await;
+                });
+                return;
+            }
+
+            // Получаем пайпы stdin, stdout и stderr
+            let stdin = child.stdin.take().expect("stdin not captured");
             let stdout = child.stdout.take().expect("stdout not captured");
             let stderr = child.stderr.take().expect("stderr not captured");
 
-            // Запускаем задачу для чтения stdout
-            let sender_stdout = sender.clone();
+            // Запускаем задачу записи команд консоли в stdin процесса
+            let (stdin_sender, mut stdin_receiver) = mpsc::channel::<String>(32);
+            if sender
+                .send(Message::ProcessStdinReady(id, stdin_sender))
+                .await
+                .is_err()
+            {
+                return; // Канал закрыт
+            }
             tokio::spawn(async move {
-                let mut reader = BufReader::new(stdout).lines();
-                // Читаем строки и отправляем их как сообщения ProcessOutput
-                while let Ok(Some(line)) = reader.next_line().await {
-                    if sender_stdout
-                        .send(Message::ProcessOutput(line))
-                        .await
-                        .is_err()
-                    {
-                        break; // Канал закрыт
+                let mut stdin = stdin;
+                while let Some(line) = stdin_receiver.recv().await {
+                    let written = async {
+                        stdin.write_all(line.as_bytes()).await?;
+                        stdin.write_all(b"\n").await?;
+                        stdin.flush().await
+                    };
+                    if let Err(e) = written.await {
+                        eprintln!("[Recipe] Ошибка записи в stdin: {}", e);
+                        break; // Процесс закрыл stdin или завершился
                     }
                 }
+                println!("[Recipe] Stdin writer finished.");
+            });
+
+            // Запускаем задачу для чтения stdout
+            let max_lines_per_sec = options.max_lines_per_sec;
+            let output_encoding = options.output_encoding;
+            let sender_stdout = sender.clone();
+            tokio::spawn(async move {
+                read_output(
+                    stdout,
+                    id,
+                    sender_stdout,
+                    LogSource::Stdout,
+                    max_lines_per_sec,
+                    output_encoding,
+                )
+                .await;
                 println!("[Recipe] Stdout reader finished.");
             });
 
-            // Запускаем задачу для чтения stderr
+            // Запускаем задачу для чтения stderr (строки помечаются как поток ошибок)
             let sender_stderr = sender.clone();
             tokio::spawn(async move {
-                let mut reader = BufReader::new(stderr).lines();
-                // Читаем строки и отправляем их как сообщения ProcessOutput с префиксом
-                while let Ok(Some(line)) = reader.next_line().await {
-                    if sender_stderr
-                        .send(Message::ProcessOutput(format!("STDERR: {}", line)))
-                        .await
-                        .is_err()
-                    {
-                        break; // Канал закрыт
-                    }
-                }
+                read_output(
+                    stderr,
+                    id,
+                    sender_stderr,
+                    LogSource::Stderr,
+                    max_lines_per_sec,
+                    output_encoding,
+                )
+                .await;
                 println!("[Recipe] Stderr reader finished.");
             });
 
@@ -264,12 +1744,12 @@ impl Recipe for ProcessListener {
             tokio::spawn(async move {
                 // Ожидаем завершения дочернего процесса
                 let message = match child.wait().await {
-                    Ok(status) => Message::ProcessTerminated(status.code().unwrap_or(-1)), // Отправляем код завершения
-                    Err(e) => Message::ProcessError(format!(
+                    Ok(status) => Message::ProcessTerminated(id, status.code().unwrap_or(-1)), // Отправляем код завершения
+                    Err(e) => Message::ProcessError(
+                        id,
                         // Отправляем ошибку ожидания
-                        "Ошибка ожидания процесса PID {}: {}",
-                        actual_pid, e
-                    )),
+                        trf!("Ошибка ожидания процесса PID {}: {}", actual_pid, e),
+                    ),
                 };
                 // Отправляем сообщение о завершении/ошибке
                 let _ = sender_termination.send(message).await;
@@ -281,3 +1761,146 @@ impl Recipe for ProcessListener {
         ReceiverStream::new(receiver).boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Сообщения read_output в упрощенном виде
+    #[derive(Debug, PartialEq)]
+    enum Output {
+        Lines(Vec<String>),
+        Progress(String),
+        Dropped(u64),
+    }
+
+    async fn read_all(input: &[u8], max_lines_per_sec: u32) -> Vec<Output> {
+        let (sender, mut receiver) = mpsc::channel(64);
+        read_output(
+            input,
+            1,
+            sender,
+            LogSource::Stdout,
+            max_lines_per_sec,
+            OutputEncoding::Utf8,
+        )
+        .await;
+        let mut output = Vec::new();
+        while let Some(message) = receiver.recv().await {
+            output.push(match message {
+                Message::ProcessOutputBatch(1, LogSource::Stdout, lines) => Output::Lines(lines),
+                Message::ProcessProgress(1, LogSource::Stdout, frame) => Output::Progress(frame),
+                Message::ProcessOutputDropped(1, LogSource::Stdout, n) => Output::Dropped(n),
+                other => panic!("неожиданное сообщение: {:?}", other),
+            });
+        }
+        output
+    }
+
+    fn lines(lines: &[&str]) -> Output {
+        Output::Lines(lines.iter().map(|line| line.to_string()).collect())
+    }
+
+    #[tokio::test]
+    async fn lines_are_batched() {
+        assert_eq!(
+            read_all(b"first\nsecond\n\nlast", 0).await,
+            vec![lines(&["first", "second", "", "last"])]
+        );
+        assert!(read_all(b"", 0).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn carriage_return_replaces_progress() {
+        // Последний кадр без перевода строки отправляется как прогресс
+        assert_eq!(
+            read_all(b"10%\r50%\r100%\r", 0).await,
+            vec![Output::Progress("100%".to_string())]
+        );
+        // \r\n оставляет последний кадр обычной строкой, CRLF не дает пустых строк
+        assert_eq!(
+            read_all(b"10%\r100%\r\ndone\r\n", 0).await,
+            vec![lines(&["100%", "done"])]
+        );
+    }
+
+    #[tokio::test]
+    async fn backspace_rewrites_previous_character() {
+        assert_eq!(read_all(b"ab\x08c\n", 0).await, vec![lines(&["ac"])]);
+        // Символ UTF-8 удаляется целиком, шаг назад в пустой строке ничего не делает
+        assert_eq!(
+            read_all("\x08лог\x08и\n".as_bytes(), 0).await,
+            vec![lines(&["лои"])]
+        );
+        // Спиннер без перевода строки: кадр прогресса, затем последняя строка
+        assert_eq!(
+            read_all(b"wait |\x08/\x08-", 0).await,
+            vec![Output::Progress("wait -".to_string()), lines(&["wait -"])]
+        );
+    }
+
+    #[tokio::test]
+    async fn lines_over_rate_limit_are_counted() {
+        assert_eq!(
+            read_all(b"1\n2\n3\n4\n5\n", 2).await,
+            vec![lines(&["1", "2"]), Output::Dropped(3)]
+        );
+    }
+
+    #[test]
+    fn rate_limit_admits_up_to_limit_per_second() {
+        let mut rate = RateLimit::new(2);
+        assert!(rate.admit());
+        assert!(rate.admit());
+        assert!(!rate.admit());
+        assert!(!rate.admit());
+        assert!(rate.deadline().is_some());
+        // Секунда еще не закончилась
+        assert_eq!(rate.roll(), None);
+        rate.window_start -= Duration::from_secs(1);
+        assert_eq!(rate.roll(), Some(2));
+        assert!(rate.deadline().is_none());
+        assert!(rate.admit());
+        // Без пропущенных строк новая секунда ничего не сообщает
+        rate.window_start -= Duration::from_secs(1);
+        assert_eq!(rate.roll(), None);
+    }
+
+    #[test]
+    fn pids_that_would_signal_groups_are_rejected() {
+        assert!(!is_signalable_pid(0));
+        assert!(!is_signalable_pid(1));
+        assert!(!is_signalable_pid(1 << 31));
+        assert!(!is_signalable_pid(u32::MAX)); // kill(-1)
+        assert!(is_signalable_pid(2));
+        assert!(is_signalable_pid(i32::MAX as u32));
+    }
+
+    #[tokio::test]
+    async fn send_kill_refuses_invalid_pids() {
+        for pid in [0, 1, u32::MAX] {
+            assert_eq!(send_kill(pid, false).await, Err(KillError::NotFound));
+        }
+    }
+
+    // Параметры вспомогательного процесса остановки разбираются лаунчером обратно
+    #[test]
+    fn stop_helper_args_round_trip() {
+        use clap::Parser;
+
+        let args = stop_helper_args(4321, Duration::from_secs(15));
+        let cli = crate::cli::CliArgs::try_parse_from(
+            std::iter::once("launcher".to_string()).chain(args),
+        )
+        .unwrap();
+        assert_eq!(cli.stop_pid, Some(4321));
+        assert_eq!(cli.stop_timeout, 15);
+    }
+
+    #[test]
+    fn rate_limit_zero_means_unlimited() {
+        let mut rate = RateLimit::new(0);
+        assert!((0..10_000).all(|_| rate.admit()));
+        assert!(rate.deadline().is_none());
+    }
+}
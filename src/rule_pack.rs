@@ -0,0 +1,136 @@
+use crate::alerts::AlertRule;
+use crate::highlight::HighlightRule;
+use crate::i18n::{tr, trf};
+use crate::keyword_alerts::KeywordAlertRule;
+use crate::metrics::MetricRule;
+use rfd::AsyncFileDialog;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::fs;
+
+// --- Наборы правил ("rule packs") для обмена между пользователями ---
+// Один JSON-файл с правилами подсветки, метрик, оповещений и оповещений по ключевым фразам.
+
+// Текущая версия формата набора
+pub const RULE_PACK_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)] // В наборе может не быть части разделов
+pub struct RulePack {
+    pub version: u32,                          // Версия формата
+    pub highlight_rules: Vec<HighlightRule>,   // Подсветка строк лога
+    pub metric_rules: Vec<MetricRule>,         // Извлечение метрик
+    pub alert_rules: Vec<AlertRule>,           // Оповещения по порогам метрик
+    pub keyword_alerts: Vec<KeywordAlertRule>, // Оповещения по ключевым фразам
+}
+
+// Сколько правил добавлено при импорте и сколько пропущено
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub added: usize,   // Добавлено новых правил
+    pub skipped: usize, // Пропущено (уже есть или конфликт имени метрики)
+}
+
+impl RulePack {
+    // Добавление правил набора к текущим. Совпадающие правила не дублируются,
+    // метрика с уже существующим именем пропускается (иначе значения бы смешивались).
+    pub fn merge_into(
+        self,
+        highlight_rules: &mut Vec<HighlightRule>,
+        metric_rules: &mut Vec<MetricRule>,
+        alert_rules: &mut Vec<AlertRule>,
+        keyword_alerts: &mut Vec<KeywordAlertRule>,
+    ) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+        for rule in self.highlight_rules {
+            if highlight_rules.contains(&rule) {
+                summary.skipped += 1;
+            } else {
+                highlight_rules.push(rule);
+                summary.added += 1;
+            }
+        }
+        for rule in self.metric_rules {
+            if metric_rules
+                .iter()
+                .any(|existing| existing.name == rule.name)
+            {
+                summary.skipped += 1;
+            } else {
+                metric_rules.push(rule);
+                summary.added += 1;
+            }
+        }
+        for rule in self.alert_rules {
+            if alert_rules.contains(&rule) {
+                summary.skipped += 1;
+            } else {
+                alert_rules.push(rule);
+                summary.added += 1;
+            }
+        }
+        for rule in self.keyword_alerts {
+            if keyword_alerts
+                .iter()
+                .any(|existing| existing.key() == rule.key())
+            {
+                summary.skipped += 1;
+            } else {
+                keyword_alerts.push(rule);
+                summary.added += 1;
+            }
+        }
+        summary
+    }
+}
+
+// Экспорт набора в файл, выбранный пользователем. None - сохранение отменено
+pub async fn export_rule_pack(pack: RulePack) -> Result<Option<PathBuf>, String> {
+    let content = serde_json::to_string_pretty(&pack)
+        .map_err(|e| trf!("Ошибка сериализации набора правил: {}", e))?;
+    let file_handle = AsyncFileDialog::new()
+        .set_title(tr("Экспорт набора правил"))
+        .set_file_name("rule_pack.json")
+        .add_filter("JSON", &["json"])
+        .save_file()
+        .await;
+    let Some(handle) = file_handle else {
+        return Ok(None);
+    };
+    let path = handle.path().to_path_buf();
+    fs::write(&path, content)
+        .await
+        .map_err(|e| trf!("Не удалось записать файл {}: {}", format!("{:?}", path), e))?;
+    Ok(Some(path))
+}
+
+// Импорт набора из файла, выбранного пользователем. None - выбор отменен
+pub async fn import_rule_pack() -> Result<Option<RulePack>, String> {
+    let file_handle = AsyncFileDialog::new()
+        .set_title(tr("Импорт набора правил"))
+        .add_filter("JSON", &["json"])
+        .pick_file()
+        .await;
+    let Some(handle) = file_handle else {
+        return Ok(None);
+    };
+    let path = handle.path().to_path_buf();
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| trf!("Ошибка чтения файла {}: {}", format!("{:?}", path), e))?;
+    let pack: RulePack = serde_json::from_str(&content).map_err(|e| {
+        trf!(
+            "Файл {} не является набором правил: {}",
+            format!("{:?}", path),
+            e
+        )
+    })?;
+    if pack.version > RULE_PACK_VERSION {
+        return Err(trf!(
+            "Набор правил создан более новой версией лаунчера (формат {}, поддерживается до {})",
+            pack.version,
+            RULE_PACK_VERSION
+        ));
+    }
+    Ok(Some(pack))
+}
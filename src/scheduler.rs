@@ -0,0 +1,289 @@
+use crate::i18n::{tr, trf};
+use chrono::{
+    DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
+
+// --- Окна технического обслуживания биржи ---
+
+// Окно обслуживания, полученное из календаря iCal
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<Local>, // Начало обслуживания
+    pub end: DateTime<Local>,   // Окончание обслуживания
+    pub summary: String,        // Описание события из календаря
+}
+
+// Действие, которое планировщик просит выполнить
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduledAction {
+    Stop(String),  // Остановить процесс (с причиной для лога)
+    Start(String), // Запустить процесс (с причиной для лога)
+}
+
+// Загрузка календаря по URL и извлечение окон обслуживания
+pub async fn fetch_maintenance_windows(url: String) -> Result<Vec<MaintenanceWindow>, String> {
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| trf!("Не удалось загрузить календарь {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(trf!(
+            "Сервер календаря {} вернул ошибку: {}",
+            url,
+            response.status()
+        ));
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|e| trf!("Не удалось прочитать календарь {}: {}", url, e))?;
+    let windows = parse_ics(&body);
+    if windows.is_empty() && !body.contains("BEGIN:VCALENDAR") {
+        return Err(trf!("Ответ {} не похож на календарь iCal", url));
+    }
+    Ok(windows)
+}
+
+// Разбор содержимого .ics: события VEVENT с DTSTART/DTEND (или DURATION).
+// Время с суффиксом Z считается UTC, остальное (в т.ч. с параметром TZID) - локальным.
+pub fn parse_ics(content: &str) -> Vec<MaintenanceWindow> {
+    // Разворачиваем перенесенные строки (продолжение начинается с пробела или табуляции)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(continuation) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        lines.push(raw.to_string());
+    }
+
+    let mut windows = Vec::new();
+    let mut in_event = false;
+    let mut start: Option<(DateTime<Local>, bool)> = None; // (время, только дата)
+    let mut end: Option<DateTime<Local>> = None;
+    let mut duration: Option<ChronoDuration> = None;
+    let mut summary = String::new();
+
+    for line in &lines {
+        if line == "BEGIN:VEVENT" {
+            in_event = true;
+            start = None;
+            end = None;
+            duration = None;
+            summary.clear();
+            continue;
+        }
+        if line == "END:VEVENT" {
+            in_event = false;
+            if let Some((start, date_only)) = start {
+                let end = end
+                    .or_else(|| duration.map(|d| start + d))
+                    .unwrap_or_else(|| {
+                        // Без окончания: событие на весь день или мгновенное событие
+                        if date_only {
+                            start + ChronoDuration::days(1)
+                        } else {
+                            start
+                        }
+                    });
+                if end > start {
+                    windows.push(MaintenanceWindow {
+                        start,
+                        end,
+                        summary: summary.clone(),
+                    });
+                }
+            }
+            continue;
+        }
+        if !in_event {
+            continue;
+        }
+        let Some((name_part, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Имя свойства без параметров (DTSTART;TZID=Europe/Moscow -> DTSTART)
+        let name = name_part.split(';').next().unwrap_or_default();
+        match name {
+            "DTSTART" => start = parse_ics_datetime(value),
+            "DTEND" => end = parse_ics_datetime(value).map(|(time, _)| time),
+            "DURATION" => duration = parse_ics_duration(value),
+            "SUMMARY" => summary = value.replace("\\,", ",").replace("\\;", ";"),
+            _ => {}
+        }
+    }
+
+    windows.sort_by_key(|window| window.start);
+    windows
+}
+
+// Разбор значения даты/времени iCal. Второй элемент - признак значения "только дата".
+fn parse_ics_datetime(value: &str) -> Option<(DateTime<Local>, bool)> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive).with_timezone(&Local), false));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some((Local.from_local_datetime(&naive).earliest()?, false));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    Some((Local.from_local_datetime(&midnight).earliest()?, true))
+}
+
+// Разбор длительности iCal (например, PT2H30M, P1D, P1W)
+fn parse_ics_duration(value: &str) -> Option<ChronoDuration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P')?;
+    let mut total = ChronoDuration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match c {
+                    'W' => ChronoDuration::weeks(n),
+                    'D' => ChronoDuration::days(n),
+                    'H' => ChronoDuration::hours(n),
+                    'M' => ChronoDuration::minutes(n),
+                    _ => ChronoDuration::seconds(n),
+                };
+            }
+            _ => return None,
+        }
+    }
+    Some(if negative { -total } else { total })
+}
+
+// Ближайшее (текущее или будущее) окно обслуживания
+pub fn next_maintenance_window(
+    windows: &[MaintenanceWindow],
+    now: DateTime<Local>,
+) -> Option<&MaintenanceWindow> {
+    windows.iter().find(|window| window.end > now)
+}
+
+// Решение планировщика по окнам обслуживания:
+//  - за `lead_minutes` до начала окна работающий процесс останавливается;
+//  - после окончания окна процесс запускается снова, если был остановлен планировщиком.
+pub fn maintenance_action(
+    windows: &[MaintenanceWindow],
+    now: DateTime<Local>,
+    lead_minutes: u32,
+    is_running: bool,
+    restart_pending: bool,
+) -> Option<ScheduledAction> {
+    let lead = ChronoDuration::minutes(lead_minutes as i64);
+    let active = windows
+        .iter()
+        .find(|window| window.start - lead <= now && now < window.end);
+    match active {
+        Some(window) if is_running => Some(ScheduledAction::Stop(trf!(
+            "Плановое обслуживание биржи{} с {} до {}",
+            if window.summary.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", window.summary)
+            },
+            window.start.format("%d.%m %H:%M"),
+            window.end.format("%d.%m %H:%M")
+        ))),
+        None if restart_pending && !is_running => Some(ScheduledAction::Start(
+            tr("Окно обслуживания биржи завершено").to_string(),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_utc_local_and_folded_lines() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART:20240310T020000Z\r\n\
+                   DTEND:20240310T040000Z\r\n\
+                   SUMMARY:Плановое обслуживание\\, этап 1\r\n\
+                   \x20(продолжение)\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;TZID=Europe/Moscow:20240301T100000\r\n\
+                   DURATION:PT1H30M\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+        let windows = parse_ics(ics);
+        assert_eq!(windows.len(), 2);
+        // Окна отсортированы по началу
+        assert_eq!(windows[0].start, local(2024, 3, 1, 10, 0));
+        assert_eq!(windows[0].end, local(2024, 3, 1, 11, 30));
+        assert_eq!(windows[0].summary, "");
+        let utc_start = Utc.with_ymd_and_hms(2024, 3, 10, 2, 0, 0).unwrap();
+        assert_eq!(windows[1].start, utc_start.with_timezone(&Local));
+        assert_eq!(windows[1].end - windows[1].start, ChronoDuration::hours(2));
+        assert_eq!(
+            windows[1].summary,
+            "Плановое обслуживание, этап 1(продолжение)"
+        );
+    }
+
+    #[test]
+    fn all_day_and_empty_events() {
+        let ics = "BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240405\nEND:VEVENT\n\
+                   BEGIN:VEVENT\nDTSTART:20240406T120000\nEND:VEVENT\n\
+                   BEGIN:VEVENT\nDTSTART:20240407T120000\nDTEND:20240407T110000\nEND:VEVENT\n\
+                   BEGIN:VEVENT\nSUMMARY:без начала\nEND:VEVENT\n\
+                   DTSTART:20240408T120000\n";
+        let windows = parse_ics(ics);
+        // Событие на весь день - сутки; мгновенное, обратное и без начала пропускаются
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].start, local(2024, 4, 5, 0, 0));
+        assert_eq!(windows[0].end, local(2024, 4, 6, 0, 0));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(
+            parse_ics_duration("PT2H30M"),
+            Some(ChronoDuration::minutes(150))
+        );
+        assert_eq!(parse_ics_duration("P1W"), Some(ChronoDuration::weeks(1)));
+        assert_eq!(
+            parse_ics_duration("P1DT12H"),
+            Some(ChronoDuration::hours(36))
+        );
+        assert_eq!(
+            parse_ics_duration("-PT15M"),
+            Some(ChronoDuration::minutes(-15))
+        );
+        assert_eq!(
+            parse_ics_duration("+PT10S"),
+            Some(ChronoDuration::seconds(10))
+        );
+        assert_eq!(parse_ics_duration("PT1X"), None);
+        assert_eq!(parse_ics_duration("1H"), None);
+        assert_eq!(parse_ics_duration("PTH"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_datetimes() {
+        assert_eq!(parse_ics_datetime("2024-03-10"), None);
+        assert_eq!(parse_ics_datetime("20241310T000000Z"), None);
+        assert!(parse_ics_datetime(" 20240310 ").is_some_and(|(_, date_only)| date_only));
+    }
+}
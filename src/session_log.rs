@@ -0,0 +1,311 @@
+use crate::i18n::trf;
+use chrono::{DateTime, Local, NaiveDateTime};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// --- Файл лога сессии ---
+// Каждый запуск процесса пишет полный лог (с метками времени) в отдельный файл,
+// так что строки, вытесненные из окна лога или скрытые фильтром, остаются доступны.
+// Когда файл достигает лимита размера, запись продолжается в следующую часть:
+// session-ГГГГММДД-ЧЧММСС.log, session-ГГГГММДД-ЧЧММСС.2.log и т.д.
+
+// Открытый файл лога сессии
+pub struct SessionLog {
+    dir: PathBuf,             // Директория логов
+    name: String,             // Имя файла без расширения и номера части
+    part: u32,                // Номер текущей части (1 - первая)
+    max_bytes: u64,           // Размер, после которого начинается новая часть (0 - без ограничения)
+    written: u64,             // Сколько байт записано в текущую часть
+    path: PathBuf,            // Путь к текущему файлу
+    writer: LineWriter<File>, // Построчно сбрасываемый на диск поток записи
+}
+
+impl SessionLog {
+    // Создание нового файла session-ГГГГММДД-ЧЧММСС.log в директории логов.
+    // Для дополнительных экземпляров к имени добавляется номер: session-2-ГГГГММДД-ЧЧММСС.log
+    pub fn create(
+        dir: &Path,
+        instance: usize,
+        started: DateTime<Local>,
+        max_mb: u32,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(|e| {
+            trf!(
+                "Не удалось создать директорию логов {}: {}",
+                format!("{:?}", dir),
+                e
+            )
+        })?;
+        let name = format!(
+            "{}{}",
+            session_prefix(instance),
+            started.format("%Y%m%d-%H%M%S")
+        );
+        let path = dir.join(format!("{}.log", name));
+        let writer = open_part(&path)?;
+        Ok(SessionLog {
+            dir: dir.to_path_buf(),
+            name,
+            part: 1,
+            max_bytes: max_mb as u64 * 1024 * 1024,
+            written: 0,
+            path,
+            writer,
+        })
+    }
+
+    // Путь к текущему файлу лога
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    // Запись строки с меткой времени (с переходом к новой части при превышении размера)
+    pub fn write_line(&mut self, time: DateTime<Local>, line: &str) -> Result<(), String> {
+        if self.max_bytes > 0 && self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+        let record = format!("{} {}\n", time.format("%Y-%m-%d %H:%M:%S%.3f"), line);
+        self.writer.write_all(record.as_bytes()).map_err(|e| {
+            trf!(
+                "Ошибка записи в файл лога {}: {}",
+                format!("{:?}", self.path),
+                e
+            )
+        })?;
+        self.written += record.len() as u64;
+        Ok(())
+    }
+
+    // Переход к следующей части лога
+    fn rotate(&mut self) -> Result<(), String> {
+        self.part += 1;
+        let path = self.dir.join(format!("{}.{}.log", self.name, self.part));
+        self.writer = open_part(&path)?;
+        self.path = path;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+// Начало имени файлов лога сессии экземпляра: session- или session-N- (N - номер с 1)
+fn session_prefix(instance: usize) -> String {
+    match instance {
+        0 => "session-".to_string(),
+        index => format!("session-{}-", index + 1),
+    }
+}
+
+// Создание файла части лога
+fn open_part(path: &Path) -> Result<LineWriter<File>, String> {
+    File::create(path).map(LineWriter::new).map_err(|e| {
+        trf!(
+            "Не удалось создать файл лога {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })
+}
+
+// --- Восстановление лога прошлого сеанса ---
+// После перезапуска лаунчера окно лога заполняется концом последнего файла лога сессии
+// экземпляра, чтобы было видно, что происходило до перезапуска.
+
+// Конец лога прошлого сеанса экземпляра
+#[derive(Debug, Clone)]
+pub struct PreviousSessionLog {
+    pub path: PathBuf,                         // Файл, из которого прочитаны строки
+    pub lines: Vec<(DateTime<Local>, String)>, // Строки с метками времени (от старых к новым)
+}
+
+// Последние `max_lines` строк самого нового файла лога сессии экземпляра (None - файлов нет)
+pub async fn read_previous_session(
+    dir: PathBuf,
+    instance: usize,
+    max_lines: usize,
+) -> Result<Option<PreviousSessionLog>, String> {
+    tokio::task::spawn_blocking(move || {
+        if !dir.exists() {
+            return Ok(None);
+        }
+        read_previous_session_blocking(&dir, instance, max_lines)
+    })
+    .await
+    .map_err(|e| trf!("Ошибка фоновой задачи чтения лога: {}", e))?
+}
+
+fn read_previous_session_blocking(
+    dir: &Path,
+    instance: usize,
+    max_lines: usize,
+) -> Result<Option<PreviousSessionLog>, String> {
+    let prefix = session_prefix(instance);
+    // Самый новый файл - по времени запуска в имени и номеру части (сжатые .gz пропускаются)
+    let newest = list_files(dir)?
+        .into_iter()
+        .filter_map(|(path, _, _)| {
+            let name = path.file_name()?.to_str()?;
+            let stem = name.strip_suffix(".log")?.strip_prefix(&prefix)?;
+            let (started, part) = match stem.split_once('.') {
+                Some((started, part)) => (started, part.parse::<u32>().ok()?),
+                None => (stem, 1),
+            };
+            // Проверка формата отсекает файлы других экземпляров (session-2-... для основного)
+            NaiveDateTime::parse_from_str(started, "%Y%m%d-%H%M%S").ok()?;
+            Some(((started.to_string(), part), path))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+    let Some((_, path)) = newest else {
+        return Ok(None);
+    };
+    let file = File::open(&path)
+        .map_err(|e| trf!("Не удалось открыть файл {}: {}", format!("{:?}", path), e))?;
+    let mut lines = VecDeque::new();
+    for record in BufReader::new(file).split(b'\n') {
+        let record =
+            record.map_err(|e| trf!("Ошибка чтения файла {}: {}", format!("{:?}", path), e))?;
+        if let Some(line) = parse_record(&String::from_utf8_lossy(&record)) {
+            if lines.len() >= max_lines.max(1) {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+    Ok(Some(PreviousSessionLog {
+        path,
+        lines: lines.into(),
+    }))
+}
+
+// Разбор строки файла лога сессии: "ГГГГ-ММ-ДД ЧЧ:ММ:СС.ммм текст" (формат write_line)
+fn parse_record(record: &str) -> Option<(DateTime<Local>, String)> {
+    let record = record.trim_end_matches(['\r', '\n']);
+    let time = NaiveDateTime::parse_from_str(record.get(..23)?, "%Y-%m-%d %H:%M:%S%.3f").ok()?;
+    let time = time.and_local_timezone(Local).earliest()?;
+    Some((time, record.get(24..).unwrap_or_default().to_string()))
+}
+
+// Итог обслуживания директории логов
+#[derive(Debug, Clone, Default)]
+pub struct LogMaintenanceReport {
+    pub compressed: usize, // Сколько файлов сжато
+    pub removed: usize,    // Сколько файлов удалено из-за превышения квоты
+    pub total_bytes: u64,  // Итоговый размер директории
+}
+
+// Обслуживание директории логов:
+//  - файлы session-*.log старше `compress_after_days` дней сжимаются в .log.gz (0 - не сжимать);
+//  - если общий размер превышает `quota_mb` МБ, удаляются самые старые файлы (0 - без квоты).
+// Текущие файлы сессий (`active`, по одному на запущенный экземпляр) не трогаются.
+pub async fn maintain_logs_dir(
+    dir: PathBuf,
+    compress_after_days: u32,
+    quota_mb: u32,
+    active: Vec<PathBuf>,
+) -> Result<LogMaintenanceReport, String> {
+    tokio::task::spawn_blocking(move || {
+        if !dir.exists() {
+            return Ok(LogMaintenanceReport::default());
+        }
+        maintain_logs_dir_blocking(&dir, compress_after_days, quota_mb, &active)
+    })
+    .await
+    .map_err(|e| trf!("Ошибка фоновой задачи обслуживания логов: {}", e))?
+}
+
+fn maintain_logs_dir_blocking(
+    dir: &Path,
+    compress_after_days: u32,
+    quota_mb: u32,
+    active: &[PathBuf],
+) -> Result<LogMaintenanceReport, String> {
+    let mut report = LogMaintenanceReport::default();
+    let now = SystemTime::now();
+
+    // Сжатие старых файлов сессий
+    if compress_after_days > 0 {
+        let max_age = Duration::from_secs(compress_after_days as u64 * 24 * 60 * 60);
+        for (path, modified, _) in list_files(dir)? {
+            let is_session_log = path.extension().is_some_and(|ext| ext == "log");
+            let old_enough = now.duration_since(modified).unwrap_or_default() >= max_age;
+            if is_session_log && old_enough && !active.contains(&path) {
+                compress_file(&path)?;
+                report.compressed += 1;
+            }
+        }
+    }
+
+    // Соблюдение квоты: удаляем самые старые файлы
+    let mut files = list_files(dir)?;
+    report.total_bytes = files.iter().map(|(_, _, size)| size).sum();
+    if quota_mb > 0 {
+        let quota = quota_mb as u64 * 1024 * 1024;
+        files.sort_by_key(|(_, modified, _)| *modified);
+        for (path, _, size) in files {
+            if report.total_bytes <= quota {
+                break;
+            }
+            if active.contains(&path) {
+                continue;
+            }
+            fs::remove_file(&path).map_err(|e| {
+                trf!(
+                    "Не удалось удалить файл лога {}: {}",
+                    format!("{:?}", path),
+                    e
+                )
+            })?;
+            report.total_bytes -= size;
+            report.removed += 1;
+        }
+    }
+    Ok(report)
+}
+
+// Файлы директории: (путь, время изменения, размер)
+fn list_files(dir: &Path) -> Result<Vec<(PathBuf, SystemTime, u64)>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| {
+        trf!(
+            "Не удалось прочитать директорию логов {}: {}",
+            format!("{:?}", dir),
+            e
+        )
+    })?;
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_file() {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((entry.path(), modified, metadata.len()));
+        }
+    }
+    Ok(files)
+}
+
+// Сжатие файла в <имя>.gz с удалением исходного
+fn compress_file(path: &Path) -> Result<(), String> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+    let mut input = File::open(path)
+        .map_err(|e| trf!("Не удалось открыть файл {}: {}", format!("{:?}", path), e))?;
+    let output = File::create(&gz_path).map_err(|e| {
+        trf!(
+            "Не удалось создать файл {}: {}",
+            format!("{:?}", gz_path),
+            e
+        )
+    })?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    io::copy(&mut input, &mut encoder)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| trf!("Ошибка сжатия файла {}: {}", format!("{:?}", path), e))?;
+    fs::remove_file(path)
+        .map_err(|e| trf!("Не удалось удалить файл {}: {}", format!("{:?}", path), e))
+}
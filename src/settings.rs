@@ -1,64 +1,1489 @@
+use crate::alerts::AlertRule;
+use crate::encoding::OutputEncoding;
+use crate::forwarder::ForwardFormat;
+use crate::highlight::{HighlightColor, HighlightRule};
+use crate::i18n::Language;
+use crate::i18n::{tr, trf};
+use crate::keyword_alerts::KeywordAlertRule;
+use crate::metrics::MetricRule;
+use crate::placeholders::Placeholders;
+use crate::settings_crypto::{unlock_envelope, Envelope, SettingsCipher};
+use crate::theme_colors::ThemeColors;
+use crate::trading_hours::TradingHours;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
 use directories_next::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
 pub const CONFIG_FILE_NAME: &str = "launcher_settings.json"; // Сделаем публичной, может понадобиться
+pub const TOML_CONFIG_FILE_NAME: &str = "launcher_settings.toml"; // Та же конфигурация в формате TOML
+pub const SETTINGS_BACKUPS_KEEP: usize = 10; // Сколько резервных копий настроек хранить
+pub const SETTINGS_VERSION: u32 = 1; // Версия формата файла настроек (увеличивается вместе с MIGRATIONS)
+pub const LOG_LINES_MIN: u32 = 500; // Допустимый размер буфера строк лога в окне
+pub const LOG_LINES_MAX: u32 = 100_000;
+pub const LOG_FONT_SIZE_MIN: u16 = 8; // Допустимый размер шрифта лога
+pub const LOG_FONT_SIZE_MAX: u16 = 32;
+pub const DEFAULT_LOG_FONT_SIZE: u16 = 12;
+const MASKED_KEY: &str = "<ключ API скрыт>"; // Замена ключа API в выводе процесса
+const MASKED_KEY_MIN_CHARS: usize = 8; // Более короткие ключи в выводе не ищутся
+pub const DEFAULT_LOG_TIME_FORMAT: &str = "%H:%M:%S"; // Формат метки времени строк лога по умолчанию
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)] // Отсутствующие в файле поля берутся из Default (совместимость со старыми файлами)
 pub struct AppSettings {
+    pub version: u32, // Версия формата файла (0 - файл до появления версий)
     pub executable_path: Option<PathBuf>, // Поля делаем публичными
     pub api_key: String,
+    pub api_key_env: String, // Переменная окружения с ключом API основного экземпляра (пусто - ключ из api_key)
     pub last_pid: Option<u32>,
+    pub battery_action: BatteryAction, // Реакция на переход на питание от батареи
+    pub battery_min_charge: u8, // Минимальный заряд (%) для автоматических запусков от батареи
+    pub maintenance_ics_url: String, // URL календаря iCal с окнами обслуживания биржи
+    pub maintenance_lead_minutes: u32, // За сколько минут до обслуживания останавливать процесс
+    pub ntp_server: String,     // NTP-сервер для контроля системных часов
+    pub clock_drift_threshold_ms: u32, // Допустимое расхождение часов (мс)
+    pub block_start_on_clock_drift: bool, // Запрещать запуск при превышении расхождения
+    pub trading_hours: TradingHours, // Расписание автоматического запуска и остановки
+    pub metric_rules: Vec<MetricRule>, // Правила извлечения метрик из вывода процесса
+    pub alert_rules: Vec<AlertRule>, // Правила оповещений по порогам метрик
+    pub keyword_alerts: Vec<KeywordAlertRule>, // Оповещения по ключевым фразам в выводе
+    pub desktop_notify_crash: bool, // Системное уведомление при сбое процесса
+    pub desktop_notify_exit: bool, // Системное уведомление при неожиданном завершении процесса
+    pub highlight_rules: Vec<HighlightRule>, // Правила подсветки строк лога (по порядку)
+    pub log_compress_after_days: u32, // Сжимать логи сессий старше N дней (0 - не сжимать)
+    pub log_dir_quota_mb: u32,  // Квота на размер директории логов, МБ (0 - без ограничения)
+    pub session_log_max_mb: u32, // Размер части лога сессии, после которого начинается новая (МБ, 0 - без ограничения)
+    pub session_log_keep_ansi: bool, // Сохранять ANSI-последовательности (цвета) в логе сессии
+    pub log_timestamps: bool,    // Показывать время получения перед каждой строкой лога
+    pub log_buffer_lines: u32, // Сколько строк лога хранить в окне (LOG_LINES_MIN..=LOG_LINES_MAX)
+    pub log_export_keep_ansi: bool, // Сохранять ANSI-последовательности при экспорте лога в файл
+    pub tint_stderr: bool,     // Окрашивать строки потока ошибок без собственного цвета
+    pub log_gutter: bool,      // Показывать номера строк и метки потоков слева от лога
+    pub log_font_size: u16,    // Размер шрифта лога (LOG_FONT_SIZE_MIN..=LOG_FONT_SIZE_MAX)
+    pub restore_previous_log: bool, // Показывать при запуске конец лога предыдущего сеанса
+    pub log_time_format: String, // Формат времени (strftime, пусто - ЧЧ:ММ:СС)
+    pub data_dir: Option<PathBuf>, // Директория для логов и истории (None - стандартная)
+    pub log_forward_enabled: bool, // Пересылать вывод процесса во внешнюю систему логирования
+    pub log_forward_address: String, // Адрес приемника (host:port)
+    pub log_forward_format: ForwardFormat, // Формат пересылки (syslog / JSON Lines)
+    pub log_forward_tls: bool, // Использовать TLS
+    pub resume_workspace: bool, // Возобновлять процесс, работавший при закрытии лаунчера
+    pub autostart: bool,       // Запускать процесс при открытии лаунчера
+    pub graceful_stop_timeout_secs: u32, // Ожидание корректного завершения до принудительного (с, 0 - сразу)
+    pub ui_lock_minutes: u32, // Блокировать интерфейс после N минут бездействия (0 - не блокировать)
+    pub ui_lock_pin: String,  // PIN для разблокировки интерфейса
+    pub ui_lock_hide_logs: bool, // Скрывать лог, пока интерфейс заблокирован
+    pub language: Language,   // Язык интерфейса
+    pub theme_colors: ThemeColors, // Цвет акцента и кнопок запуска/остановки
+    pub close_to_tray: bool, // Закрытие окна прячет его в область уведомлений (выход - из меню значка)
+    pub env_vars: Vec<EnvVar>, // Дополнительные переменные окружения процесса
+    pub working_dir: Option<PathBuf>, // Рабочая директория процесса (None - директория запуска лаунчера)
+    pub extra_args: String, // Дополнительные аргументы командной строки (разбираются split_args)
+    pub watchdog_silence_minutes: u32, // Считать процесс зависшим после N минут без вывода (0 - не следить)
+    pub watchdog_restart: bool,        // Перезапускать зависший процесс
+    pub max_runtime_minutes: u32, // Останавливать процесс через N минут работы (0 - без ограничения)
+    pub output_rate_limit: u32, // Строк вывода в секунду на поток, сверх - пропускаются (0 - без ограничения)
+    pub output_encoding: OutputEncoding, // Кодировка вывода процесса
+    pub run_elevated: bool,     // Запускать основной экземпляр с повышенными правами (UAC / pkexec)
+    pub keep_running: bool,     // Основной экземпляр продолжает работу после закрытия лаунчера
+    pub health_check: String, // Адрес проверки готовности основного экземпляра (хост:порт или URL, пусто - не проверять)
+    pub stop_command: String, // Команда консоли для корректной остановки основного экземпляра (пусто - сигнал)
+    pub memory_limit_mb: u32, // Перезапускать основной экземпляр, если его память превысила N МБ (0 - не следить)
+    pub health_start_timeout_secs: u32, // Сколько ждать первого ответа после запуска (с)
+    pub instances: Vec<InstanceSettings>, // Дополнительные экземпляры (работают параллельно с основным)
+    pub profiles: Vec<LaunchProfile>,     // Именованные профили запуска основного экземпляра
+    pub active_profile: Option<usize>,    // Выбранный профиль (его параметры - в полях выше)
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         AppSettings {
+            version: SETTINGS_VERSION,
             executable_path: None,
             api_key: String::new(),
+            api_key_env: String::new(),
             last_pid: None,
+            battery_action: BatteryAction::default(),
+            battery_min_charge: 30,
+            maintenance_ics_url: String::new(),
+            maintenance_lead_minutes: 5,
+            ntp_server: "pool.ntp.org".to_string(),
+            clock_drift_threshold_ms: 1000,
+            block_start_on_clock_drift: false,
+            trading_hours: TradingHours::default(),
+            metric_rules: Vec::new(),
+            alert_rules: Vec::new(),
+            keyword_alerts: Vec::new(),
+            desktop_notify_crash: true,
+            desktop_notify_exit: true,
+            highlight_rules: Vec::new(),
+            log_compress_after_days: 7,
+            log_dir_quota_mb: 1024,
+            session_log_max_mb: 50,
+            session_log_keep_ansi: false,
+            log_timestamps: true,
+            log_export_keep_ansi: false,
+            tint_stderr: true,
+            log_gutter: false,
+            log_font_size: DEFAULT_LOG_FONT_SIZE,
+            restore_previous_log: true,
+            log_buffer_lines: LOG_LINES_MIN,
+            log_time_format: DEFAULT_LOG_TIME_FORMAT.to_string(),
+            data_dir: None,
+            log_forward_enabled: false,
+            log_forward_address: String::new(),
+            log_forward_format: ForwardFormat::default(),
+            log_forward_tls: false,
+            resume_workspace: false,
+            autostart: false,
+            graceful_stop_timeout_secs: 10,
+            ui_lock_minutes: 0,
+            ui_lock_pin: String::new(),
+            ui_lock_hide_logs: true,
+            language: Language::default(),
+            theme_colors: ThemeColors::default(),
+            close_to_tray: false,
+            env_vars: Vec::new(),
+            working_dir: None,
+            extra_args: String::new(),
+            watchdog_silence_minutes: 0,
+            watchdog_restart: false,
+            max_runtime_minutes: 0,
+            output_rate_limit: 5000,
+            output_encoding: OutputEncoding::default(),
+            run_elevated: false,
+            keep_running: false,
+            health_check: String::new(),
+            stop_command: String::new(),
+            memory_limit_mb: 0,
+            health_start_timeout_secs: 120,
+            instances: Vec::new(),
+            profiles: Vec::new(),
+            active_profile: None,
         }
     }
 }
 
+// Действие при переходе на питание от батареи
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatteryAction {
+    Ignore, // Ничего не делать
+    #[default]
+    Warn, // Показать предупреждение
+    Stop,   // Остановить процесс
+}
+
+impl BatteryAction {
+    pub const ALL: [BatteryAction; 3] = [
+        BatteryAction::Ignore,
+        BatteryAction::Warn,
+        BatteryAction::Stop,
+    ];
+}
+
+impl std::fmt::Display for BatteryAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BatteryAction::Ignore => tr("Ничего не делать"),
+            BatteryAction::Warn => tr("Предупреждать"),
+            BatteryAction::Stop => tr("Останавливать процесс"),
+        })
+    }
+}
+
+// Дополнительный экземпляр TradingStar со своим ключом API.
+// Основной экземпляр настраивается полями api_key, extra_args и last_pid;
+// исполняемый файл, окружение и рабочая директория у всех экземпляров общие.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct InstanceSettings {
+    pub name: String,          // Название для вкладки (пустое - "Экземпляр N")
+    pub api_key: String,       // Ключ API экземпляра
+    pub extra_args: String,    // Дополнительные аргументы командной строки
+    pub last_pid: Option<u32>, // PID процесса, запущенного в прошлом сеансе
+    pub run_elevated: bool,    // Запускать с повышенными правами (UAC / pkexec)
+    pub keep_running: bool,    // Продолжать работу после закрытия лаунчера
+    pub health_check: String,  // Адрес проверки готовности (хост:порт или URL)
+    pub stop_command: String,  // Команда консоли для корректной остановки (пусто - сигнал)
+    pub memory_limit_mb: u32,  // Лимит памяти процесса для перезапуска (МБ, 0 - не следить)
+}
+
+// Параметры, с которыми запущен процесс экземпляра: их изменение вступает в силу
+// только после перезапуска
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchSettings {
+    pub executable_path: Option<PathBuf>, // Исполняемый файл
+    pub api_key: String,                  // Ключ API
+    pub extra_args: String,               // Дополнительные аргументы
+    pub env_vars: Vec<EnvVar>,            // Переменные окружения
+    pub working_dir: Option<PathBuf>,     // Рабочая директория
+    pub run_elevated: bool,               // Запуск с повышенными правами
+    pub keep_running: bool,               // Работа после закрытия лаунчера
+}
+
+impl LaunchSettings {
+    // Названия параметров, отличающихся от `current` (для баннера перезапуска)
+    pub fn changes(&self, current: &LaunchSettings) -> Vec<&'static str> {
+        [
+            (
+                self.executable_path != current.executable_path,
+                tr("исполняемый файл"),
+            ),
+            (self.api_key != current.api_key, tr("ключ API")),
+            (self.extra_args != current.extra_args, tr("аргументы")),
+            (
+                self.env_vars != current.env_vars,
+                tr("переменные окружения"),
+            ),
+            (
+                self.working_dir != current.working_dir,
+                tr("рабочая директория"),
+            ),
+            (
+                self.run_elevated != current.run_elevated,
+                tr("повышенные права"),
+            ),
+            (
+                self.keep_running != current.keep_running,
+                tr("работа после закрытия"),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(changed, name)| changed.then_some(name))
+        .collect()
+    }
+}
+
+// Именованный профиль запуска (например, реальный и демо-счет): исполняемый файл, ключ,
+// аргументы и параметры процесса основного экземпляра. Параметры выбранного профиля
+// хранятся в основных полях AppSettings, профиль обновляется из них при сохранении
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct LaunchProfile {
+    pub name: String,                     // Название профиля
+    pub executable_path: Option<PathBuf>, // Исполняемый файл
+    pub api_key: String,                  // Ключ API
+    pub extra_args: String,               // Дополнительные аргументы командной строки
+    pub env_vars: Vec<EnvVar>,            // Переменные окружения
+    pub working_dir: Option<PathBuf>,     // Рабочая директория
+    pub run_elevated: bool,               // Запуск с повышенными правами
+    pub title: String,                    // Дополнение к заголовку окна (например, "LIVE BTC")
+    pub badge: Option<HighlightColor>,    // Цвет метки на значке окна
+}
+
+// Переменная окружения, передаваемая процессу при запуске
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct EnvVar {
+    pub key: String,   // Имя переменной (например, HTTPS_PROXY)
+    pub value: String, // Значение
+}
+
+impl EnvVar {
+    // Проверка переменной. Возвращает описание ошибки или None
+    pub fn validate(&self) -> Option<String> {
+        let key = self.key.trim();
+        if key.is_empty() {
+            return Some(tr("Не задано имя переменной").to_string());
+        }
+        if key.contains(['=', '\0']) || key.chars().any(char::is_whitespace) {
+            return Some(trf!(
+                "Недопустимое имя переменной {}: пробелы и знак = запрещены",
+                format!("{:?}", key)
+            ));
+        }
+        if self.value.contains('\0') {
+            return Some(trf!("Значение переменной {} содержит нулевой символ", key));
+        }
+        None
+    }
+}
+
+impl AppSettings {
+    // Размер буфера строк лога в окне с учетом допустимых границ
+    pub fn log_buffer_limit(&self) -> usize {
+        self.log_buffer_lines.clamp(LOG_LINES_MIN, LOG_LINES_MAX) as usize
+    }
+
+    // Размер шрифта лога с учетом допустимых границ
+    pub fn log_font_size(&self) -> u16 {
+        self.log_font_size
+            .clamp(LOG_FONT_SIZE_MIN, LOG_FONT_SIZE_MAX)
+    }
+
+    // Метка времени строки лога (None - метки отключены). Ошибочный формат заменяется стандартным
+    pub fn log_time_prefix(&self, time: DateTime<Local>) -> Option<String> {
+        if !self.log_timestamps {
+            return None;
+        }
+        let format = match self.log_time_format.trim() {
+            format if format.is_empty() || time_format_problem(format).is_some() => {
+                DEFAULT_LOG_TIME_FORMAT
+            }
+            format => format,
+        };
+        Some(time.format(format).to_string())
+    }
+
+    // Блокировка интерфейса возможна только с заданным PIN
+    pub fn ui_lock_available(&self) -> bool {
+        !self.ui_lock_pin.is_empty()
+    }
+
+    // Корректные переменные окружения для запуска процесса (ошибочные пропускаются)
+    // с раскрытыми подстановками в значениях
+    pub fn process_env(&self, placeholders: &Placeholders) -> Vec<(String, String)> {
+        self.env_vars
+            .iter()
+            .filter(|var| var.validate().is_none())
+            .map(|var| (var.key.trim().to_string(), placeholders.expand(&var.value)))
+            .collect()
+    }
+
+    // Количество экземпляров (основной и дополнительные)
+    pub fn instance_count(&self) -> usize {
+        1 + self.instances.len()
+    }
+
+    // Название экземпляра по индексу (0 - основной)
+    pub fn instance_name(&self, index: usize) -> String {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            None => tr("Основной").to_string(),
+            Some(instance) if instance.name.trim().is_empty() => trf!("Экземпляр {}", index + 1),
+            Some(instance) => instance.name.trim().to_string(),
+        }
+    }
+
+    // Ключи API, скрываемые в выводе процессов (см. mask_api_keys). Список собирается
+    // при загрузке и изменении настроек, а не для каждой строки вывода
+    pub fn masked_api_keys(&self) -> Vec<String> {
+        let env_key = self.env_api_key().unwrap_or_default();
+        let mut keys: Vec<String> = [&self.api_key, &env_key]
+            .into_iter()
+            .chain(self.instances.iter().map(|instance| &instance.api_key))
+            .chain(self.profiles.iter().map(|profile| &profile.api_key))
+            .map(|key| key.trim())
+            // Слишком короткий "ключ" совпадал бы с обычным текстом
+            .filter(|key| key.chars().count() >= MASKED_KEY_MIN_CHARS)
+            .map(str::to_string)
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    // Ключ API экземпляра (у основного - из переменной окружения, если она указана;
+    // переменная читается при каждом обращении, в том числе при запуске процесса)
+    pub fn instance_api_key(&self, index: usize) -> Cow<'_, str> {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => Cow::Borrowed(&instance.api_key),
+            None if !self.api_key_env.trim().is_empty() => {
+                Cow::Owned(self.env_api_key().unwrap_or_default())
+            }
+            None => Cow::Borrowed(&self.api_key),
+        }
+    }
+
+    // Ключ API из переменной окружения api_key_env (None - переменная не указана или не задана)
+    pub fn env_api_key(&self) -> Option<String> {
+        let name = self.api_key_env.trim();
+        if name.is_empty() {
+            return None;
+        }
+        std::env::var(name)
+            .ok()
+            .map(|value| sanitize_api_key(&value).0)
+            .filter(|key| !key.is_empty())
+    }
+
+    // Дополнительные аргументы экземпляра
+    pub fn instance_extra_args(&self, index: usize) -> &str {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => &instance.extra_args,
+            None => &self.extra_args,
+        }
+    }
+
+    // Новый профиль из текущих параметров запуска основного экземпляра
+    pub fn capture_profile(&self, name: String) -> LaunchProfile {
+        LaunchProfile {
+            name,
+            executable_path: self.executable_path.clone(),
+            api_key: self.api_key.clone(),
+            extra_args: self.extra_args.clone(),
+            env_vars: self.env_vars.clone(),
+            working_dir: self.working_dir.clone(),
+            run_elevated: self.run_elevated,
+            title: String::new(),
+            badge: None,
+        }
+    }
+
+    // Перенос текущих параметров запуска в выбранный профиль (изменения не теряются)
+    pub fn sync_active_profile(&mut self) {
+        if let Some(index) = self.active_profile.filter(|i| *i < self.profiles.len()) {
+            let previous = &self.profiles[index];
+            let profile = LaunchProfile {
+                title: previous.title.clone(),
+                badge: previous.badge,
+                ..self.capture_profile(previous.name.clone())
+            };
+            self.profiles[index] = profile;
+        }
+    }
+
+    // Выбранный профиль
+    pub fn active_launch_profile(&self) -> Option<&LaunchProfile> {
+        self.active_profile
+            .and_then(|index| self.profiles.get(index))
+    }
+
+    // Выбор профиля: его параметры становятся текущими параметрами запуска
+    pub fn activate_profile(&mut self, index: usize) {
+        let Some(profile) = self.profiles.get(index).cloned() else {
+            return;
+        };
+        self.sync_active_profile();
+        self.executable_path = profile.executable_path;
+        self.api_key = profile.api_key;
+        self.extra_args = profile.extra_args;
+        self.env_vars = profile.env_vars;
+        self.working_dir = profile.working_dir;
+        self.run_elevated = profile.run_elevated;
+        self.active_profile = Some(index);
+    }
+
+    // Текущие параметры запуска экземпляра
+    pub fn launch_settings(&self, index: usize) -> LaunchSettings {
+        LaunchSettings {
+            executable_path: self.executable_path.clone(),
+            api_key: self.instance_api_key(index).to_string(),
+            extra_args: self.instance_extra_args(index).to_string(),
+            env_vars: self.env_vars.clone(),
+            working_dir: self.working_dir.clone(),
+            run_elevated: self.instance_run_elevated(index),
+            keep_running: self.instance_keep_running(index),
+        }
+    }
+
+    // Продолжает ли процесс экземпляра работу после закрытия лаунчера
+    pub fn instance_keep_running(&self, index: usize) -> bool {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => instance.keep_running,
+            None => self.keep_running,
+        }
+    }
+
+    // Запускать ли экземпляр с повышенными правами
+    pub fn instance_run_elevated(&self, index: usize) -> bool {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => instance.run_elevated,
+            None => self.run_elevated,
+        }
+    }
+
+    // Адрес проверки готовности экземпляра (пустая строка - проверка отключена)
+    pub fn instance_health_check(&self, index: usize) -> &str {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => instance.health_check.trim(),
+            None => self.health_check.trim(),
+        }
+    }
+
+    // Команда консоли, по которой экземпляр корректно завершается (пустая строка - не задана)
+    pub fn instance_stop_command(&self, index: usize) -> &str {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => instance.stop_command.trim(),
+            None => self.stop_command.trim(),
+        }
+    }
+
+    // Лимит памяти процесса экземпляра в МБ (0 - не следить)
+    pub fn instance_memory_limit_mb(&self, index: usize) -> u32 {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => instance.memory_limit_mb,
+            None => self.memory_limit_mb,
+        }
+    }
+
+    // PID процесса экземпляра, сохраненный для следующего сеанса
+    pub fn instance_last_pid(&self, index: usize) -> Option<u32> {
+        match index
+            .checked_sub(1)
+            .and_then(|extra| self.instances.get(extra))
+        {
+            Some(instance) => instance.last_pid,
+            None => self.last_pid,
+        }
+    }
+
+    // Запоминание PID экземпляра. Возвращает true, если значение изменилось
+    // (и настройки нужно сохранить)
+    pub fn set_instance_last_pid(&mut self, index: usize, pid: Option<u32>) -> bool {
+        let last_pid = match index.checked_sub(1) {
+            Some(extra) => match self.instances.get_mut(extra) {
+                Some(instance) => &mut instance.last_pid,
+                None => return false,
+            },
+            None => &mut self.last_pid,
+        };
+        std::mem::replace(last_pid, pid) != pid
+    }
+
+    // Дополнительные аргументы запуска экземпляра с раскрытыми подстановками
+    pub fn process_args(
+        &self,
+        index: usize,
+        placeholders: &Placeholders,
+    ) -> Result<Vec<String>, String> {
+        Ok(split_args(self.instance_extra_args(index))?
+            .iter()
+            .map(|arg| placeholders.expand(arg))
+            .collect())
+    }
+}
+
+// Очистка вставленного ключа API: удаляются пробелы и переводы строк (частая причина
+// ошибок авторизации - невидимый перевод строки в конце), а ключ, вставленный дважды
+// подряд, сокращается до одной копии. Возвращает ключ и список внесенных исправлений.
+pub fn sanitize_api_key(raw: &str) -> (String, Vec<&'static str>) {
+    let mut fixes = Vec::new();
+    let mut key: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+    if key.len() != raw.len() {
+        fixes.push(tr("удалены пробелы и переводы строк"));
+    }
+    let half = key.len() / 2;
+    if half > 0
+        && key.len().is_multiple_of(2)
+        && key.is_char_boundary(half)
+        && key[..half] == key[half..]
+    {
+        key.truncate(half);
+        fixes.push(tr("ключ был вставлен дважды - оставлена одна копия"));
+    }
+    (key, fixes)
+}
+
+// Проверка формата ключа API. Возвращает описание проблемы или None, если ключ корректен
+pub fn validate_api_key(key: &str) -> Option<String> {
+    const MIN_LEN: usize = 8;
+    const MAX_LEN: usize = 256;
+    if key.is_empty() {
+        return Some(tr("Ключ API не задан").to_string());
+    }
+    if key.chars().any(char::is_whitespace) {
+        return Some(tr("Ключ содержит пробелы или переводы строк").to_string());
+    }
+    if let Some(c) = key.chars().find(|c| !c.is_ascii_graphic()) {
+        return Some(trf!(
+            "Ключ содержит недопустимый символ {}",
+            format!("{:?}", c)
+        ));
+    }
+    if key.len() < MIN_LEN {
+        return Some(trf!("Ключ слишком короткий ({} симв.)", key.len()));
+    }
+    if key.len() > MAX_LEN {
+        return Some(trf!("Ключ слишком длинный ({} симв.)", key.len()));
+    }
+    None
+}
+
+// Строка вывода без ключей API (`keys` - из AppSettings::masked_api_keys): процесс может
+// напечатать свою командную строку или конфигурацию, а ключ не должен попасть в окно,
+// файлы логов и пересылку
+pub fn mask_api_keys(keys: &[String], line: String) -> String {
+    let mut line = line;
+    for key in keys {
+        if line.contains(key.as_str()) {
+            line = line.replace(key.as_str(), tr(MASKED_KEY));
+        }
+    }
+    line
+}
+
+// Проверка формата времени strftime. Возвращает описание ошибки или None
+pub fn time_format_problem(format: &str) -> Option<String> {
+    StrftimeItems::new(format.trim())
+        .any(|item| matches!(item, Item::Error))
+        .then(|| {
+            trf!(
+                "Неверный формат времени {} (пример: %H:%M:%S%.3f)",
+                format!("{:?}", format.trim())
+            )
+        })
+}
+
+// Разбор строки аргументов по правилам командной строки ОС: пробелы разделяют аргументы.
+// Windows - как CommandLineToArgvW: только " группирует, \ - обычный символ (пути вида
+// C:\Logs\ts.log пишутся без кавычек). Остальные ОС - как shell: кавычки " и ',
+// \ экранирует следующий символ.
+pub fn split_args(line: &str) -> Result<Vec<String>, String> {
+    #[cfg(windows)]
+    let args = split_windows_args(line).ok_or(tr("Незакрытая кавычка в строке аргументов"));
+    #[cfg(not(windows))]
+    let args = shlex::split(line).ok_or(tr(
+        "Незакрытая кавычка или лишняя \\ в конце строки аргументов",
+    ));
+    args.map_err(str::to_string)
+}
+
+// Разбор по правилам Windows (None - незакрытая кавычка). Обратная косая черта особая
+// только перед ": 2n черт + " - n черт и кавычка-ограничитель, 2n+1 черт + " - n черт
+// и сама кавычка. "" внутри кавычек - кавычка.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn split_windows_args(line: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false; // Аргумент начат (в том числе пустой - "")
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut backslashes = 1;
+                while chars.next_if_eq(&'\\').is_some() {
+                    backslashes += 1;
+                }
+                in_arg = true;
+                if chars.peek() == Some(&'"') {
+                    current.extend(std::iter::repeat_n('\\', backslashes / 2));
+                    if backslashes % 2 == 1 {
+                        chars.next();
+                        current.push('"');
+                    }
+                } else {
+                    current.extend(std::iter::repeat_n('\\', backslashes));
+                }
+            }
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                current.push('"');
+            }
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quoted {
+        return None;
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Some(args)
+}
+
+// Путь к файлу конфигурации в стандартной директории ОС:
+// Windows - %APPDATA%, Linux - ~/.config, macOS - ~/Library/Application Support.
+// Если рядом есть файл TOML (его удобнее готовить средствами развертывания),
+// используется он, и настройки сохраняются в нем же.
 pub fn get_config_path() -> Option<PathBuf> {
     ProjectDirs::from("com", "TradingStar", "TradingStar3Launcher").map(|dirs| {
         let config_dir = dirs.config_dir();
-        config_dir.join(CONFIG_FILE_NAME)
+        let toml_path = config_dir.join(TOML_CONFIG_FILE_NAME);
+        if toml_path.exists() {
+            toml_path
+        } else {
+            config_dir.join(CONFIG_FILE_NAME)
+        }
     })
 }
 
-pub async fn load_settings(path: Option<PathBuf>) -> Result<AppSettings, String> {
-    let path = path.ok_or_else(|| "Не удалось определить путь к конфигурации".to_string())?;
+// Формат файла конфигурации (по расширению файла)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub fn of(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    // Расширение файла (для резервных копий)
+    fn extension(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "json",
+            ConfigFormat::Toml => "toml",
+        }
+    }
+}
+
+// Текст файла настроек в формате файла `path`
+// (с ключом - зашифрованный, в том же формате)
+pub fn serialize_settings(
+    path: &Path,
+    settings: &AppSettings,
+    cipher: Option<&SettingsCipher>,
+) -> Result<String, String> {
+    let plaintext = serialize_as(path, settings)?;
+    match cipher {
+        Some(cipher) => serialize_as(path, &cipher.encrypt(&plaintext)?),
+        None => Ok(plaintext),
+    }
+}
+
+fn serialize_as<T: Serialize>(path: &Path, value: &T) -> Result<String, String> {
+    match ConfigFormat::of(path) {
+        ConfigFormat::Json => serde_json::to_string_pretty(value)
+            .map_err(|e| trf!("Ошибка сериализации настроек: {}", e)),
+        ConfigFormat::Toml => toml::to_string_pretty(value)
+            .map_err(|e| trf!("Ошибка сериализации настроек в TOML: {}", e)),
+    }
+}
+
+// --- Миграции формата настроек ---
+// Файл старой версии обновляется по шагам: MIGRATIONS[N] переводит версию N в N + 1.
+// Миграции работают с JSON до разбора в AppSettings, поэтому могут переименовывать
+// поля и менять их тип. После изменения формата полей в AppSettings нужно добавить
+// миграцию и увеличить SETTINGS_VERSION.
+type Migration = fn(&mut Map<String, Value>);
+const MIGRATIONS: [Migration; SETTINGS_VERSION as usize] = [migrate_v0_to_v1];
+
+// Перевод полей из версии `version` в последнюю версию списка `migrations`
+fn apply_migrations(migrations: &[Migration], version: u32, fields: &mut Map<String, Value>) {
+    for migrate in migrations.iter().skip(version as usize) {
+        migrate(fields);
+    }
+}
+
+// Версия 1: ключи API хранятся без пробелов и переводов строк по краям (ранние версии
+// сохраняли вставленный ключ как есть). Сохраненный ключ только обрезается: эвристики
+// sanitize_api_key (например, "ключ вставлен дважды") нужны при вставке, а не здесь.
+fn migrate_v0_to_v1(fields: &mut Map<String, Value>) {
+    let trim = |key: &mut Value| {
+        if let Some(raw) = key.as_str() {
+            *key = Value::String(raw.trim().to_string());
+        }
+    };
+    if let Some(key) = fields.get_mut("api_key") {
+        trim(key);
+    }
+    // Ключи дополнительных экземпляров и профилей запуска
+    for list in ["instances", "profiles"] {
+        if let Some(items) = fields.get_mut(list).and_then(Value::as_array_mut) {
+            for key in items.iter_mut().filter_map(|item| item.get_mut("api_key")) {
+                trim(key);
+            }
+        }
+    }
+}
+
+// Загруженные настройки и замечания о загрузке
+#[derive(Debug, Clone)]
+pub struct LoadedSettings {
+    pub settings: AppSettings,
+    pub migrated: bool,          // Формат обновлен - файл нужно перезаписать
+    pub recovered: bool,         // Файл поврежден, загружена предыдущая версия (.bak)
+    pub password_required: bool, // Файл зашифрован, а ключа нет (настройки - по умолчанию)
+    pub notes: Vec<String>,      // Обновление формата, поля, замененные значением по умолчанию
+    pub content: String,         // Текст файла (пусто - файла нет)
+}
+
+// Загрузка настроек; зашифрованный файл читается ключом `cipher`
+pub async fn load_settings(
+    path: Option<PathBuf>,
+    cipher: Option<SettingsCipher>,
+) -> Result<LoadedSettings, String> {
+    let path = path.ok_or_else(|| tr("Не удалось определить путь к конфигурации").to_string())?;
+    let empty = |content: String, password_required: bool| LoadedSettings {
+        settings: AppSettings::default(),
+        migrated: false,
+        recovered: false,
+        password_required,
+        notes: Vec::new(),
+        content,
+    };
     if !path.exists() {
-        return Ok(AppSettings::default());
+        return Ok(empty(String::new(), false));
+    }
+    let content = fs::read_to_string(&path).await.map_err(|e| {
+        trf!(
+            "Ошибка чтения файла конфигурации {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })?;
+    let mut notes = Vec::new();
+    let mut source = path.clone(); // Файл, из которого прочитаны настройки
+    let mut fields = match decode_settings(&content, &path, cipher.as_ref()) {
+        Ok(Some(fields)) => fields,
+        Ok(None) => return Ok(empty(content, true)),
+        Err(e) => {
+            // Поврежденный файл (например, обрезанный при сбое) - берем предыдущую версию
+            let previous = previous_path(&path);
+            let Ok(previous_content) = fs::read_to_string(&previous).await else {
+                return Err(e);
+            };
+            let Ok(Some(fields)) = decode_settings(&previous_content, &previous, cipher.as_ref())
+            else {
+                return Err(e);
+            };
+            notes.push(trf!(
+                "{}. Загружена предыдущая версия настроек из {}",
+                e,
+                previous.display()
+            ));
+            source = previous;
+            fields
+        }
+    };
+    let recovered = source != path;
+    let version = fields
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| u32::try_from(version).unwrap_or(u32::MAX));
+    let migrated = version < SETTINGS_VERSION;
+    if migrated {
+        // Копия файла старой версии - на случай, если понадобится откатить лаунчер
+        let backup = backup_path(&path, version);
+        match fs::copy(&source, &backup).await {
+            Ok(_) => notes.push(trf!("Формат настроек обновлен с версии {} до {} (копия прежнего файла: {})", version, SETTINGS_VERSION, backup.display())),
+            Err(e) => notes.push(trf!("Формат настроек обновлен с версии {} до {}, копию прежнего файла сохранить не удалось: {}", version, SETTINGS_VERSION, e)),
+        }
+        apply_migrations(&MIGRATIONS, version, &mut fields);
+    } else if version > SETTINGS_VERSION {
+        notes.push(trf!(
+            "Файл настроек записан более новой версией лаунчера (формат {}, поддерживается {}): \
+             незнакомые параметры не будут сохранены",
+            version,
+            SETTINGS_VERSION
+        ));
+    }
+    fields.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+    let settings = settings_from_fields(fields, &mut notes);
+    Ok(LoadedSettings {
+        settings,
+        migrated,
+        recovered,
+        password_required: false,
+        notes,
+        content,
+    })
+}
+
+// Разбор текста файла настроек как объекта JSON (TOML приводится к JSON,
+// чтобы миграции и разбор по полям работали одинаково для обоих форматов)
+fn parse_settings_object(content: &str, path: &Path) -> Result<Map<String, Value>, String> {
+    let value = match ConfigFormat::of(path) {
+        ConfigFormat::Json => serde_json::from_str::<Value>(content).map_err(|e| e.to_string()),
+        ConfigFormat::Toml => toml::from_str::<toml::Table>(content)
+            .map_err(|e| e.to_string())
+            .and_then(|table| serde_json::to_value(table).map_err(|e| e.to_string())),
+    };
+    match value {
+        Ok(Value::Object(fields)) => Ok(fields),
+        Ok(_) => Err(trf!(
+            "Файл конфигурации {} не содержит объекта настроек",
+            format!("{:?}", path)
+        )),
+        Err(e) => Err(trf!(
+            "Ошибка парсинга файла конфигурации {}: {}",
+            format!("{:?}", path),
+            e
+        )),
+    }
+}
+
+// Поля настроек из текста файла (расшифрованного, если он зашифрован).
+// None - файл зашифрован, а ключа нет.
+fn decode_settings(
+    content: &str,
+    path: &Path,
+    cipher: Option<&SettingsCipher>,
+) -> Result<Option<Map<String, Value>>, String> {
+    let fields = parse_settings_object(content, path)?;
+    let Some(envelope) = Envelope::from_fields(&fields) else {
+        return Ok(Some(fields));
+    };
+    let Some(cipher) = cipher else {
+        return Ok(None);
+    };
+    let plaintext = cipher.decrypt(&envelope?)?;
+    parse_settings_object(&plaintext, path).map(Some)
+}
+
+// Директория данных из файла настроек (синхронно - до запуска интерфейса).
+// None - стандартная директория, нет файла или файл зашифрован (пароль еще не введен).
+pub fn read_data_dir_setting(path: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let fields = decode_settings(&content, path, None).ok()??;
+    fields.get("data_dir")?.as_str().map(PathBuf::from)
+}
+
+// Ключ для зашифрованного файла настроек по паролю
+pub async fn unlock_settings(
+    path: Option<PathBuf>,
+    password: String,
+) -> Result<SettingsCipher, String> {
+    let path = path.ok_or_else(|| tr("Не удалось определить путь к конфигурации").to_string())?;
+    let content = fs::read_to_string(&path).await.map_err(|e| {
+        trf!(
+            "Ошибка чтения файла конфигурации {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })?;
+    let fields = parse_settings_object(&content, &path)?;
+    match Envelope::from_fields(&fields) {
+        Some(envelope) => unlock_envelope(envelope?, password).await,
+        None => Err(tr("Файл настроек не зашифрован").to_string()),
+    }
+}
+
+// Удаление незашифрованных копий настроек (.bak, копии при обновлении формата,
+// резервные копии) после включения шифрования. Возвращает число удаленных файлов.
+pub async fn remove_plaintext_copies(config_path: Option<PathBuf>) -> Result<usize, String> {
+    let path =
+        config_path.ok_or_else(|| tr("Не удалось определить путь к конфигурации").to_string())?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut candidates: Vec<PathBuf> = list_settings_backups(Some(path.clone()))
+        .await?
+        .into_iter()
+        .map(|backup| backup.path)
+        .collect();
+    if let Some(dir) = path.parent() {
+        let mut entries = fs::read_dir(dir).await.map_err(|e| {
+            trf!(
+                "Не удалось прочитать директорию {}: {}",
+                format!("{:?}", dir),
+                e
+            )
+        })?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with(&format!("{}.", file_name)) && name.ends_with(".bak") {
+                candidates.push(entry.path());
+            }
+        }
+    }
+    let mut removed = 0;
+    for candidate in candidates {
+        let encrypted = match fs::read_to_string(&candidate).await {
+            Ok(content) => parse_settings_object(&content, &candidate)
+                .map(|fields| Envelope::from_fields(&fields).is_some())
+                .unwrap_or(false),
+            Err(_) => continue,
+        };
+        if !encrypted {
+            fs::remove_file(&candidate)
+                .await
+                .map_err(|e| trf!("Не удалось удалить {}: {}", format!("{:?}", candidate), e))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+// Разбор настроек по полям: поле, которое не удалось прочитать (неверный тип или значение),
+// заменяется значением по умолчанию, а остальные настройки сохраняются. Каждое поле
+// проверяется один раз, само по себе (остальные берутся из Default), затем прочитанные
+// поля разбираются вместе.
+fn settings_from_fields(fields: Map<String, Value>, notes: &mut Vec<String>) -> AppSettings {
+    if let Ok(settings) = AppSettings::deserialize(&Value::Object(fields.clone())) {
+        return settings;
+    }
+    let mut accepted = Map::new();
+    for (name, value) in fields {
+        let field = Value::Object(Map::from_iter([(name.clone(), value)]));
+        match AppSettings::deserialize(&field) {
+            Ok(_) => {
+                if let Value::Object(field) = field {
+                    accepted.extend(field);
+                }
+            }
+            Err(e) => notes.push(trf!(
+                "Параметр \"{}\" не прочитан ({}), используется значение по умолчанию",
+                name,
+                e
+            )),
+        }
+    }
+    serde_json::from_value(Value::Object(accepted)).unwrap_or_default()
+}
+
+// Путь предыдущей версии файла настроек (launcher_settings.json.bak)
+fn previous_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+// Путь копии файла настроек версии N (launcher_settings.json.vN.bak)
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".v{}.bak", version));
+    PathBuf::from(name)
+}
+
+// --- Резервные копии настроек ---
+// Копии файла с меткой времени в имени хранятся в поддиректории backups рядом с файлом
+// настроек (SETTINGS_BACKUPS_KEEP последних) и восстанавливаются из экрана настроек.
+
+const BACKUP_PREFIX: &str = "launcher_settings-";
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+// Резервная копия файла настроек
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsBackup {
+    pub path: PathBuf,                  // Файл копии
+    pub created: chrono::NaiveDateTime, // Время создания (из имени файла)
+}
+
+impl std::fmt::Display for SettingsBackup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.created.format("%d.%m.%Y %H:%M:%S"))
     }
-    let content = fs::read_to_string(&path)
-        .await
-        .map_err(|e| format!("Ошибка чтения файла конфигурации {:?}: {}", path, e))?;
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Ошибка парсинга файла конфигурации {:?}: {}", path, e))
 }
 
-pub async fn save_settings(path: Option<PathBuf>, settings: AppSettings) -> Result<(), String> {
-    let path = path.ok_or_else(|| "Не удалось определить путь к конфигурации".to_string())?;
+fn backups_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("backups")
+}
+
+// Копии настроек, от новых к старым
+pub async fn list_settings_backups(
+    config_path: Option<PathBuf>,
+) -> Result<Vec<SettingsBackup>, String> {
+    let path =
+        config_path.ok_or_else(|| tr("Не удалось определить путь к конфигурации").to_string())?;
+    let dir = backups_dir(&path);
+    let mut entries = match fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(trf!(
+                "Не удалось прочитать директорию {}: {}",
+                format!("{:?}", dir),
+                e
+            ))
+        }
+    };
+    let suffix = format!(".{}", ConfigFormat::of(&path).extension());
+    let mut backups = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let created = name
+            .strip_prefix(BACKUP_PREFIX)
+            .and_then(|rest| rest.strip_suffix(suffix.as_str()))
+            .and_then(|time| chrono::NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT).ok());
+        if let Some(created) = created {
+            backups.push(SettingsBackup {
+                path: entry.path(),
+                created,
+            });
+        }
+    }
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created));
+    Ok(backups)
+}
+
+// Копия текущего файла настроек (если он отличается от последней копии) и удаление
+// лишних старых копий. Возвращает путь новой копии (None - копировать нечего).
+pub async fn backup_settings(config_path: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    let path =
+        config_path.ok_or_else(|| tr("Не удалось определить путь к конфигурации").to_string())?;
+    let content = match fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(trf!(
+                "Ошибка чтения файла конфигурации {}: {}",
+                format!("{:?}", path),
+                e
+            ))
+        }
+    };
+    let backups = list_settings_backups(Some(path.clone())).await?;
+    if let Some(latest) = backups.first() {
+        if fs::read_to_string(&latest.path).await.ok().as_deref() == Some(content.as_str()) {
+            return Ok(None);
+        }
+    }
+    let dir = backups_dir(&path);
+    fs::create_dir_all(&dir).await.map_err(|e| {
+        trf!(
+            "Не удалось создать директорию {}: {}",
+            format!("{:?}", dir),
+            e
+        )
+    })?;
+    let backup = dir.join(format!(
+        "{}{}.{}",
+        BACKUP_PREFIX,
+        Local::now().format(BACKUP_TIME_FORMAT),
+        ConfigFormat::of(&path).extension()
+    ));
+    fs::write(&backup, content).await.map_err(|e| {
+        trf!(
+            "Не удалось записать резервную копию {}: {}",
+            format!("{:?}", backup),
+            e
+        )
+    })?;
+    // Новая копия - первая в списке, удаляем самые старые сверх лимита
+    for old in backups.iter().skip(SETTINGS_BACKUPS_KEEP.saturating_sub(1)) {
+        if old.path != backup {
+            let _ = fs::remove_file(&old.path).await;
+        }
+    }
+    Ok(Some(backup))
+}
+
+// Восстановление копии: текущие настройки сначала сами сохраняются в копию,
+// чтобы восстановление можно было отменить
+pub async fn restore_settings_backup(
+    config_path: Option<PathBuf>,
+    backup: PathBuf,
+    cipher: Option<SettingsCipher>,
+) -> Result<LoadedSettings, String> {
+    if !backup.exists() {
+        return Err(trf!(
+            "Резервная копия {} не найдена",
+            format!("{:?}", backup)
+        ));
+    }
+    backup_settings(config_path).await?;
+    let loaded = load_settings(Some(backup), cipher).await?;
+    if loaded.password_required {
+        return Err(tr("Копия зашифрована другим паролем").to_string());
+    }
+    Ok(loaded)
+}
+
+// Запись файла настроек (текст готовит serialize_settings)
+pub async fn save_settings(path: Option<PathBuf>, content: String) -> Result<(), String> {
+    let path = path.ok_or_else(|| tr("Не удалось определить путь к конфигурации").to_string())?;
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .await
-            .map_err(|e| format!("Не удалось создать директорию {:?}: {}", parent, e))?;
-    }
-    let content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Ошибка сериализации настроек: {}", e))?;
-    let mut file = fs::File::create(&path).await.map_err(|e| {
-        format!(
-            "Не удалось создать/открыть файл конфигурации {:?}: {}",
-            path, e
+        fs::create_dir_all(parent).await.map_err(|e| {
+            trf!(
+                "Не удалось создать директорию {}: {}",
+                format!("{:?}", parent),
+                e
+            )
+        })?;
+    }
+    // Пишем во временный файл рядом с конфигурацией и атомарно подменяем им старый:
+    // сбой или отключение питания во время записи не испортят действующий файл.
+    // У каждого сохранения свой временный файл - сохранения могут идти параллельно.
+    static SAVE_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(format!(
+        ".{}.tmp",
+        SAVE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp_path = PathBuf::from(tmp_name);
+    let mut file = fs::File::create(&tmp_path).await.map_err(|e| {
+        trf!(
+            "Не удалось создать временный файл конфигурации {}: {}",
+            format!("{:?}", tmp_path),
+            e
         )
     })?;
-    file.write_all(content.as_bytes())
-        .await
-        .map_err(|e| format!("Не удалось записать в файл конфигурации {:?}: {}", path, e))?;
+    file.write_all(content.as_bytes()).await.map_err(|e| {
+        trf!(
+            "Не удалось записать в файл конфигурации {}: {}",
+            format!("{:?}", tmp_path),
+            e
+        )
+    })?;
+    file.sync_all().await.map_err(|e| {
+        trf!(
+            "Не удалось сбросить на диск файл {}: {}",
+            format!("{:?}", tmp_path),
+            e
+        )
+    })?;
+    drop(file); // На Windows файл должен быть закрыт до переименования
+
+    // Предыдущая версия остается в .bak (только неповрежденная - иначе затерла бы
+    // последнюю рабочую копию)
+    if let Ok(previous) = fs::read_to_string(&path).await {
+        if parse_settings_object(&previous, &path).is_ok() {
+            let previous_path = previous_path(&path);
+            if let Err(e) = fs::write(&previous_path, previous).await {
+                eprintln!(
+                    "Не удалось сохранить предыдущую версию настроек {:?}: {}",
+                    previous_path, e
+                );
+            }
+        }
+    }
+    if let Err(e) = fs::rename(&tmp_path, &path).await {
+        let _ = fs::remove_file(&tmp_path).await;
+        return Err(trf!(
+            "Не удалось заменить файл конфигурации {}: {}",
+            format!("{:?}", path),
+            e
+        ));
+    }
+    // На Unix переименование становится надежным только после fsync директории
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let dir = fs::File::open(parent).await.map_err(|e| {
+            trf!(
+                "Не удалось открыть директорию {}: {}",
+                format!("{:?}", parent),
+                e
+            )
+        })?;
+        dir.sync_all().await.map_err(|e| {
+            trf!(
+                "Не удалось сбросить на диск директорию {}: {}",
+                format!("{:?}", parent),
+                e
+            )
+        })?;
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn split_args_follows_shell_quoting() {
+        assert_eq!(
+            split_args(r#"--log "C:\My Logs" --name=a\ b 'x y'"#).unwrap(),
+            vec!["--log", r"C:\My Logs", "--name=a b", "x y"]
+        );
+        assert_eq!(split_args("   ").unwrap(), Vec::<String>::new());
+        assert!(split_args(r#"--log "unterminated"#).is_err());
+        assert!(split_args(r"trailing\").is_err());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn split_args_keeps_unquoted_windows_paths() {
+        assert_eq!(
+            split_args(r"--log C:\Logs\ts.log").unwrap(),
+            vec!["--log", r"C:\Logs\ts.log"]
+        );
+        assert!(split_args(r#"--log "C:\My Logs"#).is_err());
+    }
+
+    #[test]
+    fn windows_args_treat_backslash_literally() {
+        let split = |line| split_windows_args(line).unwrap();
+        assert_eq!(
+            split(r"--log C:\Logs\ts.log"),
+            vec!["--log", r"C:\Logs\ts.log"]
+        );
+        assert_eq!(
+            split(r#"--dir \\server\share "a\"b""#),
+            vec!["--dir", r"\\server\share", r#"a"b"#]
+        );
+        assert_eq!(
+            split(r#"--log "C:\My Logs\\" x"#),
+            vec!["--log", r"C:\My Logs\", "x"]
+        );
+        assert_eq!(
+            split(r#"a\"b "c""d" "" e'f g'"#),
+            vec![r#"a"b"#, r#"c"d"#, "", "e'f", "g'"]
+        );
+        assert_eq!(split("  \t "), Vec::<String>::new());
+        assert_eq!(split_windows_args(r#"--log "C:\Logs"#), None);
+        // \" перед закрывающей кавычкой - кавычка внутри аргумента, а не конец
+        assert_eq!(split_windows_args(r#"--log "C:\My Logs\""#), None);
+    }
+
+    #[test]
+    fn sanitize_api_key_removes_whitespace() {
+        let (key, fixes) = sanitize_api_key("  abc 123\r\n");
+        assert_eq!(key, "abc123");
+        assert_eq!(fixes.len(), 1);
+        let (key, fixes) = sanitize_api_key("abc123");
+        assert_eq!(key, "abc123");
+        assert!(fixes.is_empty());
+    }
+
+    #[test]
+    fn sanitize_api_key_collapses_doubled_paste() {
+        let (key, fixes) = sanitize_api_key("abc123abc123\n");
+        assert_eq!(key, "abc123");
+        assert_eq!(fixes.len(), 2);
+        // Нечетная длина и несовпадающие половины не трогаются
+        assert_eq!(sanitize_api_key("abcab").0, "abcab");
+        assert_eq!(sanitize_api_key("abcabd").0, "abcabd");
+        assert_eq!(sanitize_api_key("").0, "");
+        // Половина не по границе символа UTF-8
+        assert_eq!(sanitize_api_key("ключ").0, "ключ");
+    }
+
+    #[test]
+    fn masked_api_keys_are_collected_once_per_key() {
+        let settings = AppSettings {
+            api_key: "main-key-123".to_string(),
+            instances: vec![InstanceSettings {
+                api_key: " main-key-123 ".to_string(),
+                ..Default::default()
+            }],
+            profiles: vec![LaunchProfile {
+                api_key: "short".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        // Совпадающие ключи - одна запись, короткие не скрываются
+        let keys = settings.masked_api_keys();
+        assert_eq!(keys, vec!["main-key-123"]);
+        assert_eq!(
+            mask_api_keys(&keys, "--key main-key-123 --mode short".to_string()),
+            format!("--key {} --mode short", tr(MASKED_KEY))
+        );
+    }
+
+    #[test]
+    fn migration_v1_trims_all_api_keys() {
+        let Value::Object(mut fields) = json!({
+            "api_key": "  main\n",
+            "instances": [{ "api_key": "\textra " }, { "name": "без ключа" }],
+            "profiles": [{ "name": "p", "api_key": " profile\r\n" }],
+        }) else {
+            unreachable!()
+        };
+        migrate_v0_to_v1(&mut fields);
+        assert_eq!(fields["api_key"], "main");
+        assert_eq!(fields["instances"][0]["api_key"], "extra");
+        assert!(fields["instances"][1].get("api_key").is_none());
+        assert_eq!(fields["profiles"][0]["api_key"], "profile");
+    }
+
+    #[test]
+    fn migration_v1_keeps_doubled_looking_keys() {
+        let Value::Object(mut fields) =
+            json!({ "api_key": "abcabc", "profiles": [{ "api_key": "xyxy" }] })
+        else {
+            unreachable!()
+        };
+        migrate_v0_to_v1(&mut fields);
+        assert_eq!(fields["api_key"], "abcabc");
+        assert_eq!(fields["profiles"][0]["api_key"], "xyxy");
+    }
+
+    #[test]
+    fn migration_v1_ignores_missing_and_non_string_fields() {
+        let Value::Object(mut fields) = json!({ "api_key": 42, "instances": "не массив" })
+        else {
+            unreachable!()
+        };
+        migrate_v0_to_v1(&mut fields);
+        assert_eq!(fields["api_key"], 42);
+        assert_eq!(fields["instances"], "не массив");
+        let mut empty = Map::new();
+        migrate_v0_to_v1(&mut empty);
+        assert!(empty.is_empty());
+    }
+
+    // Миграция, переименовывающая поле и меняющая его тип, проходит весь путь до
+    // AppSettings; поле неверного типа заменяется значением по умолчанию
+    #[test]
+    fn migrations_rename_and_retype_fields() {
+        // Версия 1 -> 2: "exe" переименован в executable_path,
+        // таймаут остановки хранился строкой
+        fn migrate_v1_to_v2(fields: &mut Map<String, Value>) {
+            if let Some(exe) = fields.remove("exe") {
+                fields.insert("executable_path".to_string(), exe);
+            }
+            if let Some(timeout) = fields.get_mut("graceful_stop_timeout_secs") {
+                if let Some(secs) = timeout.as_str().and_then(|s| s.trim().parse::<u32>().ok()) {
+                    *timeout = Value::from(secs);
+                }
+            }
+        }
+        let migrations: [Migration; 2] = [migrate_v0_to_v1, migrate_v1_to_v2];
+
+        let Value::Object(mut fields) = json!({
+            "version": 1,
+            "exe": "/opt/ts/TradingStar",
+            "graceful_stop_timeout_secs": " 25 ",
+            "api_key": " не трогается: версия 1 уже пройдена ",
+            "autostart": "да",
+        }) else {
+            unreachable!()
+        };
+        apply_migrations(&migrations, 1, &mut fields);
+        let mut notes = Vec::new();
+        let settings = settings_from_fields(fields, &mut notes);
+        assert_eq!(
+            settings.executable_path,
+            Some(PathBuf::from("/opt/ts/TradingStar"))
+        );
+        assert_eq!(settings.graceful_stop_timeout_secs, 25);
+        assert_eq!(settings.api_key, " не трогается: версия 1 уже пройдена ");
+        assert!(!settings.autostart);
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("autostart"));
+    }
+
+    #[tokio::test]
+    async fn load_migrates_unversioned_file() {
+        let dir = std::env::temp_dir().join(format!("ts_launcher_settings_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("launcher_settings.json");
+        std::fs::write(
+            &path,
+            r#"{ "api_key": " key \n", "profiles": [{ "name": "p", "api_key": "k2\t" }] }"#,
+        )
+        .unwrap();
+        let loaded = load_settings(Some(path.clone()), None).await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(loaded.migrated);
+        assert_eq!(loaded.settings.version, SETTINGS_VERSION);
+        assert_eq!(loaded.settings.api_key, "key");
+        assert_eq!(loaded.settings.profiles[0].api_key, "k2");
+    }
+}
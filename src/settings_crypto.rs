@@ -0,0 +1,145 @@
+use crate::i18n::{tr, trf};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+// --- Шифрование файла настроек ---
+// Файл настроек может храниться зашифрованным паролем: ключ выводится из пароля
+// (Argon2id, соль хранится в файле), текст настроек шифруется XChaCha20-Poly1305
+// со случайным nonce при каждом сохранении. Пароль в памяти не хранится - только ключ.
+
+const ENVELOPE_FORMAT: u32 = 1; // Версия формата зашифрованного файла
+const SALT_LEN: usize = 16;
+
+// Ключ шифрования настроек (выведен из пароля и соли)
+#[derive(Clone, PartialEq, Eq)]
+pub struct SettingsCipher {
+    salt: [u8; SALT_LEN],
+    key: [u8; 32],
+}
+
+// Ключ не должен попасть в лог или отладочный вывод
+impl fmt::Debug for SettingsCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SettingsCipher(..)")
+    }
+}
+
+// Содержимое зашифрованного файла: { "encrypted": { ... } }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Envelope {
+    encrypted: EncryptedData,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedData {
+    format: u32,        // ENVELOPE_FORMAT
+    kdf: String,        // Функция вывода ключа ("argon2id")
+    salt: String,       // Соль (base64)
+    nonce: String,      // Nonce XChaCha20-Poly1305 (base64)
+    ciphertext: String, // Зашифрованный текст настроек (base64)
+}
+
+impl Envelope {
+    // Зашифрованный файл распознается по единственному полю "encrypted"
+    pub fn from_fields(fields: &Map<String, Value>) -> Option<Result<Envelope, String>> {
+        fields.get("encrypted")?;
+        Some(
+            serde_json::from_value(Value::Object(fields.clone()))
+                .map_err(|e| trf!("Поврежден заголовок зашифрованного файла: {}", e)),
+        )
+    }
+
+    fn salt(&self) -> Result<[u8; SALT_LEN], String> {
+        let salt = BASE64
+            .decode(&self.encrypted.salt)
+            .map_err(|e| trf!("Повреждена соль зашифрованного файла: {}", e))?;
+        salt.try_into()
+            .map_err(|_| tr("Неверная длина соли зашифрованного файла").to_string())
+    }
+}
+
+// Вывод ключа из пароля (Argon2id занимает заметное время - в отдельном потоке).
+// Без соли (новое шифрование) соль выбирается случайно.
+pub async fn derive_cipher(
+    password: String,
+    salt: Option<[u8; SALT_LEN]>,
+) -> Result<SettingsCipher, String> {
+    tokio::task::spawn_blocking(move || {
+        let salt = salt.unwrap_or_else(|| {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            salt
+        });
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), &salt, &mut key)
+            .map_err(|e| trf!("Ошибка вывода ключа из пароля: {}", e))?;
+        Ok(SettingsCipher { salt, key })
+    })
+    .await
+    .map_err(|e| trf!("Ошибка вывода ключа из пароля: {}", e))?
+}
+
+// Ключ для зашифрованного файла по паролю (с проверкой, что пароль подходит)
+pub async fn unlock_envelope(
+    envelope: Envelope,
+    password: String,
+) -> Result<SettingsCipher, String> {
+    let cipher = derive_cipher(password, Some(envelope.salt()?)).await?;
+    cipher.decrypt(&envelope)?;
+    Ok(cipher)
+}
+
+impl SettingsCipher {
+    pub fn encrypt(&self, plaintext: &str) -> Result<Envelope, String> {
+        let aead = XChaCha20Poly1305::new((&self.key).into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = aead
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| tr("Ошибка шифрования настроек").to_string())?;
+        Ok(Envelope {
+            encrypted: EncryptedData {
+                format: ENVELOPE_FORMAT,
+                kdf: "argon2id".to_string(),
+                salt: BASE64.encode(self.salt),
+                nonce: BASE64.encode(nonce),
+                ciphertext: BASE64.encode(ciphertext),
+            },
+        })
+    }
+
+    pub fn decrypt(&self, envelope: &Envelope) -> Result<String, String> {
+        let data = &envelope.encrypted;
+        if data.format != ENVELOPE_FORMAT || data.kdf != "argon2id" {
+            return Err(trf!(
+                "Неподдерживаемый формат шифрования ({}, {})",
+                data.format,
+                data.kdf
+            ));
+        }
+        if envelope.salt()? != self.salt {
+            return Err(tr("Файл зашифрован другим паролем").to_string());
+        }
+        let nonce = BASE64
+            .decode(&data.nonce)
+            .map_err(|e| trf!("Поврежден nonce зашифрованного файла: {}", e))?;
+        if nonce.len() != 24 {
+            return Err(tr("Неверная длина nonce зашифрованного файла").to_string());
+        }
+        let ciphertext = BASE64
+            .decode(&data.ciphertext)
+            .map_err(|e| trf!("Поврежден зашифрованный текст: {}", e))?;
+        let plaintext = XChaCha20Poly1305::new((&self.key).into())
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            // Неверный ключ и измененный файл неотличимы
+            .map_err(|_| tr("Неверный пароль или файл поврежден").to_string())?;
+        String::from_utf8(plaintext).map_err(|e| trf!("Расшифрованный текст не UTF-8: {}", e))
+    }
+}
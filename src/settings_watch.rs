@@ -0,0 +1,76 @@
+use crate::i18n::tr;
+use iced::futures::SinkExt;
+use iced::{subscription, Subscription};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+// --- Отслеживание изменений файла настроек ---
+// Файл настроек могут переписывать внешние инструменты (Ansible, скрипты синхронизации).
+// Наблюдение ведется за директорией: файл заменяется переименованием (так сохраняет
+// и сам лаунчер), и наблюдение за самим файлом прекратилось бы после первой замены.
+
+// Пауза после последнего события: внешняя программа может писать файл частями
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+// Ключ подписки
+struct SettingsWatch;
+
+// Выдает событие, когда файл настроек изменен, создан или заменен
+pub fn settings_watch_subscription(path: PathBuf) -> Subscription<()> {
+    subscription::channel(
+        (std::any::TypeId::of::<SettingsWatch>(), path.clone()),
+        1,
+        move |mut output| async move {
+            let (sender, mut changes) = mpsc::unbounded_channel();
+            // Наблюдатель должен жить, пока работает подписка
+            let _watcher = match watch(&path, sender) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    eprintln!("Не удалось отслеживать файл настроек {:?}: {}", path, e);
+                    return iced::futures::future::pending().await;
+                }
+            };
+            while changes.recv().await.is_some() {
+                // Дожидаемся, пока события перестанут поступать
+                while let Ok(Some(())) = tokio::time::timeout(SETTLE_DELAY, changes.recv()).await {}
+                let _ = output.send(()).await;
+            }
+            iced::futures::future::pending().await
+        },
+    )
+}
+
+fn watch(
+    path: &Path,
+    sender: mpsc::UnboundedSender<()>,
+) -> Result<notify::RecommendedWatcher, String> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| tr("у пути нет родительской директории").to_string())?;
+    // До первого сохранения директории может не быть
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        // Чтение файла (в том числе самим лаунчером) изменением не считается
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        if event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name() == file_name.as_deref())
+        {
+            let _ = sender.send(());
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+    Ok(watcher)
+}
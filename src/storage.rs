@@ -0,0 +1,119 @@
+use crate::history::HISTORY_FILE_NAME;
+use crate::i18n::trf;
+use directories_next::ProjectDirs;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+// --- Директория данных лаунчера (логи сессий, история запусков) ---
+
+// Имя поддиректории с логами сессий
+pub const LOGS_DIR_NAME: &str = "logs";
+
+// Стандартная директория данных ОС:
+// Windows - %APPDATA%, Linux - ~/.local/share, macOS - ~/Library/Application Support
+pub fn default_data_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "TradingStar", "TradingStar3Launcher")
+        .map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+// Действующая директория данных: выбранная пользователем или стандартная
+pub fn resolve_data_dir(custom: Option<&Path>) -> Option<PathBuf> {
+    custom.map(Path::to_path_buf).or_else(default_data_dir)
+}
+
+// Директория логов сессий внутри директории данных
+pub fn logs_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(LOGS_DIR_NAME)
+}
+
+// Файл истории запусков внутри директории данных
+pub fn history_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(HISTORY_FILE_NAME)
+}
+
+// Проверка, что в директорию можно писать (создаем и удаляем пробный файл)
+pub async fn validate_data_dir(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).await.map_err(|e| {
+        trf!(
+            "Не удалось создать директорию {}: {}",
+            format!("{:?}", dir),
+            e
+        )
+    })?;
+    let probe = dir.join(".write_test");
+    fs::write(&probe, b"ok")
+        .await
+        .map_err(|e| trf!("Нет доступа на запись в {}: {}", format!("{:?}", dir), e))?;
+    fs::remove_file(&probe).await.map_err(|e| {
+        trf!(
+            "Не удалось удалить пробный файл {}: {}",
+            format!("{:?}", probe),
+            e
+        )
+    })
+}
+
+// Смена директории данных: проверка новой директории и перенос существующих файлов
+// (логи сессий и история запусков). Файлы текущих сессий (`active`) остаются на месте,
+// файлы, уже существующие в новой директории, не перезаписываются.
+// Возвращает количество перенесенных файлов.
+pub async fn migrate_data_dir(
+    from: Option<PathBuf>,
+    to: PathBuf,
+    active: Vec<PathBuf>,
+) -> Result<usize, String> {
+    validate_data_dir(&to).await?;
+    let Some(from) = from.filter(|from| from != &to) else {
+        return Ok(0);
+    };
+    let mut moved = 0;
+
+    // История запусков
+    if move_file(&history_path(&from), &history_path(&to)).await? {
+        moved += 1;
+    }
+
+    // Логи сессий
+    let (old_logs, new_logs) = (logs_dir(&from), logs_dir(&to));
+    if let Ok(mut entries) = fs::read_dir(&old_logs).await {
+        fs::create_dir_all(&new_logs).await.map_err(|e| {
+            trf!(
+                "Не удалось создать директорию {}: {}",
+                format!("{:?}", new_logs),
+                e
+            )
+        })?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if active.contains(&path) || !path.is_file() {
+                continue;
+            }
+            if move_file(&path, &new_logs.join(entry.file_name())).await? {
+                moved += 1;
+            }
+        }
+    }
+    Ok(moved)
+}
+
+// Перенос файла (переименованием, а между дисками - копированием с удалением).
+// Возвращает false, если исходного файла нет или целевой уже существует.
+async fn move_file(from: &Path, to: &Path) -> Result<bool, String> {
+    if !from.exists() || to.exists() {
+        return Ok(false);
+    }
+    if fs::rename(from, to).await.is_err() {
+        fs::copy(from, to).await.map_err(|e| {
+            trf!(
+                "Не удалось скопировать {} в {}: {}",
+                format!("{:?}", from),
+                format!("{:?}", to),
+                e
+            )
+        })?;
+        fs::remove_file(from)
+            .await
+            .map_err(|e| trf!("Не удалось удалить {}: {}", format!("{:?}", from), e))?;
+    }
+    Ok(true)
+}
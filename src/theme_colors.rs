@@ -0,0 +1,76 @@
+use crate::i18n::trf;
+use iced::theme::Palette;
+use iced::{Color, Theme};
+use serde::{Deserialize, Serialize};
+
+// --- Цвета интерфейса ---
+// Цвет акцента (верхняя панель, обычные кнопки, выделение в полях и списках) и цвета
+// кнопок запуска и остановки задаются в настройках в виде #RRGGBB. Они попадают в палитру
+// темы (primary, success, danger), а стили виджетов берут цвета из нее.
+
+// Цвета по умолчанию
+pub const DEFAULT_ACCENT: &str = "#007BFF";
+pub const DEFAULT_START: &str = "#28A745";
+pub const DEFAULT_STOP: &str = "#DC3545";
+
+// Цвета интерфейса (как введены в настройках)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    pub accent: String, // Цвет акцента
+    pub start: String,  // Кнопка запуска
+    pub stop: String,   // Кнопка остановки
+}
+
+impl Default for ThemeColors {
+    fn default() -> Self {
+        ThemeColors {
+            accent: DEFAULT_ACCENT.to_string(),
+            start: DEFAULT_START.to_string(),
+            stop: DEFAULT_STOP.to_string(),
+        }
+    }
+}
+
+impl ThemeColors {
+    // Проверка цветов. Возвращает описание ошибки или None
+    pub fn validate(&self) -> Option<String> {
+        [&self.accent, &self.start, &self.stop]
+            .into_iter()
+            .find(|value| parse_hex_color(value).is_none())
+            .map(|value| trf!("Неверный цвет {} (нужно #RRGGBB)", format!("{:?}", value)))
+    }
+
+    // Тема приложения: темная, с цветами из настроек (неверный цвет - цвет по умолчанию)
+    pub fn theme(&self) -> Theme {
+        let color = |value: &str, default: &str| {
+            parse_hex_color(value)
+                .or_else(|| parse_hex_color(default))
+                .unwrap_or(Color::WHITE)
+        };
+        Theme::custom(
+            "TradingStar".to_string(),
+            Palette {
+                primary: color(&self.accent, DEFAULT_ACCENT),
+                success: color(&self.start, DEFAULT_START),
+                danger: color(&self.stop, DEFAULT_STOP),
+                ..Theme::Dark.palette()
+            },
+        )
+    }
+}
+
+// Разбор цвета #RRGGBB (решетка необязательна)
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |range| u8::from_str_radix(&hex[range], 16).ok();
+    Some(Color::from_rgb8(
+        channel(0..2)?,
+        channel(2..4)?,
+        channel(4..6)?,
+    ))
+}
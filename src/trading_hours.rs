@@ -0,0 +1,131 @@
+use crate::i18n::{tr, trf};
+use crate::scheduler::ScheduledAction;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use iced::futures::SinkExt;
+use iced::{subscription, Subscription};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// --- Торговые часы: автоматический запуск и остановка по расписанию ---
+
+// Названия дней недели (с понедельника; переводятся при выводе)
+pub const WEEKDAY_NAMES: [&str; 7] = ["Пн", "Вт", "Ср", "Чт", "Пт", "Сб", "Вс"];
+
+// Как часто проверять часы во время ожидания (на случай сна системы и перевода часов)
+const MAX_SLEEP: Duration = Duration::from_secs(30);
+// Действие, пропущенное больше чем на это время (система спала), не выполняется
+const MAX_LATENESS_MINUTES: i64 = 5;
+
+// Расписание: время запуска и остановки в выбранные дни недели.
+// Каждое действие выполняется в свой день, так что ночной режим (запуск 22:00,
+// остановка 02:00) тоже работает. Пустое время - действие не выполняется.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TradingHours {
+    pub enabled: bool,      // Расписание включено
+    pub start_time: String, // Время запуска (ЧЧ:ММ)
+    pub stop_time: String,  // Время остановки (ЧЧ:ММ)
+    pub days: [bool; 7],    // Активные дни недели (с понедельника)
+}
+
+impl Default for TradingHours {
+    fn default() -> Self {
+        TradingHours {
+            enabled: false,
+            start_time: "09:55".to_string(),
+            stop_time: "23:50".to_string(),
+            days: [true, true, true, true, true, false, false],
+        }
+    }
+}
+
+impl TradingHours {
+    // Проверка расписания. Возвращает описание ошибки или None
+    pub fn validate(&self) -> Option<String> {
+        for (label, value) in [
+            (tr("запуска"), &self.start_time),
+            (tr("остановки"), &self.stop_time),
+        ] {
+            if !value.trim().is_empty() && parse_time(value).is_none() {
+                return Some(trf!(
+                    "Неверное время {}: {} (нужно ЧЧ:ММ)",
+                    label,
+                    format!("{:?}", value)
+                ));
+            }
+        }
+        if self.start_time.trim().is_empty() && self.stop_time.trim().is_empty() {
+            return Some(tr("Не задано ни время запуска, ни время остановки").to_string());
+        }
+        if !self.days.contains(&true) {
+            return Some(tr("Не выбран ни один день недели").to_string());
+        }
+        None
+    }
+
+    // Расписание включено и корректно
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.validate().is_none()
+    }
+
+    // Ближайшее запланированное действие строго после `now`
+    pub fn next_action(&self, now: DateTime<Local>) -> Option<(DateTime<Local>, ScheduledAction)> {
+        let start = parse_time(&self.start_time);
+        let stop = parse_time(&self.stop_time);
+        // Неделя вперед (плюс сегодня) гарантированно содержит ближайшее действие
+        (0..=7)
+            .map(|offset| now.date_naive() + ChronoDuration::days(offset))
+            .filter(|date| self.days[date.weekday().num_days_from_monday() as usize])
+            .flat_map(|date| {
+                let at = |time: Option<NaiveTime>| {
+                    time.and_then(|time| Local.from_local_datetime(&date.and_time(time)).earliest())
+                };
+                [
+                    at(start).map(|time| (time, true)),
+                    at(stop).map(|time| (time, false)),
+                ]
+            })
+            .flatten()
+            .filter(|(time, _)| *time > now)
+            .min_by_key(|(time, _)| *time)
+            .map(|(time, is_start)| {
+                let action = if is_start {
+                    ScheduledAction::Start(trf!("начало торговых часов ({})", time.format("%H:%M")))
+                } else {
+                    ScheduledAction::Stop(trf!(
+                        "окончание торговых часов ({})",
+                        time.format("%H:%M")
+                    ))
+                };
+                (time, action)
+            })
+    }
+}
+
+// Разбор времени ЧЧ:ММ
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+// Подписка, которая ждет ближайшего действия расписания и выдает его в момент наступления.
+// При изменении расписания подписка пересоздается (расписание входит в ее идентификатор).
+pub fn trading_hours_subscription(schedule: TradingHours) -> Subscription<ScheduledAction> {
+    subscription::channel(schedule.clone(), 10, move |mut output| async move {
+        loop {
+            let Some((at, action)) = schedule.next_action(Local::now()) else {
+                return iced::futures::future::pending().await;
+            };
+            // Ждем короткими отрезками: монотонные таймеры не учитывают сон системы
+            loop {
+                let remaining = (at - Local::now()).to_std().unwrap_or_default();
+                if remaining.is_zero() {
+                    break;
+                }
+                tokio::time::sleep(remaining.min(MAX_SLEEP)).await;
+            }
+            if Local::now() - at <= ChronoDuration::minutes(MAX_LATENESS_MINUTES) {
+                let _ = output.send(action).await;
+            }
+        }
+    })
+}
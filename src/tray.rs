@@ -0,0 +1,350 @@
+use iced::Subscription;
+use tokio::sync::mpsc;
+
+// --- Значок в области уведомлений ---
+// Лаунчер работает круглосуточно: окно можно убрать с панели задач, а запуском,
+// остановкой и выходом управлять из меню значка. В Windows значок создает tray-icon
+// (в отдельном потоке со своим циклом сообщений), в Linux - ksni (StatusNotifierItem
+// через D-Bus, без GTK). На остальных системах значка нет.
+
+// Есть ли значок в области уведомлений на этой системе
+pub const TRAY_SUPPORTED: bool = cfg!(any(windows, target_os = "linux"));
+
+// Команда из меню значка
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    Start, // Запустить процесс выбранного экземпляра
+    Stop,  // Остановить процесс выбранного экземпляра
+    Show,  // Показать окно лаунчера
+    Quit,  // Выйти из лаунчера (с остановкой процессов)
+}
+
+// Состояние, которое показывает значок
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrayState {
+    pub running: bool,   // Процесс выбранного экземпляра запущен
+    pub tooltip: String, // Подсказка значка
+}
+
+// Канал для обновления состояния значка
+#[derive(Debug, Clone)]
+pub struct TrayHandle(mpsc::UnboundedSender<TrayState>);
+
+impl TrayHandle {
+    pub fn update(&self, state: TrayState) {
+        let _ = self.0.send(state);
+    }
+}
+
+// События значка
+#[derive(Debug, Clone)]
+pub enum TrayEvent {
+    Ready(TrayHandle),  // Значок создан
+    Action(TrayAction), // Выбран пункт меню (или двойной щелчок по значку)
+}
+
+// Ключ подписки
+#[cfg(any(windows, target_os = "linux"))]
+struct TrayIcon;
+
+// Подписка создает значок и передает его события
+#[cfg(any(windows, target_os = "linux"))]
+pub fn tray_subscription() -> Subscription<TrayEvent> {
+    use iced::futures::SinkExt;
+
+    iced::subscription::channel(
+        std::any::TypeId::of::<TrayIcon>(),
+        8,
+        |mut output| async move {
+            let (state_sender, states) = mpsc::unbounded_channel();
+            let (action_sender, mut actions) = mpsc::unbounded_channel();
+            // Значок живет, пока работает подписка (пока есть получатель действий)
+            if let Err(e) = platform::spawn_tray(states, action_sender).await {
+                eprintln!("Не удалось создать значок в области уведомлений: {}", e);
+                return iced::futures::future::pending().await;
+            }
+            let _ = output
+                .send(TrayEvent::Ready(TrayHandle(state_sender)))
+                .await;
+            while let Some(action) = actions.recv().await {
+                let _ = output.send(TrayEvent::Action(action)).await;
+            }
+            iced::futures::future::pending().await
+        },
+    )
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn tray_subscription() -> Subscription<TrayEvent> {
+    Subscription::none()
+}
+
+// Иконка значка (RGBA): у запущенного процесса - с зеленой меткой
+#[cfg(any(windows, target_os = "linux"))]
+fn tray_icon_rgba(running: bool) -> Option<image::RgbaImage> {
+    crate::app_icon_rgba(running.then_some(crate::highlight::HighlightColor::Green))
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+const MENU_START: &str = "Запустить";
+#[cfg(any(windows, target_os = "linux"))]
+const MENU_STOP: &str = "Остановить";
+#[cfg(any(windows, target_os = "linux"))]
+const MENU_SHOW: &str = "Показать окно";
+#[cfg(any(windows, target_os = "linux"))]
+const MENU_QUIT: &str = "Выход";
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{
+        tray_icon_rgba, TrayAction, TrayState, MENU_QUIT, MENU_SHOW, MENU_START, MENU_STOP,
+    };
+    use crate::i18n::tr;
+    use ksni::{menu::StandardItem, MenuItem, TrayMethods};
+    use tokio::sync::mpsc;
+
+    struct LauncherTray {
+        state: TrayState,
+        actions: mpsc::UnboundedSender<TrayAction>,
+        icons: [Vec<ksni::Icon>; 2], // Иконки остановленного и запущенного процесса
+    }
+
+    impl LauncherTray {
+        fn item(&self, label: &str, enabled: bool, action: TrayAction) -> MenuItem<Self> {
+            StandardItem {
+                label: label.to_string(),
+                enabled,
+                activate: Box::new(move |tray: &mut Self| {
+                    let _ = tray.actions.send(action);
+                }),
+                ..Default::default()
+            }
+            .into()
+        }
+    }
+
+    impl ksni::Tray for LauncherTray {
+        fn id(&self) -> String {
+            env!("CARGO_PKG_NAME").into()
+        }
+
+        fn title(&self) -> String {
+            self.state.tooltip.clone()
+        }
+
+        fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+            self.icons[usize::from(self.state.running)].clone()
+        }
+
+        fn tool_tip(&self) -> ksni::ToolTip {
+            ksni::ToolTip {
+                title: self.state.tooltip.clone(),
+                ..Default::default()
+            }
+        }
+
+        // Щелчок по значку показывает окно
+        fn activate(&mut self, _x: i32, _y: i32) {
+            let _ = self.actions.send(TrayAction::Show);
+        }
+
+        fn menu(&self) -> Vec<MenuItem<Self>> {
+            vec![
+                self.item(tr(MENU_START), !self.state.running, TrayAction::Start),
+                self.item(tr(MENU_STOP), self.state.running, TrayAction::Stop),
+                MenuItem::Separator,
+                self.item(tr(MENU_SHOW), true, TrayAction::Show),
+                self.item(tr(MENU_QUIT), true, TrayAction::Quit),
+            ]
+        }
+    }
+
+    // ksni ожидает ARGB32 в сетевом порядке байт
+    fn pixmap(running: bool) -> Vec<ksni::Icon> {
+        let Some(image) = tray_icon_rgba(running) else {
+            return Vec::new();
+        };
+        let (width, height) = image.dimensions();
+        let data = image
+            .pixels()
+            .flat_map(|pixel| {
+                let [r, g, b, a] = pixel.0;
+                [a, r, g, b]
+            })
+            .collect();
+        vec![ksni::Icon {
+            width: width as i32,
+            height: height as i32,
+            data,
+        }]
+    }
+
+    pub async fn spawn_tray(
+        mut states: mpsc::UnboundedReceiver<TrayState>,
+        actions: mpsc::UnboundedSender<TrayAction>,
+    ) -> Result<(), String> {
+        let tray = LauncherTray {
+            state: TrayState {
+                running: false,
+                tooltip: env!("CARGO_PKG_NAME").into(),
+            },
+            actions,
+            icons: [pixmap(false), pixmap(true)],
+        };
+        let handle = tray.spawn().await.map_err(|e| e.to_string())?;
+        tokio::spawn(async move {
+            while let Some(state) = states.recv().await {
+                handle.update(|tray| tray.state = state).await;
+            }
+            handle.shutdown().await;
+        });
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{
+        tray_icon_rgba, TrayAction, TrayState, MENU_QUIT, MENU_SHOW, MENU_START, MENU_STOP,
+    };
+    use crate::i18n::tr;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+    use tray_icon::{Icon, TrayIcon, TrayIconBuilder, TrayIconEvent};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DispatchMessageW, PeekMessageW, TranslateMessage, MSG, PM_REMOVE,
+    };
+
+    // Как часто поток значка проверяет сообщения и новое состояние
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    fn icon(running: bool) -> Option<Icon> {
+        let image = tray_icon_rgba(running)?;
+        let (width, height) = image.dimensions();
+        Icon::from_rgba(image.into_raw(), width, height).ok()
+    }
+
+    // Значок и меню
+    struct Tray {
+        icon: TrayIcon,
+        start: MenuItem,
+        stop: MenuItem,
+        show: MenuItem,
+        quit: MenuItem,
+        icons: [Option<Icon>; 2], // Иконки остановленного и запущенного процесса
+    }
+
+    impl Tray {
+        fn new() -> Result<Tray, String> {
+            let start = MenuItem::new(tr(MENU_START), true, None);
+            let stop = MenuItem::new(tr(MENU_STOP), false, None);
+            let show = MenuItem::new(tr(MENU_SHOW), true, None);
+            let quit = MenuItem::new(tr(MENU_QUIT), true, None);
+            let menu = Menu::new();
+            menu.append_items(&[
+                &start,
+                &stop,
+                &PredefinedMenuItem::separator(),
+                &show,
+                &quit,
+            ])
+            .map_err(|e| e.to_string())?;
+            let icons = [icon(false), icon(true)];
+            let mut builder = TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_tooltip(env!("CARGO_PKG_NAME"));
+            if let Some(icon) = icons[0].clone() {
+                builder = builder.with_icon(icon);
+            }
+            let icon = builder.build().map_err(|e| e.to_string())?;
+            Ok(Tray {
+                icon,
+                start,
+                stop,
+                show,
+                quit,
+                icons,
+            })
+        }
+
+        fn set_state(&self, state: &TrayState) {
+            // Язык интерфейса мог смениться
+            for (item, label) in [
+                (&self.start, MENU_START),
+                (&self.stop, MENU_STOP),
+                (&self.show, MENU_SHOW),
+                (&self.quit, MENU_QUIT),
+            ] {
+                item.set_text(tr(label));
+            }
+            self.start.set_enabled(!state.running);
+            self.stop.set_enabled(state.running);
+            let _ = self.icon.set_tooltip(Some(&state.tooltip));
+            let _ = self
+                .icon
+                .set_icon(self.icons[usize::from(state.running)].clone());
+        }
+
+        fn action(&self, event: &MenuEvent) -> Option<TrayAction> {
+            [
+                (&self.start, TrayAction::Start),
+                (&self.stop, TrayAction::Stop),
+                (&self.show, TrayAction::Show),
+                (&self.quit, TrayAction::Quit),
+            ]
+            .into_iter()
+            .find(|(item, _)| item.id() == event.id())
+            .map(|(_, action)| action)
+        }
+    }
+
+    // Значок создается в отдельном потоке: его окно получает сообщения в потоке,
+    // где создано, а цикл сообщений iced о нем не знает
+    pub async fn spawn_tray(
+        mut states: mpsc::UnboundedReceiver<TrayState>,
+        actions: mpsc::UnboundedSender<TrayAction>,
+    ) -> Result<(), String> {
+        let (ready_sender, ready) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            let tray = match Tray::new() {
+                Ok(tray) => {
+                    let _ = ready_sender.send(Ok(()));
+                    tray
+                }
+                Err(e) => {
+                    let _ = ready_sender.send(Err(e));
+                    return;
+                }
+            };
+            // Поток работает, пока подписка принимает действия
+            while !actions.is_closed() {
+                // SAFETY: MSG - простая структура, заполняется PeekMessageW
+                unsafe {
+                    let mut message: MSG = std::mem::zeroed();
+                    while PeekMessageW(&mut message, std::ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
+                        TranslateMessage(&message);
+                        DispatchMessageW(&message);
+                    }
+                }
+                while let Ok(state) = states.try_recv() {
+                    tray.set_state(&state);
+                }
+                while let Ok(event) = MenuEvent::receiver().try_recv() {
+                    if let Some(action) = tray.action(&event) {
+                        let _ = actions.send(action);
+                    }
+                }
+                while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+                    if matches!(event, TrayIconEvent::DoubleClick { .. }) {
+                        let _ = actions.send(TrayAction::Show);
+                    }
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+        ready
+            .await
+            .map_err(|_| tr("поток значка завершился").to_string())?
+    }
+}
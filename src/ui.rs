@@ -1,23 +1,462 @@
-use crate::settings::AppSettings; // Используем AppSettings напрямую
+use crate::alerts::{AlertRule, Comparison};
+use crate::chart::{TimelineChart, UptimeChart, TIMELINE_HEIGHT};
+use crate::encoding::OutputEncoding;
+use crate::escapes::{escape_raw, parse_pieces, EraseLine, Piece};
+use crate::events::LifecycleEvent;
+use crate::forwarder::ForwardFormat;
+use crate::health::{validate_target, HealthState};
+use crate::highlight::{HighlightColor, HighlightRule, HighlightStyle, Highlighter};
+use crate::history::{ExportFormat, RunOutcome, RunRecord};
+use crate::i18n::{tr, trf, Language};
+use crate::json_log::{parse_json_line, pretty_payload, JsonRecord};
+use crate::keyword_alerts::KeywordAlertRule;
+use crate::links::{find_urls, split_hyperlinks};
+use crate::metrics::{MetricRule, MetricValue};
+use crate::notifications::{Notification, NotificationAction, NotificationLevel};
+use crate::placeholders::PLACEHOLDERS_HELP;
+use crate::power::PowerState;
+use crate::process::{ProcessCandidate, ProcessUsage};
+use crate::scheduler::{MaintenanceWindow, ScheduledAction};
+use crate::settings::{
+    time_format_problem, AppSettings, BatteryAction, EnvVar, InstanceSettings, SettingsBackup,
+    DEFAULT_LOG_FONT_SIZE, DEFAULT_LOG_TIME_FORMAT, LOG_FONT_SIZE_MAX, LOG_FONT_SIZE_MIN,
+    LOG_LINES_MAX, LOG_LINES_MIN,
+}; // Используем AppSettings напрямую
+use crate::theme_colors::{parse_hex_color, ThemeColors};
+use crate::trading_hours::{TradingHours, WEEKDAY_NAMES};
 use crate::Message; // Импортируем Message из корневого модуля
-use ansi_parser::{AnsiParser, AnsiSequence, Output};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
 use iced::widget::{
-    button, column, container, row, scrollable, text, text_input, Button, Column, Container, Row,
-    Scrollable, Space, Text, TextInput,
+    button, canvas, checkbox, column, container, pick_list, row, scrollable, text, text_input,
+    Column, Row, Scrollable, Space, Text,
 };
-use iced::{theme, Alignment, Background, Border, Color, Element, Font, Length, Theme};
+use iced::{font, theme, Alignment, Background, Border, Color, Element, Font, Length, Theme};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::VecDeque;
+use std::path::Path;
 
 // --- Константы для UI ---
-pub const MAX_LOG_LINES: usize = 500; // Максимальное количество строк лога
+const RECENT_RUNS: usize = 50; // Сколько последних запусков показывать в статистике
 pub const BUTTON_TEXT_COLOR: Color = Color::WHITE; // Цвет текста на кнопках
+const LINK_COLOR: Color = Color::from_rgb(0.35, 0.65, 1.0); // Цвет ссылок в логе
+const INVERSE_TEXT_COLOR: Color = Color::from_rgb(0.13, 0.13, 0.13); // Текст инверсного сегмента без фона
+const GUTTER_COLOR: Color = Color::from_rgb(0.45, 0.45, 0.45); // Номера строк и метки потоков
+const JSON_EXPAND_WIDTH: f32 = 14.0; // Ширина кнопки раскрытия строки JSON
+const STDERR_COLOR: Color = Color::from_rgb(1.0, 0.45, 0.45); // Строки потока ошибок без своего цвета
 
 // --- Структура для сегмента ANSI ---
-// Представляет собой часть строки лога с определенным цветом
+// Представляет собой часть строки лога с определенным цветом и начертанием
 #[derive(Debug, Clone, PartialEq)]
 pub struct AnsiSegment {
-    pub text: String,         // Текст сегмента
-    pub color: Option<Color>, // Цвет текста (None для цвета по умолчанию)
+    pub text: String,              // Текст сегмента
+    pub color: Option<Color>,      // Цвет текста (None для цвета по умолчанию)
+    pub background: Option<Color>, // Цвет фона (None - без фона)
+    pub bold: bool,                // Жирный (SGR 1)
+    pub italic: bool,              // Курсив (SGR 3)
+    pub underline: bool,           // Подчеркивание (SGR 4)
+    pub link: Option<String>,      // Адрес, если сегмент - ссылка (OSC 8 или http(s) в тексте)
+}
+
+impl AnsiSegment {
+    // Сегмент с полной последовательностью SGR (и OSC 8, если это ссылка)
+    fn to_ansi(&self) -> String {
+        let rgb = |color: Color| {
+            let [r, g, b, _] = color.into_rgba8();
+            format!("{};{};{}", r, g, b)
+        };
+        let mut codes = vec!["0".to_string()];
+        for (enabled, code) in [(self.bold, "1"), (self.italic, "3"), (self.underline, "4")] {
+            if enabled {
+                codes.push(code.to_string());
+            }
+        }
+        if let Some(color) = self.color {
+            codes.push(format!("38;2;{}", rgb(color)));
+        }
+        if let Some(background) = self.background {
+            codes.push(format!("48;2;{}", rgb(background)));
+        }
+        let styled = format!("\u{1b}[{}m{}", codes.join(";"), self.text);
+        match &self.link {
+            Some(url) => format!("\u{1b}]8;;{}\u{1b}\\{}\u{1b}]8;;\u{1b}\\", url, styled),
+            None => styled,
+        }
+    }
+}
+
+// Текущие атрибуты SGR при разборе строки
+#[derive(Debug, Clone, Copy, Default)]
+struct SgrState {
+    color: Option<Color>,
+    background: Option<Color>,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool, // Инверсия (SGR 7): цвета текста и фона меняются местами
+}
+
+impl SgrState {
+    // Сегмент с текущими атрибутами (инверсия применяется здесь)
+    fn segment(&self, text: String, link: Option<String>) -> AnsiSegment {
+        let (color, background) = if self.inverse {
+            (
+                Some(self.background.unwrap_or(INVERSE_TEXT_COLOR)),
+                Some(self.color.unwrap_or(Color::WHITE)),
+            )
+        } else {
+            (self.color, self.background)
+        };
+        AnsiSegment {
+            text,
+            color,
+            background,
+            bold: self.bold,
+            italic: self.italic,
+            underline: self.underline,
+            link,
+        }
+    }
+}
+
+// Разбиение сегмента без ссылки по найденным в тексте адресам http(s)
+fn split_urls(segment: AnsiSegment) -> Vec<AnsiSegment> {
+    let urls = if segment.link.is_none() {
+        find_urls(&segment.text)
+    } else {
+        Vec::new()
+    };
+    if urls.is_empty() {
+        return vec![segment];
+    }
+    let mut parts = Vec::new();
+    let mut position = 0;
+    for url in urls {
+        if url.start > position {
+            parts.push(AnsiSegment {
+                text: segment.text[position..url.start].to_string(),
+                ..segment.clone()
+            });
+        }
+        parts.push(AnsiSegment {
+            text: segment.text[url.clone()].to_string(),
+            link: Some(segment.text[url.clone()].to_string()),
+            ..segment.clone()
+        });
+        position = url.end;
+    }
+    if position < segment.text.len() {
+        parts.push(AnsiSegment {
+            text: segment.text[position..].to_string(),
+            ..segment
+        });
+    }
+    parts
+}
+
+// --- Уровень строки лога ---
+// Определяется по слову уровня в строке TradingStar (ERROR, WARN, INFO, DEBUG)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+    ];
+
+    // Первое слово уровня в строке (только заглавными, чтобы не путать с обычным текстом)
+    pub fn detect(line: &str) -> Option<LogLevel> {
+        line.split(|c: char| !c.is_ascii_alphabetic())
+            .find_map(|word| match word {
+                "ERROR" | "ERR" | "FATAL" | "CRITICAL" => Some(LogLevel::Error),
+                "WARN" | "WARNING" => Some(LogLevel::Warn),
+                "INFO" => Some(LogLevel::Info),
+                "DEBUG" | "TRACE" => Some(LogLevel::Debug),
+                _ => None,
+            })
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+        })
+    }
+}
+
+// --- Источник строки лога ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogSource {
+    #[default]
+    Launcher, // Сообщение самого лаунчера
+    Stdout, // Стандартный вывод процесса
+    Stderr, // Поток ошибок процесса
+}
+
+impl LogSource {
+    // Пометка перед строкой при копировании и записи в файл (поток ошибок выделяется)
+    pub fn text_prefix(self) -> &'static str {
+        match self {
+            LogSource::Stderr => "STDERR: ",
+            LogSource::Launcher | LogSource::Stdout => "",
+        }
+    }
+}
+
+// --- Фильтр лога по потоку вывода ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LogStreamFilter {
+    #[default]
+    Both, // stdout и stderr
+    Stdout, // Только stdout (и сообщения лаунчера)
+    Stderr, // Только stderr
+}
+
+impl LogStreamFilter {
+    pub const ALL: [LogStreamFilter; 3] = [
+        LogStreamFilter::Both,
+        LogStreamFilter::Stdout,
+        LogStreamFilter::Stderr,
+    ];
+
+    // Показываются ли строки из источника `source`
+    pub fn shows(self, source: LogSource) -> bool {
+        match self {
+            LogStreamFilter::Both => true,
+            LogStreamFilter::Stdout => source != LogSource::Stderr,
+            LogStreamFilter::Stderr => source == LogSource::Stderr,
+        }
+    }
+}
+
+impl std::fmt::Display for LogStreamFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LogStreamFilter::Both => tr("stdout и stderr"),
+            LogStreamFilter::Stdout => tr("только stdout"),
+            LogStreamFilter::Stderr => tr("только stderr"),
+        })
+    }
+}
+
+// --- Строка лога ---
+// Распарсенная строка с меткой времени получения
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub id: u64,                    // Идентификатор строки (для перехода из хронологии)
+    pub number: u64,                // Номер строки с начала запуска (для ссылок на строку)
+    pub time: DateTime<Local>,      // Когда строка была получена
+    pub segments: Vec<AnsiSegment>, // Сегменты строки с цветами
+    pub level: Option<LogLevel>,    // Уровень строки (None - не определен)
+    pub source: LogSource,          // Откуда пришла строка (лаунчер, stdout, stderr)
+    pub json: Option<JsonRecord>,   // Колонки строки, если строка - объект JSON
+    pub raw: String,                // Строка как получена (с управляющими последовательностями)
+}
+
+impl LogLine {
+    // Текст строки без цветов
+    pub fn plain_text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+
+    // Текст строки с пометкой потока ошибок (для копирования и записи в файл)
+    pub fn labeled_text(&self) -> String {
+        format!("{}{}", self.source.text_prefix(), self.plain_text())
+    }
+
+    // Показывается ли строка при скрытых уровнях `hidden` (строки без уровня видны всегда)
+    // и выбранном потоке вывода `stream`
+    pub fn is_visible(&self, hidden: &[LogLevel], stream: LogStreamFilter) -> bool {
+        self.level.is_none_or(|level| !hidden.contains(&level)) && stream.shows(self.source)
+    }
+
+    // Текст строки с управляющими последовательностями ANSI, восстановленными по сегментам
+    pub fn ansi_text(&self) -> String {
+        let mut result = self.source.text_prefix().to_string();
+        result.extend(self.segments.iter().map(AnsiSegment::to_ansi));
+        result.push_str("\u{1b}[0m");
+        result
+    }
+
+    // Содержит ли строка искомый текст (без учета регистра, `query` - в нижнем регистре)
+    fn matches_query(&self, query: &str) -> bool {
+        !query.is_empty() && self.plain_text().to_lowercase().contains(query)
+    }
+}
+
+// Идентификаторы видимых строк, содержащих искомый текст, в порядке отображения
+// (от новых к старым)
+pub fn log_search_matches(
+    logs: &VecDeque<LogLine>,
+    query: &str,
+    hidden_levels: &[LogLevel],
+    stream: LogStreamFilter,
+) -> Vec<u64> {
+    let query = query.trim().to_lowercase();
+    logs.iter()
+        .rev()
+        .filter(|line| line.is_visible(hidden_levels, stream) && line.matches_query(&query))
+        .map(|line| line.id)
+        .collect()
+}
+
+// --- Фильтр лога по времени ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LogTimeFilter {
+    #[default]
+    All, // Весь лог
+    Last5Minutes, // Последние 5 минут
+    LastHour,     // Последний час
+    Custom,       // Произвольный интервал
+}
+
+impl LogTimeFilter {
+    pub const ALL: [LogTimeFilter; 4] = [
+        LogTimeFilter::All,
+        LogTimeFilter::Last5Minutes,
+        LogTimeFilter::LastHour,
+        LogTimeFilter::Custom,
+    ];
+}
+
+impl std::fmt::Display for LogTimeFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LogTimeFilter::All => tr("Весь лог"),
+            LogTimeFilter::Last5Minutes => tr("Последние 5 минут"),
+            LogTimeFilter::LastHour => tr("Последний час"),
+            LogTimeFilter::Custom => tr("Интервал..."),
+        })
+    }
+}
+
+// --- Разделы экрана настроек ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SettingsSection {
+    #[default]
+    Process, // Запуск процесса, ключи, экземпляры, расписание
+    Logging,       // Логи: хранение, отображение, пересылка, подсветка
+    Notifications, // Метрики и оповещения
+    Advanced,      // Часы, блокировка, диагностика, копии и шифрование настроек
+}
+
+impl SettingsSection {
+    pub const ALL: [SettingsSection; 4] = [
+        SettingsSection::Process,
+        SettingsSection::Logging,
+        SettingsSection::Notifications,
+        SettingsSection::Advanced,
+    ];
+}
+
+impl std::fmt::Display for SettingsSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SettingsSection::Process => tr("Процесс"),
+            SettingsSection::Logging => tr("Логи"),
+            SettingsSection::Notifications => tr("Оповещения"),
+            SettingsSection::Advanced => tr("Дополнительно"),
+        })
+    }
+}
+
+// Идентификатор прокрутки окна лога (для перехода к строке)
+pub fn log_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("log")
+}
+
+// Состояние окна лога для отрисовки: фильтр и выделенная строка
+pub struct LogViewState<'a> {
+    pub filter: LogTimeFilter,                   // Выбранный фильтр
+    pub custom_from: &'a str,                    // Начало произвольного интервала (как введено)
+    pub custom_to: &'a str,                      // Конец произвольного интервала (как введено)
+    pub session_file: Option<&'a Path>,          // Файл с полным логом сессии
+    pub highlighted_line: Option<u64>, // Строка, к которой перешли из хронологии или поиска
+    pub search: &'a str,               // Искомый текст
+    pub search_position: Option<(usize, usize)>, // Текущее совпадение (с 1) и число совпадений
+    pub hidden_levels: &'a [LogLevel], // Скрытые уровни строк
+    pub stream: LogStreamFilter,       // Показываемые потоки вывода
+    pub clear_pending: bool,           // Очистка лога ожидает подтверждения
+    pub json_view: bool,               // Строки JSON показываются таблицей
+    pub raw_view: bool,                // Рядом со строкой показывается ее исходный текст
+    pub expanded: &'a [u64],           // Строки JSON с раскрытым исходным объектом
+    pub bookmarks: &'a [u64],          // Строки с закладками (ID, по возрастанию)
+    pub highlighter: &'a Highlighter,  // Правила подсветки строк
+    pub frozen_at: Option<u64>,        // Лог на паузе: последняя показываемая строка
+    pub selection: Option<(u64, u64)>, // Выделенные строки (ID первой и последней)
+    pub console_input: Option<&'a str>, // Вводимая команда консоли (None - процесс не принимает ввод)
+}
+
+// Состояние экрана настроек, не входящее в AppSettings
+pub struct SettingsExtras<'a> {
+    pub section: SettingsSection, // Показанный раздел настроек
+    pub reset_pending: bool,      // Сброс настроек ожидает подтверждения
+    // Ошибки компиляции пользовательских правил (по индексу правила, None - правило корректно)
+    pub metric_rule_errors: &'a [Option<String>], // Правила метрик
+    pub highlight_rule_errors: &'a [Option<String>], // Правила подсветки
+    pub api_key_revealed: bool,                   // Ключ API показан открытым текстом
+    pub executable_problem: Option<&'a str>,      // Почему исполняемый файл нельзя запустить
+    pub settings_backups: &'a [SettingsBackup],   // Резервные копии настроек (от новых к старым)
+    pub selected_settings_backup: Option<&'a SettingsBackup>, // Копия, выбранная для восстановления
+    pub cli_overrides: Vec<&'static str>,         // Настройки, заданные в командной строке
+    pub encryption: EncryptionState<'a>,          // Шифрование файла настроек
+}
+
+// Состояние шифрования файла настроек для экрана настроек
+pub struct EncryptionState<'a> {
+    pub enabled: bool,                 // Файл настроек зашифрован
+    pub new_password: &'a str,         // Новый пароль
+    pub new_password_confirm: &'a str, // Повтор нового пароля
+    pub pending: bool,                 // Идет вывод ключа из пароля
+}
+
+// Разбор границы интервала: "ЧЧ:ММ" (сегодня), "ДД.ММ ЧЧ:ММ" (текущий год) или
+// "ДД.ММ.ГГГГ ЧЧ:ММ"; секунды (":СС") допускаются. Пустая строка - граница не задана.
+pub fn parse_filter_time(
+    value: &str,
+    now: DateTime<Local>,
+) -> Result<Option<DateTime<Local>>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let parse_time = |time: &str| {
+        NaiveTime::parse_from_str(time, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+            .ok()
+    };
+    let naive = match value.split_once(' ') {
+        None => parse_time(value).map(|time| now.date_naive().and_time(time)),
+        Some((date, time)) => {
+            let date = NaiveDate::parse_from_str(date, "%d.%m.%Y")
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(&format!("{}.{}", date, now.format("%Y")), "%d.%m.%Y")
+                        .ok()
+                });
+            date.zip(parse_time(time.trim()))
+                .map(|(date, time)| date.and_time(time))
+        }
+    };
+    naive
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(Some)
+        .ok_or_else(|| trf!("Не удалось разобрать время \"{}\"", value))
 }
 
 // --- Логика обработки и добавления логов ---
@@ -51,85 +490,263 @@ fn ansi_to_iced_color(code: u8) -> Color {
     }
 }
 
+// Разбор параметров расширенного цвета (после 38/48): "5;N" или "2;R;G;B".
+// Возвращает цвет (None - параметры неполные или неизвестный режим) и оставшиеся коды
+fn extended_color(codes: &[u8]) -> (Option<Color>, &[u8]) {
+    match codes {
+        [5, index, rest @ ..] => (Some(ansi_256_color(*index)), rest),
+        [2, r, g, b, rest @ ..] => (Some(Color::from_rgb8(*r, *g, *b)), rest),
+        [_, rest @ ..] => (None, rest),
+        [] => (None, codes),
+    }
+}
+
+// Цвет палитры 256 цветов: 0-15 - стандартные и яркие, 16-231 - куб 6x6x6, 232-255 - оттенки серого
+fn ansi_256_color(index: u8) -> Color {
+    // https://en.wikipedia.org/wiki/ANSI_escape_code#8-bit
+    const CUBE_LEVELS: [u8; 6] = [0x00, 0x5F, 0x87, 0xAF, 0xD7, 0xFF];
+    match index {
+        0..=7 => ansi_to_iced_color(30 + index),
+        8..=15 => ansi_to_iced_color(90 + index - 8),
+        16..=231 => {
+            let cube = index - 16;
+            Color::from_rgb8(
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let level = 8 + (index - 232) * 10;
+            Color::from_rgb8(level, level, level)
+        }
+    }
+}
+
 // Реализация добавления и парсинга лога
-pub fn add_log_impl(logs: &mut VecDeque<Vec<AnsiSegment>>, message: String) {
+pub fn add_log_impl(
+    logs: &mut VecDeque<LogLine>,
+    max_lines: usize,
+    id: u64,
+    number: u64,
+    time: DateTime<Local>,
+    source: LogSource,
+    message: String,
+) {
+    // Добавляем распарсенную строку в очередь логов, если она не пустая
+    if let Some(line) = parse_log_line(id, number, time, source, &message) {
+        // Ограничиваем максимальное количество строк (лимит мог уменьшиться в настройках)
+        while logs.len() >= max_lines.max(1) {
+            logs.pop_front();
+        }
+        logs.push_back(line);
+    }
+}
+
+// Замена содержимого строки лога `id` (обновление строки прогресса)
+pub fn replace_log_line(
+    logs: &mut VecDeque<LogLine>,
+    id: u64,
+    time: DateTime<Local>,
+    source: LogSource,
+    message: String,
+) {
+    if let Some(position) = logs.iter().rposition(|line| line.id == id) {
+        // Номер строки сохраняется: строка прогресса остается на своем месте
+        let number = logs[position].number;
+        if let Some(line) = parse_log_line(id, number, time, source, &message) {
+            logs[position] = line;
+        }
+    }
+}
+
+// Разбор строки с ANSI-последовательностями в сегменты (None - строка пустая)
+fn parse_log_line(
+    id: u64,
+    number: u64,
+    time: DateTime<Local>,
+    source: LogSource,
+    message: &str,
+) -> Option<LogLine> {
     let mut segments = Vec::new(); // Вектор для хранения сегментов текущей строки
-    let mut current_color: Option<Color> = None; // Текущий цвет текста
+    let mut current_style = SgrState::default(); // Текущие цвета и атрибуты текста
     let mut current_text = String::new(); // Текущий накапливаемый текст
+    let mut current_link: Option<String> = None; // Текущая гиперссылка OSC 8
 
-    // Парсим строку с помощью ansi_parser
-    for block in message.ansi_parse() {
-        match block {
-            // Если это текстовый блок, добавляем его к текущему тексту
-            Output::TextBlock(text) => {
-                current_text.push_str(text);
+    // Гиперссылки OSC 8 делят строку на части, каждую парсим с помощью ansi_parser
+    for (chunk, link) in split_hyperlinks(message) {
+        if link != current_link {
+            if !current_text.is_empty() {
+                segments
+                    .push(current_style.segment(std::mem::take(&mut current_text), current_link));
             }
-            // Если это управляющая последовательность ANSI
-            Output::Escape(sequence) => {
-                // Нас интересует только SetGraphicsMode (SGR) для установки стилей/цветов
-                if let AnsiSequence::SetGraphicsMode(codes) = sequence {
-                    // Перед изменением цвета сохраняем предыдущий сегмент, если он был
+            current_link = link;
+        }
+        for piece in parse_pieces(chunk) {
+            match piece {
+                // Если это текстовый блок, добавляем его к текущему тексту
+                Piece::Text(text) => {
+                    current_text.push_str(text);
+                }
+                // Стирание до начала или всей строки: выведенное ранее пропадает.
+                // Курсор всегда в конце выведенного текста, поэтому стирание до конца ничего не меняет
+                Piece::EraseLine(EraseLine::ToStart | EraseLine::All) => {
+                    segments.clear();
+                    current_text.clear();
+                }
+                Piece::EraseLine(EraseLine::ToEnd) => {}
+                // SGR - установка стилей/цветов
+                Piece::Sgr(codes) => {
+                    // Перед изменением стиля сохраняем предыдущий сегмент, если он был
                     if !current_text.is_empty() {
-                        segments.push(AnsiSegment {
-                            text: std::mem::take(&mut current_text),
-                            color: current_color,
-                        });
+                        segments.push(
+                            current_style
+                                .segment(std::mem::take(&mut current_text), current_link.clone()),
+                        );
                     }
 
                     // Обрабатываем коды SGR
                     if codes.is_empty() {
                         // `ESC[m` (пустой код) - сброс всех атрибутов
-                        current_color = None;
+                        current_style = SgrState::default();
                     } else {
-                        for code in codes {
+                        let mut rest = &codes[..];
+                        while let Some((&code, tail)) = rest.split_first() {
+                            rest = tail;
                             match code {
                                 // Код 0 - сброс
-                                0 => current_color = None,
+                                0 => current_style = SgrState::default(),
+                                // Атрибуты начертания и их сброс
+                                1 => current_style.bold = true,
+                                3 => current_style.italic = true,
+                                4 => current_style.underline = true,
+                                7 => current_style.inverse = true,
+                                22 => current_style.bold = false,
+                                23 => current_style.italic = false,
+                                24 => current_style.underline = false,
+                                27 => current_style.inverse = false,
                                 // Коды цвета переднего плана (30-37, 90-97)
                                 c @ 30..=37 | c @ 90..=97 => {
-                                    current_color = Some(ansi_to_iced_color(c));
+                                    current_style.color = Some(ansi_to_iced_color(c));
+                                }
+                                // Коды цвета фона (40-47, 100-107) - те же цвета со сдвигом 10
+                                c @ 40..=47 | c @ 100..=107 => {
+                                    current_style.background = Some(ansi_to_iced_color(c - 10));
                                 }
-                                // Код 39 - сброс цвета переднего плана по умолчанию
-                                39 => current_color = None,
-                                // Пока игнорируем цвета фона (40-47, 100-107) и другие атрибуты (жирность, курсив и т.д.)
+                                // Расширенный цвет: 38;5;N (палитра 256) или 38;2;R;G;B, для фона - 48;...
+                                38 | 48 => {
+                                    let (color, tail) = extended_color(rest);
+                                    if color.is_some() {
+                                        if code == 38 {
+                                            current_style.color = color;
+                                        } else {
+                                            current_style.background = color;
+                                        }
+                                    }
+                                    rest = tail;
+                                }
+                                // Коды 39 и 49 - сброс цвета текста и фона по умолчанию
+                                39 => current_style.color = None,
+                                49 => current_style.background = None,
+                                // Остальные атрибуты (мигание, зачеркивание и т.д.) игнорируем
                                 _ => {}
                             }
                         }
                     }
                 }
-                // Игнорируем другие Escape последовательности (перемещение курсора и т.д.)
             }
         }
     }
 
     // Добавляем последний сегмент текста, если он остался
     if !current_text.is_empty() {
-        segments.push(AnsiSegment {
-            text: current_text,
-            color: current_color,
-        });
+        segments.push(current_style.segment(current_text, current_link));
     }
 
-    // Удаляем пустые сегменты, которые могли образоваться (например, из-за `ESC[mESC[31m`)
+    // Удаляем пустые сегменты, которые могли образоваться (например, из-за `ESC[mESC[31m`),
+    // и выделяем адреса http(s) в тексте в отдельные сегменты-ссылки
     segments.retain(|seg| !seg.text.is_empty());
+    let segments: Vec<AnsiSegment> = segments.into_iter().flat_map(split_urls).collect();
 
-    // Добавляем распарсенную строку в очередь логов, если она не пустая
-    if !segments.is_empty() {
-        // Ограничиваем максимальное количество строк
-        if logs.len() >= MAX_LOG_LINES {
-            logs.pop_front();
+    if segments.is_empty() {
+        return None;
+    }
+    let plain: String = segments.iter().map(|s| s.text.as_str()).collect();
+    // Уровень строки JSON берется из ее поля уровня
+    let json = parse_json_line(&plain);
+    let level = json
+        .as_ref()
+        .and_then(|record| record.level.as_deref())
+        .and_then(|level| LogLevel::detect(&level.to_uppercase()))
+        .or_else(|| LogLevel::detect(&plain));
+    Some(LogLine {
+        id,
+        number,
+        time,
+        level,
+        segments,
+        source,
+        json,
+        raw: message.to_string(),
+    })
+}
+
+// Текст строки без управляющих последовательностей ANSI (и гиперссылок OSC 8)
+pub fn strip_ansi(line: &str) -> String {
+    let mut text = String::new();
+    for (chunk, _) in split_hyperlinks(line) {
+        for piece in parse_pieces(chunk) {
+            match piece {
+                Piece::Text(part) => text.push_str(part),
+                Piece::EraseLine(EraseLine::ToStart | EraseLine::All) => text.clear(),
+                Piece::EraseLine(EraseLine::ToEnd) | Piece::Sgr(_) => {}
+            }
         }
-        logs.push_back(segments);
     }
+    text
 }
 
 // --- Функции отрисовки View ---
 
+// Сводка данных мониторинга для главного экрана
+pub struct MonitorState<'a> {
+    pub power_state: Option<PowerState>, // Состояние питания (для предупреждения о батарее)
+    pub next_maintenance: Option<&'a MaintenanceWindow>, // Ближайшее окно обслуживания биржи
+    pub clock_drift_ms: Option<f64>,     // Расхождение часов с NTP, если оно превышает порог
+    pub metrics: Vec<(String, Option<MetricValue>)>, // Метрики в порядке правил и их последние значения
+    pub notifications: &'a [Notification],           // Активные уведомления
+    pub events: &'a [LifecycleEvent],                // События жизненного цикла (для хронологии)
+    pub maintenance_windows: &'a [MaintenanceWindow], // Окна обслуживания (для хронологии)
+    pub launch_changes: Vec<&'static str>, // Измененные параметры запуска (процесс работает со старыми)
+    pub data_dir_error: Option<&'a str>,   // Почему в директорию данных нельзя писать
+    pub executable_problem: Option<&'a str>, // Почему исполняемый файл нельзя запустить
+    pub process_usage: Option<(ProcessUsage, u64)>, // Загрузка процесса и пиковая память (байт)
+    pub silent_minutes: Option<u64>,       // Процесс завис: сколько минут нет вывода
+    pub dropped_lines: Option<u64>, // Вывод недавно превышал лимит частоты: пропущено строк за запуск
+}
+
+// Выбранный экземпляр TradingStar и вкладки всех экземпляров
+pub struct InstanceView<'a> {
+    pub id: u64,                                      // Идентификатор выбранного экземпляра
+    pub tabs: Vec<(String, bool)>,                    // Названия экземпляров и запущены ли они
+    pub selected: usize,                              // Индекс выбранного экземпляра
+    pub is_running: bool,                             // Запущен ли процесс выбранного экземпляра?
+    pub health: Option<HealthState>, // Результат проверок готовности (None - проверка не задана)
+    pub uptime: Option<chrono::Duration>, // Время работы процесса (None - PID еще не получен)
+    pub pid: Option<u32>,            // PID запущенного процесса
+    pub crashed: bool,               // Последний запуск закончился сбоем
+    pub api_key: Cow<'a, str>,       // Ключ API выбранного экземпляра
+    pub orphan_process: Option<&'a ProcessCandidate>, // Процесс прошлого сеанса, ожидающий решения
+}
+
 // Отрисовка основного экрана приложения
 pub fn view_main(
-    is_running: bool,                  // Запущен ли процесс?
-    logs: &VecDeque<Vec<AnsiSegment>>, // Ссылка на логи
-    settings: &AppSettings,            // Ссылка на настройки (для проверки кнопки Start)
+    instance: InstanceView,                       // Выбранный экземпляр и вкладки
+    logs: &VecDeque<LogLine>,                     // Ссылка на логи выбранного экземпляра
+    settings: &AppSettings,                       // Ссылка на настройки (для проверки кнопки Start)
+    kill_candidates: Option<&[ProcessCandidate]>, // Найденные процессы, ожидающие подтверждения
+    log_filter: LogViewState, // Фильтр лога по времени, выделенная строка и консоль
+    monitor: MonitorState,    // Данные мониторинга (питание, часы, метрики...)
 ) -> Element<'static, Message> {
     // 'static lifetime необходим для элементов Iced
 
@@ -137,8 +754,20 @@ pub fn view_main(
     let top_bar_content = row![
         text("TradingStar 3 Launcher").size(20),
         Space::with_width(Length::Fill), // Растягиваем пространство
+        // Выбор профиля запуска основного экземпляра
+        profile_selector(
+            settings,
+            instance.tabs.first().is_some_and(|(_, running)| *running)
+        ),
+        // Кнопка "Заблокировать" (если задан PIN)
+        lock_button(settings.ui_lock_available()),
+        // Кнопка "Статистика"
+        button(text(tr("Статистика")))
+            .padding(10)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::StatsButtonPressed),
         // Кнопка "Настройки"
-        button(text("Настройки"))
+        button(text(tr("Настройки")))
             .padding(10)
             .style(theme::Button::Custom(Box::new(DefaultButtonStyle))) // Используем стиль
             .on_press(Message::SettingsButtonPressed) // Сообщение при нажатии
@@ -152,16 +781,53 @@ pub fn view_main(
         .width(Length::Fill)
         .style(theme::Container::Custom(Box::new(TopBarStyle))); // Используем стиль
 
+    let is_running = instance.is_running;
+
+    // Строка состояния внизу экрана: состояние, PID, время работы и файл лога
+    let status_bar = status_bar(&instance, log_filter.session_file);
+
+    // Вкладки экземпляров (пока экземпляр один, не показываются)
+    let instance_tabs: Element<'static, Message> = if instance.tabs.len() > 1 {
+        let tabs = instance.tabs.into_iter().enumerate().fold(
+            row![].spacing(5),
+            |tabs, (index, (name, running))| {
+                let label = if running {
+                    format!("● {}", name)
+                } else {
+                    name
+                };
+                let tab = button(text(label).size(14)).padding([5, 10]);
+                tabs.push(if index == instance.selected {
+                    tab.style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                } else {
+                    tab.style(theme::Button::Custom(Box::new(TabButtonStyle)))
+                        .on_press(Message::SelectInstance(index))
+                })
+            },
+        );
+        scrollable(tabs.padding([0, 10]))
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Properties::default(),
+            ))
+            .width(Length::Fill)
+            .into()
+    } else {
+        Space::with_height(0).into()
+    };
+
     // Кнопка "Запуск/Остановка"
     let control_button_element: Element<'static, Message> = if is_running {
-        button(text("Остановка программы"))
+        button(text(tr("Остановка программы")))
             .padding(10)
             .style(theme::Button::Custom(Box::new(StopButtonStyle)))
             .on_press(Message::StopButtonPressed)
             .into()
     } else {
-        let start_button = button(text("Запуск программы")).padding(10);
-        if settings.executable_path.is_some() && !settings.api_key.is_empty() {
+        let start_button = button(text(tr("Запуск программы"))).padding(10);
+        if settings.executable_path.is_some()
+            && monitor.executable_problem.is_none()
+            && !instance.api_key.is_empty()
+        {
             start_button
                 .style(theme::Button::Custom(Box::new(StartButtonStyle)))
                 .on_press(Message::StartButtonPressed)
@@ -174,125 +840,2819 @@ pub fn view_main(
     };
 
     // Кнопка Копировать лог
-    let copy_log_button: Element<'static, Message> = button(text("Копировать лог"))
+    let copy_log_button: Element<'static, Message> = button(text(tr("Копировать лог")))
         .padding(10)
         .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
         .on_press(Message::CopyLogsPressed)
         .into();
 
+    // Кнопка сохранения лога в файл
+    let export_log_button: Element<'static, Message> = button(text(tr("Экспорт лога...")))
+        .padding(10)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+        .on_press(Message::ExportLogPressed)
+        .into();
+
+    // Кнопка открытия директории логов сессий в файловом менеджере
+    let log_folder_button: Element<'static, Message> = button(text(tr("Папка логов")))
+        .padding(10)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+        .on_press(Message::OpenLogFolderPressed)
+        .into();
+
+    // Кнопка очистки лога (с подтверждением)
+    let clear_log_button: Element<'static, Message> = button(text(tr("Очистить лог")))
+        .padding(10)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+        .on_press(Message::ClearLogPressed)
+        .into();
+
+    // Предупреждение о слишком частом выводе (строки сверх лимита пропускаются)
+    let flood_text: Element<'static, Message> = match monitor.dropped_lines {
+        Some(dropped) => text(trf!(
+            "Слишком частый вывод: пропущено строк {}",
+            format_line_number(dropped)
+        ))
+        .size(14)
+        .style(Color::from_rgb8(0xFF, 0xD7, 0x00))
+        .into(),
+        None => Space::with_width(0).into(),
+    };
+
+    // Загрузка CPU и память процесса
+    let usage_text: Element<'static, Message> = match monitor.process_usage {
+        Some((usage, peak_rss)) => text(trf!(
+            "CPU: {}%  RAM: {} (пик {})",
+            format!("{:.1}", usage.cpu_percent),
+            format_bytes(usage.rss_bytes),
+            format_bytes(peak_rss)
+        ))
+        .size(14)
+        .font(Font::MONOSPACE)
+        .into(),
+        None => Space::with_width(0).into(),
+    };
+
+    // Индикатор готовности процесса (по опросу его порта)
+    let health_indicator: Element<'static, Message> = match instance.health {
+        Some(health) => {
+            let color = match health {
+                HealthState::Starting => Color::from_rgb8(0xDD, 0xAA, 0x00),
+                HealthState::Healthy => Color::from_rgb8(0x00, 0xAA, 0x00),
+                HealthState::Unresponsive => Color::from_rgb8(0xFF, 0x55, 0x55),
+            };
+            text(format!("● {}", health)).style(color).into()
+        }
+        None => Space::with_width(0).into(),
+    };
+
     // Строка с кнопками управления
     let control_row = row![
         copy_log_button,
+        export_log_button,
+        log_folder_button,
+        clear_log_button,
         Space::with_width(Length::Fill),
+        health_indicator,
+        flood_text,
+        usage_text,
         control_button_element
     ]
     .spacing(10) // Добавим немного места между кнопками
-    .padding(10);
+    .padding(10)
+    .align_items(Alignment::Center);
+
+    // Предупреждение о работе от батареи
+    let power_banner: Element<'static, Message> = match monitor.power_state {
+        Some(state) if state.on_battery && settings.battery_action != BatteryAction::Ignore => {
+            let mut message = match state.charge_percent {
+                Some(charge) => trf!("Питание от батареи (заряд {}%).", format!("{:.0}", charge)),
+                None => tr("Питание от батареи.").to_string(),
+            };
+            if !state.allows_auto_start(settings.battery_min_charge) {
+                message.push_str(&trf!(
+                    " Автоматические запуски отключены: заряд ниже {}%.",
+                    settings.battery_min_charge
+                ));
+            }
+            container(text(message))
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(WarningBannerStyle)))
+                .into()
+        }
+        _ => Space::with_height(0).into(),
+    };
+
+    // Предложение перезапустить процесс с измененными настройками запуска
+    let launch_banner = launch_changes_banner(&monitor.launch_changes);
+
+    // Чек-лист готовности к запуску (пока чего-то не хватает)
+    let readiness = if is_running {
+        Space::with_height(0).into()
+    } else {
+        readiness_checklist(settings, &instance.api_key, &monitor)
+    };
+
+    // Предупреждение о расхождении системных часов
+    let clock_banner: Element<'static, Message> = match monitor.clock_drift_ms {
+        Some(offset) => container(text(trf!(
+            "Системные часы расходятся с NTP на {} мс (допустимо {} мс)! \
+             Биржа может отклонять подписанные запросы - синхронизируйте время.",
+            format!("{:+.0}", offset),
+            settings.clock_drift_threshold_ms
+        )))
+        .padding(10)
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(ErrorBannerStyle)))
+        .into(),
+        None => Space::with_height(0).into(),
+    };
+
+    // Предупреждение сторожевого таймера: процесс давно ничего не выводит
+    let hung_banner: Element<'static, Message> = match monitor.silent_minutes {
+        Some(minutes) => container(text(trf!(
+            "Процесс не выводит данных уже {} мин - возможно, он завис.",
+            minutes
+        )))
+        .padding(10)
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(ErrorBannerStyle)))
+        .into(),
+        None => Space::with_height(0).into(),
+    };
+
+    // Информация о ближайшем окне обслуживания биржи
+    let maintenance_info: Element<'static, Message> = match monitor.next_maintenance {
+        Some(window) => {
+            let summary = if window.summary.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", window.summary)
+            };
+            container(
+                text(trf!(
+                    "Ближайшее обслуживание биржи: {} - {}{}",
+                    window.start.format("%d.%m.%Y %H:%M"),
+                    window.end.format("%d.%m.%Y %H:%M"),
+                    summary
+                ))
+                .size(14),
+            )
+            .padding([0, 10])
+            .into()
+        }
+        None => Space::with_height(0).into(),
+    };
+
+    // Баннеры уведомлений (новые сверху) с кнопкой закрытия
+    let notification_banners =
+        monitor
+            .notifications
+            .iter()
+            .rev()
+            .fold(column![].spacing(5), |banners, notification| {
+                let style: Box<dyn container::StyleSheet<Style = Theme>> = match notification.level
+                {
+                    NotificationLevel::Warning => Box::new(WarningBannerStyle),
+                    NotificationLevel::Critical => Box::new(ErrorBannerStyle),
+                };
+                let action: Element<'static, Message> = match &notification.action {
+                    Some(NotificationAction::OpenFolder(dir)) => button(text(tr("Открыть папку")))
+                        .padding([0, 8])
+                        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                        .on_press(Message::OpenFolderPressed(dir.clone()))
+                        .into(),
+                    None => Space::with_width(0).into(),
+                };
+                banners.push(
+                    container(
+                        row![
+                            text(format!(
+                                "[{}] {}",
+                                notification.time.format("%H:%M:%S"),
+                                notification.message
+                            ))
+                            .width(Length::Fill),
+                            action,
+                            button(text("×"))
+                                .padding([0, 8])
+                                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                                .on_press(Message::DismissNotification(notification.id)),
+                        ]
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                    )
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(theme::Container::Custom(style)),
+                )
+            });
+
+    // Панель пользовательских метрик
+    let metrics_panel: Element<'static, Message> = if monitor.metrics.is_empty() {
+        Space::with_height(0).into()
+    } else {
+        let chips = monitor
+            .metrics
+            .into_iter()
+            .fold(row![].spacing(10), |chips, (name, value)| {
+                let value_text = match value {
+                    Some(metric) => format!(
+                        "{}: {} ({})",
+                        name,
+                        metric.value,
+                        metric.updated.format("%H:%M:%S")
+                    ),
+                    None => format!("{}: -", name),
+                };
+                chips.push(
+                    container(text(value_text).size(14).font(Font::MONOSPACE))
+                        .padding([4, 8])
+                        .style(theme::Container::Custom(Box::new(MetricChipStyle))),
+                )
+            });
+        scrollable(chips.padding([0, 10]))
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Properties::default(),
+            ))
+            .width(Length::Fill)
+            .into()
+    };
+
+    // Карточка подтверждения очистки лога
+    let clear_confirmation: Element<'static, Message> = if log_filter.clear_pending {
+        let mut buttons = row![button(text(tr("Очистить")))
+            .padding(10)
+            .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+            .on_press(Message::ConfirmClearLog(false))]
+        .spacing(10);
+        // Новый файл лога сессии - только если лог сессии сейчас пишется
+        if log_filter.session_file.is_some() {
+            buttons = buttons.push(
+                button(text(tr("Очистить и начать новый файл лога")))
+                    .padding(10)
+                    .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                    .on_press(Message::ConfirmClearLog(true)),
+            );
+        }
+        buttons = buttons.push(
+            button(text(tr("Отмена")))
+                .padding(10)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::CancelClearLog),
+        );
+        container(
+            column![
+                text(tr(
+                    "Очистить окно лога? Уже записанный лог сессии на диске сохранится."
+                )),
+                buttons
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .into()
+    } else {
+        Space::with_height(0).into()
+    };
+
+    // Карточка подтверждения завершения процессов, найденных по пути
+    let kill_confirmation: Element<'static, Message> = match kill_candidates {
+        Some(candidates) => {
+            let list = candidates.iter().fold(
+                column![text(tr(
+                    "PID процесса неизвестен. Найдены процессы с тем же исполняемым файлом:"
+                ))]
+                .spacing(5),
+                |list, candidate| {
+                    list.push(
+                        text(format!(
+                            "PID {} - {}",
+                            candidate.pid,
+                            candidate.exe.display()
+                        ))
+                        .size(12)
+                        .font(Font::MONOSPACE),
+                    )
+                },
+            );
+            container(
+                column![
+                    list,
+                    row![
+                        button(text(tr("Завершить найденные процессы")))
+                            .padding(10)
+                            .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                            .on_press(Message::ConfirmKillCandidates),
+                        button(text(tr("Отмена")))
+                            .padding(10)
+                            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                            .on_press(Message::CancelKillCandidates),
+                    ]
+                    .spacing(10)
+                ]
+                .spacing(10),
+            )
+            .padding(10)
+            .width(Length::Fill)
+            .into()
+        }
+        None => Space::with_height(0).into(),
+    };
+
+    // Карточка уже работающего процесса (от прошлого сеанса лаунчера или запущенного вручную)
+    let orphan_prompt: Element<'static, Message> = match instance.orphan_process {
+        Some(orphan) => container(
+            column![
+                text(tr(
+                    "TradingStar уже работает (запущен в прошлом сеансе или без лаунчера):"
+                )),
+                text(format!("PID {} - {}", orphan.pid, orphan.exe.display()))
+                    .size(12)
+                    .font(Font::MONOSPACE),
+                text(tr(
+                    "После подключения вывод процесса недоступен, но его можно остановить."
+                ))
+                .size(12),
+                row![
+                    button(text(tr("Подключиться")))
+                        .padding(10)
+                        .style(theme::Button::Custom(Box::new(StartButtonStyle)))
+                        .on_press(Message::ReattachPressed(instance.id)),
+                    button(text(tr("Завершить")))
+                        .padding(10)
+                        .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                        .on_press(Message::StopOrphanPressed(instance.id)),
+                ]
+                .spacing(10)
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .into(),
+        None => Space::with_height(0).into(),
+    };
 
     // Формирование вида логов
-    let log_lines: Column<'static, Message> = logs.iter().rev().fold(
+    // Фильтр лога по времени: выбор интервала и (для произвольного) поля границ
+    let now = Local::now();
+    let (range_from, range_to, range_error) = match log_filter.filter {
+        LogTimeFilter::All => (None, None, None),
+        LogTimeFilter::Last5Minutes => (Some(now - chrono::Duration::minutes(5)), None, None),
+        LogTimeFilter::LastHour => (Some(now - chrono::Duration::hours(1)), None, None),
+        LogTimeFilter::Custom => {
+            let from = parse_filter_time(log_filter.custom_from, now);
+            let to = parse_filter_time(log_filter.custom_to, now);
+            let error = from.as_ref().err().or(to.as_ref().err()).cloned();
+            (from.ok().flatten(), to.ok().flatten(), error)
+        }
+    };
+    let mut filter_row = row![
+        text(tr("Показать:")).size(14),
+        pick_list(
+            &LogTimeFilter::ALL[..],
+            Some(log_filter.filter),
+            Message::LogTimeFilterSelected
+        )
+        .text_size(14),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+    filter_row = filter_row.push(
+        pick_list(
+            &LogStreamFilter::ALL[..],
+            Some(log_filter.stream),
+            Message::LogStreamFilterSelected,
+        )
+        .text_size(14),
+    );
+    // Кнопки уровней: нажатие скрывает или снова показывает строки уровня
+    filter_row = filter_row.push(text(tr("Уровни:")).size(14));
+    for level in LogLevel::ALL {
+        let style: Box<dyn button::StyleSheet<Style = Theme>> =
+            if log_filter.hidden_levels.contains(&level) {
+                Box::new(DisabledButtonStyle)
+            } else {
+                Box::new(DefaultButtonStyle)
+            };
+        filter_row = filter_row.push(
+            button(text(level.to_string()).size(12))
+                .padding(5)
+                .style(theme::Button::Custom(style))
+                .on_press(Message::LogLevelToggled(level)),
+        );
+    }
+    if log_filter.filter == LogTimeFilter::Custom {
+        filter_row = filter_row.push(
+            text_input(tr("с (ЧЧ:ММ или ДД.ММ ЧЧ:ММ)"), log_filter.custom_from)
+                .on_input(Message::LogRangeFromChanged)
+                .size(14)
+                .padding(5),
+        );
+        filter_row = filter_row.push(
+            text_input(tr("по (пусто - до сейчас)"), log_filter.custom_to)
+                .on_input(Message::LogRangeToChanged)
+                .size(14)
+                .padding(5),
+        );
+    }
+    filter_row = filter_row.push(
+        checkbox(tr("Таблица JSON"), log_filter.json_view)
+            .on_toggle(Message::LogJsonViewToggled)
+            .size(16)
+            .text_size(14),
+    );
+    filter_row = filter_row.push(
+        checkbox(tr("Исходный текст"), log_filter.raw_view)
+            .on_toggle(Message::LogRawViewToggled)
+            .size(16)
+            .text_size(14),
+    );
+    // Пауза вывода: новые строки копятся, но не сдвигают читаемый фрагмент
+    let follow_button = match log_filter.frozen_at {
+        Some(last) => {
+            let new_lines = logs.iter().filter(|line| line.id > last).count();
+            button(text(trf!("Продолжить (новых строк: {})", new_lines)).size(14))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(StartButtonStyle)))
+        }
+        None => button(text(tr("Пауза")).size(14))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle))),
+    };
+    filter_row = filter_row.push(follow_button.on_press(Message::LogFollowToggled));
+    // Переход к самой новой (Home) и самой старой (End) строке
+    filter_row = filter_row.push(
+        button(text(tr("К новым")).size(14))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::JumpToNewest),
+    );
+    filter_row = filter_row.push(
+        button(text(tr("К старым")).size(14))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::JumpToOldest),
+    );
+    // Копирование выделенных строк (клик - строка, Shift+клик - диапазон)
+    if let Some((first, last)) = log_filter.selection {
+        let selected = logs
+            .iter()
+            .filter(|line| (first..=last).contains(&line.id))
+            .count();
+        filter_row = filter_row.push(
+            button(text(trf!("Копировать выделенное ({})", selected)).size(14))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::CopySelectedPressed),
+        );
+    }
+    let mut log_filter_panel = column![filter_row].spacing(5).padding([0, 10]);
+    if let Some(error) = range_error {
+        log_filter_panel = log_filter_panel.push(
+            text(error)
+                .size(12)
+                .style(Color::from_rgb8(0xFF, 0x55, 0x55)),
+        );
+    }
+    // Поиск по логу: совпадения подсвечиваются, кнопки переходят между ними
+    let search_status = match log_filter.search_position {
+        _ if log_filter.search.trim().is_empty() => String::new(),
+        Some((current, total)) => trf!("{} из {}", current, total),
+        None => tr("Не найдено").to_string(),
+    };
+    let mut previous_button = button(text("▲").size(14))
+        .padding(5)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)));
+    let mut next_button = button(text("▼").size(14))
+        .padding(5)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)));
+    if log_filter.search_position.is_some() {
+        previous_button = previous_button.on_press(Message::LogSearchPrevious);
+        next_button = next_button.on_press(Message::LogSearchNext);
+    }
+    log_filter_panel = log_filter_panel.push(
+        row![
+            text_input(
+                tr("Поиск в логе (Enter - следующее совпадение)"),
+                log_filter.search
+            )
+            .on_input(Message::LogSearchChanged)
+            .on_submit(Message::LogSearchNext)
+            .size(14)
+            .padding(5),
+            previous_button,
+            next_button,
+            text(search_status).size(14),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    );
+    let search_query = log_filter.search.trim().to_lowercase();
+    // Список закладок (от новых строк к старым): клик - переход к строке
+    let bookmarked: Vec<&LogLine> = logs
+        .iter()
+        .rev()
+        .filter(|line| log_filter.bookmarks.binary_search(&line.id).is_ok())
+        .collect();
+    if !bookmarked.is_empty() {
+        let markers = bookmarked.iter().fold(row![].spacing(5), |markers, line| {
+            let plain = line.plain_text();
+            let mut preview: String = plain.chars().take(40).collect();
+            if preview.len() < plain.len() {
+                preview.push('…');
+            }
+            markers.push(
+                button(
+                    text(format!("★ {} {}", line.time.format("%H:%M:%S"), preview))
+                        .size(12)
+                        .font(Font::MONOSPACE),
+                )
+                .padding([2, 6])
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::JumpToLogLine(line.id)),
+            )
+        });
+        log_filter_panel = log_filter_panel.push(
+            row![
+                text(trf!("Закладки ({}):", bookmarked.len())).size(14),
+                button(text("▲").size(14))
+                    .padding(5)
+                    .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                    .on_press(Message::PreviousBookmark),
+                button(text("▼").size(14))
+                    .padding(5)
+                    .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                    .on_press(Message::NextBookmark),
+                scrollable(markers)
+                    .direction(scrollable::Direction::Horizontal(
+                        scrollable::Properties::default(),
+                    ))
+                    .width(Length::Fill),
+                button(text(tr("Удалить все")).size(14))
+                    .padding(5)
+                    .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                    .on_press(Message::ClearBookmarks),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        );
+    }
+
+    let in_range = |line: &&LogLine| {
+        log_filter.frozen_at.is_none_or(|last| line.id <= last)
+            && range_from.is_none_or(|from| line.time >= from)
+            && range_to.is_none_or(|to| line.time <= to)
+            && line.is_visible(log_filter.hidden_levels, log_filter.stream)
+    };
+
+    let font_size = settings.log_font_size();
+    let log_lines: Column<'static, Message> = logs.iter().rev().filter(in_range).fold(
         column![]
             .spacing(2) // <-- Возвращаем небольшой spacing для колонки
             .padding(10),
-        |column, line_segments| {
-            let log_row: Row<'static, Message> =
-                line_segments
-                    .iter()
-                    .fold(row![].spacing(0), |row_acc, segment| {
-                        let segment_text: Text<'static> = text(&segment.text)
-                            .size(12)
+        |column, line| {
+            // Подсветка правилами переопределяет ANSI-цвета всей строки (или задает ее фон)
+            let plain: String = line.segments.iter().map(|s| s.text.as_str()).collect();
+            let highlight = log_filter.highlighter.style(&plain);
+            // Время получения строки - приглушенным цветом (если метки включены)
+            let bookmarked = log_filter.bookmarks.binary_search(&line.id).is_ok();
+            let mut time_row =
+                row![view_bookmark_toggle(line.id, bookmarked, font_size)].spacing(0);
+            if settings.log_gutter {
+                time_row = time_row.push(view_gutter(line, font_size));
+            }
+            if let Some(time) = settings.log_time_prefix(line.time) {
+                let time_text: Text<'static> = text(format!("{} ", time))
+                    .size(font_size)
+                    .font(Font::MONOSPACE)
+                    .style(Color::from_rgb8(0x88, 0x88, 0x88));
+                time_row = time_row.push(time_text);
+            }
+            // Строки потока ошибок без собственного цвета окрашиваются (по настройке)
+            let tint =
+                (settings.tint_stderr && line.source == LogSource::Stderr).then_some(STDERR_COLOR);
+            let expanded = log_filter.expanded.contains(&line.id);
+            let log_row: Row<'static, Message> = match &line.json {
+                // Строка JSON в режиме таблицы: колонки и кнопка раскрытия исходного объекта
+                Some(record) if log_filter.json_view => {
+                    let expand_button = button(
+                        text(if expanded { "▾" } else { "▸" })
+                            .size(font_size)
+                            .font(Font::MONOSPACE),
+                    )
+                    .padding(0)
+                    .width(JSON_EXPAND_WIDTH)
+                    .style(theme::Button::Custom(Box::new(LinkButtonStyle)))
+                    .on_press(Message::LogLineExpandToggled(line.id));
+                    let color = highlight
+                        .filter(|style| !style.background)
+                        .map(|style| highlight_color(style.color))
+                        .or(tint)
+                        .unwrap_or_else(|| level_color(line.level));
+                    let mut json_row =
+                        row![view_bookmark_toggle(line.id, bookmarked, font_size)].spacing(5);
+                    if settings.log_gutter {
+                        json_row = json_row.push(view_gutter(line, font_size));
+                    }
+                    json_row
+                        .push(expand_button)
+                        .push(view_json_columns(record, color, font_size))
+                }
+                _ => line.segments.iter().fold(time_row, |row_acc, segment| {
+                    row_acc.push(view_segment(segment, highlight, tint, font_size))
+                }),
+            };
+            // Клик по строке выделяет ее (ссылки внутри строки обрабатывают клик сами)
+            let log_row = button(log_row)
+                .padding(0)
+                .width(Length::Fill)
+                .style(theme::Button::Custom(Box::new(LinkButtonStyle)))
+                .on_press(Message::LogLineClicked(line.id));
+            // Режим исходного текста: справа от разобранной строки - полученная строка как есть
+            let log_row: Element<'static, Message> = if log_filter.raw_view {
+                row![
+                    log_row.width(Length::FillPortion(1)),
+                    text(escape_raw(&line.raw))
+                        .size(font_size)
+                        .font(Font::MONOSPACE)
+                        .style(Color::from_rgb8(0xBB, 0xBB, 0xBB))
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(10)
+                .into()
+            } else {
+                log_row.into()
+            };
+            // Раскрытая строка JSON: исходный объект с отступами под строкой таблицы
+            let payload = (log_filter.json_view && expanded)
+                .then(|| pretty_payload(&plain))
+                .flatten();
+            let log_row: Element<'static, Message> = match payload {
+                Some(payload) => column![
+                    log_row,
+                    container(
+                        text(payload)
+                            .size(font_size)
                             .font(Font::MONOSPACE)
-                            .style(segment.color.unwrap_or(Color::WHITE));
-                        row_acc.push(segment_text)
-                    });
-            // Убираем контейнер, добавляем Row напрямую
-            // let line_container = container(log_row)
-            //                         .width(Length::Fill)
-            //                         .style(theme::Container::Custom(Box::new(LogLineStyle)));
-            // column.push(line_container)
-            column.push(log_row) // <-- Добавляем Row напрямую
+                            .style(Color::from_rgb8(0xBB, 0xBB, 0xBB))
+                    )
+                    .padding([2, 0, 4, JSON_EXPAND_WIDTH as u16 + 5])
+                ]
+                .into(),
+                None => log_row,
+            };
+            let selected = log_filter
+                .selection
+                .is_some_and(|(first, last)| (first..=last).contains(&line.id));
+            // Выделенные строки и строку, к которой перешли из хронологии или поиска, выделяем
+            // фоном, остальные совпадения поиска - более светлым
+            if selected || log_filter.highlighted_line == Some(line.id) {
+                column.push(
+                    container(log_row)
+                        .width(Length::Fill)
+                        .style(theme::Container::Custom(Box::new(HighlightedLineStyle))),
+                )
+            } else if line.matches_query(&search_query) {
+                column.push(
+                    container(log_row)
+                        .width(Length::Fill)
+                        .style(theme::Container::Custom(Box::new(SearchMatchStyle))),
+                )
+            } else {
+                column.push(log_row)
+            }
         },
     );
 
+    // В режиме таблицы над строками JSON - заголовок колонок
+    let log_lines: Column<'static, Message> =
+        if log_filter.json_view && logs.iter().filter(in_range).any(|line| line.json.is_some()) {
+            let header = JsonRecord {
+                time: Some(tr("Время").to_string()),
+                level: Some(tr("Уровень").to_string()),
+                module: Some(tr("Модуль").to_string()),
+                message: tr("Сообщение").to_string(),
+            };
+            column![
+                row![
+                    Space::with_width(JSON_EXPAND_WIDTH),
+                    view_json_columns(&header, Color::from_rgb8(0x88, 0x88, 0x88), font_size)
+                ]
+                .spacing(5)
+                .padding([10, 10, 0, 10]),
+                log_lines
+            ]
+        } else {
+            column![log_lines]
+        };
+
     // Оборачиваем колонку логов в Scrollable
     let log_view: Scrollable<'static, Message> = scrollable(log_lines)
+        .id(log_scroll_id())
+        .on_scroll(|viewport| Message::LogScrolled(viewport.absolute_offset().y))
         .height(Length::Fill)
         .width(Length::Fill);
 
-    // Собираем главный экран
-    column![top_bar_container, control_row, log_view]
-        .spacing(10)
-        .padding(0)
+    // Хронология событий за последние сутки (клик по отметке - переход к строке лога)
+    let timeline: Element<'static, Message> = if monitor.events.is_empty() {
+        Space::with_height(0).into()
+    } else {
+        container(
+            canvas(TimelineChart {
+                events: monitor.events.to_vec(),
+                maintenance: monitor
+                    .maintenance_windows
+                    .iter()
+                    .map(|window| (window.start, window.end))
+                    .collect(),
+                hours: 24,
+                now,
+            })
+            .width(Length::Fill)
+            .height(TIMELINE_HEIGHT),
+        )
+        .padding([0, 10])
         .into()
-}
-
-// Отрисовка экрана настроек
-pub fn view_settings(settings: &AppSettings) -> Element<'static, Message> {
-    // 'static lifetime необходим для элементов Iced
+    };
 
-    // Отображение выбранного пути
-    let path_display = match &settings.executable_path {
-        Some(path) => path.display().to_string(),
-        None => "Путь не выбран".to_string(),
+    // Консоль: строка ввода команд в stdin процесса
+    let console_input = log_filter.console_input;
+    let console_field = text_input(
+        if console_input.is_some() {
+            tr("Команда для процесса (Enter - отправить)")
+        } else {
+            tr("Консоль доступна, пока процесс запущен лаунчером")
+        },
+        console_input.unwrap_or_default(),
+    )
+    .font(Font::MONOSPACE)
+    .padding(8)
+    .width(Length::Fill);
+    let mut send_button = button(text(tr("Отправить")))
+        .padding(8)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)));
+    let console_field = match console_input {
+        Some(_) => {
+            send_button = send_button.on_press(Message::ConsoleSubmitted);
+            console_field
+                .on_input(Message::ConsoleInputChanged)
+                .on_submit(Message::ConsoleSubmitted)
+        }
+        None => console_field,
     };
+    let console_row = row![console_field, send_button]
+        .spacing(10)
+        .padding([0, 10, 10, 10])
+        .align_items(Alignment::Center);
 
-    // Формируем колонку с элементами настроек
+    // Собираем главный экран
     column![
-        text("Настройки").size(24),
-        Space::with_height(20), // Отступ
-        text("Путь к исполняемому файлу:"),
+        top_bar_container,
+        instance_tabs,
+        notification_banners,
+        launch_banner,
+        clock_banner,
+        hung_banner,
+        power_banner,
+        readiness,
+        control_row,
+        metrics_panel,
+        maintenance_info,
+        orphan_prompt,
+        kill_confirmation,
+        clear_confirmation,
+        timeline,
+        log_filter_panel,
+        log_view,
+        console_row,
+        status_bar
+    ]
+    .spacing(10)
+    .padding(0)
+    .into()
+}
+
+// Строка состояния выбранного экземпляра
+fn status_bar(instance: &InstanceView, session_file: Option<&Path>) -> Element<'static, Message> {
+    let (state, color) = if !instance.is_running {
+        if instance.crashed {
+            (tr("Сбой"), Color::from_rgb8(0xFF, 0x55, 0x55))
+        } else {
+            (tr("Остановлен"), Color::from_rgb8(0xAA, 0xAA, 0xAA))
+        }
+    } else if instance.pid.is_none() || instance.health == Some(HealthState::Starting) {
+        (tr("Запускается"), Color::from_rgb8(0xDD, 0xAA, 0x00))
+    } else {
+        (tr("Работает"), Color::from_rgb8(0x55, 0xCC, 0x55))
+    };
+    let separator = || text("|").size(12).style(GUTTER_COLOR);
+    let mut content = row![text(format!("● {}", state)).size(12).style(color)]
+        .spacing(10)
+        .align_items(Alignment::Center);
+    if let Some(pid) = instance.pid {
+        content = content
+            .push(separator())
+            .push(text(format!("PID: {}", pid)).size(12).font(Font::MONOSPACE));
+    }
+    if let Some(uptime) = instance.uptime {
+        content = content.push(separator()).push(
+            text(trf!("Время работы: {}", format_uptime(uptime)))
+                .size(12)
+                .font(Font::MONOSPACE),
+        );
+    }
+    content = content.push(separator()).push(
+        text(match session_file {
+            Some(path) => trf!("Лог: {}", path.display()),
+            None => tr("Лог сессии не сохраняется").to_string(),
+        })
+        .size(12),
+    );
+    container(content)
+        .padding([3, 10])
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(StatusBarStyle)))
+        .into()
+}
+
+// Отрисовка экрана настроек
+pub fn view_settings(
+    settings: &AppSettings,               // Текущие настройки
+    logs_dir: Option<std::path::PathBuf>, // Директория логов сессий
+    log_forward_status: Option<&str>,     // Состояние пересылки логов
+    launch_changes: Vec<&'static str>,    // Процесс работает со старыми параметрами запуска
+    maintenance_windows: usize,           // Количество загруженных окон обслуживания
+    extras: SettingsExtras,               // Раздел, ошибки в правилах, видимость ключа API
+    instances_running: &[bool],           // Запущены ли экземпляры (0 - основной)
+) -> Element<'static, Message> {
+    // 'static lifetime необходим для элементов Iced
+
+    // Содержимое выбранного раздела
+    let section = match extras.section {
+        SettingsSection::Process => settings_process_section(
+            settings,
+            &extras,
+            launch_changes,
+            maintenance_windows,
+            instances_running,
+        ),
+        SettingsSection::Logging => {
+            settings_logging_section(settings, logs_dir, log_forward_status, &extras)
+        }
+        SettingsSection::Notifications => settings_notifications_section(settings, &extras),
+        SettingsSection::Advanced => settings_advanced_section(settings, &extras),
+    };
+
+    let content = column![
+        text(tr("Настройки")).size(24),
+        cli_overrides_note(&extras.cli_overrides),
+        settings_section_tabs(extras.section),
+        // Настроек много - делаем их прокручиваемыми, кнопка закрытия остается внизу
+        scrollable(container(section).padding([0, 15, 0, 0])) // Отступ справа под полосу прокрутки
+            .height(Length::Fill),
+        // Кнопка "Закрыть настройки"
+        button(text(tr("Закрыть настройки")))
+            .padding(10)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle))) // Используем стиль
+            .on_press(Message::CloseSettingsPressed) // Сообщение при нажатии
+    ]
+    .padding(20) // Внутренние отступы колонки
+    .spacing(10) // Пространство между элементами колонки
+    .max_width(600); // Ограничиваем максимальную ширину
+
+    // Настройки открываются окном поверх главного экрана - нужен свой фон и рамка
+    container(content)
+        .style(theme::Container::Custom(Box::new(DialogStyle)))
+        .into()
+}
+
+// Переключатель разделов настроек
+fn settings_section_tabs(selected: SettingsSection) -> Element<'static, Message> {
+    SettingsSection::ALL
+        .into_iter()
+        .fold(row![].spacing(5), |tabs, section| {
+            let tab = button(text(section.to_string()).size(14)).padding([5, 10]);
+            tabs.push(if section == selected {
+                tab.style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            } else {
+                tab.style(theme::Button::Custom(Box::new(TabButtonStyle)))
+                    .on_press(Message::SettingsSectionSelected(section))
+            })
+        })
+        .into()
+}
+
+// Раздел "Процесс": запуск, ключи, экземпляры, расписание
+fn settings_process_section(
+    settings: &AppSettings,
+    extras: &SettingsExtras,
+    launch_changes: Vec<&'static str>,
+    maintenance_windows: usize,
+    instances_running: &[bool],
+) -> Element<'static, Message> {
+    // Отображение выбранного пути
+    let path_display = match &settings.executable_path {
+        Some(path) => path.display().to_string(),
+        None => tr("Путь не выбран").to_string(),
+    };
+
+    column![
+        text(tr("Профили запуска (исполняемый файл, ключ, аргументы и параметры процесса основного экземпляра):")),
+        view_launch_profiles(settings),
+        Space::with_height(15), // Отступ
+        text(tr("Путь к исполняемому файлу:")),
         // Строка с путем и кнопкой выбора
         row![
             text(path_display).width(Length::Fill), // Текст пути растягивается
-            button(text("Выбрать..."))
+            button(text(tr("Выбрать...")))
                 .padding(5)
                 .style(theme::Button::Custom(Box::new(DefaultButtonStyle))) // Используем стиль
                 .on_press(Message::SelectExecutablePath)  // Сообщение при нажатии
         ]
         .spacing(10)
         .align_items(Alignment::Center),
+        match extras.executable_problem {
+            Some(problem) => Element::from(
+                text(problem)
+                    .size(14)
+                    .style(Color::from_rgb8(0xFF, 0x55, 0x55)),
+            ),
+            None => Space::with_height(0).into(),
+        },
         Space::with_height(15), // Отступ
-        text("Ключ API (параметр -k):"),
-        // Поле ввода ключа API
-        text_input("Введите ваш API ключ...", &settings.api_key)
-            .on_input(Message::ApiKeyChanged) // Сообщение при изменении
-            .padding(10),
-        Space::with_height(Length::Fill), // Растягиваем пространство до низа
-        // Кнопка "Закрыть настройки"
-        button(text("Закрыть настройки"))
+        text(tr("Ключ API основного экземпляра (параметр -k):")),
+        // Поле ввода ключа API (скрыто, пока не нажата кнопка показа)
+        row![
+            text_input(tr("Введите ваш API ключ..."), &settings.api_key)
+                .secure(!extras.api_key_revealed)
+                .on_input(Message::ApiKeyChanged) // Сообщение при изменении
+                .on_paste(Message::ApiKeyPasted) // Вставка очищается и проверяется отдельно
+                .padding(10),
+            button(text(if extras.api_key_revealed {
+                tr("Скрыть")
+            } else {
+                tr("Показать")
+            }))
             .padding(10)
-            .style(theme::Button::Custom(Box::new(DefaultButtonStyle))) // Используем стиль
-            .on_press(Message::CloseSettingsPressed) // Сообщение при нажатии
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::ApiKeyRevealToggled),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        api_key_problem(&settings.api_key),
+        text(tr("Переменная окружения с ключом API (пусто - ключ из поля выше):")),
+        text_input(tr("Например, TRADINGSTAR_API_KEY"), &settings.api_key_env)
+            .on_input(Message::ApiKeyEnvChanged)
+            .padding(10),
+        api_key_env_status(settings),
+        text(tr("Рабочая директория процесса:")),
+        row![
+            text(match &settings.working_dir {
+                Some(dir) => dir.display().to_string(),
+                None => tr("Директория, из которой запущен лаунчер").to_string(),
+            })
+            .width(Length::Fill),
+            button(text(tr("Выбрать...")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::SelectWorkingDir),
+            button(text(tr("По умолчанию")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::ResetWorkingDir),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        launch_changes_banner(&launch_changes),
+        checkbox(
+            tr("Возобновлять процесс, если он работал при закрытии лаунчера"),
+            settings.resume_workspace
+        )
+        .on_toggle(Message::ResumeWorkspaceToggled),
+        checkbox(
+            tr("Запускать TradingStar автоматически при открытии лаунчера"),
+            settings.autostart
+        )
+        .on_toggle(Message::AutostartToggled),
+        text(tr("Ожидание корректного завершения при остановке, с (0 - завершать сразу принудительно):")),
+        text_input("10", &settings.graceful_stop_timeout_secs.to_string())
+            .on_input(Message::GracefulStopTimeoutChanged)
+            .padding(10),
+        text(tr("Считать процесс зависшим, если нет вывода дольше (мин, 0 - не следить):")),
+        text_input("0", &settings.watchdog_silence_minutes.to_string())
+            .on_input(Message::WatchdogMinutesChanged)
+            .padding(10),
+        checkbox(tr("Перезапускать зависший процесс"), settings.watchdog_restart)
+            .on_toggle(Message::WatchdogRestartToggled),
+        text(tr("Пропускать вывод сверх (строк в секунду на поток, 0 - без ограничения; при следующем запуске):")),
+        text_input("5000", &settings.output_rate_limit.to_string())
+            .on_input(Message::OutputRateLimitChanged)
+            .padding(10),
+        row![
+            text(tr("Кодировка вывода процесса (при следующем запуске):")),
+            pick_list(
+                &OutputEncoding::ALL[..],
+                Some(settings.output_encoding),
+                Message::OutputEncodingSelected
+            ),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        text(tr("Останавливать процесс через (мин работы, 0 - без ограничения):")),
+        text_input("0", &settings.max_runtime_minutes.to_string())
+            .on_input(Message::MaxRuntimeChanged)
+            .padding(10),
+        Space::with_height(15), // Отступ
+        text(tr("Дополнительные аргументы командной строки (после -k, аргумент с пробелами - в кавычках):")),
+        text_input("--log-level debug", &settings.extra_args)
+            .on_input(Message::ExtraArgsChanged)
+            .font(Font::MONOSPACE)
+            .padding(10),
+        extra_args_problem(&settings.extra_args),
+        checkbox(
+            tr("Запускать с повышенными правами (UAC в Windows, pkexec/sudo в Linux)"),
+            settings.run_elevated
+        )
+        .on_toggle(Message::RunElevatedToggled),
+        checkbox(
+            tr("Не останавливать процесс при закрытии лаунчера (вывод пишется в файл, консоль недоступна)"),
+            settings.keep_running
+        )
+        .on_toggle(Message::KeepRunningToggled),
+        text(tr("Проверка готовности: порт веб-интерфейса/API (хост:порт или http://..., пусто - не проверять):")),
+        text_input("127.0.0.1:8080", &settings.health_check)
+            .on_input(Message::HealthCheckChanged)
+            .font(Font::MONOSPACE)
+            .padding(10),
+        health_check_problem(&settings.health_check),
+        text(tr("Команда консоли для корректной остановки (пишется в stdin, пусто - сигнал завершения):")),
+        text_input("quit", &settings.stop_command)
+            .on_input(Message::StopCommandChanged)
+            .font(Font::MONOSPACE)
+            .padding(10),
+        text(tr("Перезапускать процесс, если он занял больше памяти (МБ, 0 - не следить):")),
+        text_input("0", &settings.memory_limit_mb.to_string())
+            .on_input(Message::MemoryLimitChanged)
+            .padding(10),
+        text(tr("Считать процесс не отвечающим, если он не ответил после запуска за (с):")),
+        text_input("120", &settings.health_start_timeout_secs.to_string())
+            .on_input(Message::HealthStartTimeoutChanged)
+            .padding(10),
+        text(tr("Дополнительные экземпляры (свой ключ API и аргументы, работают параллельно с основным):")),
+        view_instances(&settings.instances, instances_running),
+        text(tr("Переменные окружения процесса (применяются при следующем запуске):")),
+        view_env_vars(&settings.env_vars),
+        text(trf!("Подстановки в аргументах и значениях переменных: {}", tr(PLACEHOLDERS_HELP)))
+            .size(12),
+        Space::with_height(15), // Отступ
+        text(tr("При переходе на питание от батареи:")),
+        pick_list(
+            &BatteryAction::ALL[..],
+            Some(settings.battery_action),
+            Message::BatteryActionChanged
+        ),
+        text(tr("Минимальный заряд батареи для автоматических запусков (%):")),
+        text_input("30", &settings.battery_min_charge.to_string())
+            .on_input(Message::BatteryMinChargeChanged)
+            .padding(10),
+        Space::with_height(15), // Отступ
+        text(tr("Календарь обслуживания биржи (URL iCal/ICS):")),
+        row![
+            text_input("https://...", &settings.maintenance_ics_url)
+                .on_input(Message::MaintenanceUrlChanged)
+                .padding(10),
+            button(text(tr("Обновить")))
+                .padding(10)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::RefreshMaintenanceCalendar)
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        text(trf!("Загружено окон обслуживания: {}", maintenance_windows))
+        .size(12),
+        text(tr("Останавливать процесс до начала обслуживания за (мин):")),
+        text_input("5", &settings.maintenance_lead_minutes.to_string())
+            .on_input(Message::MaintenanceLeadChanged)
+            .padding(10),
+        Space::with_height(15), // Отступ
+        view_trading_hours(&settings.trading_hours),
+    ]
+    .spacing(10) // Пространство между элементами колонки
+    .into()
+}
+
+// Раздел "Логи": хранение, отображение, пересылка и подсветка
+fn settings_logging_section(
+    settings: &AppSettings,
+    logs_dir: Option<std::path::PathBuf>,
+    log_forward_status: Option<&str>,
+    extras: &SettingsExtras,
+) -> Element<'static, Message> {
+    column![
+        text(tr("Директория данных (логи сессий, история запусков):")),
+        row![
+            text(match &settings.data_dir {
+                Some(dir) => dir.display().to_string(),
+                None => tr("Стандартная директория").to_string(),
+            })
+            .width(Length::Fill),
+            button(text(tr("Выбрать...")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::SelectDataDir),
+            button(text(tr("По умолчанию")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::ResetDataDir),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        text(match &logs_dir {
+            Some(dir) => trf!("Хранятся в {}", dir.display()),
+            None => tr("Директория логов недоступна").to_string(),
+        })
+        .size(12),
+        text(tr("Сжимать логи старше (дней, 0 - не сжимать):")),
+        text_input("7", &settings.log_compress_after_days.to_string())
+            .on_input(Message::LogCompressDaysChanged)
+            .padding(10),
+        text(tr(
+            "Максимальный размер директории логов (МБ, 0 - без ограничения):"
+        )),
+        text_input("1024", &settings.log_dir_quota_mb.to_string())
+            .on_input(Message::LogQuotaChanged)
+            .padding(10),
+        text(trf!(
+            "Строк лога в окне (от {} до {}, применяется к новым строкам):",
+            LOG_LINES_MIN,
+            LOG_LINES_MAX
+        )),
+        text_input("500", &settings.log_buffer_lines.to_string())
+            .on_input(Message::LogBufferLinesChanged)
+            .padding(10),
+        text(trf!(
+            "Размер шрифта лога (от {} до {}, Ctrl+= / Ctrl+- / Ctrl+0 на главном экране):",
+            LOG_FONT_SIZE_MIN,
+            LOG_FONT_SIZE_MAX
+        )),
+        text_input("12", &settings.log_font_size.to_string())
+            .on_input(Message::LogFontSizeChanged)
+            .padding(10),
+        text(tr(
+            "Начинать новый файл лога сессии после (МБ, 0 - без ограничения):"
+        )),
+        text_input("50", &settings.session_log_max_mb.to_string())
+            .on_input(Message::SessionLogMaxChanged)
+            .padding(10),
+        checkbox(
+            tr("Сохранять цвета (ANSI-последовательности) в файле лога сессии"),
+            settings.session_log_keep_ansi
+        )
+        .on_toggle(Message::SessionLogKeepAnsiToggled),
+        checkbox(
+            tr("Сохранять цвета (ANSI-последовательности) при экспорте лога"),
+            settings.log_export_keep_ansi
+        )
+        .on_toggle(Message::LogExportKeepAnsiToggled),
+        checkbox(
+            tr("Показывать номера строк и метки потоков ([out]/[err]) слева от лога"),
+            settings.log_gutter
+        )
+        .on_toggle(Message::LogGutterToggled),
+        checkbox(
+            tr("Показывать при запуске лаунчера конец лога предыдущего сеанса"),
+            settings.restore_previous_log
+        )
+        .on_toggle(Message::RestorePreviousLogToggled),
+        checkbox(
+            tr("Окрашивать строки потока ошибок (stderr) в красный"),
+            settings.tint_stderr
+        )
+        .on_toggle(Message::TintStderrToggled),
+        checkbox(
+            tr("Показывать время получения перед каждой строкой лога"),
+            settings.log_timestamps
+        )
+        .on_toggle(Message::LogTimestampsToggled),
+        text(tr(
+            "Формат времени строк лога (strftime, например %H:%M:%S%.3f или %d.%m %H:%M:%S):"
+        )),
+        text_input(DEFAULT_LOG_TIME_FORMAT, &settings.log_time_format)
+            .on_input(Message::LogTimeFormatChanged)
+            .font(Font::MONOSPACE)
+            .padding(10),
+        time_format_problem_text(&settings.log_time_format),
+        Space::with_height(15), // Отступ
+        checkbox(
+            tr("Пересылать вывод процесса в syslog / Vector / Fluent (TCP)"),
+            settings.log_forward_enabled
+        )
+        .on_toggle(Message::LogForwardToggled),
+        row![
+            text_input("logs.example.com:6514", &settings.log_forward_address)
+                .on_input(Message::LogForwardAddressChanged)
+                .padding(10),
+            pick_list(
+                &ForwardFormat::ALL[..],
+                Some(settings.log_forward_format),
+                Message::LogForwardFormatChanged
+            ),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        checkbox("TLS", settings.log_forward_tls).on_toggle(Message::LogForwardTlsToggled),
+        text(
+            log_forward_status
+                .unwrap_or(tr("Пересылка не активна"))
+                .to_string()
+        )
+        .size(12),
+        Space::with_height(15), // Отступ
+        text(tr(
+            "Подсветка строк лога по ключевым словам (первое совпадение сверху):"
+        )),
+        view_highlight_rules(&settings.highlight_rules, extras.highlight_rule_errors),
+    ]
+    .spacing(10) // Пространство между элементами колонки
+    .into()
+}
+
+// Раздел "Оповещения": метрики и правила оповещений
+fn settings_notifications_section(
+    settings: &AppSettings,
+    extras: &SettingsExtras,
+) -> Element<'static, Message> {
+    column![
+        text(tr(
+            "Системные уведомления (видны, даже когда окно свернуто):"
+        )),
+        checkbox(tr("При сбое процесса"), settings.desktop_notify_crash)
+            .on_toggle(Message::DesktopNotifyCrashToggled),
+        checkbox(
+            tr("При завершении процесса без команды остановки"),
+            settings.desktop_notify_exit
+        )
+        .on_toggle(Message::DesktopNotifyExitToggled),
+        Space::with_height(15), // Отступ
+        text(tr(
+            "Метрики из вывода процесса (регулярное выражение с группой захвата):"
+        )),
+        view_metric_rules(&settings.metric_rules, extras.metric_rule_errors),
+        Space::with_height(15), // Отступ
+        text(tr("Оповещения по порогам метрик:")),
+        view_alert_rules(settings),
+        Space::with_height(15), // Отступ
+        text(tr(
+            "Оповещения по ключевым фразам в выводе процесса (например, margin call):"
+        )),
+        view_keyword_alerts(&settings.keyword_alerts),
+    ]
+    .spacing(10) // Пространство между элементами колонки
+    .into()
+}
+
+// Раздел "Дополнительно": часы, блокировка, диагностика, копии и шифрование настроек
+fn settings_advanced_section(
+    settings: &AppSettings,
+    extras: &SettingsExtras,
+) -> Element<'static, Message> {
+    column![
+        row![
+            text(tr("Язык интерфейса:")),
+            pick_list(
+                &Language::ALL[..],
+                Some(settings.language),
+                Message::LanguageSelected
+            ),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        view_theme_colors(&settings.theme_colors),
+        Space::with_height(15), // Отступ
+        text(tr("NTP-сервер для проверки системных часов:")),
+        text_input("pool.ntp.org", &settings.ntp_server)
+            .on_input(Message::NtpServerChanged)
+            .padding(10),
+        text(tr("Допустимое расхождение часов (мс):")),
+        text_input("1000", &settings.clock_drift_threshold_ms.to_string())
+            .on_input(Message::ClockDriftThresholdChanged)
+            .padding(10),
+        checkbox(
+            tr("Запрещать запуск при превышении расхождения"),
+            settings.block_start_on_clock_drift
+        )
+        .on_toggle(Message::BlockStartOnClockDriftToggled),
+        Space::with_height(15), // Отступ
+        text(tr("Блокировать интерфейс после бездействия (мин, 0 - не блокировать):")),
+        text_input("0", &settings.ui_lock_minutes.to_string())
+            .on_input(Message::UiLockMinutesChanged)
+            .padding(10),
+        text(tr("PIN для разблокировки:")),
+        text_input(tr("Без PIN блокировка недоступна"), &settings.ui_lock_pin)
+            .secure(true)
+            .on_input(Message::UiLockPinChanged)
+            .padding(10),
+        checkbox(tr("Скрывать лог, пока интерфейс заблокирован"), settings.ui_lock_hide_logs)
+            .on_toggle(Message::UiLockHideLogsToggled),
+        close_to_tray_option(settings.close_to_tray),
+        Space::with_height(15), // Отступ
+        row![
+            button(text(tr("Собрать диагностику")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::CollectDiagnosticsPressed),
+            text(tr("Архив с версией, сведениями об ОС, настройками (без ключа API) и последним логом для отчета об ошибке"))
+                .size(12),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        Space::with_height(15), // Отступ
+        text(tr("Наборы правил (подсветка, метрики, оповещения) для обмена с другими пользователями:")),
+        row![
+            button(text(tr("Экспорт набора...")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::ExportRulePackPressed),
+            button(text(tr("Импорт набора...")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::ImportRulePackPressed),
+        ]
+        .spacing(10),
+        Space::with_height(15), // Отступ
+        text(tr("Восстановить предыдущие настройки (текущие тоже сохранятся в копию):")),
+        view_settings_backups(extras.settings_backups, extras.selected_settings_backup),
+        Space::with_height(15), // Отступ
+        view_settings_encryption(&extras.encryption),
+        Space::with_height(15), // Отступ
+        view_settings_reset(extras.reset_pending),
     ]
-    .padding(20) // Внутренние отступы колонки
     .spacing(10) // Пространство между элементами колонки
-    .max_width(600) // Ограничиваем максимальную ширину
-    .into() // Преобразуем в Element
+    .into()
+}
+
+// Предупреждение о настройках из командной строки: их изменения не сохраняются
+fn cli_overrides_note(overrides: &[&'static str]) -> Element<'static, Message> {
+    if overrides.is_empty() {
+        return Space::with_height(0).into();
+    }
+    text(trf!(
+        "Заданы в командной строке: {}. Пока значение не изменено здесь, \
+         в файле настроек остается прежнее.",
+        overrides.join(", ")
+    ))
+    .size(14)
+    .style(Color::from_rgb8(0xFF, 0xD7, 0x00))
+    .into()
+}
+
+// Закрытие окна в область уведомлений (только там, где есть значок)
+fn close_to_tray_option(enabled: bool) -> Element<'static, Message> {
+    if !crate::tray::TRAY_SUPPORTED {
+        return Space::with_height(0).into();
+    }
+    checkbox(
+        tr("Закрывать окно в область уведомлений (выход - через меню значка)"),
+        enabled,
+    )
+    .on_toggle(Message::CloseToTrayToggled)
+    .into()
+}
+
+// Цвета интерфейса: поле #RRGGBB и образец цвета
+fn view_theme_colors(colors: &ThemeColors) -> Element<'static, Message> {
+    let color_row = |label: &'static str, value: &str, on_input: fn(String) -> Message| {
+        let swatch = container(Space::new(24, 24)).style(theme::Container::Custom(Box::new(
+            LegendSwatchStyle(parse_hex_color(value).unwrap_or(Color::TRANSPARENT)),
+        )));
+        row![
+            text(label).width(Length::Fixed(220.0)),
+            text_input("#RRGGBB", value)
+                .on_input(on_input)
+                .padding(5)
+                .width(Length::Fixed(120.0)),
+            swatch,
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+    };
+    let error: Element<'static, Message> = match colors.validate() {
+        Some(problem) => text(problem)
+            .size(12)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into(),
+        None => Space::with_height(0).into(),
+    };
+    column![
+        text(tr("Цвета интерфейса:")),
+        color_row(
+            tr("Акцент (панель и кнопки)"),
+            &colors.accent,
+            Message::AccentColorChanged
+        ),
+        color_row(
+            tr("Кнопка запуска"),
+            &colors.start,
+            Message::StartColorChanged
+        ),
+        color_row(
+            tr("Кнопка остановки"),
+            &colors.stop,
+            Message::StopColorChanged
+        ),
+        error,
+        button(text(tr("Цвета по умолчанию")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::ResetThemeColorsPressed),
+    ]
+    .spacing(10)
+    .into()
+}
+
+// Сброс всех настроек по умолчанию (с подтверждением)
+fn view_settings_reset(pending: bool) -> Element<'static, Message> {
+    if !pending {
+        return button(text(tr("Сбросить все настройки...")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::ResetSettingsPressed)
+            .into();
+    }
+    container(
+        column![
+            text(tr(
+                "Сбросить все настройки к значениям по умолчанию? Профили, экземпляры и правила \
+                 будут удалены; текущие настройки сохранятся в резервную копию."
+            )),
+            row![
+                button(text(tr("Сбросить")))
+                    .padding(10)
+                    .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                    .on_press(Message::ConfirmResetSettings),
+                button(text(tr("Отмена")))
+                    .padding(10)
+                    .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                    .on_press(Message::CancelResetSettings),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .into()
+}
+
+// Шифрование файла настроек паролем: включение, смена пароля и отключение
+fn view_settings_encryption(state: &EncryptionState) -> Element<'static, Message> {
+    let status = if state.enabled {
+        tr("Файл настроек зашифрован паролем. Пароль запрашивается при запуске лаунчера.")
+    } else {
+        tr(
+            "Файл настроек хранится открытым текстом. Его можно зашифровать паролем \
+         (незашифрованные копии будут удалены).",
+        )
+    };
+    let mismatch =
+        !state.new_password_confirm.is_empty() && state.new_password != state.new_password_confirm;
+    let hint: Element<'static, Message> = if mismatch {
+        text(tr("Пароли не совпадают"))
+            .size(14)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into()
+    } else {
+        Space::with_height(0).into()
+    };
+    let mut encrypt = button(text(if state.enabled {
+        tr("Сменить пароль")
+    } else {
+        tr("Зашифровать")
+    }))
+    .padding(5)
+    .style(theme::Button::Custom(Box::new(DefaultButtonStyle)));
+    if !state.pending
+        && !state.new_password.is_empty()
+        && state.new_password == state.new_password_confirm
+    {
+        encrypt = encrypt.on_press(Message::EncryptSettingsPressed);
+    }
+    let mut buttons = row![encrypt].spacing(10);
+    if state.enabled {
+        let mut decrypt = button(text(tr("Отключить шифрование")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)));
+        if !state.pending {
+            decrypt = decrypt.on_press(Message::DecryptSettingsPressed);
+        }
+        buttons = buttons.push(decrypt);
+    }
+    column![
+        text(status).size(14),
+        row![
+            text_input(tr("Новый пароль"), state.new_password)
+                .secure(true)
+                .on_input(Message::NewSettingsPasswordChanged)
+                .padding(5),
+            text_input(tr("Повторите пароль"), state.new_password_confirm)
+                .secure(true)
+                .on_input(Message::NewSettingsPasswordConfirmChanged)
+                .on_submit(Message::EncryptSettingsPressed)
+                .padding(5),
+        ]
+        .spacing(10),
+        hint,
+        buttons,
+    ]
+    .spacing(10)
+    .into()
+}
+
+// Выбор резервной копии настроек и кнопка восстановления
+fn view_settings_backups(
+    backups: &[SettingsBackup],
+    selected: Option<&SettingsBackup>,
+) -> Element<'static, Message> {
+    if backups.is_empty() {
+        return text(tr(
+            "Резервных копий пока нет: они появляются при изменении настроек.",
+        ))
+        .size(14)
+        .into();
+    }
+    let mut restore = button(text(tr("Восстановить")))
+        .padding(5)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)));
+    if selected.is_some() {
+        restore = restore.on_press(Message::RestoreSettingsBackup);
+    }
+    row![
+        pick_list(
+            backups.to_vec(),
+            selected.cloned(),
+            Message::SettingsBackupSelected
+        )
+        .placeholder(tr("Копия от..."))
+        .width(Length::Fill),
+        restore,
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+// Подсказка под полем ключа API, если ключ имеет неверный формат
+fn api_key_problem(key: &str) -> Element<'static, Message> {
+    match crate::settings::validate_api_key(key) {
+        Some(problem) if !key.is_empty() => text(problem)
+            .size(14)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into(),
+        _ => Space::with_height(0).into(),
+    }
+}
+
+// Состояние переменной окружения с ключом API: ключ читается из нее при запуске процесса
+fn api_key_env_status(settings: &AppSettings) -> Element<'static, Message> {
+    let name = settings.api_key_env.trim();
+    if name.is_empty() {
+        return Space::with_height(0).into();
+    }
+    match settings.env_api_key() {
+        Some(key) => text(match crate::settings::validate_api_key(&key) {
+            Some(problem) => trf!("Переменная {} задана, но ключ в ней: {}", name, problem),
+            None => trf!("Ключ берется из переменной {} при запуске процесса; поле ключа выше не используется.", name),
+        })
+        .size(14)
+        .into(),
+        None => text(trf!("Переменная окружения {} не задана: процесс не запустится без ключа.", name))
+        .size(14)
+        .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+        .into(),
+    }
+}
+
+// Подсказка под полем дополнительных аргументов, если строку не удается разобрать
+fn extra_args_problem(args: &str) -> Element<'static, Message> {
+    match crate::settings::split_args(args) {
+        Err(problem) => text(problem)
+            .size(14)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into(),
+        Ok(_) => Space::with_height(0).into(),
+    }
+}
+
+// Сообщение об ошибке в формате времени строк лога
+fn time_format_problem_text(format: &str) -> Element<'static, Message> {
+    match time_format_problem(format) {
+        Some(problem) => text(problem)
+            .size(14)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into(),
+        None => Space::with_height(0).into(),
+    }
+}
+
+// Сообщение об ошибке в адресе проверки готовности
+fn health_check_problem(target: &str) -> Element<'static, Message> {
+    match validate_target(target) {
+        Some(problem) => text(problem)
+            .size(14)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into(),
+        None => Space::with_height(0).into(),
+    }
+}
+
+// Настройки расписания торговых часов
+fn view_trading_hours(schedule: &TradingHours) -> Element<'static, Message> {
+    let mut days = row![].spacing(10);
+    for (day, name) in WEEKDAY_NAMES.iter().enumerate() {
+        days = days.push(
+            checkbox(tr(name), schedule.days[day])
+                .on_toggle(move |enabled| Message::TradingHoursDayToggled(day, enabled)),
+        );
+    }
+    let status = match schedule.validate() {
+        Some(problem) if schedule.enabled => text(problem)
+            .size(12)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55)),
+        _ if schedule.enabled => text(match schedule.next_action(Local::now()) {
+            Some((time, action)) => trf!(
+                "Следующее действие: {} в {}",
+                match action {
+                    ScheduledAction::Start(_) => tr("запуск"),
+                    ScheduledAction::Stop(_) => tr("остановка"),
+                },
+                time.format("%d.%m %H:%M")
+            ),
+            None => tr("Нет запланированных действий").to_string(),
+        })
+        .size(12),
+        _ => text(tr("Расписание выключено")).size(12),
+    };
+    column![
+        checkbox(
+            tr("Запускать и останавливать процесс по расписанию торговых часов"),
+            schedule.enabled
+        )
+        .on_toggle(Message::TradingHoursToggled),
+        row![
+            text(tr("Запуск (ЧЧ:ММ):")),
+            text_input("09:55", &schedule.start_time)
+                .on_input(Message::TradingHoursStartChanged)
+                .padding(10)
+                .width(100),
+            text(tr("Остановка (ЧЧ:ММ):")),
+            text_input("23:50", &schedule.stop_time)
+                .on_input(Message::TradingHoursStopChanged)
+                .padding(10)
+                .width(100),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        days,
+        status,
+    ]
+    .spacing(10)
+    .into()
+}
+
+// Колонки строки JSON-лога фиксированной ширины (сообщение занимает остаток строки).
+// Ширина колонок растет вместе с размером шрифта
+fn view_json_columns(record: &JsonRecord, color: Color, size: u16) -> Row<'static, Message> {
+    let scale = f32::from(size) / f32::from(DEFAULT_LOG_FONT_SIZE);
+    let cell = |value: &Option<String>, width: f32| {
+        text(value.clone().unwrap_or_default())
+            .size(size)
+            .font(Font::MONOSPACE)
+            .style(color)
+            .width(width * scale)
+    };
+    row![
+        cell(&record.time, 200.0),
+        cell(&record.level, 60.0),
+        cell(&record.module, 160.0),
+        text(&record.message)
+            .size(size)
+            .font(Font::MONOSPACE)
+            .style(color)
+            .width(Length::Fill),
+    ]
+    .spacing(10)
+}
+
+// Значок закладки в начале строки лога (клик ставит или снимает закладку)
+fn view_bookmark_toggle(id: u64, bookmarked: bool, size: u16) -> Element<'static, Message> {
+    let (icon, color) = if bookmarked {
+        ("★", Color::from_rgb8(0xFF, 0xD7, 0x00))
+    } else {
+        ("☆", Color::from_rgb8(0x55, 0x55, 0x55))
+    };
+    button(text(icon).size(size).style(color))
+        .padding([0, 4, 0, 0])
+        .style(theme::Button::Custom(Box::new(LinkButtonStyle)))
+        .on_press(Message::LogBookmarkToggled(id))
+        .into()
+}
+
+// Поле слева от строки лога: номер строки и поток вывода ([out]/[err])
+fn view_gutter(line: &LogLine, size: u16) -> Element<'static, Message> {
+    let (badge, color) = match line.source {
+        LogSource::Stdout => ("[out]", GUTTER_COLOR),
+        LogSource::Stderr => ("[err]", STDERR_COLOR),
+        LogSource::Launcher => ("     ", GUTTER_COLOR),
+    };
+    row![
+        text(format!("{:>9} ", format_line_number(line.number)))
+            .size(size)
+            .font(Font::MONOSPACE)
+            .style(GUTTER_COLOR),
+        text(format!("{} ", badge))
+            .size(size)
+            .font(Font::MONOSPACE)
+            .style(color),
+    ]
+    .into()
+}
+
+// Номер строки с разделением разрядов пробелом ("18 342")
+pub fn format_line_number(number: u64) -> String {
+    let digits = number.to_string();
+    let mut result = String::new();
+    for (position, digit) in digits.chars().enumerate() {
+        if position > 0 && (digits.len() - position).is_multiple_of(3) {
+            result.push(' ');
+        }
+        result.push(digit);
+    }
+    result
+}
+
+// Цвет строки таблицы JSON по уровню
+fn level_color(level: Option<LogLevel>) -> Color {
+    match level {
+        Some(LogLevel::Error) => Color::from_rgb8(0xFF, 0x55, 0x55),
+        Some(LogLevel::Warn) => Color::from_rgb8(0xFF, 0xD7, 0x00),
+        Some(LogLevel::Debug) => Color::from_rgb8(0x88, 0x88, 0x88),
+        Some(LogLevel::Info) | None => Color::WHITE,
+    }
+}
+
+// Цвет подсветки в цвет Iced
+fn highlight_color(color: HighlightColor) -> Color {
+    let (r, g, b) = color.rgb();
+    Color::from_rgb8(r, g, b)
+}
+
+// Сегмент строки лога: цвет (подсветка правилом важнее ANSI), начертание, фон и подчеркивание
+fn view_segment(
+    segment: &AnsiSegment,
+    highlight: Option<HighlightStyle>,
+    tint: Option<Color>,
+    size: u16,
+) -> Element<'static, Message> {
+    let default_color = if segment.link.is_some() {
+        LINK_COLOR
+    } else {
+        tint.unwrap_or(Color::WHITE)
+    };
+    // Цвет правила подсветки относится либо к тексту, либо к фону
+    let (highlight_text, highlight_background) = match highlight {
+        Some(style) if style.background => (None, Some(highlight_color(style.color))),
+        Some(style) => (Some(highlight_color(style.color)), None),
+        None => (None, None),
+    };
+    let color = highlight_text.or(segment.color).unwrap_or(default_color);
+    let font = Font {
+        weight: if segment.bold || highlight.is_some_and(|style| style.bold) {
+            font::Weight::Bold
+        } else {
+            font::Weight::Normal
+        },
+        style: if segment.italic {
+            font::Style::Italic
+        } else {
+            font::Style::Normal
+        },
+        ..Font::MONOSPACE
+    };
+    let segment_text: Text<'static> = text(&segment.text).size(size).font(font).style(color);
+    // Подчеркивание - линия толщиной 1 пиксель под текстом (ширина колонки равна ширине текста)
+    // Ссылки всегда подчеркнуты
+    let content: Element<'static, Message> = if segment.underline || segment.link.is_some() {
+        column![
+            segment_text,
+            container(Space::with_height(1))
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(FillStyle(color))))
+        ]
+        .into()
+    } else {
+        segment_text.into()
+    };
+    let content = match highlight_background.or(segment.background) {
+        Some(background) => container(content)
+            .style(theme::Container::Custom(Box::new(FillStyle(background))))
+            .into(),
+        None => content,
+    };
+    // Ссылка открывается в браузере по клику
+    match &segment.link {
+        Some(url) => button(content)
+            .padding(0)
+            .style(theme::Button::Custom(Box::new(LinkButtonStyle)))
+            .on_press(Message::OpenUrlPressed(url.clone()))
+            .into(),
+        None => content,
+    }
+}
+
+// Размер в байтах в удобочитаемом виде
+fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        trf!("{} ГБ", format!("{:.2}", mb / 1024.0))
+    } else {
+        trf!("{} МБ", format!("{:.0}", mb))
+    }
+}
+
+// Время работы в удобочитаемом виде ("2 ч 13 мин", меньше часа - с секундами)
+fn format_uptime(uptime: chrono::Duration) -> String {
+    let seconds = uptime.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        trf!("{} д {} ч {} мин", days, hours, minutes)
+    } else if hours > 0 {
+        trf!("{} ч {} мин", hours, minutes)
+    } else {
+        trf!("{} мин {} с", minutes, seconds % 60)
+    }
+}
+
+// Кнопка ручной блокировки интерфейса
+fn lock_button(available: bool) -> Element<'static, Message> {
+    if !available {
+        return Space::with_width(0).into();
+    }
+    button(text(tr("Заблокировать")))
+        .padding(10)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+        .on_press(Message::LockNowPressed)
+        .into()
+}
+
+// Ввод пароля зашифрованного файла настроек (при запуске, до загрузки настроек)
+pub fn view_settings_unlock(
+    password: &str,
+    error: Option<&str>,
+    pending: bool, // Пароль проверяется
+) -> Element<'static, Message> {
+    let status: Element<'static, Message> = match error {
+        _ if pending => text(tr("Проверка пароля...")).size(14).into(),
+        Some(error) => text(error)
+            .size(14)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into(),
+        None => Space::with_height(0).into(),
+    };
+    let mut open = button(text(tr("Открыть")))
+        .padding(10)
+        .style(theme::Button::Custom(Box::new(DefaultButtonStyle)));
+    if !pending {
+        open = open.on_press(Message::SettingsPasswordSubmitted);
+    }
+    container(
+        column![
+            text(tr("Файл настроек зашифрован. Введите пароль.")).size(18),
+            row![
+                text_input(tr("Пароль"), password)
+                    .secure(true)
+                    .on_input(Message::SettingsPasswordChanged)
+                    .on_submit(Message::SettingsPasswordSubmitted)
+                    .padding(10)
+                    .width(300),
+                open,
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+            status,
+        ]
+        .spacing(10),
+    )
+    .padding(20)
+    .center_y()
+    .height(Length::Fill)
+    .into()
+}
+
+// Панель разблокировки интерфейса
+pub fn view_lock(pin_input: &str, error: Option<&str>) -> Element<'static, Message> {
+    let error: Element<'static, Message> = match error {
+        Some(error) => text(error)
+            .size(14)
+            .style(Color::from_rgb8(0xFF, 0x55, 0x55))
+            .into(),
+        None => Space::with_height(0).into(),
+    };
+    container(
+        column![
+            text(tr("Интерфейс заблокирован. Процесс продолжает работать.")).size(18),
+            row![
+                text_input("PIN", pin_input)
+                    .secure(true)
+                    .on_input(Message::UnlockPinChanged)
+                    .on_submit(Message::UnlockPressed)
+                    .padding(10)
+                    .width(200),
+                button(text(tr("Разблокировать")))
+                    .padding(10)
+                    .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                    .on_press(Message::UnlockPressed),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+            error,
+        ]
+        .spacing(10),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(theme::Container::Custom(Box::new(WarningBannerStyle)))
+    .into()
+}
+
+// Чек-лист условий запуска: каждый невыполненный пункт с кнопкой перехода к исправлению.
+// Если все условия выполнены, чек-лист не показывается.
+fn readiness_checklist(
+    settings: &AppSettings,
+    api_key: &str, // Ключ API выбранного экземпляра
+    monitor: &MonitorState,
+) -> Element<'static, Message> {
+    let key_problem = crate::settings::validate_api_key(api_key);
+    let data_dir_error = monitor.data_dir_error;
+    let items = [
+        (
+            match monitor.executable_problem {
+                Some(problem) => trf!("Исполняемый файл TradingStar: {}", problem),
+                None => tr("Указан исполняемый файл TradingStar").to_string(),
+            },
+            settings.executable_path.is_some() && monitor.executable_problem.is_none(),
+            tr("Выбрать файл..."),
+            Message::SelectExecutablePath,
+        ),
+        (
+            match &key_problem {
+                Some(problem) => trf!("Ключ API: {}", problem),
+                None => tr("Задан ключ API").to_string(),
+            },
+            key_problem.is_none(),
+            tr("Открыть настройки"),
+            Message::SettingsSectionSelected(SettingsSection::Process),
+        ),
+        (
+            match data_dir_error {
+                Some(error) => trf!("Директория данных недоступна: {}", error),
+                None => tr("Директория данных доступна для записи").to_string(),
+            },
+            data_dir_error.is_none(),
+            tr("Выбрать директорию..."),
+            Message::SelectDataDir,
+        ),
+    ];
+    if items.iter().all(|(_, done, _, _)| *done) {
+        return Space::with_height(0).into();
+    }
+
+    let mut checklist = column![text(tr("Перед запуском:")).size(16)].spacing(6);
+    for (label, done, fix_label, fix) in items {
+        let (mark, color) = if done {
+            ("✓", Color::from_rgb8(0x55, 0xCC, 0x55))
+        } else {
+            ("✗", Color::from_rgb8(0xFF, 0x55, 0x55))
+        };
+        let mut item = row![text(mark).style(color), text(label).width(Length::Fill),]
+            .spacing(10)
+            .align_items(Alignment::Center);
+        if !done {
+            item = item.push(
+                button(text(fix_label))
+                    .padding(5)
+                    .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                    .on_press(fix),
+            );
+        }
+        checklist = checklist.push(item);
+    }
+    container(checklist)
+        .padding(10)
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(ChecklistCardStyle)))
+        .into()
+}
+
+// Баннер "настройки запуска изменены" с кнопкой перезапуска процесса
+fn launch_changes_banner(changes: &[&'static str]) -> Element<'static, Message> {
+    if changes.is_empty() {
+        return Space::with_height(0).into();
+    }
+    container(
+        row![
+            text(trf!(
+                "Изменены настройки запуска ({}) - перезапустите процесс, чтобы применить их.",
+                changes.join(", ")
+            ))
+            .width(Length::Fill),
+            button(text(tr("Перезапустить сейчас")))
+                .padding(5)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::ApplyLaunchSettingsNow),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(theme::Container::Custom(Box::new(WarningBannerStyle)))
+    .into()
+}
+
+// Отрисовка экрана статистики: график времени работы и перезапусков за неделю
+pub fn view_stats(
+    history: &[RunRecord],                                  // Завершенные запуски
+    current_start: Option<chrono::DateTime<chrono::Local>>, // Начало текущего запуска
+) -> Element<'static, Message> {
+    const DAYS: usize = 7;
+    let now = chrono::Local::now();
+    let period_start = now - chrono::Duration::days(DAYS as i64);
+
+    // Запуски, попадающие в период графика
+    let runs: Vec<RunRecord> = history
+        .iter()
+        .filter(|run| run.end > period_start)
+        .cloned()
+        .collect();
+
+    // Сводка за период
+    let uptime_seconds: i64 = runs
+        .iter()
+        .map(|run| (run.start.max(period_start), run.end))
+        .chain(current_start.map(|start| (start.max(period_start), now)))
+        .map(|(start, end)| (end - start).num_seconds().max(0))
+        .sum();
+    let period_seconds = (now - period_start).num_seconds().max(1);
+    let crashes = runs
+        .iter()
+        .filter(|run| run.outcome == RunOutcome::Crashed)
+        .count();
+    let scheduled = runs
+        .iter()
+        .filter(|run| run.outcome == RunOutcome::Scheduled)
+        .count();
+    let summary = text(trf!("За {} дней: время работы {}% ({} ч {} мин), запусков - {}, сбоев - {}, плановых остановок - {}", DAYS, format!("{:.1}", uptime_seconds as f64 * 100.0 / period_seconds as f64), uptime_seconds / 3600, uptime_seconds % 3600 / 60, runs.len() + current_start.iter().count(), crashes, scheduled));
+
+    let chart = canvas(UptimeChart {
+        runs,
+        current_start,
+        days: DAYS,
+        now,
+    })
+    .width(Length::Fill)
+    .height(UptimeChart::height(DAYS));
+
+    // Легенда графика
+    let legend_item = |color: Color, label: &'static str| {
+        row![
+            container(Space::new(12, 12))
+                .style(theme::Container::Custom(Box::new(LegendSwatchStyle(color)))),
+            text(label).size(12)
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center)
+    };
+    let legend = row![
+        legend_item(Color::from_rgb(0.16, 0.65, 0.27), tr("Процесс работал")),
+        legend_item(Color::from_rgb(0.86, 0.21, 0.27), tr("Сбой")),
+        legend_item(Color::from_rgb(1.0, 0.76, 0.03), tr("Плановая остановка")),
+    ]
+    .spacing(20);
+
+    // Панель "Запуски": итоги за всю историю и последние запуски с кодами завершения
+    let count = |outcome: RunOutcome| history.iter().filter(|run| run.outcome == outcome).count();
+    let totals = text(trf!("Всего в истории: {} запусков - завершились сами: {}, сбоев: {}, остановлено: {}, плановых остановок: {}", history.len(), count(RunOutcome::Exited), count(RunOutcome::Crashed), count(RunOutcome::Stopped), count(RunOutcome::Scheduled)))
+    .size(14);
+    let recent_runs =
+        history
+            .iter()
+            .rev()
+            .take(RECENT_RUNS)
+            .fold(column![].spacing(2), |list, run| {
+                let duration = (run.end - run.start).num_seconds().max(0);
+                let color = match run.outcome {
+                    RunOutcome::Crashed => Color::from_rgb8(0xFF, 0x55, 0x55),
+                    RunOutcome::Scheduled => Color::from_rgb8(0xFF, 0xC1, 0x07),
+                    RunOutcome::Stopped | RunOutcome::Exited => Color::from_rgb8(0xCC, 0xCC, 0xCC),
+                };
+                list.push(
+                    text(trf!(
+                        "{} - {}  {} ч {} мин  код {}  {}",
+                        run.start.format("%d.%m.%Y %H:%M:%S"),
+                        run.end.format("%d.%m.%Y %H:%M:%S"),
+                        format!("{:>3}", duration / 3600),
+                        format!("{:02}", duration % 3600 / 60),
+                        format!(
+                            "{:>4}",
+                            run.exit_code
+                                .map(|code| code.to_string())
+                                .unwrap_or_else(|| "-".to_string())
+                        ),
+                        run.outcome
+                    ))
+                    .size(12)
+                    .font(Font::MONOSPACE)
+                    .style(color),
+                )
+            });
+
+    column![
+        text(tr("Статистика")).size(24),
+        Space::with_height(10),
+        summary,
+        chart,
+        legend,
+        Space::with_height(10),
+        text(tr("Запуски")).size(18),
+        totals,
+        scrollable(recent_runs).height(Length::Fill),
+        row![
+            button(text(tr("Закрыть статистику")))
+                .padding(10)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::CloseStatsPressed),
+            Space::with_width(Length::Fill),
+            // Экспорт всей истории для внешних отчетов
+            button(text(tr("Экспорт CSV")))
+                .padding(10)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::ExportHistoryPressed(ExportFormat::Csv)),
+            button(text(tr("Экспорт JSON")))
+                .padding(10)
+                .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                .on_press(Message::ExportHistoryPressed(ExportFormat::Json)),
+        ]
+        .spacing(10)
+    ]
+    .padding(20)
+    .spacing(10)
+    .into()
+}
+
+// Редактор дополнительных экземпляров. Запущенный экземпляр удалить нельзя.
+fn view_instances(instances: &[InstanceSettings], running: &[bool]) -> Element<'static, Message> {
+    let list =
+        instances
+            .iter()
+            .enumerate()
+            .fold(column![].spacing(10), |list, (index, instance)| {
+                let mut remove_button = button(text(tr("Удалить"))).padding(5);
+                remove_button = if running.get(index + 1).copied().unwrap_or_default() {
+                    remove_button.style(theme::Button::Custom(Box::new(DisabledButtonStyle)))
+                } else {
+                    remove_button
+                        .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                        .on_press(Message::RemoveInstance(index))
+                };
+                list.push(
+                    column![
+                        row![
+                            text_input(&trf!("Экземпляр {}", index + 2), &instance.name)
+                                .on_input(move |name| Message::InstanceNameChanged(index, name))
+                                .padding(5)
+                                .width(Length::Fill),
+                            remove_button,
+                        ]
+                        .spacing(5)
+                        .align_items(Alignment::Center),
+                        text_input(tr("Ключ API экземпляра"), &instance.api_key)
+                            .secure(true)
+                            .on_input(move |key| Message::InstanceApiKeyChanged(index, key))
+                            .on_paste(move |key| Message::InstanceApiKeyPasted(index, key))
+                            .padding(5),
+                        api_key_problem(&instance.api_key),
+                        text_input(tr("Дополнительные аргументы"), &instance.extra_args)
+                            .on_input(move |args| Message::InstanceArgsChanged(index, args))
+                            .font(Font::MONOSPACE)
+                            .padding(5),
+                        extra_args_problem(&instance.extra_args),
+                        text_input(
+                            tr("Адрес проверки готовности (хост:порт или URL)"),
+                            &instance.health_check
+                        )
+                        .on_input(move |target| Message::InstanceHealthCheckChanged(index, target))
+                        .font(Font::MONOSPACE)
+                        .padding(5),
+                        health_check_problem(&instance.health_check),
+                        text_input(
+                            tr("Команда остановки (например, quit)"),
+                            &instance.stop_command
+                        )
+                        .on_input(move |command| Message::InstanceStopCommandChanged(
+                            index, command
+                        ))
+                        .font(Font::MONOSPACE)
+                        .padding(5),
+                        text_input(
+                            tr("Лимит памяти для перезапуска, МБ (0 - не следить)"),
+                            &instance.memory_limit_mb.to_string()
+                        )
+                        .on_input(move |value| Message::InstanceMemoryLimitChanged(index, value))
+                        .padding(5),
+                        checkbox(tr("Запускать с повышенными правами"), instance.run_elevated)
+                            .on_toggle(move |enabled| {
+                                Message::InstanceRunElevatedToggled(index, enabled)
+                            }),
+                        checkbox(
+                            tr("Не останавливать при закрытии лаунчера"),
+                            instance.keep_running
+                        )
+                        .on_toggle(move |enabled| {
+                            Message::InstanceKeepRunningToggled(index, enabled)
+                        }),
+                    ]
+                    .spacing(5),
+                )
+            });
+    column![
+        list,
+        button(text(tr("Добавить экземпляр")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::AddInstance)
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Редактор переменных окружения процесса
+fn view_env_vars(vars: &[EnvVar]) -> Element<'static, Message> {
+    let list = vars
+        .iter()
+        .enumerate()
+        .fold(column![].spacing(5), |list, (index, var)| {
+            let editor = row![
+                text_input("HTTPS_PROXY", &var.key)
+                    .on_input(move |key| Message::EnvVarKeyChanged(index, key))
+                    .padding(5)
+                    .font(Font::MONOSPACE)
+                    .width(Length::FillPortion(1)),
+                text("="),
+                text_input("http://proxy:3128", &var.value)
+                    .on_input(move |value| Message::EnvVarValueChanged(index, value))
+                    .padding(5)
+                    .font(Font::MONOSPACE)
+                    .width(Length::FillPortion(2)),
+                button(text(tr("Удалить")))
+                    .padding(5)
+                    .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                    .on_press(Message::RemoveEnvVar(index)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center);
+            let list = list.push(editor);
+            // Только что добавленную пустую строку не подсвечиваем как ошибку
+            match var.validate().filter(|_| var != &EnvVar::default()) {
+                Some(error) => list.push(
+                    text(error)
+                        .size(12)
+                        .style(Color::from_rgb8(0xFF, 0x55, 0x55)),
+                ),
+                None => list,
+            }
+        });
+    column![
+        list,
+        button(text(tr("Добавить переменную")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::AddEnvVar)
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Редактор правил извлечения метрик
+fn view_metric_rules(rules: &[MetricRule], errors: &[Option<String>]) -> Element<'static, Message> {
+    let list = rules
+        .iter()
+        .enumerate()
+        .fold(column![].spacing(5), |list, (index, rule)| {
+            let editor = row![
+                text_input(tr("Имя"), &rule.name)
+                    .on_input(move |name| Message::MetricRuleNameChanged(index, name))
+                    .padding(5)
+                    .width(Length::FillPortion(1)),
+                text_input("PnL: (?P<value>-?\\d+\\.\\d+)", &rule.pattern)
+                    .on_input(move |pattern| Message::MetricRulePatternChanged(index, pattern))
+                    .padding(5)
+                    .font(Font::MONOSPACE)
+                    .width(Length::FillPortion(3)),
+                button(text(tr("Удалить")))
+                    .padding(5)
+                    .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                    .on_press(Message::RemoveMetricRule(index)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center);
+            let list = list.push(editor);
+            match errors.get(index).cloned().flatten() {
+                Some(error) => list.push(
+                    text(error)
+                        .size(12)
+                        .style(Color::from_rgb8(0xFF, 0x55, 0x55)),
+                ),
+                None => list,
+            }
+        });
+    column![
+        list,
+        button(text(tr("Добавить метрику")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::AddMetricRule)
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Редактор правил подсветки строк лога
+fn view_highlight_rules(
+    rules: &[HighlightRule],
+    errors: &[Option<String>],
+) -> Element<'static, Message> {
+    let last = rules.len().saturating_sub(1);
+    let list = rules
+        .iter()
+        .enumerate()
+        .fold(column![].spacing(5), |list, (index, rule)| {
+            let move_up = button(text("↑")).padding([5, 8]);
+            let move_down = button(text("↓")).padding([5, 8]);
+            let list = list.push(
+                row![
+                    text_input(
+                        if rule.regex {
+                            tr("Регулярное выражение (например, order \\d+ (filled|rejected))")
+                        } else {
+                            tr("Ключевое слово (например, timeout)")
+                        },
+                        &rule.keyword
+                    )
+                    .on_input(move |keyword| Message::HighlightKeywordChanged(index, keyword))
+                    .padding(5)
+                    .width(Length::Fill),
+                    checkbox("Regex", rule.regex)
+                        .on_toggle(move |enabled| Message::HighlightRegexToggled(index, enabled)),
+                    pick_list(&HighlightColor::ALL[..], Some(rule.color), move |color| {
+                        Message::HighlightColorChanged(index, color)
+                    }),
+                    checkbox(tr("Фон"), rule.background).on_toggle(move |enabled| {
+                        Message::HighlightBackgroundToggled(index, enabled)
+                    }),
+                    checkbox(tr("Жирный"), rule.bold)
+                        .on_toggle(move |enabled| Message::HighlightBoldToggled(index, enabled)),
+                    if index > 0 {
+                        move_up
+                            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                            .on_press(Message::MoveHighlightRuleUp(index))
+                    } else {
+                        move_up.style(theme::Button::Custom(Box::new(DisabledButtonStyle)))
+                    },
+                    if index < last {
+                        move_down
+                            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+                            .on_press(Message::MoveHighlightRuleDown(index))
+                    } else {
+                        move_down.style(theme::Button::Custom(Box::new(DisabledButtonStyle)))
+                    },
+                    button(text(tr("Удалить")))
+                        .padding(5)
+                        .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                        .on_press(Message::RemoveHighlightRule(index)),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            );
+            match errors.get(index).cloned().flatten() {
+                Some(error) => list.push(
+                    text(error)
+                        .size(12)
+                        .style(Color::from_rgb8(0xFF, 0x55, 0x55)),
+                ),
+                None => list,
+            }
+        });
+    column![
+        list,
+        button(text(tr("Добавить правило подсветки")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::AddHighlightRule)
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Профиль запуска в списке выбора на главном экране
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProfileChoice {
+    index: usize, // Индекс профиля в настройках
+    name: String, // Название
+}
+
+impl std::fmt::Display for ProfileChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+// Вариант метки значка окна в списке выбора
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BadgeChoice(Option<HighlightColor>);
+
+impl std::fmt::Display for BadgeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(color) => f.write_str(&trf!("Метка: {}", color)),
+            None => f.write_str(tr("Без метки")),
+        }
+    }
+}
+
+// Выбор профиля запуска (только если профили есть; во время работы процесса недоступен)
+fn profile_selector(settings: &AppSettings, is_running: bool) -> Element<'static, Message> {
+    if settings.profiles.is_empty() {
+        return Space::with_width(0).into();
+    }
+    let choices: Vec<ProfileChoice> = settings
+        .profiles
+        .iter()
+        .enumerate()
+        .map(|(index, profile)| ProfileChoice {
+            index,
+            name: profile.name.clone(),
+        })
+        .collect();
+    let selected = settings
+        .active_profile
+        .and_then(|index| choices.get(index).cloned());
+    if is_running {
+        let name = selected.map_or_else(|| tr("Без профиля").to_string(), |choice| choice.name);
+        return text(trf!("Профиль: {}", name)).size(14).into();
+    }
+    pick_list(choices, selected, |choice| {
+        Message::LaunchProfileSelected(choice.index)
+    })
+    .placeholder(tr("Профиль запуска"))
+    .text_size(14)
+    .into()
+}
+
+// Редактор профилей запуска
+fn view_launch_profiles(settings: &AppSettings) -> Element<'static, Message> {
+    let list = settings.profiles.iter().enumerate().fold(
+        column![].spacing(5),
+        |list, (index, profile)| {
+            let status: Element<'static, Message> = if settings.active_profile == Some(index) {
+                text(tr("выбран")).size(14).width(80).into()
+            } else {
+                Space::with_width(80).into()
+            };
+            let badges: Vec<BadgeChoice> = std::iter::once(BadgeChoice(None))
+                .chain(
+                    HighlightColor::ALL
+                        .into_iter()
+                        .map(|color| BadgeChoice(Some(color))),
+                )
+                .collect();
+            list.push(
+                column![
+                    row![
+                        text_input(tr("Название профиля"), &profile.name)
+                            .on_input(move |name| Message::LaunchProfileRenamed(index, name))
+                            .padding(5)
+                            .width(Length::Fill),
+                        status,
+                        button(text(tr("Удалить")))
+                            .padding(5)
+                            .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                            .on_press(Message::RemoveLaunchProfile(index)),
+                    ]
+                    .spacing(5)
+                    .align_items(Alignment::Center),
+                    row![
+                        text_input(
+                            tr("Дополнение к заголовку окна (например, LIVE BTC)"),
+                            &profile.title
+                        )
+                        .on_input(move |title| Message::LaunchProfileTitleChanged(index, title))
+                        .padding(5)
+                        .width(Length::Fill),
+                        pick_list(badges, Some(BadgeChoice(profile.badge)), move |choice| {
+                            Message::LaunchProfileBadgeSelected(index, choice.0)
+                        }),
+                    ]
+                    .spacing(5)
+                    .align_items(Alignment::Center),
+                ]
+                .spacing(5),
+            )
+        },
+    );
+    column![
+        list,
+        button(text(tr("Сохранить текущие параметры как новый профиль")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::AddLaunchProfile)
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Редактор оповещений по ключевым фразам
+fn view_keyword_alerts(rules: &[KeywordAlertRule]) -> Element<'static, Message> {
+    let list = rules
+        .iter()
+        .enumerate()
+        .fold(column![].spacing(5), |list, (index, rule)| {
+            list.push(
+                row![
+                    text_input(
+                        tr("Ключевая фраза (например, connection lost)"),
+                        &rule.keyword
+                    )
+                    .on_input(move |keyword| Message::KeywordAlertChanged(index, keyword))
+                    .padding(5)
+                    .width(Length::Fill),
+                    checkbox(tr("Баннер"), rule.banner).on_toggle(move |enabled| {
+                        Message::KeywordAlertBannerToggled(index, enabled)
+                    }),
+                    checkbox(tr("Звук"), rule.sound).on_toggle(move |enabled| {
+                        Message::KeywordAlertSoundToggled(index, enabled)
+                    }),
+                    checkbox(tr("Уведомление ОС"), rule.desktop).on_toggle(move |enabled| {
+                        Message::KeywordAlertDesktopToggled(index, enabled)
+                    }),
+                    button(text(tr("Удалить")))
+                        .padding(5)
+                        .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                        .on_press(Message::RemoveKeywordAlert(index)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            )
+        });
+    column![
+        list,
+        button(text(tr("Добавить оповещение")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::AddKeywordAlert)
+    ]
+    .spacing(5)
+    .into()
+}
+
+// Редактор правил оповещений по порогам метрик
+fn view_alert_rules(settings: &AppSettings) -> Element<'static, Message> {
+    // Список имен метрик для выбора в правилах
+    let metric_names: Vec<String> = settings
+        .metric_rules
+        .iter()
+        .map(|rule| rule.name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    let list = settings.alert_rules.iter().enumerate().fold(
+        column![].spacing(5),
+        |list, (index, rule): (usize, &AlertRule)| {
+            let selected_metric = Some(rule.metric.clone()).filter(|metric| !metric.is_empty());
+            let condition = row![
+                text(tr("Если")),
+                pick_list(metric_names.clone(), selected_metric, move |metric| {
+                    Message::AlertMetricChanged(index, metric)
+                })
+                .placeholder(tr("метрика"))
+                .width(Length::FillPortion(2)),
+                pick_list(
+                    &Comparison::ALL[..],
+                    Some(rule.comparison),
+                    move |comparison| { Message::AlertComparisonChanged(index, comparison) }
+                ),
+                text_input("0", &rule.threshold)
+                    .on_input(move |threshold| Message::AlertThresholdChanged(index, threshold))
+                    .padding(5)
+                    .width(Length::FillPortion(1)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center);
+            let action = row![
+                text(tr("в течение (мин):")),
+                text_input("0", &rule.hold_minutes.to_string())
+                    .on_input(move |minutes| Message::AlertHoldChanged(index, minutes))
+                    .padding(5)
+                    .width(60),
+                checkbox(tr("уведомить и остановить процесс"), rule.stop_process)
+                    .on_toggle(move |enabled| Message::AlertStopToggled(index, enabled)),
+                Space::with_width(Length::Fill),
+                button(text(tr("Удалить")))
+                    .padding(5)
+                    .style(theme::Button::Custom(Box::new(StopButtonStyle)))
+                    .on_press(Message::RemoveAlertRule(index)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center);
+            let list = list.push(column![condition, action].spacing(5));
+            match rule.validate(&metric_names) {
+                Some(error) => list.push(
+                    text(error)
+                        .size(12)
+                        .style(Color::from_rgb8(0xFF, 0x55, 0x55)),
+                ),
+                None => list,
+            }
+        },
+    );
+    column![
+        list,
+        button(text(tr("Добавить оповещение")))
+            .padding(5)
+            .style(theme::Button::Custom(Box::new(DefaultButtonStyle)))
+            .on_press(Message::AddAlertRule)
+    ]
+    .spacing(5)
+    .into()
 }
 
 // --- Стили виджетов ---
 
-// Стиль для верхней панели
+// Цвета акцента и кнопок запуска/остановки берутся из палитры темы (задаются в настройках)
+
+// Текст на цветном фоне: белый, а на светлом фоне - темный
+fn text_color_on(background: Color) -> Color {
+    let luminance = 0.299 * background.r + 0.587 * background.g + 0.114 * background.b;
+    if luminance > 0.65 {
+        INVERSE_TEXT_COLOR
+    } else {
+        BUTTON_TEXT_COLOR
+    }
+}
+
+// Более темный оттенок цвета (фон кнопки при наведении)
+fn darken(color: Color) -> Color {
+    Color::from_rgb(color.r * 0.8, color.g * 0.8, color.b * 0.8)
+}
+
+// Кнопка с фоном заданного цвета
+fn colored_button(background: Color) -> button::Appearance {
+    button::Appearance {
+        background: Some(Background::Color(background)),
+        text_color: text_color_on(background),
+        border: Border {
+            radius: 4.0.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+// Стиль для верхней панели (цвет акцента)
 struct TopBarStyle;
 impl container::StyleSheet for TopBarStyle {
+    type Style = Theme;
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let background = style.palette().primary;
+        container::Appearance {
+            background: Some(background.into()),
+            text_color: Some(text_color_on(background)),
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль для предупреждающих баннеров (оранжевый)
+struct WarningBannerStyle;
+impl container::StyleSheet for WarningBannerStyle {
     type Style = Theme;
     fn appearance(&self, _style: &Self::Style) -> container::Appearance {
         container::Appearance {
-            background: Some(Color::from_rgb8(0x00, 0x7B, 0xFF).into()), // Синий фон
-            text_color: Some(Color::WHITE),                              // Белый текст по умолчанию
+            background: Some(Color::from_rgb8(0xE0, 0x8A, 0x00).into()), // Оранжевый фон
+            text_color: Some(Color::WHITE),
             ..Default::default()
         }
     }
 }
 
-// Общий стиль для кнопок по умолчанию (синий)
-struct DefaultButtonStyle;
-impl button::StyleSheet for DefaultButtonStyle {
+// Стиль для баннеров об ошибках (красный)
+struct ErrorBannerStyle;
+impl container::StyleSheet for ErrorBannerStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Color::from_rgb8(0xDC, 0x35, 0x45).into()), // Красный фон
+            text_color: Some(Color::WHITE),
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль для цветного образца в легенде графика
+struct LegendSwatchStyle(Color);
+impl container::StyleSheet for LegendSwatchStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(self.0.into()),
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль для выделенной строки лога
+struct HighlightedLineStyle;
+impl container::StyleSheet for HighlightedLineStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Color::from_rgba8(0x00, 0x7B, 0xFF, 0.35).into()),
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль ссылки в логе: без фона и рамки (цвет задает сам текст)
+struct LinkButtonStyle;
+impl button::StyleSheet for LinkButtonStyle {
     type Style = Theme;
     fn active(&self, _style: &Self::Style) -> button::Appearance {
         button::Appearance {
-            background: Some(Background::Color(Color::from_rgb8(0x00, 0x7B, 0xFF))), // Синий
-            text_color: BUTTON_TEXT_COLOR, // Белый текст (из константы)
+            background: None,
+            text_color: LINK_COLOR,
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль заливки заданным цветом (фон сегментов лога, подчеркивание)
+struct FillStyle(Color);
+impl container::StyleSheet for FillStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(self.0.into()),
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль для строк, совпавших с поиском (приглушенный желтый фон)
+struct SearchMatchStyle;
+impl container::StyleSheet for SearchMatchStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Color::from_rgba8(0xDD, 0xAA, 0x00, 0.25).into()),
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль строки состояния
+struct StatusBarStyle;
+impl container::StyleSheet for StatusBarStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Color::from_rgb8(0x2B, 0x30, 0x35).into()),
+            text_color: Some(Color::from_rgb8(0xCC, 0xCC, 0xCC)),
+            ..Default::default()
+        }
+    }
+}
+
+// Стиль для плашек метрик (темно-серый фон)
+struct MetricChipStyle;
+impl container::StyleSheet for MetricChipStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Color::from_rgb8(0x34, 0x3A, 0x40).into()),
+            text_color: Some(Color::WHITE),
             border: Border {
                 radius: 4.0.into(),
                 ..Default::default()
@@ -300,48 +3660,90 @@ impl button::StyleSheet for DefaultButtonStyle {
             ..Default::default()
         }
     }
-    // Стиль при наведении
-    fn hovered(&self, style: &Self::Style) -> button::Appearance {
-        let active = self.active(style);
-        button::Appearance {
-            background: Some(Background::Color(Color::from_rgb8(0x00, 0x56, 0xB3))), // Темнее синий
-            ..active // Остальные свойства как у active
+}
+
+// Карточка чек-листа готовности к запуску
+struct ChecklistCardStyle;
+impl container::StyleSheet for ChecklistCardStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Color::from_rgb8(0x2B, 0x30, 0x35).into()),
+            text_color: Some(Color::WHITE),
+            border: Border {
+                color: Color::from_rgb8(0x55, 0x5D, 0x66),
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            ..Default::default()
         }
     }
 }
 
-// Стиль для кнопки "Старт" (зеленый)
-struct StartButtonStyle;
-impl button::StyleSheet for StartButtonStyle {
+// Модальное окно (настройки поверх главного экрана)
+struct DialogStyle;
+impl container::StyleSheet for DialogStyle {
     type Style = Theme;
-    fn active(&self, _style: &Self::Style) -> button::Appearance {
-        button::Appearance {
-            background: Some(Background::Color(Color::from_rgb8(0x28, 0xA7, 0x45))), // Зеленый
-            text_color: BUTTON_TEXT_COLOR,
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(style.palette().background.into()),
+            text_color: Some(style.palette().text),
             border: Border {
-                radius: 4.0.into(),
-                ..Default::default()
+                color: Color::from_rgb8(0x55, 0x5D, 0x66),
+                width: 1.0,
+                radius: 6.0.into(),
             },
             ..Default::default()
         }
     }
+}
+
+// Общий стиль для кнопок по умолчанию (цвет акцента)
+struct DefaultButtonStyle;
+impl button::StyleSheet for DefaultButtonStyle {
+    type Style = Theme;
+    fn active(&self, style: &Self::Style) -> button::Appearance {
+        colored_button(style.palette().primary)
+    }
     // Стиль при наведении
     fn hovered(&self, style: &Self::Style) -> button::Appearance {
-        let active = self.active(style);
-        button::Appearance {
-            background: Some(Background::Color(Color::from_rgb8(0x21, 0x88, 0x38))), // Темнее зеленый
-            ..active
-        }
+        colored_button(darken(style.palette().primary))
     }
 }
 
-// Стиль для кнопки "Стоп" (красный)
+// Стиль для кнопки "Старт" (по умолчанию зеленый)
+struct StartButtonStyle;
+impl button::StyleSheet for StartButtonStyle {
+    type Style = Theme;
+    fn active(&self, style: &Self::Style) -> button::Appearance {
+        colored_button(style.palette().success)
+    }
+    // Стиль при наведении
+    fn hovered(&self, style: &Self::Style) -> button::Appearance {
+        colored_button(darken(style.palette().success))
+    }
+}
+
+// Стиль для кнопки "Стоп" (по умолчанию красный)
 struct StopButtonStyle;
 impl button::StyleSheet for StopButtonStyle {
+    type Style = Theme;
+    fn active(&self, style: &Self::Style) -> button::Appearance {
+        colored_button(style.palette().danger)
+    }
+    // Стиль при наведении
+    fn hovered(&self, style: &Self::Style) -> button::Appearance {
+        colored_button(darken(style.palette().danger))
+    }
+}
+
+// Стиль для невыбранной вкладки экземпляра (темно-серый)
+struct TabButtonStyle;
+impl button::StyleSheet for TabButtonStyle {
     type Style = Theme;
     fn active(&self, _style: &Self::Style) -> button::Appearance {
         button::Appearance {
-            background: Some(Background::Color(Color::from_rgb8(0xDC, 0x35, 0x45))), // Красный
+            background: Some(Background::Color(Color::from_rgb8(0x3A, 0x3F, 0x44))), // Темно-серый
             text_color: BUTTON_TEXT_COLOR,
             border: Border {
                 radius: 4.0.into(),
@@ -354,7 +3756,7 @@ impl button::StyleSheet for StopButtonStyle {
     fn hovered(&self, style: &Self::Style) -> button::Appearance {
         let active = self.active(style);
         button::Appearance {
-            background: Some(Background::Color(Color::from_rgb8(0xC8, 0x23, 0x33))), // Темнее красный
+            background: Some(Background::Color(Color::from_rgb8(0x4B, 0x51, 0x57))), // Светлее серый
             ..active
         }
     }
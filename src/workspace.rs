@@ -0,0 +1,122 @@
+use crate::i18n::{tr, trf};
+use crate::ui::{LogLevel, LogStreamFilter, LogTimeFilter};
+use crate::Screen;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+// --- Рабочее пространство: состояние лаунчера между сеансами ---
+// Сохраняется в директории данных и восстанавливается при следующем запуске,
+// так что после перезагрузки компьютера лаунчер возвращается в прежнее состояние.
+
+pub const WORKSPACE_FILE_NAME: &str = "workspace.json";
+
+// Размер и положение окна
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: u32,
+    pub height: u32,
+    pub x: Option<i32>, // Положение (None - не менялось, окно размещает ОС)
+    pub y: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)] // Отсутствующие поля берутся из Default
+pub struct Workspace {
+    pub was_running: bool, // Процесс основного экземпляра работал в момент закрытия лаунчера
+    pub running_instances: Vec<usize>, // Работавшие дополнительные экземпляры (индексы с 1)
+    pub selected_instance: usize, // Выбранная вкладка экземпляра
+    pub screen: Screen,    // Открытый экран
+    pub log_filter: LogTimeFilter, // Фильтр лога
+    pub log_range_from: String, // Начало произвольного интервала фильтра
+    pub log_range_to: String, // Конец произвольного интервала фильтра
+    pub hidden_log_levels: Vec<LogLevel>, // Скрытые уровни строк лога
+    pub log_stream: LogStreamFilter, // Показываемые потоки вывода
+    pub log_json_view: bool, // Строки JSON показываются таблицей
+    pub log_raw_view: bool, // Рядом со строками лога показывается их исходный текст
+    pub window: Option<WindowGeometry>, // Размер и положение окна
+}
+
+// Файл рабочего пространства внутри директории данных
+pub fn workspace_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(WORKSPACE_FILE_NAME)
+}
+
+pub async fn load_workspace(path: Option<PathBuf>) -> Result<Workspace, String> {
+    let path =
+        path.ok_or_else(|| tr("Не удалось определить путь к рабочему пространству").to_string())?;
+    if !path.exists() {
+        return Ok(Workspace::default());
+    }
+    let content = fs::read_to_string(&path).await.map_err(|e| {
+        trf!(
+            "Ошибка чтения рабочего пространства {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        trf!(
+            "Ошибка парсинга рабочего пространства {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })
+}
+
+// Синхронное сохранение (при закрытии окна, когда асинхронная команда может не успеть)
+pub fn write_workspace(path: Option<PathBuf>, workspace: &Workspace) -> Result<(), String> {
+    let path =
+        path.ok_or_else(|| tr("Не удалось определить путь к рабочему пространству").to_string())?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            trf!(
+                "Не удалось создать директорию {}: {}",
+                format!("{:?}", parent),
+                e
+            )
+        })?;
+    }
+    let content = serde_json::to_string_pretty(workspace)
+        .map_err(|e| trf!("Ошибка сериализации рабочего пространства: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| {
+        trf!(
+            "Не удалось записать рабочее пространство {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })
+}
+
+// Размер и положение окна из файла рабочего пространства (синхронно - для параметров
+// окна до запуска интерфейса). Без файла или при ошибке - None.
+pub fn read_window_geometry(path: &Path) -> Option<WindowGeometry> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let workspace: Workspace = serde_json::from_str(&content).ok()?;
+    workspace
+        .window
+        .filter(|geometry| geometry.width > 0 && geometry.height > 0)
+}
+
+pub async fn save_workspace(path: Option<PathBuf>, workspace: Workspace) -> Result<(), String> {
+    let path =
+        path.ok_or_else(|| tr("Не удалось определить путь к рабочему пространству").to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await.map_err(|e| {
+            trf!(
+                "Не удалось создать директорию {}: {}",
+                format!("{:?}", parent),
+                e
+            )
+        })?;
+    }
+    let content = serde_json::to_string_pretty(&workspace)
+        .map_err(|e| trf!("Ошибка сериализации рабочего пространства: {}", e))?;
+    fs::write(&path, content).await.map_err(|e| {
+        trf!(
+            "Не удалось записать рабочее пространство {}: {}",
+            format!("{:?}", path),
+            e
+        )
+    })
+}